@@ -16,7 +16,7 @@ fn main() {
 
     // Create core data structures
     let mut arena = Arena::new();
-    let mut symbols = SymbolTable::new();
+    let symbols = SymbolTable::new();
     let mut levels = LevelArena::new();
     let _env = Environment::new();
 
@@ -11,14 +11,12 @@
 //!
 //! Run: cargo run --example 02_verified_calculator
 
-use lean_agentic::{Arena, Environment, SymbolTable, TermId};
+use lean_agentic::{Arena, SymbolTable, TermId};
 use lean_agentic::level::LevelArena;
-use lean_agentic::term::{Binder, BinderInfo};
 
 /// Calculator that produces proof certificates
 struct VerifiedCalculator {
     arena: Arena,
-    env: Environment,
     symbols: SymbolTable,
     levels: LevelArena,
 }
@@ -34,7 +32,6 @@ impl VerifiedCalculator {
     fn new() -> Self {
         Self {
             arena: Arena::new(),
-            env: Environment::new(),
             symbols: SymbolTable::new(),
             levels: LevelArena::new(),
         }
@@ -51,16 +48,9 @@ impl VerifiedCalculator {
 
         // Create proof term: add(a, b) = result
         let add_name = self.symbols.intern("add");
-        let proof = self.create_equality_proof(
-            self.arena.mk_app(
-                self.arena.mk_app(
-                    self.arena.mk_const(add_name, vec![]),
-                    a_term
-                ),
-                b_term
-            ),
-            result_term
-        );
+        let add = self.arena.mk_const(add_name, vec![]);
+        let sum = self.arena.mk_app_spine(add, &[a_term, b_term]);
+        let proof = self.create_equality_proof(sum, result_term);
 
         VerifiedResult {
             value: result,
@@ -76,19 +66,12 @@ impl VerifiedCalculator {
         // Create proof that result is within bounds
         let a_term = self.arena.mk_nat(a as u64);
         let b_term = self.arena.mk_nat(b as u64);
-        let result_term = self.arena.mk_nat(result.abs() as u64);
+        let result_term = self.arena.mk_nat(result.unsigned_abs());
 
         let mul_name = self.symbols.intern("mul");
-        let proof = self.create_equality_proof(
-            self.arena.mk_app(
-                self.arena.mk_app(
-                    self.arena.mk_const(mul_name, vec![]),
-                    a_term
-                ),
-                b_term
-            ),
-            result_term
-        );
+        let mul = self.arena.mk_const(mul_name, vec![]);
+        let product = self.arena.mk_app_spine(mul, &[a_term, b_term]);
+        let proof = self.create_equality_proof(product, result_term);
 
         VerifiedResult {
             value: result,
@@ -113,27 +96,18 @@ impl VerifiedCalculator {
         let neq_name = self.symbols.intern("ne");
         let div_name = self.symbols.intern("div");
 
-        let b_nonzero_proof = self.arena.mk_app(
-            self.arena.mk_app(
-                self.arena.mk_const(neq_name, vec![]),
-                b_term
-            ),
-            zero
-        );
+        let neq = self.arena.mk_const(neq_name, vec![]);
+        let b_nonzero_proof = self.arena.mk_app_spine(neq, &[b_term, zero]);
 
         let a_term = self.arena.mk_nat(a as u64);
         let result_term = self.arena.mk_nat(result as u64);
 
-        let division = self.arena.mk_app(
-            self.arena.mk_app(
-                self.arena.mk_const(div_name, vec![]),
-                a_term
-            ),
-            b_term
-        );
+        let div = self.arena.mk_const(div_name, vec![]);
+        let division = self.arena.mk_app_spine(div, &[a_term, b_term]);
 
         // Combine proofs: b ≠ 0 ∧ div(a,b) = result
-        let proof = self.arena.mk_app(b_nonzero_proof, division);
+        let quotient_eq = self.create_equality_proof(division, result_term);
+        let proof = self.arena.mk_app(b_nonzero_proof, quotient_eq);
 
         Ok(VerifiedResult {
             value: result,
@@ -149,16 +123,8 @@ impl VerifiedCalculator {
         let type_term = self.arena.mk_sort(level_zero);
 
         // eq : ∀ (α : Type), α → α → Prop
-        self.arena.mk_app(
-            self.arena.mk_app(
-                self.arena.mk_app(
-                    self.arena.mk_const(eq_name, vec![]),
-                    type_term
-                ),
-                lhs
-            ),
-            rhs
-        )
+        let eq = self.arena.mk_const(eq_name, vec![]);
+        self.arena.mk_app_spine(eq, &[type_term, lhs, rhs])
     }
 }
 
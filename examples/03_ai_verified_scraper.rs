@@ -13,8 +13,9 @@
 //!
 //! Run: cargo run --example 03_ai_verified_scraper
 
+#![allow(dead_code)]
+
 use lean_agentic::{Arena, Environment, SymbolTable};
-use std::collections::HashMap;
 
 /// Web scraper with formal safety guarantees
 struct VerifiedScraper {
@@ -117,15 +118,16 @@ impl VerifiedScraper {
                 format!("domain_allowed({})", url),
                 format!("cost_optimal({}, ${:.4})", lane, cost),
             ],
-            proof_hash: format!("{:x}", self.arena.mk_var(0).as_u32()),
+            proof_hash: format!("{:x}", self.arena.mk_var(0).raw()),
         };
 
+        let tokens_used = extracted_data.len() / 4;
         Ok(VerifiedScrapingResult {
             data: extracted_data,
             metadata: ScrapingMetadata {
                 url: url.to_string(),
                 timestamp: 1729872000,
-                tokens_used: extracted_data.len() / 4,
+                tokens_used,
                 cost_usd: cost,
                 lane_used: lane.to_string(),
             },
@@ -143,7 +145,7 @@ impl VerifiedScraper {
     }
 
     fn verify_no_pii(&self, data: &str) -> Result<String, String> {
-        for (i, pattern) in self.safety_policy.forbidden_patterns.iter().enumerate() {
+        for i in 0..self.safety_policy.forbidden_patterns.len() {
             // In production: use regex
             if data.contains("123-45-6789") || data.contains("user@email.com") {
                 return Err(format!("PII detected matching pattern {}", i));
@@ -165,7 +167,7 @@ impl VerifiedScraper {
         }
     }
 
-    fn select_optimal_lane(&self, data_size: usize) -> (&str, f64) {
+    fn select_optimal_lane(&self, data_size: usize) -> (&'static str, f64) {
         // Multi-lane routing: local ONNX vs cloud APIs
         if data_size < 1000 {
             ("local_onnx", 0.0)  // Free for small tasks
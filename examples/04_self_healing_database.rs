@@ -13,13 +13,22 @@
 //!
 //! Run: cargo run --example 04_self_healing_database
 
+#![allow(dead_code)]
+
 use lean_agentic::{Arena, Environment, SymbolTable};
-use runtime::{AgentId, Message, capabilities::Iso};
 use std::collections::HashMap;
-use std::time::Duration;
+
+/// Identifies a database node agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct AgentId(u64);
+
+impl AgentId {
+    fn from_u64(id: u64) -> Self {
+        AgentId(id)
+    }
+}
 
 /// Database node with self-healing capabilities
-#[derive(Debug)]
 struct DatabaseNode {
     id: AgentId,
     arena: Arena,
@@ -130,7 +139,7 @@ impl SelfHealingDatabase {
                 "quorum_write(key={}, nodes={}, quorum={})",
                 key, write_count, self.quorum_size
             ),
-            proof_term_id: proof_term.as_u32(),
+            proof_term_id: proof_term.raw(),
         };
 
         println!("  ✅ Quorum write complete: {} nodes", write_count);
@@ -235,7 +244,7 @@ impl SelfHealingDatabase {
                 "recovery_preserves_consistency(node={}, source={})",
                 failed_idx, healthy_peer
             ),
-            proof_term_id: proof_term.as_u32(),
+            proof_term_id: proof_term.raw(),
         };
 
         println!("  ✅ Recovery strategy: Replicate from node {}", healthy_peer);
@@ -253,7 +262,7 @@ impl SelfHealingDatabase {
     fn execute_recovery(&mut self, action: &RecoveryAction) -> Result<(), String> {
         match &action.strategy {
             RecoveryStep::ReplicateFromPeer { peer_id } => {
-                let peer_idx = peer_id.as_u64() as usize;
+                let peer_idx = peer_id.0 as usize;
 
                 // Copy data from healthy peer
                 let peer_data = self.nodes[peer_idx].data.clone();
@@ -319,12 +328,12 @@ fn main() {
     println!("────────────────────────────────────────────");
 
     match db.verified_write("user:123".to_string(), "Alice".to_string()) {
-        Ok(proof) => println!("  Write succeeded with proof!\n"),
+        Ok(_proof) => println!("  Write succeeded with proof!\n"),
         Err(e) => println!("  ❌ Write failed: {}\n", e),
     }
 
     match db.verified_read("user:123") {
-        Ok((value, proof)) => {
+        Ok((value, _proof)) => {
             println!("  Read value: '{}'\n", value);
         }
         Err(e) => println!("  ❌ Read failed: {}\n", e),
@@ -8,9 +8,7 @@
 //! - Low-latency reasoning (<10ms P99)
 //!
 //! Research Citations:
-//! - "Interactive Theorem Proving and Program Development" (Bertot & Cast
-
-éran, 2004)
+//! - "Interactive Theorem Proving and Program Development" (Bertot & Castéran, 2004)
 //! - "Linear Temporal Logic" (Pnueli, 1977)
 //! - "Sublinear Algorithms" (Rubinfeld & Shapira, 2011)
 //! - "Meta Large Language Model Compiler" (Meta AI, 2024)
@@ -19,11 +17,15 @@
 //!
 //! Build for WASM: wasm-pack build --example 05_browser_theorem_prover
 
+#![allow(dead_code)]
+
 use lean_agentic::{Arena, Environment, SymbolTable, TermId};
 use lean_agentic::level::LevelArena;
-use lean_agentic::term::{Binder, BinderInfo};
-use std::collections::{HashMap, VecDeque};
-use std::time::Instant;
+use lean_agentic::term::{Binder, BinderInfo, Literal};
+use lean_agentic::{fold_term, TermKind};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
 
 /// Temporal operators for Linear Temporal Logic (LTL)
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -63,10 +65,41 @@ struct ProofState {
 #[derive(Debug, Clone)]
 struct ProofStep {
     tactic: String,
+    /// Goal the tactic was applied to
+    goal_before: TermId,
+    /// Rendered form of `goal_before`
+    rendered_before: String,
+    /// Goals left after the tactic; empty if it closed the goal
+    goals_after: Vec<TermId>,
+    /// Rendered forms of `goals_after`
     resulting_goals: Vec<String>,
     time_ns: u64,
 }
 
+/// Bounds on proof search
+#[derive(Debug, Clone)]
+struct ProofSearchConfig {
+    /// Most tactics in one proof
+    max_depth: usize,
+    /// Most tactic applications tried in total
+    max_branches: usize,
+    /// Wall-clock budget for one search, if any
+    ///
+    /// The depth and branch limits bound the search on their own; tests
+    /// rely on those alone so results do not depend on machine speed.
+    timeout: Option<Duration>,
+}
+
+impl Default for ProofSearchConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_branches: 64,
+            timeout: Some(Duration::from_millis(10)), // P99 latency target
+        }
+    }
+}
+
 /// AI-assisted theorem prover
 struct BrowserTheoremProver {
     arena: Arena,
@@ -74,18 +107,73 @@ struct BrowserTheoremProver {
     symbols: SymbolTable,
     levels: LevelArena,
 
-    /// Proof cache for sub-linear lookup
-    proof_cache: HashMap<u32, CachedProof>,
+    /// Proof cache for sub-linear lookup, keyed by the hash-consed goal
+    proof_cache: HashMap<TermId, CachedProof>,
 
     /// AI suggestions from LLM compiler
     ai_suggestions: VecDeque<String>,
+
+    /// Bounds on proof search
+    search_config: ProofSearchConfig,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 struct CachedProof {
-    term_id: u32,
+    goal: TermId,
     proof_term: TermId,
     tactic_sequence: Vec<String>,
+    features: GoalFeatures,
+}
+
+/// Minimum multiset overlap for two goals to count as similar
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Structural fingerprint of a goal for similarity lookup
+///
+/// Literal values and variable indices are collapsed, so `2 + 2 = 4` and
+/// `3 + 5 = 8` have the same fingerprint and can share a proof skeleton.
+#[derive(Debug, Clone, PartialEq)]
+struct GoalFeatures {
+    /// Name of the head constant of the application spine
+    head: Option<String>,
+    /// Height of the term tree
+    depth: usize,
+    /// How often each node label occurs
+    labels: BTreeMap<String, usize>,
+}
+
+impl GoalFeatures {
+    /// Overlap of the label multisets, in `[0, 1]`
+    fn overlap(&self, other: &GoalFeatures) -> f64 {
+        let (mut shared, mut total) = (0, 0);
+        for label in self.labels.keys().chain(other.labels.keys()) {
+            let a = self.labels.get(label).copied().unwrap_or(0);
+            let b = other.labels.get(label).copied().unwrap_or(0);
+            shared += a.min(b);
+            total += a.max(b);
+        }
+        if total == 0 {
+            1.0
+        } else {
+            shared as f64 / total as f64
+        }
+    }
+
+    /// Same head, depth within one, and mostly the same subterms
+    fn is_similar(&self, other: &GoalFeatures) -> bool {
+        self.head == other.head
+            && self.depth.abs_diff(other.depth) <= 1
+            && self.overlap(other) >= SIMILARITY_THRESHOLD
+    }
+}
+
+/// Result of looking a goal up in the proof cache
+#[derive(Debug, Clone, PartialEq)]
+enum CacheLookup {
+    /// The same goal was proved before
+    Exact(CachedProof),
+    /// A structurally similar goal was proved with these tactics
+    Similar(Vec<String>),
 }
 
 impl BrowserTheoremProver {
@@ -97,9 +185,15 @@ impl BrowserTheoremProver {
             levels: LevelArena::new(),
             proof_cache: HashMap::new(),
             ai_suggestions: VecDeque::new(),
+            search_config: ProofSearchConfig::default(),
         }
     }
 
+    fn with_search_config(mut self, config: ProofSearchConfig) -> Self {
+        self.search_config = config;
+        self
+    }
+
     /// Prove a theorem with AI assistance and temporal reasoning
     fn prove_with_ai(
         &mut self,
@@ -116,20 +210,29 @@ impl BrowserTheoremProver {
         println!("  ✅ Parsed to term: {:?}", goal_term);
 
         // Step 2: Check cache (sub-linear lookup via hash)
-        if let Some(cached) = self.proof_cache.get(&goal_term.as_u32()) {
-            let elapsed = start.elapsed();
-            println!("  ⚡ Cache hit! Proof found in {:.2}µs", elapsed.as_micros());
-            return Ok(ProofResult {
-                proof_term: cached.proof_term,
-                tactics_used: cached.tactic_sequence.clone(),
-                latency_ns: elapsed.as_nanos() as u64,
-                cache_hit: true,
-                ai_assisted: false,
-            });
+        let mut skeleton = Vec::new();
+        match self.sublinear_search(goal_term) {
+            Some(CacheLookup::Exact(cached)) => {
+                let elapsed = start.elapsed();
+                println!("  ⚡ Cache hit! Proof found in {:.2}µs", elapsed.as_micros());
+                return Ok(ProofResult {
+                    proof_term: cached.proof_term,
+                    tactics_used: cached.tactic_sequence,
+                    latency_ns: elapsed.as_nanos() as u64,
+                    cache_hit: true,
+                    ai_assisted: false,
+                });
+            }
+            Some(CacheLookup::Similar(tactics)) => {
+                println!("  🔁 Similar goal cached, trying its {} tactics first", tactics.len());
+                skeleton = tactics;
+            }
+            None => {}
         }
 
-        // Step 3: Generate AI suggestions
-        let suggestions = self.generate_ai_tactics(theorem);
+        // Step 3: Generate AI suggestions, after any reused skeleton
+        let mut suggestions = skeleton;
+        suggestions.extend(self.generate_ai_tactics(theorem));
         println!("  🤖 AI suggested {} tactics", suggestions.len());
 
         // Step 4: Attempt proof with temporal reasoning
@@ -149,14 +252,16 @@ impl BrowserTheoremProver {
         let elapsed = start.elapsed();
 
         // Step 6: Cache the proof (sub-linear future lookups)
+        let features = self.goal_features(goal_term);
         self.proof_cache.insert(
-            goal_term.as_u32(),
+            goal_term,
             CachedProof {
-                term_id: goal_term.as_u32(),
+                goal: goal_term,
                 proof_term: proof,
                 tactic_sequence: proof_state.proof_steps.iter()
                     .map(|s| s.tactic.clone())
                     .collect(),
+                features,
             },
         );
 
@@ -180,21 +285,11 @@ impl BrowserTheoremProver {
                 let add = self.symbols.intern("add");
                 let eq = self.symbols.intern("eq");
 
-                let sum = self.arena.mk_app(
-                    self.arena.mk_app(
-                        self.arena.mk_const(add, vec![]),
-                        two
-                    ),
-                    two
-                );
-
-                Ok(self.arena.mk_app(
-                    self.arena.mk_app(
-                        self.arena.mk_const(eq, vec![]),
-                        sum
-                    ),
-                    four
-                ))
+                let add = self.arena.mk_const(add, vec![]);
+                let sum = self.arena.mk_app_spine(add, &[two, two]);
+
+                let eq = self.arena.mk_const(eq, vec![]);
+                Ok(self.arena.mk_app_spine(eq, &[sum, four]))
             }
             "∀x, x + 0 = x" => {
                 let x = self.symbols.intern("x");
@@ -202,13 +297,8 @@ impl BrowserTheoremProver {
                 let add = self.symbols.intern("add");
 
                 let var_x = self.arena.mk_var(0);
-                let sum = self.arena.mk_app(
-                    self.arena.mk_app(
-                        self.arena.mk_const(add, vec![]),
-                        var_x
-                    ),
-                    zero
-                );
+                let add = self.arena.mk_const(add, vec![]);
+                let sum = self.arena.mk_app_spine(add, &[var_x, zero]);
 
                 // ∀ binder
                 let level_zero = self.levels.zero();
@@ -222,13 +312,8 @@ impl BrowserTheoremProver {
                 };
 
                 let eq = self.symbols.intern("eq");
-                let body = self.arena.mk_app(
-                    self.arena.mk_app(
-                        self.arena.mk_const(eq, vec![]),
-                        sum
-                    ),
-                    var_x
-                );
+                let eq = self.arena.mk_const(eq, vec![]);
+                let body = self.arena.mk_app_spine(eq, &[sum, var_x]);
 
                 Ok(self.arena.mk_pi(binder, body))
             }
@@ -240,7 +325,7 @@ impl BrowserTheoremProver {
         }
     }
 
-    fn generate_ai_tactics(&mut self, theorem: &str) -> Vec<String> {
+    fn generate_ai_tactics(&mut self, _theorem: &str) -> Vec<String> {
         // Simulate LLM compiler suggestions
         // In production: call Meta LLM Compiler API
         vec![
@@ -252,40 +337,200 @@ impl BrowserTheoremProver {
         ]
     }
 
+    /// Best-first search for a tactic sequence that closes the goal
+    ///
+    /// Open goals are expanded shortest-proof-first, then smallest goal
+    /// first, trying each suggested tactic in turn. Search stops with an
+    /// error when every sequence within `max_depth` fails, or when
+    /// `max_branches` or `timeout` is hit first; it never reports a proof
+    /// it did not find. On success `state.proof_steps` holds the proof.
     fn search_proof(
         &mut self,
         state: &mut ProofState,
         tactics: Vec<String>,
     ) -> Result<TermId, String> {
-        // Simulate proof search with AI-guided tactics
-        for tactic in tactics {
-            let step_start = Instant::now();
+        let config = self.search_config.clone();
+        let start = Instant::now();
+
+        let mut seen = HashSet::new();
+        let mut tactics_seen = HashSet::new();
+        let tactics: Vec<String> = tactics.into_iter().filter(|t| tactics_seen.insert(t.clone())).collect();
+
+        // Nodes are ordered by (proof length, goal size, insertion order)
+        let mut frontier = BinaryHeap::new();
+        let mut paths: Vec<Vec<ProofStep>> = vec![Vec::new()];
+        frontier.push(Reverse((0, self.depth(state.goal), 0, state.goal)));
+        seen.insert(state.goal);
 
-            // Simulate tactic application
-            let success = self.apply_tactic(&tactic, state)?;
+        let mut branches = 0;
+        let mut depth_limited = false;
 
-            if success {
-                state.proof_steps.push(ProofStep {
+        while let Some(Reverse((depth, _, node, goal))) = frontier.pop() {
+            if depth >= config.max_depth {
+                depth_limited = true;
+                continue;
+            }
+
+            for tactic in &tactics {
+                if let Some(timeout) = config.timeout.filter(|&t| start.elapsed() > t) {
+                    return Err(format!(
+                        "Proof search timed out after {:?} ({} branches tried)",
+                        timeout, branches
+                    ));
+                }
+                if branches >= config.max_branches {
+                    return Err(format!(
+                        "Proof search hit the branch limit ({}) without closing the goal",
+                        config.max_branches
+                    ));
+                }
+                branches += 1;
+
+                let step_start = Instant::now();
+                let Some(goals_after) = self.apply_tactic(tactic, goal) else {
+                    continue;
+                };
+
+                let mut path = paths[node].clone();
+                path.push(ProofStep {
                     tactic: tactic.clone(),
-                    resulting_goals: vec![],
+                    goal_before: goal,
+                    rendered_before: self.render(goal),
+                    resulting_goals: goals_after.iter().map(|&g| self.render(g)).collect(),
+                    goals_after: goals_after.clone(),
                     time_ns: step_start.elapsed().as_nanos() as u64,
                 });
 
-                // Goal proved!
-                if state.proof_steps.len() >= 2 {
-                    return Ok(state.goal);
+                match goals_after.first() {
+                    None => {
+                        // Goal proved!
+                        state.proof_steps = path;
+                        return Ok(state.goal);
+                    }
+                    Some(&next) if seen.insert(next) => {
+                        paths.push(path);
+                        frontier.push(Reverse((depth + 1, self.depth(next), paths.len() - 1, next)));
+                    }
+                    Some(_) => {} // Already explored
                 }
             }
         }
 
-        Ok(state.goal)
+        if depth_limited {
+            Err(format!(
+                "Proof search hit the depth limit ({}) without closing the goal",
+                config.max_depth
+            ))
+        } else {
+            Err("Proof search exhausted: no tactic sequence closes the goal".to_string())
+        }
     }
 
-    fn apply_tactic(&self, tactic: &str, _state: &ProofState) -> Result<bool, String> {
-        // Simulate tactic execution
+    /// Apply a tactic to a goal
+    ///
+    /// Returns the goals left afterwards (empty when the tactic closes the
+    /// goal), or `None` if the tactic does not apply.
+    fn apply_tactic(&mut self, tactic: &str, goal: TermId) -> Option<Vec<TermId>> {
         match tactic {
-            "intro" | "induction" | "rewrite" | "reflexivity" => Ok(true),
-            _ => Ok(false),
+            // ∀x, P x  ~>  P x with x free
+            "intro" => match self.arena.kind(goal)? {
+                TermKind::Pi(_, body) => Some(vec![*body]),
+                _ => None,
+            },
+
+            // Compute literal arithmetic anywhere in the goal
+            "rewrite" => {
+                let rewritten = self.eval_arith(goal);
+                (rewritten != goal).then(|| vec![rewritten])
+            }
+
+            // a = a, by hash-consing a single ID comparison
+            "reflexivity" => {
+                let (lhs, rhs) = self.as_binop(goal, "eq")?;
+                (lhs == rhs).then(Vec::new)
+            }
+
+            // a + 0 = a
+            "apply add_zero_right" => {
+                let (lhs, rhs) = self.as_binop(goal, "eq")?;
+                let (a, zero) = self.as_binop(lhs, "add")?;
+                let is_zero = matches!(self.arena.kind(zero), Some(TermKind::Lit(Literal::Nat(0))));
+                (is_zero && a == rhs).then(Vec::new)
+            }
+
+            // Induction is not modelled; leave it to the other tactics
+            _ => None,
+        }
+    }
+
+    /// Match `name a b`, returning `(a, b)`
+    fn as_binop(&self, term: TermId, name: &str) -> Option<(TermId, TermId)> {
+        let TermKind::App(partial, rhs) = self.arena.kind(term)? else {
+            return None;
+        };
+        let TermKind::App(head, lhs) = self.arena.kind(*partial)? else {
+            return None;
+        };
+        match self.arena.kind(*head)? {
+            TermKind::Const(sym, _) if self.symbols.resolve(*sym).as_deref() == Some(name) => {
+                Some((*lhs, *rhs))
+            }
+            _ => None,
+        }
+    }
+
+    /// Evaluate `add` on Nat literals, bottom-up
+    fn eval_arith(&mut self, term: TermId) -> TermId {
+        let Some(TermKind::App(func, arg)) = self.arena.kind(term).cloned() else {
+            return term;
+        };
+        let new_func = self.eval_arith(func);
+        let new_arg = self.eval_arith(arg);
+        let rebuilt = if new_func == func && new_arg == arg {
+            term
+        } else {
+            self.arena.mk_app(new_func, new_arg)
+        };
+
+        if let Some((a, b)) = self.as_binop(rebuilt, "add") {
+            if let (Some(TermKind::Lit(Literal::Nat(a))), Some(TermKind::Lit(Literal::Nat(b)))) =
+                (self.arena.kind(a), self.arena.kind(b))
+            {
+                if let Some(sum) = a.checked_add(*b) {
+                    return self.arena.mk_nat(sum);
+                }
+            }
+        }
+        rebuilt
+    }
+
+    /// Render a goal for display, e.g. `∀ x, x + 0 = x`
+    fn render(&self, term: TermId) -> String {
+        let Some(kind) = self.arena.kind(term) else {
+            return format!("<{}>", term.raw());
+        };
+
+        if let Some((lhs, rhs)) = self.as_binop(term, "eq") {
+            return format!("{} = {}", self.render(lhs), self.render(rhs));
+        }
+        if let Some((lhs, rhs)) = self.as_binop(term, "add") {
+            return format!("{} + {}", self.render(lhs), self.render(rhs));
+        }
+
+        match kind {
+            TermKind::Const(sym, _) => self
+                .symbols
+                .resolve(*sym)
+                .map(|name| name.to_string())
+                .unwrap_or_else(|| format!("c{}", sym.raw())),
+            TermKind::Var(idx) => format!("#{}", idx),
+            TermKind::Lit(Literal::Nat(n)) => n.to_string(),
+            TermKind::Pi(binder, body) => {
+                let name = self.symbols.resolve(binder.name).unwrap_or_else(|| "_".into());
+                format!("∀ {}, {}", name, self.render(*body))
+            }
+            TermKind::App(func, arg) => format!("({} {})", self.render(*func), self.render(*arg)),
+            other => other.to_string(),
         }
     }
 
@@ -313,33 +558,70 @@ impl BrowserTheoremProver {
     }
 
     /// Sub-linear proof search using learned heuristics
-    fn sublinear_search(&mut self, goal: TermId) -> Option<TermId> {
-        // O(log n) search through cached proofs
+    fn sublinear_search(&self, goal: TermId) -> Option<CacheLookup> {
         // Uses hash-consing for O(1) equality
 
         // Check exact match
-        if let Some(cached) = self.proof_cache.get(&goal.as_u32()) {
-            return Some(cached.proof_term);
+        if let Some(cached) = self.proof_cache.get(&goal) {
+            return Some(CacheLookup::Exact(cached.clone()));
         }
 
         // Check similar proofs (sublinear sampling)
-        let sample_rate = (self.proof_cache.len() as f64).sqrt() as usize;
-        for (i, (_, cached)) in self.proof_cache.iter().enumerate() {
-            if i % sample_rate == 0 {
-                // Sample every sqrt(n) entries
-                // In production: use learned similarity metrics
-                if self.is_similar(goal, cached.proof_term) {
-                    return Some(cached.proof_term);
-                }
-            }
+        let features = self.goal_features(goal);
+        let sample_rate = ((self.proof_cache.len() as f64).sqrt() as usize).max(1);
+        self.proof_cache
+            .values()
+            .step_by(sample_rate) // Sample every sqrt(n) entries
+            .find(|cached| cached.features.is_similar(&features))
+            .map(|cached| CacheLookup::Similar(cached.tactic_sequence.clone()))
+    }
+
+    /// Compute the structural fingerprint of a goal
+    fn goal_features(&self, goal: TermId) -> GoalFeatures {
+        let mut head = goal;
+        while let Some(TermKind::App(func, _)) = self.arena.kind(head) {
+            head = *func;
         }
+        let head = match self.arena.kind(head) {
+            Some(TermKind::Const(sym, _)) => self.symbols.resolve(*sym).map(|n| n.to_string()),
+            _ => None,
+        };
 
-        None
+        let labels = fold_term(&self.arena, goal, BTreeMap::new(), &mut |mut labels, _, kind, _| {
+            let label = match kind {
+                TermKind::Const(sym, _) => format!(
+                    "const:{}",
+                    self.symbols.resolve(*sym).as_deref().unwrap_or("?")
+                ),
+                TermKind::Sort(_) => "sort".to_string(),
+                TermKind::Var(_) => "var".to_string(),
+                TermKind::App(_, _) => "app".to_string(),
+                TermKind::Lam(_, _) => "lam".to_string(),
+                TermKind::Pi(_, _) => "pi".to_string(),
+                TermKind::Let(_, _, _) => "let".to_string(),
+                TermKind::MVar(_) => "mvar".to_string(),
+                TermKind::Lit(Literal::Nat(_)) => "nat".to_string(),
+                TermKind::Lit(Literal::String(_)) => "string".to_string(),
+            };
+            *labels.entry(label).or_insert(0) += 1;
+            labels
+        });
+
+        GoalFeatures {
+            head,
+            depth: self.depth(goal),
+            labels,
+        }
     }
 
-    fn is_similar(&self, _goal: TermId, _proof: TermId) -> bool {
-        // Simplified similarity check
-        false
+    fn depth(&self, term: TermId) -> usize {
+        let children = match self.arena.kind(term) {
+            Some(TermKind::App(f, a)) => vec![*f, *a],
+            Some(TermKind::Lam(b, body)) | Some(TermKind::Pi(b, body)) => vec![b.ty, *body],
+            Some(TermKind::Let(b, v, body)) => vec![b.ty, *v, *body],
+            _ => vec![],
+        };
+        1 + children.iter().map(|&c| self.depth(c)).max().unwrap_or(0)
     }
 }
 
@@ -457,3 +739,188 @@ fn main() {
     println!("   wasm-pack build --example 05_browser_theorem_prover");
     println!("   Open examples/wasm-demo/theorem_prover.html");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Search bounds without the wall-clock timeout
+    fn untimed_config() -> ProofSearchConfig {
+        ProofSearchConfig {
+            timeout: None,
+            ..ProofSearchConfig::default()
+        }
+    }
+
+    fn untimed_prover() -> BrowserTheoremProver {
+        BrowserTheoremProver::new().with_search_config(untimed_config())
+    }
+
+    #[test]
+    fn test_proof_steps_record_goal_evolution() {
+        let mut prover = untimed_prover();
+        let result = prover.prove_with_ai("2 + 2 = 4", vec![]).unwrap();
+        assert_eq!(result.tactics_used, vec!["rewrite", "reflexivity"]);
+
+        let goal = prover.parse_theorem("2 + 2 = 4").unwrap();
+        let mut state = ProofState {
+            goal,
+            hypotheses: vec![],
+            ltl_constraints: vec![],
+            proof_steps: vec![],
+        };
+        let tactics = prover.generate_ai_tactics("2 + 2 = 4");
+        prover.search_proof(&mut state, tactics).unwrap();
+
+        let [rewrite, refl] = &state.proof_steps[..] else {
+            panic!("expected two steps, got {:?}", state.proof_steps);
+        };
+
+        // rewrite turns `2 + 2 = 4` into `4 = 4`
+        assert_eq!(rewrite.goal_before, goal);
+        assert_eq!(rewrite.rendered_before, "2 + 2 = 4");
+        assert_eq!(rewrite.goals_after.len(), 1);
+        assert_ne!(rewrite.goals_after[0], goal);
+        assert_eq!(rewrite.resulting_goals, vec!["4 = 4"]);
+
+        // reflexivity picks up where rewrite left off and closes the goal
+        assert_eq!(refl.goal_before, rewrite.goals_after[0]);
+        assert_eq!(refl.rendered_before, "4 = 4");
+        assert!(refl.goals_after.is_empty());
+    }
+
+    #[test]
+    fn test_intro_exposes_body() {
+        let mut prover = untimed_prover();
+        let goal = prover.parse_theorem("∀x, x + 0 = x").unwrap();
+        let mut state = ProofState {
+            goal,
+            hypotheses: vec![],
+            ltl_constraints: vec![],
+            proof_steps: vec![],
+        };
+        let tactics = prover.generate_ai_tactics("∀x, x + 0 = x");
+        prover.search_proof(&mut state, tactics).unwrap();
+
+        let steps: Vec<_> = state.proof_steps.iter().map(|s| s.tactic.as_str()).collect();
+        assert_eq!(steps, vec!["intro", "apply add_zero_right"]);
+        assert_eq!(state.proof_steps[0].rendered_before, "∀ x, #0 + 0 = #0");
+        assert_eq!(state.proof_steps[0].resulting_goals, vec!["#0 + 0 = #0"]);
+        assert!(state.proof_steps[1].goals_after.is_empty());
+    }
+
+    /// Build `eq (add a b) c`
+    fn mk_sum_goal(prover: &mut BrowserTheoremProver, a: u64, b: u64, c: u64) -> TermId {
+        let add = prover.symbols.intern("add");
+        let eq = prover.symbols.intern("eq");
+        let (a, b, c) = (prover.arena.mk_nat(a), prover.arena.mk_nat(b), prover.arena.mk_nat(c));
+        let add = prover.arena.mk_const(add, vec![]);
+        let sum = prover.arena.mk_app_spine(add, &[a, b]);
+        let eq = prover.arena.mk_const(eq, vec![]);
+        prover.arena.mk_app_spine(eq, &[sum, c])
+    }
+
+    #[test]
+    fn test_similar_goal_reuses_cached_skeleton() {
+        let mut prover = untimed_prover();
+        prover.prove_with_ai("2 + 2 = 4", vec![]).unwrap();
+
+        let cached = mk_sum_goal(&mut prover, 2, 2, 4);
+        assert!(matches!(prover.sublinear_search(cached), Some(CacheLookup::Exact(_))));
+
+        // Different literals, same shape
+        let similar = mk_sum_goal(&mut prover, 3, 5, 8);
+        assert_eq!(
+            prover.sublinear_search(similar),
+            Some(CacheLookup::Similar(vec!["rewrite".to_string(), "reflexivity".to_string()]))
+        );
+
+        // Different head and shape
+        let unrelated = prover.parse_theorem("∀x, x + 0 = x").unwrap();
+        assert_eq!(prover.sublinear_search(unrelated), None);
+        let atom = prover.parse_theorem("P").unwrap();
+        assert_eq!(prover.sublinear_search(atom), None);
+    }
+
+    fn search(prover: &mut BrowserTheoremProver, theorem: &str) -> (Result<TermId, String>, ProofState) {
+        let goal = prover.parse_theorem(theorem).unwrap();
+        let mut state = ProofState {
+            goal,
+            hypotheses: vec![],
+            ltl_constraints: vec![],
+            proof_steps: vec![],
+        };
+        let tactics = prover.generate_ai_tactics(theorem);
+        let result = prover.search_proof(&mut state, tactics);
+        (result, state)
+    }
+
+    #[test]
+    fn test_search_reports_failure_instead_of_success() {
+        let mut prover = untimed_prover();
+
+        // No suggested tactic applies to an opaque proposition
+        let (result, state) = search(&mut prover, "P");
+        assert!(result.unwrap_err().contains("no tactic sequence closes the goal"));
+        assert!(state.proof_steps.is_empty());
+        assert!(prover.prove_with_ai("P", vec![]).is_err());
+        assert!(prover.proof_cache.is_empty());
+    }
+
+    #[test]
+    fn test_search_respects_bounds() {
+        let config = ProofSearchConfig {
+            max_depth: 1,
+            ..untimed_config()
+        };
+        let mut prover = BrowserTheoremProver::new().with_search_config(config);
+
+        // `2 + 2 = 4` needs two tactics
+        let (result, state) = search(&mut prover, "2 + 2 = 4");
+        assert!(result.unwrap_err().contains("depth limit (1)"));
+        assert!(state.proof_steps.is_empty());
+
+        let config = ProofSearchConfig {
+            max_branches: 3,
+            ..untimed_config()
+        };
+        let mut prover = BrowserTheoremProver::new().with_search_config(config);
+        let (result, _) = search(&mut prover, "2 + 2 = 4");
+        assert!(result.unwrap_err().contains("branch limit (3)"));
+
+        let config = ProofSearchConfig {
+            max_depth: 2,
+            ..untimed_config()
+        };
+        let mut prover = BrowserTheoremProver::new().with_search_config(config);
+        let (result, state) = search(&mut prover, "2 + 2 = 4");
+        assert!(result.is_ok());
+        assert!(state.proof_steps.len() <= 2);
+        assert!(state.proof_steps.last().unwrap().goals_after.is_empty());
+    }
+
+    #[test]
+    fn test_distinct_goals_have_distinct_cache_entries() {
+        let mut prover = untimed_prover();
+        prover.prove_with_ai("2 + 2 = 4", vec![]).unwrap();
+        prover.prove_with_ai("∀x, x + 0 = x", vec![]).unwrap();
+        assert_eq!(prover.proof_cache.len(), 2);
+
+        for (goal, cached) in &prover.proof_cache {
+            assert_eq!(cached.goal, *goal);
+        }
+
+        // A goal that was never proved is not an exact hit, even when a
+        // structurally identical one is cached
+        let other = mk_sum_goal(&mut prover, 1, 1, 2);
+        assert!(!prover.proof_cache.contains_key(&other));
+        assert!(!matches!(prover.sublinear_search(other), Some(CacheLookup::Exact(_))));
+
+        let sum_goal = prover.parse_theorem("2 + 2 = 4").unwrap();
+        let Some(CacheLookup::Exact(cached)) = prover.sublinear_search(sum_goal) else {
+            panic!("expected an exact hit");
+        };
+        assert_eq!(cached.goal, sum_goal);
+        assert_eq!(cached.tactic_sequence, vec!["rewrite", "reflexivity"]);
+    }
+}
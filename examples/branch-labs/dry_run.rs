@@ -0,0 +1,97 @@
+//! # Branch Labs: dry-run simulation for example agents
+//!
+//! Trial an action against a copy of an agent before committing it live.
+//! The agent is cloned, the action is applied to the clone, and the
+//! resulting proof plus the change in the agent's key metrics are returned.
+//! The real agent is never touched.
+//!
+//! Agents opt in by implementing [`Simulated`]. Examples include this file
+//! with `#[path = "../branch-labs/dry_run.rs"] mod dry_run;`.
+
+use std::collections::BTreeMap;
+
+/// An agent whose actions can be trialed on a clone
+pub trait Simulated: Clone {
+    /// Action the agent executes
+    type Action;
+    /// Proof returned for an executed action
+    type Proof;
+
+    /// Execute an action, mutating the agent
+    fn apply(&mut self, action: Self::Action) -> Result<Self::Proof, String>;
+
+    /// Named metrics describing the agent's state, e.g. balances or cash
+    fn metrics(&self) -> BTreeMap<String, f64>;
+}
+
+/// Change in one metric between the real agent and its simulated clone
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricChange {
+    pub before: f64,
+    pub after: f64,
+}
+
+impl MetricChange {
+    pub fn delta(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+/// Metrics that changed, keyed by name
+///
+/// Metrics present on only one side are compared against 0.
+pub type StateDelta = BTreeMap<String, MetricChange>;
+
+/// Result of a dry run
+#[derive(Debug, Clone)]
+pub struct DryRunOutcome<P> {
+    /// What `apply` returned on the clone
+    pub result: Result<P, String>,
+    /// Metrics that the action would change
+    pub delta: StateDelta,
+}
+
+impl<P> DryRunOutcome<P> {
+    pub fn succeeded(&self) -> bool {
+        self.result.is_ok()
+    }
+}
+
+/// Dry-run wrapper around an agent
+pub struct DryRun<'a, A: Simulated> {
+    agent: &'a A,
+}
+
+impl<'a, A: Simulated> DryRun<'a, A> {
+    pub fn new(agent: &'a A) -> Self {
+        Self { agent }
+    }
+
+    /// Apply `action` to a clone of the agent and report what would happen
+    pub fn run(&self, action: A::Action) -> DryRunOutcome<A::Proof> {
+        let before = self.agent.metrics();
+        let mut twin = self.agent.clone();
+        let result = twin.apply(action);
+        let after = twin.metrics();
+
+        DryRunOutcome {
+            result,
+            delta: diff(&before, &after),
+        }
+    }
+}
+
+/// Metrics whose values differ between two snapshots
+pub fn diff(before: &BTreeMap<String, f64>, after: &BTreeMap<String, f64>) -> StateDelta {
+    let mut delta = StateDelta::new();
+    for name in before.keys().chain(after.keys()) {
+        let change = MetricChange {
+            before: before.get(name).copied().unwrap_or(0.0),
+            after: after.get(name).copied().unwrap_or(0.0),
+        };
+        if change.before != change.after {
+            delta.insert(name.clone(), change);
+        }
+    }
+    delta
+}
@@ -5,8 +5,8 @@
 //! 2. OpenRouter-only
 //! 3. Multi-lane adaptive routing (our approach)
 
-use lean_agentic::multi_lane::{
-    LaneRouter, LaneRouterConfig, InferenceRequest, RequestPriority, Provider,
+use leanr::multi_lane::{
+    LaneRouter, LaneRouterConfig, InferenceRequest, RequestPriority,
 };
 use std::time::Duration;
 
@@ -18,9 +18,15 @@
 //! - Zero unauthorized calls
 //! - Cost variance under 5% vs prediction
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+#[path = "../branch-labs/dry_run.rs"]
+pub mod dry_run;
+
+use dry_run::Simulated;
+
 /// Financial capability types
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Capability {
@@ -51,6 +57,78 @@ pub struct TimeWindow {
     pub days: Vec<String>, // Mon, Tue, etc.
 }
 
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+impl TimeWindow {
+    /// Check whether a UTC instant falls inside the window
+    ///
+    /// Hours are `[start_hour, end_hour)`; a window with `start_hour >
+    /// end_hour` wraps past midnight. An empty `days` list allows every day.
+    pub fn contains(&self, unix_secs: u64) -> bool {
+        let (day, hour) = day_and_hour(unix_secs);
+
+        let day_ok = self.days.is_empty() || self.days.iter().any(|d| d == day);
+        let hour_ok = if self.start_hour <= self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        };
+
+        day_ok && hour_ok
+    }
+}
+
+/// Weekday name and hour of a UTC instant
+fn day_and_hour(unix_secs: u64) -> (&'static str, u8) {
+    let days = unix_secs / 86_400;
+    // 1970-01-01 was a Thursday
+    let day = WEEKDAYS[((days + 3) % 7) as usize];
+    let hour = ((unix_secs % 86_400) / 3_600) as u8;
+    (day, hour)
+}
+
+/// Source of the current time, injectable for tests
+pub trait Clock {
+    /// Seconds since the Unix epoch
+    fn now(&self) -> u64;
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+}
+
+/// Action rejected for falling outside the agent's time window
+#[derive(Debug, Clone)]
+pub struct TimeWindowViolation {
+    pub day: String,
+    pub hour: u8,
+    /// Proof of the rejected action, with `time_window_check` false
+    pub proof: FinanceProof,
+}
+
+/// Whether a capability was added to or removed from an agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapabilityChangeKind {
+    Granted,
+    Revoked,
+}
+
+/// Audit record of a runtime capability change
+#[derive(Debug, Clone)]
+pub struct CapabilityChange {
+    pub capability: Capability,
+    pub kind: CapabilityChangeKind,
+    pub timestamp: u64,
+}
+
 /// Financial action that requires authorization
 #[derive(Debug, Clone)]
 pub enum FinancialAction {
@@ -89,6 +167,7 @@ pub struct FinanceProof {
     pub agent_id: String,
     pub capability_check: bool,
     pub budget_check: bool,
+    pub time_window_check: bool,
     pub ledger_balanced: bool,
     pub timestamp: u64,
     pub witness: String,
@@ -96,7 +175,7 @@ pub struct FinanceProof {
 
 impl FinanceProof {
     pub fn verify(&self) -> bool {
-        self.capability_check && self.budget_check && self.ledger_balanced
+        self.capability_check && self.budget_check && self.time_window_check && self.ledger_balanced
     }
 
     pub fn to_receipt(&self) -> String {
@@ -106,6 +185,7 @@ impl FinanceProof {
              Agent: {}\n\
              Capability Valid: {}\n\
              Budget OK: {}\n\
+             Time Window OK: {}\n\
              Ledger Balanced: {}\n\
              Timestamp: {}\n\
              Witness: {}\n\
@@ -114,6 +194,7 @@ impl FinanceProof {
             self.agent_id,
             self.capability_check,
             self.budget_check,
+            self.time_window_check,
             self.ledger_balanced,
             self.timestamp,
             self.witness,
@@ -122,32 +203,63 @@ impl FinanceProof {
     }
 }
 
-/// Ledger with balance conservation
+/// One leg of a journal entry
+///
+/// Positive amounts credit the account, negative amounts debit it.
+#[derive(Debug, Clone)]
+pub struct Posting {
+    pub account: String,
+    pub currency: String,
+    pub amount: i64,
+}
+
+/// Ledger with per-currency balance conservation
+///
+/// Amounts in different currencies are never added together: each
+/// currency's balances must sum to zero on their own. FX rates are only
+/// used to report value in the base currency.
+#[derive(Clone)]
 pub struct Ledger {
     entries: Vec<LedgerEntry>,
-    balances: HashMap<String, i64>, // Account -> Balance
+    journals: Vec<Vec<Posting>>,
+    balances: HashMap<String, HashMap<String, i64>>, // Currency -> Account -> Balance
+    base_currency: String,
+    fx_rates: HashMap<String, f64>, // Currency -> base units per unit
 }
 
 impl Ledger {
     pub fn new() -> Self {
+        Self::with_base_currency("USD")
+    }
+
+    /// Create a ledger reporting value in `base_currency`
+    pub fn with_base_currency(base_currency: &str) -> Self {
         Self {
             entries: Vec::new(),
+            journals: Vec::new(),
             balances: HashMap::new(),
+            base_currency: base_currency.to_string(),
+            fx_rates: HashMap::new(),
         }
     }
 
     /// Add entry and verify conservation
     pub fn add_entry(&mut self, entry: LedgerEntry) -> Result<(), String> {
         // Debit decreases balance, credit increases
-        let debit_balance = self.balances.entry(entry.debit_account.clone()).or_insert(0);
-        *debit_balance -= entry.amount as i64;
-
-        let credit_balance = self.balances.entry(entry.credit_account.clone()).or_insert(0);
-        *credit_balance += entry.amount as i64;
+        self.post(&Posting {
+            account: entry.debit_account.clone(),
+            currency: entry.currency.clone(),
+            amount: -(entry.amount as i64),
+        });
+        self.post(&Posting {
+            account: entry.credit_account.clone(),
+            currency: entry.currency.clone(),
+            amount: entry.amount as i64,
+        });
 
         self.entries.push(entry);
 
-        // Verify total balance is conserved (sum should always be 0)
+        // Verify each currency is conserved (its balances sum to 0)
         if !self.verify_conservation() {
             return Err("Balance conservation violated".to_string());
         }
@@ -155,28 +267,121 @@ impl Ledger {
         Ok(())
     }
 
-    /// Verify that total balance is conserved
+    /// Add a multi-leg journal entry, e.g. an FX conversion
+    ///
+    /// The postings in each currency must sum to zero; otherwise the
+    /// journal is rejected without touching any balance. A USD to EUR
+    /// conversion therefore goes through an FX account that is credited
+    /// in USD and debited in EUR.
+    pub fn add_journal(&mut self, postings: Vec<Posting>) -> Result<(), String> {
+        let mut totals: HashMap<&str, i64> = HashMap::new();
+        for posting in &postings {
+            *totals.entry(posting.currency.as_str()).or_insert(0) += posting.amount;
+        }
+
+        let mut unbalanced: Vec<_> = totals
+            .into_iter()
+            .filter(|(_, total)| *total != 0)
+            .map(|(currency, total)| format!("{} {:+}", currency, total))
+            .collect();
+        if !unbalanced.is_empty() {
+            unbalanced.sort();
+            return Err(format!("Unbalanced currencies: {}", unbalanced.join(", ")));
+        }
+
+        for posting in &postings {
+            self.post(posting);
+        }
+        self.journals.push(postings);
+        Ok(())
+    }
+
+    fn post(&mut self, posting: &Posting) {
+        *self
+            .balances
+            .entry(posting.currency.clone())
+            .or_default()
+            .entry(posting.account.clone())
+            .or_insert(0) += posting.amount;
+    }
+
+    /// Verify that every currency's balance is conserved
     pub fn verify_conservation(&self) -> bool {
-        let total: i64 = self.balances.values().sum();
-        total == 0
+        self.unbalanced_currencies().is_empty()
     }
 
+    /// Currencies whose balances do not sum to zero, sorted
+    pub fn unbalanced_currencies(&self) -> Vec<String> {
+        let mut unbalanced: Vec<String> = self
+            .balances
+            .iter()
+            .filter(|(_, accounts)| accounts.values().sum::<i64>() != 0)
+            .map(|(currency, _)| currency.clone())
+            .collect();
+        unbalanced.sort();
+        unbalanced
+    }
+
+    /// Balance of an account in the base currency only
     pub fn get_balance(&self, account: &str) -> i64 {
-        *self.balances.get(account).unwrap_or(&0)
+        self.get_balance_in(account, &self.base_currency)
+    }
+
+    /// Balance of an account in one currency
+    pub fn get_balance_in(&self, account: &str, currency: &str) -> i64 {
+        self.balances
+            .get(currency)
+            .and_then(|accounts| accounts.get(account))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Set how many base-currency units one unit of `currency` is worth
+    pub fn set_fx_rate(&mut self, currency: &str, rate: f64) {
+        self.fx_rates.insert(currency.to_string(), rate);
+    }
+
+    /// Value of an account across all currencies, in the base currency
+    ///
+    /// Returns `None` if the account holds a currency with no FX rate.
+    pub fn account_value(&self, account: &str) -> Option<f64> {
+        let mut total = 0.0;
+        for (currency, accounts) in &self.balances {
+            let Some(&balance) = accounts.get(account) else {
+                continue;
+            };
+            let rate = if *currency == self.base_currency {
+                1.0
+            } else {
+                *self.fx_rates.get(currency)?
+            };
+            total += balance as f64 * rate;
+        }
+        Some(total)
     }
 
     pub fn total_entries(&self) -> usize {
-        self.entries.len()
+        self.entries.len() + self.journals.len()
+    }
+}
+
+impl Default for Ledger {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// Finance agent with capability checking
+#[derive(Clone)]
 pub struct FinanceAgent {
     pub agent_id: String,
     pub role: AgentRole,
     pub ledger: Ledger,
     pub budget_quota: u64,
     pub budget_used: u64,
+    capability_changes: Vec<CapabilityChange>,
+    time_window_violations: Vec<TimeWindowViolation>,
+    clock: Rc<dyn Clock>,
 }
 
 impl FinanceAgent {
@@ -187,9 +392,63 @@ impl FinanceAgent {
             ledger: Ledger::new(),
             budget_quota,
             budget_used: 0,
+            capability_changes: Vec::new(),
+            time_window_violations: Vec::new(),
+            clock: Rc::new(SystemClock),
         }
     }
 
+    /// Use a different clock for time-window checks and timestamps
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Rc::new(clock);
+        self
+    }
+
+    /// Actions rejected for falling outside the time window, oldest first
+    pub fn time_window_violations(&self) -> &[TimeWindowViolation] {
+        &self.time_window_violations
+    }
+
+    /// Grant a capability at runtime
+    ///
+    /// Returns `false` (and records nothing) if the agent already has it.
+    pub fn grant_capability(&mut self, cap: Capability) -> bool {
+        if self.role.capabilities.contains(&cap) {
+            return false;
+        }
+        self.role.capabilities.push(cap.clone());
+        self.record_capability_change(cap, CapabilityChangeKind::Granted);
+        true
+    }
+
+    /// Revoke a capability, e.g. after suspicious activity
+    ///
+    /// Takes effect for every later `execute`; actions already executed
+    /// stay in the ledger. Returns `false` if the agent did not have it.
+    pub fn revoke_capability(&mut self, cap: &Capability) -> bool {
+        let before = self.role.capabilities.len();
+        self.role.capabilities.retain(|c| c != cap);
+        if self.role.capabilities.len() == before {
+            return false;
+        }
+        self.record_capability_change(cap.clone(), CapabilityChangeKind::Revoked);
+        true
+    }
+
+    /// Audit trail of runtime grants and revocations, oldest first
+    pub fn capability_changes(&self) -> &[CapabilityChange] {
+        &self.capability_changes
+    }
+
+    fn record_capability_change(&mut self, capability: Capability, kind: CapabilityChangeKind) {
+        let timestamp = self.clock.now();
+        self.capability_changes.push(CapabilityChange {
+            capability,
+            kind,
+            timestamp,
+        });
+    }
+
     /// Check if agent has capability for action
     fn check_capability(&self, action: &FinancialAction) -> bool {
         match action {
@@ -226,13 +485,40 @@ impl FinanceAgent {
     pub fn execute(&mut self, action: FinancialAction) -> Result<FinanceProof, String> {
         let start = std::time::Instant::now();
 
+        let timestamp = self.clock.now();
+
         // Step 1: Check capability
         let capability_check = self.check_capability(&action);
         if !capability_check {
             return Err("Capability check failed".to_string());
         }
 
-        // Step 2: Extract amount and check budget
+        // Step 2: Check time window
+        let time_window_check = self
+            .role
+            .time_window
+            .as_ref()
+            .is_none_or(|window| window.contains(timestamp));
+        if !time_window_check {
+            let (day, hour) = day_and_hour(timestamp);
+            self.time_window_violations.push(TimeWindowViolation {
+                day: day.to_string(),
+                hour,
+                proof: FinanceProof {
+                    action: format!("{:?}", action),
+                    agent_id: self.agent_id.clone(),
+                    capability_check,
+                    budget_check: false,
+                    time_window_check,
+                    ledger_balanced: self.ledger.verify_conservation(),
+                    timestamp,
+                    witness: format!("outside-window-{}-{:02}h", day, hour),
+                },
+            });
+            return Err("Outside allowed time window".to_string());
+        }
+
+        // Step 3: Extract amount and check budget
         let amount = match &action {
             FinancialAction::Payment { amount, .. } => *amount,
             FinancialAction::ApproveInvoice { amount, .. } => *amount,
@@ -244,11 +530,11 @@ impl FinanceAgent {
             return Err("Budget exceeded".to_string());
         }
 
-        // Step 3: Create ledger entry
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        // Step 4: Create ledger entry
+        let currency = match &action {
+            FinancialAction::Payment { currency, .. } => currency.clone(),
+            _ => self.ledger.base_currency.clone(),
+        };
 
         let (debit, credit) = match &action {
             FinancialAction::Payment { from_account, to_account, .. } => {
@@ -270,23 +556,24 @@ impl FinanceAgent {
                 debit_account: debit,
                 credit_account: credit,
                 amount,
-                currency: "USD".to_string(),
+                currency,
                 proof_hash: format!("hash-{}", timestamp),
             };
 
             self.ledger.add_entry(entry)?;
         }
 
-        // Step 4: Update budget
+        // Step 5: Update budget
         self.budget_used += amount;
 
-        // Step 5: Generate proof
+        // Step 6: Generate proof
         let latency = start.elapsed();
         let proof = FinanceProof {
             action: format!("{:?}", action),
             agent_id: self.agent_id.clone(),
             capability_check,
             budget_check,
+            time_window_check,
             ledger_balanced: self.ledger.verify_conservation(),
             timestamp,
             witness: format!("latency-{}ms", latency.as_millis()),
@@ -307,6 +594,27 @@ impl FinanceAgent {
     }
 }
 
+impl Simulated for FinanceAgent {
+    type Action = FinancialAction;
+    type Proof = FinanceProof;
+
+    fn apply(&mut self, action: FinancialAction) -> Result<FinanceProof, String> {
+        self.execute(action)
+    }
+
+    fn metrics(&self) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        metrics.insert("budget_used".to_string(), self.budget_used as f64);
+        metrics.insert("ledger_entries".to_string(), self.ledger.total_entries() as f64);
+        for (currency, accounts) in &self.ledger.balances {
+            for (account, balance) in accounts {
+                metrics.insert(format!("balance:{}:{}", currency, account), *balance as f64);
+            }
+        }
+        metrics
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -476,4 +784,236 @@ mod tests {
         // Should meet p99 < 10ms target
         assert!(latency.as_millis() < 20, "Latency should be low");
     }
+
+    #[test]
+    fn test_grant_then_revoke_payment_capability() {
+        let role = AgentRole {
+            role_name: "Agent".to_string(),
+            capabilities: vec![Capability::ViewLedger],
+            time_window: None,
+        };
+
+        let mut agent = FinanceAgent::new("agent-007".to_string(), role, 50000);
+        let payment = |amount| FinancialAction::Payment {
+            from_account: "checking".to_string(),
+            to_account: "vendor".to_string(),
+            amount,
+            currency: "USD".to_string(),
+        };
+
+        // No payment capability yet
+        assert_eq!(agent.execute(payment(100)).unwrap_err(), "Capability check failed");
+
+        let cap = Capability::Payment { max_amount: 10000 };
+        assert!(agent.grant_capability(cap.clone()));
+        assert!(!agent.grant_capability(cap.clone()));
+        assert!(agent.execute(payment(1000)).unwrap().verify());
+        assert!(agent.execute(payment(2000)).unwrap().verify());
+
+        assert!(agent.revoke_capability(&cap));
+        assert!(!agent.revoke_capability(&cap));
+        assert_eq!(agent.execute(payment(100)).unwrap_err(), "Capability check failed");
+
+        // Payments made while the capability was held remain recorded
+        assert_eq!(agent.ledger.total_entries(), 2);
+        assert_eq!(agent.ledger.get_balance("vendor"), 3000);
+        assert_eq!(agent.budget_used, 3000);
+
+        let kinds: Vec<_> = agent.capability_changes().iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![CapabilityChangeKind::Granted, CapabilityChangeKind::Revoked]);
+        assert!(agent.capability_changes().iter().all(|c| c.capability == cap));
+    }
+
+    struct FixedClock(u64);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_time_window_enforced() {
+        // 2024-01-01 was a Monday
+        let monday = 1_704_067_200;
+        let hour = 3_600;
+
+        let role = AgentRole {
+            role_name: "BusinessHours".to_string(),
+            capabilities: vec![Capability::Payment { max_amount: 10000 }],
+            time_window: Some(TimeWindow {
+                start_hour: 9,
+                end_hour: 17,
+                days: vec!["Mon".to_string(), "Tue".to_string()],
+            }),
+        };
+        let payment = || FinancialAction::Payment {
+            from_account: "checking".to_string(),
+            to_account: "vendor".to_string(),
+            amount: 1000,
+            currency: "USD".to_string(),
+        };
+
+        // Monday 10:00 is inside the window
+        let mut agent = FinanceAgent::new("agent-008".to_string(), role.clone(), 50000)
+            .with_clock(FixedClock(monday + 10 * hour));
+        let proof = agent.execute(payment()).unwrap();
+        assert!(proof.time_window_check && proof.verify());
+
+        // Monday 20:00 is after hours
+        let mut agent = FinanceAgent::new("agent-008".to_string(), role.clone(), 50000)
+            .with_clock(FixedClock(monday + 20 * hour));
+        assert_eq!(agent.execute(payment()).unwrap_err(), "Outside allowed time window");
+
+        // Wednesday 10:00 is the right hour on the wrong day
+        let mut agent = FinanceAgent::new("agent-008".to_string(), role, 50000)
+            .with_clock(FixedClock(monday + 2 * 24 * hour + 10 * hour));
+        assert!(agent.execute(payment()).is_err());
+
+        let violation = &agent.time_window_violations()[0];
+        assert_eq!((violation.day.as_str(), violation.hour), ("Wed", 10));
+        assert!(!violation.proof.time_window_check);
+        assert!(!violation.proof.verify());
+        assert_eq!(agent.ledger.total_entries(), 0);
+        assert_eq!(agent.budget_used, 0);
+    }
+
+    #[test]
+    fn test_time_window_wraps_midnight() {
+        let window = TimeWindow {
+            start_hour: 22,
+            end_hour: 6,
+            days: vec![],
+        };
+        let monday = 1_704_067_200;
+        assert!(window.contains(monday + 23 * 3_600));
+        assert!(window.contains(monday + 2 * 3_600));
+        assert!(!window.contains(monday + 12 * 3_600));
+    }
+
+    fn entry(id: &str, debit: &str, credit: &str, amount: u64, currency: &str) -> LedgerEntry {
+        LedgerEntry {
+            entry_id: id.to_string(),
+            timestamp: 1000,
+            debit_account: debit.to_string(),
+            credit_account: credit.to_string(),
+            amount,
+            currency: currency.to_string(),
+            proof_hash: format!("hash-{}", id),
+        }
+    }
+
+    fn posting(account: &str, currency: &str, amount: i64) -> Posting {
+        Posting {
+            account: account.to_string(),
+            currency: currency.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_multi_currency_conservation() {
+        let mut ledger = Ledger::new();
+        ledger.add_entry(entry("e1", "checking", "vendor", 1000, "USD")).unwrap();
+        ledger.add_entry(entry("e2", "checking-eu", "supplier", 500, "EUR")).unwrap();
+        assert!(ledger.verify_conservation());
+
+        // Convert 110 USD into 100 EUR through the FX desk
+        ledger
+            .add_journal(vec![
+                posting("checking", "USD", -110),
+                posting("fx", "USD", 110),
+                posting("fx", "EUR", -100),
+                posting("checking-eu", "EUR", 100),
+            ])
+            .unwrap();
+        assert!(ledger.verify_conservation());
+        assert_eq!(ledger.get_balance("checking"), -1110);
+        assert_eq!(ledger.get_balance_in("checking-eu", "EUR"), -400);
+
+        // Value in the base currency needs a rate for every currency held
+        assert_eq!(ledger.account_value("supplier"), None);
+        ledger.set_fx_rate("EUR", 1.1);
+        assert!((ledger.account_value("fx").unwrap() - 0.0).abs() < 1e-9);
+        assert!((ledger.account_value("supplier").unwrap() - 550.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_cross_currency_imbalance_detected() {
+        let mut ledger = Ledger::new();
+
+        // Debiting USD and crediting EUR nets to zero only if the
+        // currencies are (wrongly) added together
+        let err = ledger
+            .add_journal(vec![
+                posting("checking", "USD", -100),
+                posting("checking-eu", "EUR", 100),
+            ])
+            .unwrap_err();
+        assert_eq!(err, "Unbalanced currencies: EUR +100, USD -100");
+
+        // Nothing was applied
+        assert!(ledger.verify_conservation());
+        assert_eq!(ledger.get_balance("checking"), 0);
+        assert_eq!(ledger.total_entries(), 0);
+    }
+
+    #[test]
+    fn test_payment_uses_action_currency() {
+        let role = AgentRole {
+            role_name: "Agent".to_string(),
+            capabilities: vec![Capability::Payment { max_amount: 10000 }],
+            time_window: None,
+        };
+        let mut agent = FinanceAgent::new("agent-009".to_string(), role, 50000);
+
+        let action = FinancialAction::Payment {
+            from_account: "checking-eu".to_string(),
+            to_account: "supplier".to_string(),
+            amount: 700,
+            currency: "EUR".to_string(),
+        };
+        assert!(agent.execute(action).unwrap().ledger_balanced);
+
+        assert_eq!(agent.ledger.get_balance_in("supplier", "EUR"), 700);
+        assert_eq!(agent.ledger.get_balance("supplier"), 0);
+    }
+
+    #[test]
+    fn test_dry_run_payment_leaves_agent_unchanged() {
+        use dry_run::DryRun;
+
+        let role = AgentRole {
+            role_name: "Agent".to_string(),
+            capabilities: vec![Capability::Payment { max_amount: 10000 }],
+            time_window: None,
+        };
+        let agent = FinanceAgent::new("agent-010".to_string(), role, 50000);
+
+        let outcome = DryRun::new(&agent).run(FinancialAction::Payment {
+            from_account: "checking".to_string(),
+            to_account: "vendor".to_string(),
+            amount: 2500,
+            currency: "USD".to_string(),
+        });
+
+        let proof = outcome.result.as_ref().unwrap();
+        assert!(proof.verify());
+        assert_eq!(outcome.delta["budget_used"].delta(), 2500.0);
+        assert_eq!(outcome.delta["balance:USD:checking"].after, -2500.0);
+        assert_eq!(outcome.delta["balance:USD:vendor"].after, 2500.0);
+
+        // The real agent has not moved any money
+        assert_eq!(agent.budget_used, 0);
+        assert_eq!(agent.ledger.total_entries(), 0);
+        assert_eq!(agent.ledger.get_balance("vendor"), 0);
+
+        // A rejected action predicts no change
+        let outcome = DryRun::new(&agent).run(FinancialAction::AddVendor {
+            vendor_id: "v1".to_string(),
+            name: "Vendor".to_string(),
+        });
+        assert!(!outcome.succeeded());
+        assert!(outcome.delta.is_empty());
+    }
 }
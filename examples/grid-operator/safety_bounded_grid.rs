@@ -21,6 +21,12 @@
 
 use std::collections::HashMap;
 
+/// Default spacing between sampled points on a robot's path, in metres
+pub const DEFAULT_PATH_STEP_M: f64 = 0.1;
+
+/// `prev_hash` of the first proof in a chain
+pub const GENESIS_HASH: &str = "0000000000000000";
+
 /// Safety envelope defining allowed operating ranges
 #[derive(Debug, Clone)]
 pub struct SafetyEnvelope {
@@ -69,7 +75,7 @@ pub enum RobotStatus {
 }
 
 /// Control command for robot
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ControlCommand {
     pub robot_id: String,
     pub target_x: f64,
@@ -80,7 +86,7 @@ pub struct ControlCommand {
 }
 
 /// Safety proof for control command
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SafetyProof {
     pub command: ControlCommand,
     pub envelope_satisfied: bool,
@@ -89,6 +95,10 @@ pub struct SafetyProof {
     pub failsafe_ready: bool,
     pub simulated_outcome: String,
     pub timestamp: u64,
+    /// Hash of the previous proof in the command log
+    pub prev_hash: String,
+    /// Hash of this proof's contents, including `prev_hash`
+    pub hash: String,
 }
 
 impl SafetyProof {
@@ -96,6 +106,50 @@ impl SafetyProof {
         self.envelope_satisfied && self.humans_excluded && self.torque_ok && self.failsafe_ready
     }
 
+    /// Check that two proofs reach the same verdict for the same command
+    ///
+    /// The generation timestamp is ignored, since a replayed proof is
+    /// necessarily produced later than the original.
+    pub fn same_outcome(&self, other: &SafetyProof) -> bool {
+        self.command == other.command
+            && self.envelope_satisfied == other.envelope_satisfied
+            && self.humans_excluded == other.humans_excluded
+            && self.torque_ok == other.torque_ok
+            && self.failsafe_ready == other.failsafe_ready
+            && self.simulated_outcome == other.simulated_outcome
+    }
+
+    /// Hash this proof's contents and its link to the previous proof
+    ///
+    /// FNV-1a over a canonical encoding of every field except `hash`. This
+    /// makes accidental or naive edits to the log detectable; a deployment
+    /// facing a deliberate forger would use a cryptographic hash instead.
+    pub fn compute_hash(&self) -> String {
+        let encoded = format!(
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
+            self.command.robot_id,
+            self.command.target_x.to_bits(),
+            self.command.target_y.to_bits(),
+            self.command.target_speed.to_bits(),
+            self.command.target_torque.to_bits(),
+            self.command.timestamp,
+            self.envelope_satisfied,
+            self.humans_excluded,
+            self.torque_ok,
+            self.failsafe_ready,
+            self.simulated_outcome,
+            self.timestamp,
+            self.prev_hash
+        );
+
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for byte in encoded.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{:016x}", hash)
+    }
+
     pub fn to_safety_report(&self) -> String {
         format!(
             "=== Safety Proof Report ===\n\
@@ -126,6 +180,16 @@ impl SafetyProof {
     }
 }
 
+/// Robots and zones of a cell at a point in time
+///
+/// Taken before a run so the offline twin can replay the run from the same
+/// starting point.
+#[derive(Debug, Clone)]
+pub struct GridSnapshot {
+    pub zones: HashMap<String, SafetyZone>,
+    pub robots: HashMap<String, RobotState>,
+}
+
 /// Grid cell operator with safety verification
 pub struct GridCellOperator {
     pub cell_id: String,
@@ -133,7 +197,10 @@ pub struct GridCellOperator {
     pub zones: HashMap<String, SafetyZone>,
     pub robots: HashMap<String, RobotState>,
     pub command_history: Vec<ControlCommand>,
+    pub proof_history: Vec<SafetyProof>,
     pub near_miss_count: usize,
+    /// Spacing between points checked along a robot's path
+    pub path_step_m: f64,
 }
 
 impl GridCellOperator {
@@ -144,7 +211,23 @@ impl GridCellOperator {
             zones: HashMap::new(),
             robots: HashMap::new(),
             command_history: Vec::new(),
+            proof_history: Vec::new(),
             near_miss_count: 0,
+            path_step_m: DEFAULT_PATH_STEP_M,
+        }
+    }
+
+    /// Set the spacing between points checked along a robot's path
+    pub fn with_path_step(mut self, step_m: f64) -> Self {
+        self.path_step_m = step_m;
+        self
+    }
+
+    /// Capture the current robots and zones
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot {
+            zones: self.zones.clone(),
+            robots: self.robots.clone(),
         }
     }
 
@@ -164,13 +247,33 @@ impl GridCellOperator {
             && command.target_torque <= self.envelope.max_torque_nm
     }
 
-    /// Check if humans are excluded from operation zone
+    /// Check if humans are excluded along the command's whole path
+    ///
+    /// Samples the straight line from the robot's current position to the
+    /// target every `path_step_m`, so a move that passes through a human
+    /// zone is rejected even when both endpoints are clear.
     fn check_human_exclusion(&self, command: &ControlCommand) -> bool {
+        let (start_x, start_y) = match self.robots.get(&command.robot_id) {
+            Some(robot) => (robot.position_x, robot.position_y),
+            None => (command.target_x, command.target_y),
+        };
+
+        let dx = command.target_x - start_x;
+        let dy = command.target_y - start_y;
+        let distance = (dx.powi(2) + dy.powi(2)).sqrt();
+        let steps = (distance / self.path_step_m.max(f64::EPSILON)).ceil().max(1.0) as usize;
+
+        (0..=steps).all(|i| {
+            let t = i as f64 / steps as f64;
+            self.point_clear_of_humans(start_x + t * dx, start_y + t * dy)
+        })
+    }
+
+    /// Check if a single point is clear of every zone with humans
+    fn point_clear_of_humans(&self, x: f64, y: f64) -> bool {
         for zone in self.zones.values() {
-            if zone.contains_point(command.target_x, command.target_y) {
-                if zone.is_human_present {
-                    return false; // Human present in target zone
-                }
+            if zone.contains_point(x, y) && zone.is_human_present {
+                return false; // Human present in zone
             }
         }
 
@@ -180,9 +283,7 @@ impl GridCellOperator {
                 let zone_center_x = (zone.x_min + zone.x_max) / 2.0;
                 let zone_center_y = (zone.y_min + zone.y_max) / 2.0;
 
-                let distance = ((command.target_x - zone_center_x).powi(2)
-                    + (command.target_y - zone_center_y).powi(2))
-                .sqrt();
+                let distance = ((x - zone_center_x).powi(2) + (y - zone_center_y).powi(2)).sqrt();
 
                 if distance < self.envelope.human_exclusion_radius_m {
                     return false; // Too close to human
@@ -289,7 +390,7 @@ impl GridCellOperator {
             .unwrap()
             .as_secs();
 
-        let proof = SafetyProof {
+        let mut proof = SafetyProof {
             command,
             envelope_satisfied,
             humans_excluded,
@@ -297,11 +398,86 @@ impl GridCellOperator {
             failsafe_ready,
             simulated_outcome,
             timestamp,
+            prev_hash: self
+                .proof_history
+                .last()
+                .map(|p| p.hash.clone())
+                .unwrap_or_else(|| GENESIS_HASH.to_string()),
+            hash: String::new(),
         };
+        proof.hash = proof.compute_hash();
+
+        self.proof_history.push(proof.clone());
 
         Ok(proof)
     }
 
+    /// Check that the proof log is an unbroken hash chain
+    ///
+    /// Each proof must hash to its recorded `hash`, link to the previous
+    /// proof's hash, and match the command recorded at the same position,
+    /// so any modified, inserted or deleted entry breaks the chain.
+    pub fn verify_chain(&self) -> bool {
+        if self.proof_history.len() != self.command_history.len() {
+            return false;
+        }
+
+        let mut prev_hash = GENESIS_HASH;
+        for (proof, command) in self.proof_history.iter().zip(&self.command_history) {
+            if proof.prev_hash != prev_hash
+                || proof.hash != proof.compute_hash()
+                || proof.command != *command
+            {
+                return false;
+            }
+            prev_hash = &proof.hash;
+        }
+
+        true
+    }
+
+    /// Re-run the recorded commands from `initial_state`
+    ///
+    /// Runs on a twin of this cell with the same envelope, so the live
+    /// operator is untouched. Every recorded command was accepted the first
+    /// time, so a rejection during replay means the run is not reproducible
+    /// from `initial_state`.
+    pub fn replay(&self, initial_state: GridSnapshot) -> Result<Vec<SafetyProof>, String> {
+        let mut twin = GridCellOperator::new(self.cell_id.clone(), self.envelope.clone())
+            .with_path_step(self.path_step_m);
+        twin.zones = initial_state.zones;
+        twin.robots = initial_state.robots;
+
+        self.command_history
+            .iter()
+            .enumerate()
+            .map(|(i, command)| {
+                twin.execute_command(command.clone())
+                    .map_err(|e| format!("Replay rejected command {}: {}", i, e))
+            })
+            .collect()
+    }
+
+    /// Replay the recorded commands and check they regenerate the same proofs
+    pub fn verify_replay(&self, initial_state: GridSnapshot) -> Result<(), String> {
+        let replayed = self.replay(initial_state)?;
+        if replayed.len() != self.proof_history.len() {
+            return Err(format!(
+                "Replay produced {} proofs, expected {}",
+                replayed.len(),
+                self.proof_history.len()
+            ));
+        }
+
+        for (i, (original, replayed)) in self.proof_history.iter().zip(&replayed).enumerate() {
+            if !original.same_outcome(replayed) {
+                return Err(format!("Replayed proof {} differs from the original", i));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Emergency stop all robots
     pub fn emergency_stop_all(&mut self) {
         for robot in self.robots.values_mut() {
@@ -517,4 +693,153 @@ mod tests {
         assert!(report.contains("Safety Proof Report"));
         assert!(report.contains("VERIFIED: true"));
     }
+
+    #[test]
+    fn test_replay_reproduces_proofs() {
+        let envelope = create_test_envelope();
+        let mut operator = GridCellOperator::new("cell-008".to_string(), envelope);
+
+        operator.add_zone(create_test_zone("zone1", false));
+        operator.add_robot(create_test_robot("robot1"));
+        operator.add_robot(create_test_robot("robot2"));
+        let initial = operator.snapshot();
+
+        let targets = [("robot1", 5.0, 5.0), ("robot2", 3.0, 8.0), ("robot1", 9.0, 1.0)];
+        for (robot_id, x, y) in targets {
+            operator
+                .execute_command(ControlCommand {
+                    robot_id: robot_id.to_string(),
+                    target_x: x,
+                    target_y: y,
+                    target_speed: 1.5,
+                    target_torque: 20.0,
+                    timestamp: 1000,
+                })
+                .unwrap();
+        }
+
+        let replayed = operator.replay(initial.clone()).unwrap();
+        assert_eq!(replayed.len(), 3);
+        for (original, replayed) in operator.proof_history.iter().zip(&replayed) {
+            assert!(original.same_outcome(replayed));
+        }
+        // The third proof depends on where the first command left robot1
+        assert!(replayed[2].simulated_outcome.contains("from (5.00, 5.00)"));
+        assert!(operator.verify_replay(initial.clone()).is_ok());
+
+        // A different starting position regenerates different proofs
+        let mut moved = initial;
+        moved.robots.get_mut("robot1").unwrap().position_x = 2.0;
+        assert!(operator.verify_replay(moved).is_err());
+    }
+
+    #[test]
+    fn test_path_through_human_zone_rejected() {
+        let envelope = create_test_envelope();
+        let mut operator = GridCellOperator::new("cell-009".to_string(), envelope);
+
+        operator.add_zone(SafetyZone {
+            id: "walkway".to_string(),
+            x_min: 4.0,
+            x_max: 6.0,
+            y_min: 4.0,
+            y_max: 6.0,
+            is_human_present: true,
+            last_scan_timestamp: 1000,
+        });
+        let mut robot = create_test_robot("robot1");
+        robot.position_y = 5.0;
+        operator.add_robot(robot);
+
+        // Both (0, 5) and (10, 5) are clear, but the line between them is not
+        let crossing = ControlCommand {
+            robot_id: "robot1".to_string(),
+            target_x: 10.0,
+            target_y: 5.0,
+            target_speed: 1.0,
+            target_torque: 30.0,
+            timestamp: 1000,
+        };
+        let result = operator.execute_command(crossing);
+        assert_eq!(result.unwrap_err(), "Human exclusion zone violation");
+        assert_eq!(operator.near_miss_count, 1);
+        assert_eq!(operator.robots["robot1"].position_x, 0.0);
+
+        // Going around the zone is allowed
+        let detour = ControlCommand {
+            robot_id: "robot1".to_string(),
+            target_x: 0.0,
+            target_y: 12.0,
+            target_speed: 1.0,
+            target_torque: 30.0,
+            timestamp: 1001,
+        };
+        assert!(operator.execute_command(detour).unwrap().verify());
+    }
+
+    fn chained_operator() -> GridCellOperator {
+        let envelope = create_test_envelope();
+        let mut operator = GridCellOperator::new("cell-010".to_string(), envelope);
+
+        operator.add_zone(create_test_zone("zone1", false));
+        operator.add_robot(create_test_robot("robot1"));
+
+        for (i, (x, y)) in [(2.0, 2.0), (4.0, 6.0), (8.0, 3.0), (1.0, 9.0)].into_iter().enumerate() {
+            operator
+                .execute_command(ControlCommand {
+                    robot_id: "robot1".to_string(),
+                    target_x: x,
+                    target_y: y,
+                    target_speed: 1.0,
+                    target_torque: 30.0,
+                    timestamp: 1000 + i as u64,
+                })
+                .unwrap();
+        }
+        operator
+    }
+
+    #[test]
+    fn test_proof_chain_links_history() {
+        let operator = chained_operator();
+        assert!(operator.verify_chain());
+
+        let proofs = &operator.proof_history;
+        assert_eq!(proofs[0].prev_hash, GENESIS_HASH);
+        for pair in proofs.windows(2) {
+            assert_eq!(pair[1].prev_hash, pair[0].hash);
+        }
+    }
+
+    #[test]
+    fn test_proof_chain_detects_tampering() {
+        // Modified outcome in a middle proof
+        let mut operator = chained_operator();
+        operator.proof_history[1].torque_ok = false;
+        assert!(!operator.verify_chain());
+
+        // Modified proof with its own hash recomputed: the next link breaks
+        let mut operator = chained_operator();
+        operator.proof_history[1].simulated_outcome = "SUCCESS: nothing to see".to_string();
+        operator.proof_history[1].hash = operator.proof_history[1].compute_hash();
+        assert!(!operator.verify_chain());
+
+        // Deleted middle entry, from both logs
+        let mut operator = chained_operator();
+        operator.proof_history.remove(2);
+        operator.command_history.remove(2);
+        assert!(!operator.verify_chain());
+
+        // Inserted entry
+        let mut operator = chained_operator();
+        let forged = operator.proof_history[1].clone();
+        operator.proof_history.insert(2, forged.clone());
+        operator.command_history.insert(2, forged.command);
+        assert!(!operator.verify_chain());
+
+        // Command rewritten after the fact
+        let mut operator = chained_operator();
+        operator.command_history[2].target_torque = 45.0;
+        assert!(!operator.verify_chain());
+    }
 }
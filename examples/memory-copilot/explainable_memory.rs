@@ -13,9 +13,15 @@
 //! - Task completion time
 //! - User trust score
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Dimension of the default hashed embedding
+pub const DEFAULT_EMBEDDING_DIM: usize = 128;
+
+/// ACL entry that shares an episode with every user
+pub const EVERYONE: &str = "*";
+
 /// Memory episode with context
 #[derive(Debug, Clone)]
 pub struct Episode {
@@ -25,6 +31,15 @@ pub struct Episode {
     pub entities: Vec<String>,
     pub embedding: Vec<f32>, // Simplified embedding
     pub source: String,
+    pub owner: String,
+    pub acl: Vec<String>, // Other users who may see this episode, or EVERYONE
+}
+
+impl Episode {
+    /// Check if a user may see this episode
+    pub fn visible_to(&self, user_id: &str) -> bool {
+        self.owner == user_id || self.acl.iter().any(|u| u == user_id || u == EVERYONE)
+    }
 }
 
 /// Entity tracked across episodes
@@ -78,11 +93,76 @@ impl RecallResult {
     }
 }
 
+/// Turns text into a fixed-size embedding vector
+///
+/// Implement this to plug in a real embedding model, e.g. one producing
+/// the 1536-dim vectors `AgentDb` stores.
+pub trait Embedder {
+    /// Length of every vector `embed` returns
+    fn dimension(&self) -> usize;
+
+    /// Embed a piece of text
+    fn embed(&self, text: &str) -> Vec<f32>;
+}
+
+/// Bag-of-words embedding that hashes each word into a bucket
+#[derive(Debug, Clone)]
+pub struct HashingEmbedder {
+    dimension: usize,
+}
+
+impl HashingEmbedder {
+    /// Create an embedder producing `dimension`-long vectors
+    ///
+    /// Fails for a zero dimension, which leaves no bucket to hash words into.
+    pub fn new(dimension: usize) -> Result<Self, String> {
+        if dimension == 0 {
+            return Err("Embedding dimension must be non-zero".to_string());
+        }
+        Ok(Self { dimension })
+    }
+}
+
+impl Default for HashingEmbedder {
+    fn default() -> Self {
+        Self { dimension: DEFAULT_EMBEDDING_DIM }
+    }
+}
+
+impl Embedder for HashingEmbedder {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let mut embedding = vec![0.0; self.dimension];
+
+        for (i, word) in words.iter().enumerate() {
+            let hash = word.chars().map(|c| c as u32).sum::<u32>();
+            let idx = hash as usize % self.dimension;
+            embedding[idx] += 1.0 / (i + 1) as f32;
+        }
+
+        // Normalize
+        let magnitude: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if magnitude > 0.0 {
+            for v in &mut embedding {
+                *v /= magnitude;
+            }
+        }
+
+        embedding
+    }
+}
+
 /// Memory database with explainable recall
 pub struct MemoryCopilot {
     episodes: HashMap<String, Episode>,
     entities: HashMap<String, Entity>,
     causal_edges: Vec<CausalEdge>,
+    embedder: Box<dyn Embedder>,
+    min_similarity: f32,
 }
 
 impl MemoryCopilot {
@@ -91,16 +171,58 @@ impl MemoryCopilot {
             episodes: HashMap::new(),
             entities: HashMap::new(),
             causal_edges: Vec::new(),
+            embedder: Box::new(HashingEmbedder::default()),
+            min_similarity: 0.0,
         }
     }
 
+    /// Drop recall results less similar to the query than `threshold`
+    ///
+    /// Recall may then return fewer than `k` episodes, or none when
+    /// nothing stored is related to the query.
+    pub fn with_min_similarity(mut self, threshold: f32) -> Self {
+        self.min_similarity = threshold;
+        self
+    }
+
+    /// Use the hashed embedding with a different, non-zero dimension
+    pub fn with_dimension(self, dimension: usize) -> Result<Self, String> {
+        Ok(self.with_embedder(Box::new(HashingEmbedder::new(dimension)?)))
+    }
+
+    /// Use a custom embedding model
+    ///
+    /// Set this before adding episodes: stored embeddings are not
+    /// recomputed, so episodes already added would no longer match.
+    pub fn with_embedder(mut self, embedder: Box<dyn Embedder>) -> Self {
+        self.embedder = embedder;
+        self
+    }
+
+    /// Dimension every stored episode embedding has
+    pub fn embedding_dimension(&self) -> usize {
+        self.embedder.dimension()
+    }
+
     /// Add an episode to memory
+    ///
+    /// Episodes without an embedding are embedded with the configured
+    /// model. Episodes whose embedding has a different dimension from the
+    /// model's are rejected, so all stored episodes stay comparable.
     pub fn add_episode(&mut self, mut episode: Episode) -> Result<(), String> {
-        // Generate simple embedding (in production, use actual embedding model)
         if episode.embedding.is_empty() {
             episode.embedding = self.generate_embedding(&episode.content);
         }
 
+        if episode.embedding.len() != self.embedding_dimension() {
+            return Err(format!(
+                "Embedding dimension mismatch for episode {}: expected {}, got {}",
+                episode.id,
+                self.embedding_dimension(),
+                episode.embedding.len()
+            ));
+        }
+
         // Update entities
         for entity_name in &episode.entities {
             let entity = self.entities.entry(entity_name.clone()).or_insert(Entity {
@@ -121,26 +243,9 @@ impl MemoryCopilot {
         Ok(())
     }
 
-    /// Generate simple embedding based on word hashing
+    /// Embed text with the configured model
     fn generate_embedding(&self, text: &str) -> Vec<f32> {
-        let words: Vec<&str> = text.split_whitespace().collect();
-        let mut embedding = vec![0.0; 128]; // 128-dim vector
-
-        for (i, word) in words.iter().enumerate() {
-            let hash = word.chars().map(|c| c as u32).sum::<u32>();
-            let idx = (hash % 128) as usize;
-            embedding[idx] += 1.0 / (i + 1) as f32;
-        }
-
-        // Normalize
-        let magnitude: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
-        if magnitude > 0.0 {
-            for v in &mut embedding {
-                *v /= magnitude;
-            }
-        }
-
-        embedding
+        self.embedder.embed(text)
     }
 
     /// Compute cosine similarity between embeddings
@@ -159,7 +264,7 @@ impl MemoryCopilot {
     /// Detect causal edges to new episode
     fn detect_causal_edges(&mut self, episode: &Episode) {
         // Look for references to entities in recent episodes
-        for (ep_id, prev_episode) in &self.episodes {
+        for prev_episode in self.episodes.values() {
             // Check for shared entities
             let shared_entities: Vec<_> = episode
                 .entities
@@ -181,7 +286,27 @@ impl MemoryCopilot {
     }
 
     /// Recall episodes with explanation
+    ///
+    /// Searches every episode regardless of owner; for admin use. Use
+    /// `recall_for_user` to answer on behalf of a user.
     pub fn recall(&self, query: &str, k: usize) -> Vec<RecallResult> {
+        self.recall_where(query, k, |_| true)
+    }
+
+    /// Recall only episodes `user_id` may see
+    ///
+    /// Filtering happens before ranking, so hidden episodes never take a
+    /// top-k slot, and causal paths stop at the first hidden episode.
+    pub fn recall_for_user(&self, query: &str, k: usize, user_id: &str) -> Vec<RecallResult> {
+        self.recall_where(query, k, |episode| episode.visible_to(user_id))
+    }
+
+    fn recall_where(
+        &self,
+        query: &str,
+        k: usize,
+        visible: impl Fn(&Episode) -> bool,
+    ) -> Vec<RecallResult> {
         let query_embedding = self.generate_embedding(query);
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -191,6 +316,7 @@ impl MemoryCopilot {
         let mut scored_episodes: Vec<(String, f32, f32)> = self
             .episodes
             .iter()
+            .filter(|(_, episode)| visible(episode))
             .map(|(id, episode)| {
                 let similarity = Self::cosine_similarity(&query_embedding, &episode.embedding);
 
@@ -198,13 +324,14 @@ impl MemoryCopilot {
                 let age_seconds = current_time.saturating_sub(episode.timestamp);
                 let time_relevance = 1.0 / (1.0 + (age_seconds as f32 / 86400.0)); // Decay over days
 
-                let combined_score = similarity * 0.7 + time_relevance * 0.3;
-
                 (id.clone(), similarity, time_relevance)
             })
             .collect();
 
-        // Sort by combined score
+        // Drop matches below the relevance threshold
+        scored_episodes.retain(|(_, similarity, _)| *similarity >= self.min_similarity);
+
+        // Sort by similarity
         scored_episodes.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
 
         // Take top k results
@@ -215,7 +342,7 @@ impl MemoryCopilot {
                 let episode = self.episodes.get(&id).unwrap().clone();
 
                 // Find causal path
-                let causal_path = self.find_causal_path(&id);
+                let causal_path = self.find_causal_path(&id, &visible);
 
                 // Generate explanation
                 let explanation = self.generate_explanation(&episode, similarity, time_relevance, &causal_path);
@@ -232,8 +359,15 @@ impl MemoryCopilot {
     }
 
     /// Find causal path to episode
-    fn find_causal_path(&self, episode_id: &str) -> Vec<String> {
+    ///
+    /// Walks backward through the highest-confidence predecessor, treating
+    /// the causal graph as a DAG. Predecessors already on the path are
+    /// skipped, so a cycle is broken at the edge that would close it and
+    /// the path never repeats an episode. Ties in confidence go to the
+    /// smaller episode ID, keeping the path deterministic.
+    fn find_causal_path(&self, episode_id: &str, visible: &impl Fn(&Episode) -> bool) -> Vec<String> {
         let mut path = vec![episode_id.to_string()];
+        let mut visited: HashSet<&str> = HashSet::from([episode_id]);
 
         // Find edges leading to this episode
         let mut current_id = episode_id;
@@ -243,9 +377,17 @@ impl MemoryCopilot {
                 .causal_edges
                 .iter()
                 .filter(|e| e.to_episode == current_id)
-                .max_by(|a, b| a.confidence.partial_cmp(&b.confidence).unwrap())
+                .filter(|e| !visited.contains(e.from_episode.as_str()))
+                .filter(|e| self.episodes.get(&e.from_episode).is_some_and(visible))
+                .max_by(|a, b| {
+                    a.confidence
+                        .partial_cmp(&b.confidence)
+                        .unwrap()
+                        .then_with(|| b.from_episode.cmp(&a.from_episode))
+                })
             {
                 path.insert(0, edge.from_episode.clone());
+                visited.insert(&edge.from_episode);
                 current_id = &edge.from_episode;
             } else {
                 break;
@@ -303,6 +445,9 @@ impl MemoryCopilot {
     }
 
     /// Get precision at k metric
+    ///
+    /// Slots left empty by the relevance threshold count as misses, so the
+    /// denominator is always `k`.
     pub fn precision_at_k(&self, query: &str, k: usize, relevant_ids: &[String]) -> f32 {
         let results = self.recall(query, k);
         let retrieved_relevant = results
@@ -318,7 +463,7 @@ impl MemoryCopilot {
         let results = self.recall(query, k);
 
         let mut bundle = String::new();
-        bundle.push_str(&format!("=== Memory Copilot Audit ===\n"));
+        bundle.push_str("=== Memory Copilot Audit ===\n");
         bundle.push_str(&format!("Query: {}\n", query));
         bundle.push_str(&format!("Results: {}\n\n", results.len()));
 
@@ -344,6 +489,12 @@ impl MemoryCopilot {
     }
 }
 
+impl Default for MemoryCopilot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,9 +510,18 @@ mod tests {
             entities,
             embedding: vec![],
             source: "test".to_string(),
+            owner: "test".to_string(),
+            acl: vec![],
         }
     }
 
+    fn owned_episode(id: &str, content: &str, owner: &str, acl: &[&str]) -> Episode {
+        let mut episode = create_test_episode(id, content, vec!["launch".to_string()]);
+        episode.owner = owner.to_string();
+        episode.acl = acl.iter().map(|u| u.to_string()).collect();
+        episode
+    }
+
     #[test]
     fn test_add_episode() {
         let mut copilot = MemoryCopilot::new();
@@ -501,4 +661,137 @@ mod tests {
 
         assert!(recent_result.time_relevance > old_result.time_relevance);
     }
+
+    #[test]
+    fn test_recall_for_user_respects_acl() {
+        let mut copilot = MemoryCopilot::new();
+
+        copilot.add_episode(owned_episode("alice-1", "launch plan draft", "alice", &[])).unwrap();
+        copilot.add_episode(owned_episode("bob-1", "launch plan budget", "bob", &[])).unwrap();
+        copilot.add_episode(owned_episode("bob-2", "launch plan review", "bob", &["alice"])).unwrap();
+        copilot.add_episode(owned_episode("team-1", "launch plan kickoff", "carol", &[EVERYONE])).unwrap();
+
+        let ids = |results: Vec<RecallResult>| {
+            let mut ids: Vec<String> = results.into_iter().map(|r| r.episode.id).collect();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(
+            ids(copilot.recall_for_user("launch plan", 10, "alice")),
+            vec!["alice-1", "bob-2", "team-1"]
+        );
+        assert_eq!(
+            ids(copilot.recall_for_user("launch plan", 10, "bob")),
+            vec!["bob-1", "bob-2", "team-1"]
+        );
+        assert_eq!(ids(copilot.recall_for_user("launch plan", 10, "dave")), vec!["team-1"]);
+
+        // Causal paths never reveal episodes the user cannot see
+        for result in copilot.recall_for_user("launch plan", 10, "alice") {
+            assert!(!result.causal_path.contains(&"bob-1".to_string()));
+        }
+
+        // Admin recall still sees everything
+        assert_eq!(copilot.recall("launch plan", 10).len(), 4);
+    }
+
+    #[test]
+    fn test_causal_path_breaks_cycles() {
+        let mut copilot = MemoryCopilot::new();
+
+        // No shared entities, so no edges are detected automatically
+        copilot.add_episode(create_test_episode("a", "Draft proposal", vec![])).unwrap();
+        copilot.add_episode(create_test_episode("b", "Review proposal", vec![])).unwrap();
+        copilot.add_episode(create_test_episode("c", "Revise proposal", vec![])).unwrap();
+        assert_eq!(copilot.total_causal_edges(), 0);
+
+        // a -> b -> c -> a
+        for (from, to) in [("a", "b"), ("b", "c"), ("c", "a")] {
+            copilot.causal_edges.push(CausalEdge {
+                from_episode: from.to_string(),
+                to_episode: to.to_string(),
+                relationship: "follows".to_string(),
+                confidence: 0.9,
+            });
+        }
+
+        let path = copilot.find_causal_path("c", &|_: &Episode| true);
+        assert_eq!(path, vec!["a", "b", "c"]);
+
+        let results = copilot.recall("proposal", 3);
+        for result in results {
+            let unique: HashSet<_> = result.causal_path.iter().collect();
+            assert_eq!(unique.len(), result.causal_path.len());
+            assert_eq!(result.causal_path.len(), 3);
+        }
+    }
+
+    /// Character-trigram embedder standing in for an external model
+    struct TrigramEmbedder;
+
+    impl Embedder for TrigramEmbedder {
+        fn dimension(&self) -> usize {
+            256
+        }
+
+        fn embed(&self, text: &str) -> Vec<f32> {
+            let chars: Vec<char> = text.to_lowercase().chars().collect();
+            let mut embedding = vec![0.0; 256];
+            for window in chars.windows(3) {
+                let hash = window.iter().fold(0u32, |h, &c| h.wrapping_mul(31).wrapping_add(c as u32));
+                embedding[hash as usize % 256] += 1.0;
+            }
+            embedding
+        }
+    }
+
+    #[test]
+    fn test_injected_embedder_dimension() {
+        let mut copilot = MemoryCopilot::new().with_embedder(Box::new(TrigramEmbedder));
+        assert_eq!(copilot.embedding_dimension(), 256);
+
+        copilot.add_episode(create_test_episode("ep1", "Quarterly budget review", vec![])).unwrap();
+        copilot.add_episode(create_test_episode("ep2", "Team offsite logistics", vec![])).unwrap();
+
+        let results = copilot.recall("budget review", 1);
+        assert_eq!(results[0].episode.id, "ep1");
+        assert_eq!(results[0].episode.embedding.len(), 256);
+
+        // A precomputed 128-dim embedding does not fit a 256-dim store
+        let mut foreign = create_test_episode("ep3", "Imported from another index", vec![]);
+        foreign.embedding = HashingEmbedder::default().embed(&foreign.content);
+        let err = copilot.add_episode(foreign).unwrap_err();
+        assert!(err.contains("expected 256, got 128"));
+        assert_eq!(copilot.total_episodes(), 2);
+
+        // The hashed default can be resized too
+        let mut resized = MemoryCopilot::new().with_dimension(64).unwrap();
+        resized.add_episode(create_test_episode("ep1", "Resized store", vec![])).unwrap();
+        assert_eq!(resized.recall("store", 1)[0].episode.embedding.len(), 64);
+
+        // ...but not to nothing
+        assert!(HashingEmbedder::new(0).is_err());
+        assert!(MemoryCopilot::new().with_dimension(0).is_err());
+    }
+
+    #[test]
+    fn test_min_similarity_drops_unrelated() {
+        let mut copilot = MemoryCopilot::new().with_min_similarity(0.3);
+
+        copilot.add_episode(create_test_episode("ep1", "Budget review for Q3", vec![])).unwrap();
+        copilot.add_episode(create_test_episode("ep2", "Hiring plan for the platform team", vec![])).unwrap();
+        copilot.add_episode(create_test_episode("ep3", "Budget sign-off", vec![])).unwrap();
+
+        assert!(copilot.recall("zebra xylophone", 3).is_empty());
+        assert!(copilot.export_audit_bundles("zebra xylophone", 3).contains("Results: 0"));
+        let relevant = vec!["ep1".to_string()];
+        assert_eq!(copilot.precision_at_k("zebra xylophone", 3, &relevant), 0.0);
+
+        // Related queries still match, but only above the threshold
+        let results = copilot.recall("Budget review", 3);
+        assert!(!results.is_empty() && results.len() < 3);
+        assert!(results.iter().all(|r| r.similarity_score >= 0.3));
+        assert_eq!(results[0].episode.id, "ep1");
+    }
 }
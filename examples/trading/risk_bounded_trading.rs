@@ -18,7 +18,12 @@
 //! - Slippage vs model: <2%
 //! - Auditability score: 100%
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+#[path = "../branch-labs/dry_run.rs"]
+pub mod dry_run;
+
+use dry_run::Simulated;
 
 /// Trading mandate with risk limits
 #[derive(Debug, Clone)]
@@ -29,6 +34,7 @@ pub struct TradingMandate {
     pub kelly_fraction: f64, // 0.0-1.0, typically 0.25-0.5
     pub allowed_symbols: Vec<String>,
     pub max_trades_per_day: usize,
+    pub max_slippage_percent: f64,
 }
 
 /// Market quote with latency tracking
@@ -41,6 +47,40 @@ pub struct MarketQuote {
     pub latency_ms: u64,
 }
 
+/// Predicts how far a fill moves away from the quote
+///
+/// Size-proportional: each unit traded moves the price by
+/// `percent_per_unit` percent against the trader.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlippageModel {
+    pub percent_per_unit: f64,
+}
+
+impl SlippageModel {
+    /// Fill exactly at the quote
+    pub fn none() -> Self {
+        Self { percent_per_unit: 0.0 }
+    }
+
+    pub fn size_proportional(percent_per_unit: f64) -> Self {
+        Self { percent_per_unit }
+    }
+
+    /// Expected slippage for an order, in percent
+    pub fn slippage_percent(&self, quantity: f64) -> f64 {
+        self.percent_per_unit * quantity.abs()
+    }
+
+    /// Price an order is expected to fill at
+    pub fn fill_price(&self, side: &TradeSide, quote_price: f64, quantity: f64) -> f64 {
+        let slip = self.slippage_percent(quantity) / 100.0;
+        match side {
+            TradeSide::Buy => quote_price * (1.0 + slip),
+            TradeSide::Sell => quote_price * (1.0 - slip),
+        }
+    }
+}
+
 /// Position in portfolio
 #[derive(Debug, Clone)]
 pub struct Position {
@@ -68,11 +108,27 @@ pub struct Trade {
     pub symbol: String,
     pub side: TradeSide,
     pub quantity: f64,
+    /// Realized fill price
     pub price: f64,
+    /// Quoted price before slippage
+    pub quote_price: f64,
+    /// Slippage predicted by the model, in percent
+    pub modeled_slippage_percent: f64,
     pub timestamp: u64,
     pub proof_hash: String,
 }
 
+impl Trade {
+    /// Slippage actually paid relative to the quote, in percent
+    pub fn realized_slippage_percent(&self) -> f64 {
+        let moved = match self.side {
+            TradeSide::Buy => self.price - self.quote_price,
+            TradeSide::Sell => self.quote_price - self.price,
+        };
+        moved / self.quote_price * 100.0
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TradeSide {
     Buy,
@@ -87,13 +143,18 @@ pub struct RiskProof {
     pub drawdown_ok: bool,
     pub position_limits_ok: bool,
     pub mandate_ok: bool,
+    pub slippage_ok: bool,
     pub simulated_pnl: f64,
     pub timestamp: u64,
 }
 
 impl RiskProof {
     pub fn verify(&self) -> bool {
-        self.kelly_satisfied && self.drawdown_ok && self.position_limits_ok && self.mandate_ok
+        self.kelly_satisfied
+            && self.drawdown_ok
+            && self.position_limits_ok
+            && self.mandate_ok
+            && self.slippage_ok
     }
 
     pub fn to_audit_record(&self) -> String {
@@ -104,6 +165,7 @@ impl RiskProof {
              Drawdown OK: {}\n\
              Position Limits OK: {}\n\
              Mandate OK: {}\n\
+             Slippage OK: {} ({:.2}%)\n\
              Simulated P&L: ${:.2}\n\
              Valid: {}\n\
              Timestamp: {}",
@@ -114,6 +176,8 @@ impl RiskProof {
             self.drawdown_ok,
             self.position_limits_ok,
             self.mandate_ok,
+            self.slippage_ok,
+            self.trade.realized_slippage_percent(),
             self.simulated_pnl,
             self.verify(),
             self.timestamp
@@ -122,6 +186,7 @@ impl RiskProof {
 }
 
 /// Portfolio with risk management
+#[derive(Debug, Clone)]
 pub struct Portfolio {
     pub cash: f64,
     pub positions: HashMap<String, Position>,
@@ -159,22 +224,111 @@ impl Portfolio {
     }
 }
 
+/// Default fraction of `max_drawdown_percent` at which trading halts
+pub const DEFAULT_HALT_FRACTION: f64 = 0.8;
+
+/// Circuit-breaker transition, kept for audit
+#[derive(Debug, Clone, PartialEq)]
+pub enum HaltEvent {
+    /// Drawdown crossed the warning threshold; all trading stopped
+    Halted {
+        drawdown_percent: f64,
+        threshold_percent: f64,
+        timestamp: u64,
+    },
+    /// An operator resumed trading
+    Reset {
+        drawdown_percent: f64,
+        timestamp: u64,
+    },
+}
+
 /// Trading agent with risk-bounded execution
+#[derive(Debug, Clone)]
 pub struct TradingAgent {
     pub agent_id: String,
     pub mandate: TradingMandate,
     pub portfolio: Portfolio,
     pub trade_history: Vec<Trade>,
+    slippage: SlippageModel,
+    halt_fraction: f64,
+    halted: bool,
+    /// Drawdown the breaker trips above; raised on reset
+    halt_level: f64,
+    halt_events: Vec<HaltEvent>,
 }
 
 impl TradingAgent {
     pub fn new(agent_id: String, mandate: TradingMandate, initial_cash: f64) -> Self {
+        let halt_level = mandate.max_drawdown_percent * DEFAULT_HALT_FRACTION;
         Self {
             agent_id,
             mandate,
             portfolio: Portfolio::new(initial_cash),
             trade_history: Vec::new(),
+            slippage: SlippageModel::none(),
+            halt_fraction: DEFAULT_HALT_FRACTION,
+            halted: false,
+            halt_level,
+            halt_events: Vec::new(),
+        }
+    }
+
+    /// Apply a slippage model to fills
+    pub fn with_slippage_model(mut self, model: SlippageModel) -> Self {
+        self.slippage = model;
+        self
+    }
+
+    /// Halt once drawdown exceeds `fraction` of the mandate's limit
+    pub fn with_halt_fraction(mut self, fraction: f64) -> Self {
+        self.halt_fraction = fraction;
+        self.halt_level = self.halt_threshold();
+        self
+    }
+
+    /// Drawdown percentage at which the circuit breaker trips
+    pub fn halt_threshold(&self) -> f64 {
+        self.mandate.max_drawdown_percent * self.halt_fraction
+    }
+
+    /// Check if trading is halted
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Circuit-breaker history, oldest first
+    pub fn halt_events(&self) -> &[HaltEvent] {
+        &self.halt_events
+    }
+
+    /// Resume trading after a halt
+    ///
+    /// The breaker re-arms at the current drawdown (or the threshold, if
+    /// higher), so trading halts again only if losses deepen.
+    pub fn reset_halt(&mut self) {
+        if !self.halted {
+            return;
         }
+        self.halted = false;
+        self.halt_level = self.portfolio.current_drawdown.max(self.halt_threshold());
+        self.halt_events.push(HaltEvent::Reset {
+            drawdown_percent: self.portfolio.current_drawdown,
+            timestamp: unix_now(),
+        });
+    }
+
+    /// Trip the circuit breaker if drawdown is past the halt level
+    fn check_halt(&mut self) -> bool {
+        if !self.halted && self.portfolio.current_drawdown > self.halt_level {
+            self.halted = true;
+            self.halt_events.push(HaltEvent::Halted {
+                drawdown_percent: self.portfolio.current_drawdown,
+                threshold_percent: self.halt_level,
+                timestamp: unix_now(),
+            });
+        }
+        self.halted
     }
 
     /// Calculate optimal position size using Kelly criterion
@@ -227,7 +381,7 @@ impl TradingAgent {
     /// Simulate trade to check risk
     fn simulate_trade(
         &self,
-        symbol: &str,
+        _symbol: &str,
         side: &TradeSide,
         quantity: f64,
         price: f64,
@@ -257,11 +411,17 @@ impl TradingAgent {
         win_prob: f64,
         win_loss_ratio: f64,
     ) -> Result<RiskProof, String> {
-        // Step 1: Check mandate
-        let price = match side {
+        // Step 0: Circuit breaker
+        if self.check_halt() {
+            return Err("Trading halted: drawdown warning threshold exceeded".to_string());
+        }
+
+        // Step 1: Check mandate at the expected fill price
+        let quote_price = match side {
             TradeSide::Buy => quote.ask,
             TradeSide::Sell => quote.bid,
         };
+        let price = self.slippage.fill_price(&side, quote_price, quantity);
 
         let mandate_ok = self.check_mandate(&symbol, quantity, price);
         if !mandate_ok {
@@ -292,16 +452,15 @@ impl TradingAgent {
         }
 
         // Step 5: Execute trade
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let timestamp = unix_now();
 
         let trade = Trade {
             symbol: symbol.clone(),
             side: side.clone(),
             quantity,
             price,
+            quote_price,
+            modeled_slippage_percent: self.slippage.slippage_percent(quantity),
             timestamp,
             proof_hash: format!("hash-{}", timestamp),
         };
@@ -340,14 +499,18 @@ impl TradingAgent {
         self.portfolio.trades_today += 1;
         self.portfolio.update_drawdown();
         self.trade_history.push(trade.clone());
+        self.check_halt();
 
-        // Step 6: Generate proof
+        // Step 6: Generate proof; excess slippage is flagged, not refused,
+        // since the fill has already happened
+        let slippage_ok = trade.realized_slippage_percent() <= self.mandate.max_slippage_percent;
         let proof = RiskProof {
             trade,
             kelly_satisfied,
             drawdown_ok,
             position_limits_ok,
             mandate_ok,
+            slippage_ok,
             simulated_pnl,
             timestamp,
         };
@@ -368,6 +531,51 @@ impl TradingAgent {
     }
 }
 
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// Arguments of `TradingAgent::execute_trade`, bundled for dry runs
+#[derive(Debug, Clone)]
+pub struct TradeOrder {
+    pub symbol: String,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub quote: MarketQuote,
+    pub win_prob: f64,
+    pub win_loss_ratio: f64,
+}
+
+impl Simulated for TradingAgent {
+    type Action = TradeOrder;
+    type Proof = RiskProof;
+
+    fn apply(&mut self, order: TradeOrder) -> Result<RiskProof, String> {
+        self.execute_trade(
+            order.symbol,
+            order.side,
+            order.quantity,
+            &order.quote,
+            order.win_prob,
+            order.win_loss_ratio,
+        )
+    }
+
+    fn metrics(&self) -> BTreeMap<String, f64> {
+        let mut metrics = BTreeMap::new();
+        metrics.insert("cash".to_string(), self.portfolio.cash);
+        metrics.insert("total_value".to_string(), self.portfolio.total_value());
+        metrics.insert("trades_today".to_string(), self.portfolio.trades_today as f64);
+        for (symbol, position) in &self.portfolio.positions {
+            metrics.insert(format!("position:{}", symbol), position.quantity);
+        }
+        metrics
+    }
+}
+
 #[derive(Debug)]
 pub struct RiskMetrics {
     pub current_drawdown: f64,
@@ -390,6 +598,7 @@ mod tests {
             kelly_fraction: 0.25,
             allowed_symbols: vec!["AAPL".to_string(), "MSFT".to_string()],
             max_trades_per_day: 10,
+            max_slippage_percent: 2.0,
         }
     }
 
@@ -473,7 +682,7 @@ mod tests {
         agent.portfolio.cash = 85000.0; // 15% drawdown
 
         agent.portfolio.update_drawdown();
-        assert!(agent.portfolio.current_drawdown > mandate.max_drawdown_percent);
+        assert!(agent.portfolio.current_drawdown > agent.mandate.max_drawdown_percent);
     }
 
     #[test]
@@ -530,4 +739,100 @@ mod tests {
         assert!(audit.contains("Risk Proof Audit"));
         assert!(audit.contains("Valid: true"));
     }
+
+    #[test]
+    fn test_dry_run_trade_leaves_agent_unchanged() {
+        use dry_run::DryRun;
+
+        let mandate = create_test_mandate();
+        let agent = TradingAgent::new("trader-008".to_string(), mandate, 100000.0);
+
+        let outcome = DryRun::new(&agent).run(TradeOrder {
+            symbol: "AAPL".to_string(),
+            side: TradeSide::Buy,
+            quantity: 50.0,
+            quote: create_test_quote("AAPL"),
+            win_prob: 0.6,
+            win_loss_ratio: 2.0,
+        });
+
+        let proof = outcome.result.as_ref().unwrap();
+        assert!(proof.verify());
+        assert_eq!(outcome.delta["position:AAPL"].after, 50.0);
+        assert_eq!(outcome.delta["cash"].delta(), -50.0 * 100.5);
+        assert_eq!(outcome.delta["trades_today"].after, 1.0);
+
+        // The live portfolio is untouched
+        assert_eq!(agent.portfolio.cash, 100000.0);
+        assert!(agent.portfolio.positions.is_empty());
+        assert!(agent.trade_history.is_empty());
+    }
+
+    #[test]
+    fn test_drawdown_halt_until_reset() {
+        let mandate = create_test_mandate();
+        let mut agent = TradingAgent::new("trader-009".to_string(), mandate, 100000.0);
+        assert_eq!(agent.halt_threshold(), 8.0);
+
+        let buy = |agent: &mut TradingAgent| {
+            agent.execute_trade(
+                "AAPL".to_string(),
+                TradeSide::Buy,
+                10.0,
+                &create_test_quote("AAPL"),
+                0.6,
+                2.0,
+            )
+        };
+        assert!(buy(&mut agent).is_ok());
+
+        // A 9% drawdown is inside the 10% limit but past the 8% warning
+        agent.portfolio.cash -= 9000.0;
+        agent.portfolio.update_drawdown();
+
+        let err = buy(&mut agent).unwrap_err();
+        assert!(err.starts_with("Trading halted"));
+        assert!(agent.is_halted());
+        assert!(buy(&mut agent).is_err());
+        assert_eq!(agent.trade_history.len(), 1);
+
+        agent.reset_halt();
+        assert!(!agent.is_halted());
+        assert!(buy(&mut agent).is_ok());
+
+        assert!(matches!(
+            agent.halt_events(),
+            [HaltEvent::Halted { threshold_percent, .. }, HaltEvent::Reset { .. }]
+                if *threshold_percent == 8.0
+        ));
+    }
+
+    #[test]
+    fn test_slippage_applied_and_bounded() {
+        let mandate = create_test_mandate();
+        let model = SlippageModel::size_proportional(0.02); // 0.02% per share
+        let mut agent = TradingAgent::new("trader-010".to_string(), mandate, 100000.0)
+            .with_slippage_model(model);
+        let quote = create_test_quote("AAPL");
+
+        // 50 shares: 1% slippage, within the 2% bound
+        let proof = agent
+            .execute_trade("AAPL".to_string(), TradeSide::Buy, 50.0, &quote, 0.6, 2.0)
+            .unwrap();
+        assert!((proof.trade.price - 100.5 * 1.01).abs() < 1e-9);
+        assert_eq!(proof.trade.quote_price, 100.5);
+        assert!((proof.trade.realized_slippage_percent() - 1.0).abs() < 1e-9);
+        assert!((proof.trade.modeled_slippage_percent - 1.0).abs() < 1e-9);
+        assert!(proof.slippage_ok && proof.verify());
+
+        // 90 shares sold: 1.8% is still fine, but 0.03% per share is not
+        let mut agent = agent.with_slippage_model(SlippageModel::size_proportional(0.03));
+        let proof = agent
+            .execute_trade("AAPL".to_string(), TradeSide::Sell, 90.0, &quote, 0.6, 2.0)
+            .unwrap();
+        assert!((proof.trade.price - 100.0 * (1.0 - 0.027)).abs() < 1e-9);
+        assert!(!proof.slippage_ok);
+        assert!(!proof.verify());
+        assert!(proof.to_audit_record().contains("Slippage OK: false (2.70%)"));
+    }
 }
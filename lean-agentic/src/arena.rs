@@ -4,7 +4,7 @@
 //! hash-consing, making equality checks O(1).
 
 use crate::term::{Term, TermId, TermKind};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// Arena for interning terms with hash-consing
 pub struct Arena {
@@ -43,25 +43,22 @@ impl Arena {
 
     /// Intern a term and return its ID
     pub fn intern(&mut self, kind: TermKind) -> TermId {
-        let term = Term::new(kind);
-        let hash = term.hash();
+        self.intern_term(Term::new(kind))
+    }
 
+    /// Intern an already-hashed term
+    pub(crate) fn intern_term(&mut self, term: Term) -> TermId {
         // Check cache for existing term
-        if let Some(candidates) = self.cache.get(&hash) {
-            for &id in candidates {
-                if let Some(existing) = self.terms.get(id.raw() as usize) {
-                    if existing.kind == term.kind {
-                        self.stats.cache_hits += 1;
-                        return id;
-                    }
-                }
-            }
+        if let Some(id) = self.lookup(&term) {
+            self.stats.cache_hits += 1;
+            return id;
         }
 
         // Not found, allocate new term
         self.stats.cache_misses += 1;
         self.stats.allocated += 1;
 
+        let hash = term.hash();
         let id = TermId::new(self.terms.len() as u32);
         self.terms.push(term);
 
@@ -71,6 +68,15 @@ impl Arena {
         id
     }
 
+    /// Find the ID of a structurally equal term, if already interned
+    pub(crate) fn lookup(&self, term: &Term) -> Option<TermId> {
+        self.cache.get(&term.hash())?.iter().copied().find(|id| {
+            self.terms
+                .get(id.raw() as usize)
+                .is_some_and(|existing| existing.kind == term.kind)
+        })
+    }
+
     /// Get a term by its ID
     pub fn get(&self, id: TermId) -> Option<&Term> {
         self.terms.get(id.raw() as usize)
@@ -171,6 +177,49 @@ impl Arena {
         args.iter().fold(func, |acc, &arg| self.mk_app(acc, arg))
     }
 
+    /// Shift free de Bruijn indices `>= cutoff` by `amount`
+    ///
+    /// Variables below the cutoff (bound within the shifted region) are left
+    /// alone. A negative `amount` lowers indices; callers must ensure no free
+    /// variable in `[cutoff, cutoff - amount)` occurs in `term`.
+    pub fn shift(&mut self, term: TermId, cutoff: u32, amount: i32) -> TermId {
+        if amount == 0 {
+            return term;
+        }
+
+        crate::visit::map_term(self, term, &mut |arena, sub, depth| {
+            match arena.kind(sub) {
+                Some(TermKind::Var(idx)) if *idx >= cutoff + depth => {
+                    let shifted = *idx as i64 + amount as i64;
+                    debug_assert!(
+                        shifted >= (cutoff + depth) as i64,
+                        "shift captured or underflowed variable #{}",
+                        idx
+                    );
+                    Some(arena.mk_var(shifted as u32))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Collect the free de Bruijn indices of a term, sorted and deduplicated
+    ///
+    /// Indices are relative to the term's root context.
+    pub fn free_var_indices(&self, term: TermId) -> Vec<u32> {
+        let mut collect = |mut free: BTreeSet<u32>, _, kind: &TermKind, depth| {
+            if let TermKind::Var(idx) = kind {
+                if *idx >= depth {
+                    free.insert(idx - depth);
+                }
+            }
+            free
+        };
+        crate::visit::fold_term(self, term, BTreeSet::new(), &mut collect)
+            .into_iter()
+            .collect()
+    }
+
     /// Create a zero universe level
     pub fn mk_level_zero(&mut self) -> crate::level::LevelId {
         // This is a placeholder - in production this would use LevelArena
@@ -248,4 +297,56 @@ mod tests {
         assert!(arena.cache_hit_rate() > 0.95);
         assert_eq!(arena.terms(), 1);
     }
+
+    #[test]
+    fn test_shift_lifts_only_free_variables() {
+        let mut arena = Arena::new();
+        let name = crate::symbol::SymbolId::new(0);
+        let ty = arena.mk_sort(LevelId::new(0));
+
+        // λ (_ : Type). #0 #1
+        let v0 = arena.mk_var(0);
+        let v1 = arena.mk_var(1);
+        let body = arena.mk_app(v0, v1);
+        let lam = arena.mk_lam(crate::term::Binder::new(name, ty), body);
+
+        let shifted = arena.shift(lam, 0, 2);
+
+        // The bound #0 stays put; the free #1 (free #0 outside) becomes #3
+        let v3 = arena.mk_var(3);
+        let expected_body = arena.mk_app(v0, v3);
+        let expected = arena.mk_lam(crate::term::Binder::new(name, ty), expected_body);
+        assert_eq!(shifted, expected);
+
+        // Variables below the cutoff are untouched; shifting back round-trips
+        assert_eq!(arena.shift(v0, 1, 5), v0);
+        assert_eq!(arena.shift(shifted, 0, -2), lam);
+        assert_eq!(arena.shift(ty, 0, 7), ty);
+    }
+
+    #[test]
+    fn test_free_var_indices() {
+        let mut arena = Arena::new();
+        let name = crate::symbol::SymbolId::new(0);
+
+        let v0 = arena.mk_var(0);
+        let v2 = arena.mk_var(2);
+        let v4 = arena.mk_var(4);
+
+        // λ (_ : #2). #0 #4 #2 has free #2 (binder type) and #3, #1 (body)
+        let body = arena.mk_app_spine(v0, &[v4, v2]);
+        let lam = arena.mk_lam(crate::term::Binder::new(name, v2), body);
+        assert_eq!(arena.free_var_indices(lam), vec![1, 2, 3]);
+
+        // The binder type is outside the binder's scope
+        let identity = arena.mk_lam(crate::term::Binder::new(name, v0), v0);
+        assert_eq!(arena.free_var_indices(identity), vec![0]);
+
+        // Closed terms have none
+        let ty = arena.mk_sort(LevelId::new(0));
+        let closed = arena.mk_lam(crate::term::Binder::new(name, ty), v0);
+        assert!(arena.free_var_indices(closed).is_empty());
+        let nat = arena.mk_nat(3);
+        assert!(arena.free_var_indices(nat).is_empty());
+    }
 }
@@ -2,6 +2,7 @@
 //!
 //! Manages the local context (Γ) in typing judgments Γ ⊢ t : T
 
+use crate::arena::Arena;
 use crate::symbol::SymbolId;
 use crate::term::TermId;
 
@@ -89,16 +90,35 @@ impl Context {
         self.entries.get(pos)
     }
 
-    /// Get the type of a variable by de Bruijn index
+    /// Get the type of a variable by de Bruijn index, as it was stored
+    ///
+    /// The result is relative to the context at the point the variable was
+    /// bound; use `type_of_var` to get it relative to the full context.
     pub fn type_of(&self, index: u32) -> Option<TermId> {
         self.lookup(index).map(|e| e.ty)
     }
 
-    /// Get the value of a variable (if it's a let binding)
+    /// Get the value of a variable (if it's a let binding), as it was stored
     pub fn value_of(&self, index: u32) -> Option<TermId> {
         self.lookup(index).and_then(|e| e.value)
     }
 
+    /// Get the type of variable `#index`, valid in the full context
+    ///
+    /// The stored type only sees the `index` bindings older than the entry
+    /// itself, so its free variables are lifted past the `index + 1`
+    /// bindings pushed since.
+    pub fn type_of_var(&self, arena: &mut Arena, index: u32) -> Option<TermId> {
+        let ty = self.type_of(index)?;
+        Some(arena.shift(ty, 0, index as i32 + 1))
+    }
+
+    /// Get the value of let-bound variable `#index`, valid in the full context
+    pub fn value_of_var(&self, arena: &mut Arena, index: u32) -> Option<TermId> {
+        let value = self.value_of(index)?;
+        Some(arena.shift(value, 0, index as i32 + 1))
+    }
+
     /// Extend the context with multiple entries
     pub fn extend(&mut self, entries: impl IntoIterator<Item = ContextEntry>) {
         self.entries.extend(entries);
@@ -235,4 +255,25 @@ mod tests {
         assert_eq!(ctx.type_of(0), Some(ty));
         assert_eq!(ctx.value_of(0), Some(val));
     }
+
+    #[test]
+    fn test_type_of_var_is_shifted() {
+        let mut arena = Arena::new();
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let a = arena.mk_var(0);
+
+        // A : Type, x : A  (x's type refers to A as #0 at its binding site)
+        let mut ctx = Context::new();
+        ctx.push_var(SymbolId::new(0), ty);
+        ctx.push_var(SymbolId::new(1), a);
+
+        // From the full context, A is #1
+        let expected = arena.mk_var(1);
+        assert_eq!(ctx.type_of_var(&mut arena, 0), Some(expected));
+        assert_eq!(ctx.type_of_var(&mut arena, 1), Some(ty));
+        assert_eq!(ctx.type_of_var(&mut arena, 2), None);
+
+        // The raw lookup still returns the stored type
+        assert_eq!(ctx.type_of(0), Some(a));
+    }
 }
@@ -2,31 +2,79 @@
 //!
 //! Implements conversion checking through normalization with
 //! beta, delta, zeta, and iota reductions.
+//!
+//! Conversion is lazy: both sides are first reduced without unfolding
+//! constants, applications with the same head are compared argument-wise,
+//! and delta reduction only happens when that fails. Hash-consed `TermId`
+//! equality settles identical subterms in O(1).
 
 use crate::arena::Arena;
 use crate::context::Context;
-use crate::environment::Environment;
+use crate::environment::{Environment, Transparency};
+use crate::symbol::SymbolId;
 use crate::term::{TermId, TermKind};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::RwLock;
 
-/// Fuel for preventing non-termination in reduction
+/// Fuel for preventing non-termination in reduction, per top-level check
 const DEFAULT_FUEL: u32 = 10000;
 
-/// Cache for memoizing WHNF computations
-type WhnfCache = Arc<RwLock<HashMap<(TermId, usize), TermId>>>;
+/// Cache for memoizing WHNF computations, keyed by term, context depth, and transparency
+type WhnfCache = Arc<RwLock<HashMap<(TermId, usize, Transparency), TermId>>>;
 
 /// Conversion checker with WHNF evaluation
 pub struct Converter {
     /// Fuel remaining to prevent infinite loops
     fuel: u32,
 
+    /// Fuel granted to each top-level conversion or WHNF call
+    max_fuel: u32,
+
     /// Cache for WHNF results
     cache: WhnfCache,
 
+    /// Environment generation the cached results were computed at
+    generation: u64,
+
+    /// Which constants may be delta-unfolded
+    transparency: Transparency,
+
     /// Statistics
     stats: ConversionStats,
+
+    /// Reductions performed so far, when tracing is enabled
+    trace: Option<Vec<ReductionStep>>,
+}
+
+/// A single reduction performed while checking conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReductionStep {
+    /// `(λx. body) arg ~> body[x := arg]`
+    Beta {
+        /// The redex
+        from: TermId,
+        /// The substituted body
+        to: TermId,
+    },
+
+    /// A constant unfolded to its definition
+    Delta {
+        /// The unfolded constant
+        name: SymbolId,
+        /// The constant occurrence
+        from: TermId,
+        /// Its definition
+        to: TermId,
+    },
+
+    /// `let x := v; body ~> body[x := v]`
+    Zeta {
+        /// The let expression
+        from: TermId,
+        /// The substituted body
+        to: TermId,
+    },
 }
 
 /// Statistics for conversion checking
@@ -43,6 +91,9 @@ pub struct ConversionStats {
 
     /// Cache hits
     pub cache_hits: usize,
+
+    /// Number of constants delta-unfolded
+    pub unfolds: usize,
 }
 
 impl Converter {
@@ -50,8 +101,12 @@ impl Converter {
     pub fn new() -> Self {
         Self {
             fuel: DEFAULT_FUEL,
+            max_fuel: DEFAULT_FUEL,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            generation: 0,
+            transparency: Transparency::Default,
             stats: ConversionStats::default(),
+            trace: None,
         }
     }
 
@@ -59,11 +114,51 @@ impl Converter {
     pub fn with_fuel(fuel: u32) -> Self {
         Self {
             fuel,
+            max_fuel: fuel,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            generation: 0,
+            transparency: Transparency::Default,
             stats: ConversionStats::default(),
+            trace: None,
         }
     }
 
+    /// Record every reduction step taken by later calls
+    ///
+    /// While tracing, the WHNF cache is bypassed so that no step is hidden
+    /// behind a memoized result.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    /// Take the steps recorded so far, leaving tracing enabled
+    pub fn take_trace(&mut self) -> Vec<ReductionStep> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    fn record(&mut self, step: ReductionStep) {
+        if let Some(trace) = &mut self.trace {
+            trace.push(step);
+        }
+    }
+
+    /// Set the transparency used for delta reduction
+    pub fn with_transparency(mut self, transparency: Transparency) -> Self {
+        self.transparency = transparency;
+        self
+    }
+
+    /// Change the transparency used for delta reduction
+    pub fn set_transparency(&mut self, transparency: Transparency) {
+        self.transparency = transparency;
+    }
+
+    /// Get the current transparency
+    pub fn transparency(&self) -> Transparency {
+        self.transparency
+    }
+
     /// Check if two terms are definitionally equal
     pub fn is_def_eq(
         &mut self,
@@ -72,6 +167,55 @@ impl Converter {
         ctx: &Context,
         t1: TermId,
         t2: TermId,
+    ) -> crate::Result<bool> {
+        self.is_def_eq_with_fuel(arena, env, ctx, t1, t2, self.max_fuel)
+    }
+
+    /// Check definitional equality within an explicit step budget
+    ///
+    /// Returns `Error::Timeout` if the budget runs out before a verdict.
+    pub fn is_def_eq_with_fuel(
+        &mut self,
+        arena: &mut Arena,
+        env: &Environment,
+        ctx: &Context,
+        t1: TermId,
+        t2: TermId,
+        fuel: u32,
+    ) -> crate::Result<bool> {
+        self.fuel = fuel;
+        self.sync_generation(env);
+        self.def_eq(arena, env, ctx, t1, t2)
+    }
+
+    /// Drop cached WHNF results if `env` has changed since they were computed
+    fn sync_generation(&mut self, env: &Environment) {
+        if env.generation() != self.generation {
+            self.clear_cache();
+            self.generation = env.generation();
+        }
+    }
+
+    /// Consume one unit of fuel
+    fn consume_fuel(&mut self, what: &str) -> crate::Result<()> {
+        if self.fuel == 0 {
+            return Err(crate::Error::Timeout(format!(
+                "Out of fuel during {}",
+                what
+            )));
+        }
+        self.fuel -= 1;
+        Ok(())
+    }
+
+    /// Definitional equality, sharing the fuel of the enclosing call
+    fn def_eq(
+        &mut self,
+        arena: &mut Arena,
+        env: &Environment,
+        ctx: &Context,
+        t1: TermId,
+        t2: TermId,
     ) -> crate::Result<bool> {
         self.stats.checks += 1;
 
@@ -81,22 +225,72 @@ impl Converter {
             return Ok(true);
         }
 
-        // Reduce both to WHNF and compare
-        let whnf1 = self.whnf(arena, env, ctx, t1)?;
-        let whnf2 = self.whnf(arena, env, ctx, t2)?;
+        self.consume_fuel("conversion")?;
 
-        if whnf1 == whnf2 {
+        let result = self.is_def_eq_lazy(arena, env, ctx, t1, t2)?;
+        if result {
             self.stats.successes += 1;
+        }
+
+        Ok(result)
+    }
+
+    /// Compare two terms, unfolding constants only when cheaper checks fail
+    fn is_def_eq_lazy(
+        &mut self,
+        arena: &mut Arena,
+        env: &Environment,
+        ctx: &Context,
+        t1: TermId,
+        t2: TermId,
+    ) -> crate::Result<bool> {
+        // Beta/zeta only: no constants are unfolded yet
+        let core1 = self.whnf_core(arena, env, ctx, t1)?;
+        let core2 = self.whnf_core(arena, env, ctx, t2)?;
+
+        if core1 == core2 {
             return Ok(true);
         }
 
-        // Structural comparison
-        let result = self.is_def_eq_whnf(arena, env, ctx, whnf1, whnf2)?;
-        if result {
-            self.stats.successes += 1;
+        // Same constant head: compare arguments before unfolding the head
+        let (head1, args1) = app_spine(arena, core1);
+        let (head2, args2) = app_spine(arena, core2);
+        if head1 == head2 && !args1.is_empty() && args1.len() == args2.len() {
+            let mut args_eq = true;
+            for (&a1, &a2) in args1.iter().zip(&args2) {
+                if !self.def_eq(arena, env, ctx, a1, a2)? {
+                    args_eq = false;
+                    break;
+                }
+            }
+            if args_eq {
+                return Ok(true);
+            }
         }
 
-        Ok(result)
+        // Fall back to full WHNF and structural comparison
+        let whnf1 = self.reduce(arena, env, ctx, core1)?;
+        let whnf2 = self.reduce(arena, env, ctx, core2)?;
+
+        if whnf1 == whnf2 {
+            return Ok(true);
+        }
+
+        self.is_def_eq_whnf(arena, env, ctx, whnf1, whnf2)
+    }
+
+    /// Reduce a term to weak head normal form without unfolding constants
+    fn whnf_core(
+        &mut self,
+        arena: &mut Arena,
+        env: &Environment,
+        ctx: &Context,
+        term: TermId,
+    ) -> crate::Result<TermId> {
+        let transparency = std::mem::replace(&mut self.transparency, Transparency::None);
+        let result = self.reduce(arena, env, ctx, term);
+        self.transparency = transparency;
+        result
     }
 
     /// Reduce a term to weak head normal form
@@ -107,15 +301,34 @@ impl Converter {
         ctx: &Context,
         term: TermId,
     ) -> crate::Result<TermId> {
-        if self.fuel == 0 {
-            return Err(crate::Error::Internal(
-                "Out of fuel during normalization".to_string(),
-            ));
-        }
+        self.whnf_with_fuel(arena, env, ctx, term, self.max_fuel)
+    }
+
+    /// Reduce to weak head normal form within an explicit step budget
+    pub fn whnf_with_fuel(
+        &mut self,
+        arena: &mut Arena,
+        env: &Environment,
+        ctx: &Context,
+        term: TermId,
+        fuel: u32,
+    ) -> crate::Result<TermId> {
+        self.fuel = fuel;
+        self.sync_generation(env);
+        self.reduce(arena, env, ctx, term)
+    }
 
+    /// WHNF reduction, sharing the fuel of the enclosing call
+    fn reduce(
+        &mut self,
+        arena: &mut Arena,
+        env: &Environment,
+        ctx: &Context,
+        term: TermId,
+    ) -> crate::Result<TermId> {
         // Check cache
-        let cache_key = (term, ctx.len());
-        {
+        let cache_key = (term, ctx.len(), self.transparency);
+        if self.trace.is_none() {
             let cache = self.cache.read().unwrap();
             if let Some(&cached) = cache.get(&cache_key) {
                 self.stats.cache_hits += 1;
@@ -123,7 +336,7 @@ impl Converter {
             }
         }
 
-        self.fuel -= 1;
+        self.consume_fuel("normalization")?;
         self.stats.reductions += 1;
 
         let kind = arena.kind(term).ok_or_else(|| {
@@ -133,21 +346,23 @@ impl Converter {
         let result = match kind {
             // Variables: look up in context for let-bound values
             TermKind::Var(idx) => {
-                if let Some(value) = ctx.value_of(idx) {
-                    self.whnf(arena, env, ctx, value)?
+                if let Some(value) = ctx.value_of_var(arena, idx) {
+                    self.reduce(arena, env, ctx, value)?
                 } else {
                     term
                 }
             }
 
-            // Constants: unfold if reducible
+            // Constants: unfold if the current transparency permits
             TermKind::Const(name, _levels) => {
                 if let Some(decl) = env.get_decl(name) {
-                    if decl.is_reducible() {
+                    if decl.can_unfold(self.transparency) {
                         if let Some(body) = decl.value {
                             // Instantiate universe parameters if needed
                             // For now, just reduce the body
-                            self.whnf(arena, env, ctx, body)?
+                            self.stats.unfolds += 1;
+                            self.record(ReductionStep::Delta { name, from: term, to: body });
+                            self.reduce(arena, env, ctx, body)?
                         } else {
                             term
                         }
@@ -161,17 +376,18 @@ impl Converter {
 
             // Application: try beta reduction
             TermKind::App(func, arg) => {
-                let func_whnf = self.whnf(arena, env, ctx, func)?;
+                let func_whnf = self.reduce(arena, env, ctx, func)?;
 
                 if let Some(TermKind::Lam(_binder, body)) = arena.kind(func_whnf).cloned() {
                     // Beta reduction: (λx.body) arg ~> body[x := arg]
                     let subst = self.substitute(arena, body, 0, arg)?;
-                    self.whnf(arena, env, ctx, subst)?
+                    self.record(ReductionStep::Beta { from: term, to: subst });
+                    self.reduce(arena, env, ctx, subst)?
                 } else {
                     // Can't reduce further
                     if func_whnf != func {
                         let new_app = arena.mk_app(func_whnf, arg);
-                        self.whnf(arena, env, ctx, new_app)?
+                        self.reduce(arena, env, ctx, new_app)?
                     } else {
                         term
                     }
@@ -182,7 +398,8 @@ impl Converter {
             TermKind::Let(_binder, value, body) => {
                 // Substitute value into body
                 let subst = self.substitute(arena, body, 0, value)?;
-                self.whnf(arena, env, ctx, subst)?
+                self.record(ReductionStep::Zeta { from: term, to: subst });
+                self.reduce(arena, env, ctx, subst)?
             }
 
             // Already in WHNF
@@ -236,15 +453,15 @@ impl Converter {
 
             // Applications
             (TermKind::App(f1, a1), TermKind::App(f2, a2)) => {
-                let funcs_eq = self.is_def_eq(arena, env, ctx, f1, f2)?;
-                let args_eq = self.is_def_eq(arena, env, ctx, a1, a2)?;
+                let funcs_eq = self.def_eq(arena, env, ctx, f1, f2)?;
+                let args_eq = self.def_eq(arena, env, ctx, a1, a2)?;
                 Ok(funcs_eq && args_eq)
             }
 
             // Lambda
             (TermKind::Lam(b1, body1), TermKind::Lam(b2, body2)) => {
                 // Check binder types
-                let types_eq = self.is_def_eq(arena, env, ctx, b1.ty, b2.ty)?;
+                let types_eq = self.def_eq(arena, env, ctx, b1.ty, b2.ty)?;
                 if !types_eq {
                     return Ok(false);
                 }
@@ -252,13 +469,13 @@ impl Converter {
                 // Check bodies under extended context
                 let mut new_ctx = ctx.clone();
                 new_ctx.push_var(b1.name, b1.ty);
-                self.is_def_eq(arena, env, &new_ctx, body1, body2)
+                self.def_eq(arena, env, &new_ctx, body1, body2)
             }
 
             // Pi types
             (TermKind::Pi(b1, body1), TermKind::Pi(b2, body2)) => {
                 // Check binder types
-                let types_eq = self.is_def_eq(arena, env, ctx, b1.ty, b2.ty)?;
+                let types_eq = self.def_eq(arena, env, ctx, b1.ty, b2.ty)?;
                 if !types_eq {
                     return Ok(false);
                 }
@@ -266,7 +483,7 @@ impl Converter {
                 // Check bodies under extended context
                 let mut new_ctx = ctx.clone();
                 new_ctx.push_var(b1.name, b1.ty);
-                self.is_def_eq(arena, env, &new_ctx, body1, body2)
+                self.def_eq(arena, env, &new_ctx, body1, body2)
             }
 
             // Literals
@@ -364,12 +581,47 @@ impl Converter {
         cache.clear();
     }
 
-    /// Reset fuel to default
+    /// Get the fuel left over from the last call
+    pub fn fuel(&self) -> u32 {
+        self.fuel
+    }
+
+    /// Reset fuel to the per-call budget
     pub fn reset_fuel(&mut self) {
-        self.fuel = DEFAULT_FUEL;
+        self.fuel = self.max_fuel;
+    }
+}
+
+/// Check definitional equality and return the reductions that equated the terms
+///
+/// Returns `None` if the terms are not definitionally equal. Steps are listed
+/// in the order they were performed, across both sides of the comparison.
+pub fn are_defeq_with_trace(
+    arena: &mut Arena,
+    env: &Environment,
+    a: TermId,
+    b: TermId,
+) -> crate::Result<Option<Vec<ReductionStep>>> {
+    let mut conv = Converter::new().with_trace();
+    if conv.is_def_eq(arena, env, &Context::new(), a, b)? {
+        Ok(Some(conv.take_trace()))
+    } else {
+        Ok(None)
     }
 }
 
+/// Split an application into its head and arguments, in application order
+fn app_spine(arena: &Arena, term: TermId) -> (TermId, Vec<TermId>) {
+    let mut head = term;
+    let mut args = Vec::new();
+    while let Some(TermKind::App(func, arg)) = arena.kind(head) {
+        args.push(*arg);
+        head = *func;
+    }
+    args.reverse();
+    (head, args)
+}
+
 impl Default for Converter {
     fn default() -> Self {
         Self::new()
@@ -428,4 +680,180 @@ mod tests {
         // This should work with minimal fuel
         assert!(conv.whnf(&mut arena, &env, &ctx, var).is_ok());
     }
+
+    #[test]
+    fn test_irreducible_constant_not_unfolded() {
+        use crate::environment::{Declaration, Reducibility};
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let seven = arena.mk_nat(7);
+
+        let opaque_name = SymbolId::new(0);
+        let transparent_name = SymbolId::new(1);
+        env.add_decl(
+            Declaration::def(opaque_name, vec![], ty, seven)
+                .with_reducibility(Reducibility::Irreducible),
+        ).unwrap();
+        env.add_decl(
+            Declaration::def(transparent_name, vec![], ty, seven)
+                .with_reducibility(Reducibility::Reducible),
+        ).unwrap();
+
+        let opaque = arena.mk_const(opaque_name, vec![]);
+        let transparent = arena.mk_const(transparent_name, vec![]);
+
+        let mut conv = Converter::new();
+        assert!(!conv.is_def_eq(&mut arena, &env, &ctx, opaque, seven).unwrap());
+        assert!(conv.is_def_eq(&mut arena, &env, &ctx, transparent, seven).unwrap());
+
+        // Only Transparency::All looks through @[irreducible]
+        conv.set_transparency(Transparency::All);
+        assert!(conv.is_def_eq(&mut arena, &env, &ctx, opaque, seven).unwrap());
+
+        conv.set_transparency(Transparency::None);
+        assert!(!conv.is_def_eq(&mut arena, &env, &ctx, transparent, seven).unwrap());
+    }
+
+    #[test]
+    fn test_cache_invalidated_by_environment_change() {
+        use crate::environment::{Declaration, Reducibility};
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let seven = arena.mk_nat(7);
+        let eight = arena.mk_nat(8);
+
+        let name = SymbolId::new(0);
+        env.add_decl(Declaration::def(name, vec![], ty, seven)).unwrap();
+        let constant = arena.mk_const(name, vec![]);
+
+        let mut conv = Converter::new();
+        assert_eq!(conv.whnf(&mut arena, &env, &ctx, constant).unwrap(), seven);
+
+        // A redefinition is seen by the same converter
+        env.add_or_replace(Declaration::def(name, vec![], ty, eight));
+        assert_eq!(conv.whnf(&mut arena, &env, &ctx, constant).unwrap(), eight);
+
+        // So is a change of reducibility
+        env.set_reducibility(name, Reducibility::Irreducible).unwrap();
+        assert_eq!(conv.whnf(&mut arena, &env, &ctx, constant).unwrap(), constant);
+    }
+
+    #[test]
+    fn test_large_identical_terms_short_circuit() {
+        use crate::environment::Declaration;
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+
+        // id := λx. x, reducible by default
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let x = arena.mk_var(0);
+        let id_body = arena.mk_lam(Binder::new(SymbolId::new(1), ty), x);
+        let id_name = SymbolId::new(0);
+        env.add_decl(Declaration::def(id_name, vec![], ty, id_body)).unwrap();
+
+        // Build id (id (... (id 0))) twice, independently
+        let build = |arena: &mut Arena| {
+            let id = arena.mk_const(id_name, vec![]);
+            let mut term = arena.mk_nat(0);
+            for _ in 0..10_000 {
+                term = arena.mk_app(id, term);
+            }
+            term
+        };
+        let lhs = build(&mut arena);
+        let rhs = build(&mut arena);
+
+        let mut conv = Converter::new();
+        assert!(conv.is_def_eq(&mut arena, &env, &ctx, lhs, rhs).unwrap());
+
+        // Settled by hash-consed identity, without normalizing either side
+        assert_eq!(conv.stats().reductions, 0);
+        assert_eq!(conv.stats().unfolds, 0);
+    }
+
+    #[test]
+    fn test_same_head_compares_arguments_before_unfolding() {
+        use crate::environment::Declaration;
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let x = arena.mk_var(0);
+        let lam = arena.mk_lam(Binder::new(SymbolId::new(2), ty), x);
+
+        let big = SymbolId::new(0);
+        let id = SymbolId::new(1);
+        env.add_decl(Declaration::def(big, vec![], ty, lam)).unwrap();
+        env.add_decl(Declaration::def(id, vec![], ty, lam)).unwrap();
+
+        // big (id 7) =?= big 7
+        let big_c = arena.mk_const(big, vec![]);
+        let id_c = arena.mk_const(id, vec![]);
+        let seven = arena.mk_nat(7);
+        let id_seven = arena.mk_app(id_c, seven);
+        let lhs = arena.mk_app(big_c, id_seven);
+        let rhs = arena.mk_app(big_c, seven);
+
+        let mut conv = Converter::new();
+        assert!(conv.is_def_eq(&mut arena, &env, &ctx, lhs, rhs).unwrap());
+
+        // Only `id` had to be unfolded; `big` was never touched
+        assert_eq!(conv.stats().unfolds, 1);
+    }
+
+    #[test]
+    fn test_self_expanding_conversion_times_out() {
+        use crate::environment::Declaration;
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+
+        // loop := loop loop, which grows on every unfolding
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let name = SymbolId::new(0);
+        let looping = arena.mk_const(name, vec![]);
+        let body = arena.mk_app(looping, looping);
+        env.add_decl(Declaration::def(name, vec![], ty, body)).unwrap();
+
+        let zero = arena.mk_nat(0);
+        let mut conv = Converter::new();
+        let result = conv.is_def_eq_with_fuel(&mut arena, &env, &ctx, looping, zero, 200);
+        assert!(matches!(result, Err(crate::Error::Timeout(_))));
+        assert_eq!(conv.fuel(), 0);
+
+        // The next call gets a fresh budget
+        assert!(conv.is_def_eq(&mut arena, &env, &ctx, zero, zero).unwrap());
+    }
+
+    #[test]
+    fn test_defeq_trace_records_single_beta() {
+        let mut arena = Arena::new();
+        let env = Environment::new();
+
+        // (λx. x) a ≡ a
+        let ty = arena.mk_sort(crate::level::LevelId::new(0));
+        let x = arena.mk_var(0);
+        let id = arena.mk_lam(Binder::new(SymbolId::new(0), ty), x);
+        let a = arena.mk_nat(3);
+        let app = arena.mk_app(id, a);
+
+        let trace = are_defeq_with_trace(&mut arena, &env, app, a).unwrap().unwrap();
+        assert_eq!(trace, vec![ReductionStep::Beta { from: app, to: a }]);
+
+        let b = arena.mk_nat(4);
+        assert!(are_defeq_with_trace(&mut arena, &env, app, b).unwrap().is_none());
+    }
 }
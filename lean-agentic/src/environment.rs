@@ -3,9 +3,76 @@
 //! Stores all top-level definitions, axioms, and inductive types
 //! using persistent data structures for efficient cloning.
 
-use crate::symbol::SymbolId;
+use crate::arena::Arena;
+use crate::level::{Level, LevelArena, LevelId};
+use crate::serialize::{
+    deserialize_term_with, serialize_term_with, write_uint, IdDecoder, IdEncoder, Reader,
+};
+use crate::symbol::{SymbolId, SymbolTable};
 use crate::term::TermId;
+use crate::{Error, Result};
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Magic bytes at the start of an exported environment
+const MODULE_MAGIC: &[u8; 8] = b"LEANRENV";
+
+/// Version of the exported environment layout
+pub const MODULE_FORMAT_VERSION: u8 = 3;
+
+/// Source of generation stamps, shared by every environment in the process
+///
+/// Starts at 1 so that 0 never names a real environment state.
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+fn next_generation() -> u64 {
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Definitional transparency of a constant: `@[reducible]`, default, or `@[irreducible]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Reducibility {
+    /// Unfolded even under `Transparency::Reducible`
+    Reducible,
+
+    /// Unfolded under the default transparency
+    #[default]
+    Default,
+
+    /// Only unfolded under `Transparency::All`
+    Irreducible,
+}
+
+/// How eagerly constants are delta-unfolded during conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Transparency {
+    /// Unfold every constant with a body, including irreducible ones
+    All,
+
+    /// Unfold reducible and default constants
+    #[default]
+    Default,
+
+    /// Unfold only `@[reducible]` constants
+    Reducible,
+
+    /// Never unfold
+    None,
+}
+
+impl Transparency {
+    /// Check whether a constant with the given reducibility may be unfolded
+    pub fn allows(self, reducibility: Reducibility) -> bool {
+        match self {
+            Transparency::All => true,
+            Transparency::Default => reducibility != Reducibility::Irreducible,
+            Transparency::Reducible => reducibility == Reducibility::Reducible,
+            Transparency::None => false,
+        }
+    }
+}
 
 /// Attributes for declarations
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -13,6 +80,9 @@ pub struct Attributes {
     /// Is this definition reducible (can be unfolded)?
     pub reducible: bool,
 
+    /// Transparency level at which this definition may be unfolded
+    pub reducibility: Reducibility,
+
     /// Is this an instance (for type class resolution)?
     pub instance: bool,
 
@@ -27,6 +97,7 @@ impl Default for Attributes {
     fn default() -> Self {
         Self {
             reducible: true,
+            reducibility: Reducibility::Default,
             instance: false,
             recursor: false,
             custom: Vec::new(),
@@ -43,6 +114,22 @@ impl Attributes {
         }
     }
 
+    /// Create attributes for an `@[reducible]` definition
+    pub fn reducible() -> Self {
+        Self {
+            reducibility: Reducibility::Reducible,
+            ..Default::default()
+        }
+    }
+
+    /// Create attributes for an `@[irreducible]` definition
+    pub fn irreducible() -> Self {
+        Self {
+            reducibility: Reducibility::Irreducible,
+            ..Default::default()
+        }
+    }
+
     /// Create attributes for a type class instance
     pub fn instance() -> Self {
         Self {
@@ -156,6 +243,17 @@ impl Declaration {
         self.attrs.reducible && self.value.is_some()
     }
 
+    /// Set the reducibility of this declaration
+    pub fn with_reducibility(mut self, reducibility: Reducibility) -> Self {
+        self.attrs.reducibility = reducibility;
+        self
+    }
+
+    /// Check if this declaration may be unfolded at the given transparency
+    pub fn can_unfold(&self, transparency: Transparency) -> bool {
+        self.is_reducible() && transparency.allows(self.attrs.reducibility)
+    }
+
     /// Check if this declaration is opaque (cannot be unfolded)
     pub fn is_opaque(&self) -> bool {
         !self.attrs.reducible
@@ -210,6 +308,10 @@ pub struct Environment {
 
     /// Reverse lookup: constructor -> inductive
     constructor_to_ind: HashMap<SymbolId, SymbolId>,
+
+    /// Replaced by a fresh, process-wide unique stamp on every change, so
+    /// caches of unfolded terms can be invalidated
+    generation: u64,
 }
 
 impl Environment {
@@ -219,6 +321,7 @@ impl Environment {
             declarations: HashMap::new(),
             inductives: HashMap::new(),
             constructor_to_ind: HashMap::new(),
+            generation: next_generation(),
         }
     }
 
@@ -232,9 +335,21 @@ impl Environment {
         }
 
         self.declarations.insert(decl.name, decl);
+        self.generation = next_generation();
         Ok(())
     }
 
+    /// Add a declaration, replacing any existing one with the same name
+    ///
+    /// Used for redefinition in interactive sessions. Returns the previous
+    /// declaration, if any. The generation changes either way, so terms
+    /// normalized against the old definition are not reused.
+    pub fn add_or_replace(&mut self, decl: Declaration) -> Option<Declaration> {
+        let previous = self.declarations.insert(decl.name, decl);
+        self.generation = next_generation();
+        previous
+    }
+
     /// Get a declaration by name
     pub fn get_decl(&self, name: SymbolId) -> Option<&Declaration> {
         self.declarations.get(&name)
@@ -245,6 +360,23 @@ impl Environment {
         self.declarations.contains_key(&name)
     }
 
+    /// Change the reducibility of an existing declaration
+    pub fn set_reducibility(
+        &mut self,
+        name: SymbolId,
+        reducibility: Reducibility,
+    ) -> crate::Result<()> {
+        let decl = self.declarations.get_mut(&name).ok_or_else(|| {
+            crate::Error::NotFound(format!("Declaration not found: {:?}", name))
+        })?;
+
+        if decl.attrs.reducibility != reducibility {
+            decl.attrs.reducibility = reducibility;
+            self.generation = next_generation();
+        }
+        Ok(())
+    }
+
     /// Add an inductive type
     pub fn add_inductive(&mut self, ind: InductiveDecl) -> crate::Result<()> {
         // Add constructor mappings
@@ -253,6 +385,7 @@ impl Environment {
         }
 
         self.inductives.insert(ind.name, ind);
+        self.generation = next_generation();
         Ok(())
     }
 
@@ -282,12 +415,111 @@ impl Environment {
         self.declarations.len()
     }
 
+    /// Get the generation counter
+    ///
+    /// Changes whenever a declaration is added or replaced, so a cached result computed
+    /// at one generation may be stale at another. Stamps are unique across all
+    /// environments, so forks that diverge never share a generation.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Write the declarations to `path`, in the spirit of a Lean `.olean`
+    ///
+    /// Terms are read from `arena` and written in the `serialize_term`
+    /// encoding. Symbols and levels are written as indices into a name table
+    /// and a level table stored in the file, so any session can import it.
+    /// Inductive types follow the declarations, with their constructors and
+    /// recursor. The file ends with a checksum over everything before it.
+    pub fn export(
+        &self,
+        path: impl AsRef<Path>,
+        arena: &Arena,
+        symbols: &SymbolTable,
+        levels: &LevelArena,
+    ) -> Result<()> {
+        let mut decls: Vec<_> = self.declarations.values().collect();
+        decls.sort_by_key(|decl| decl.name);
+
+        let mut tables = ModuleTables::new(symbols, levels);
+        let mut body = Vec::new();
+        write_uint(&mut body, decls.len() as u64);
+        for decl in decls {
+            write_decl(&mut body, decl, arena, &mut tables)?;
+        }
+
+        let mut inductives: Vec<_> = self.inductives.values().collect();
+        inductives.sort_by_key(|ind| ind.name);
+        write_uint(&mut body, inductives.len() as u64);
+        for ind in inductives {
+            write_inductive(&mut body, ind, arena, &mut tables)?;
+        }
+
+        let mut out = MODULE_MAGIC.to_vec();
+        out.push(MODULE_FORMAT_VERSION);
+        tables.write(&mut out);
+        out.extend_from_slice(&body);
+        out.extend_from_slice(&checksum(&out).to_le_bytes());
+
+        std::fs::write(path.as_ref(), out)
+            .map_err(|e| Error::Io(format!("{}: {}", path.as_ref().display(), e)))
+    }
+
+    /// Load an environment written by `export`
+    ///
+    /// Names are interned into `symbols`, levels into `levels` and terms into
+    /// `arena`, none of which need to be the ones the file was exported from.
+    pub fn import(
+        path: impl AsRef<Path>,
+        arena: &mut Arena,
+        symbols: &SymbolTable,
+        levels: &mut LevelArena,
+    ) -> Result<Self> {
+        let bytes = std::fs::read(path.as_ref())
+            .map_err(|e| Error::Io(format!("{}: {}", path.as_ref().display(), e)))?;
+
+        let header = MODULE_MAGIC.len() + 1;
+        if bytes.len() < header + 8 || &bytes[..MODULE_MAGIC.len()] != MODULE_MAGIC {
+            return Err(Error::Decode("not an exported environment".to_string()));
+        }
+
+        let (body, sum) = bytes.split_at(bytes.len() - 8);
+        if checksum(body).to_le_bytes() != sum {
+            return Err(Error::Decode("environment checksum mismatch".to_string()));
+        }
+
+        let version = body[MODULE_MAGIC.len()];
+        if version != MODULE_FORMAT_VERSION {
+            return Err(Error::Decode(format!(
+                "unsupported environment format version {} (expected {})",
+                version, MODULE_FORMAT_VERSION
+            )));
+        }
+
+        let mut reader = Reader::new(&body[header..]);
+        let ids = ModuleIds::read(&mut reader, symbols, levels)?;
+        let mut env = Self::new();
+        for _ in 0..reader.uint()? {
+            env.add_decl(read_decl(&mut reader, arena, &ids)?)?;
+        }
+        for _ in 0..reader.uint()? {
+            env.add_inductive(read_inductive(&mut reader, arena, &ids)?)?;
+        }
+        if !reader.is_done() {
+            return Err(Error::Decode("trailing bytes after inductive types".to_string()));
+        }
+        Ok(env)
+    }
+
     /// Clone the environment (cheap due to persistent data structures)
+    ///
+    /// The fork keeps the parent's generation until either side changes.
     pub fn fork(&self) -> Self {
         Self {
             declarations: self.declarations.clone(),
             inductives: self.inductives.clone(),
             constructor_to_ind: self.constructor_to_ind.clone(),
+            generation: self.generation,
         }
     }
 }
@@ -298,6 +530,339 @@ impl Default for Environment {
     }
 }
 
+/// FNV-1a over the exported bytes
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_uint(out, bytes.len() as u64);
+    out.extend_from_slice(bytes);
+}
+
+const LEVEL_ZERO: u8 = 0;
+const LEVEL_CONST: u8 = 1;
+const LEVEL_PARAM: u8 = 2;
+const LEVEL_SUCC: u8 = 3;
+const LEVEL_MAX: u8 = 4;
+const LEVEL_IMAX: u8 = 5;
+
+/// Name and level tables built while exporting
+///
+/// Each symbol or level is given the next free index the first time it is
+/// written. Levels are stored in post-order, so a level only refers to
+/// entries before it.
+struct ModuleTables<'a> {
+    symbols: &'a SymbolTable,
+    levels: &'a LevelArena,
+    names: Vec<Arc<str>>,
+    name_index: HashMap<SymbolId, u64>,
+    level_nodes: Vec<u8>,
+    level_count: u64,
+    level_index: HashMap<LevelId, u64>,
+}
+
+impl<'a> ModuleTables<'a> {
+    fn new(symbols: &'a SymbolTable, levels: &'a LevelArena) -> Self {
+        Self {
+            symbols,
+            levels,
+            names: Vec::new(),
+            name_index: HashMap::new(),
+            level_nodes: Vec::new(),
+            level_count: 0,
+            level_index: HashMap::new(),
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        write_uint(out, self.names.len() as u64);
+        for name in &self.names {
+            write_bytes(out, name.as_bytes());
+        }
+        write_uint(out, self.level_count);
+        out.extend_from_slice(&self.level_nodes);
+    }
+}
+
+impl IdEncoder for ModuleTables<'_> {
+    fn symbol(&mut self, id: SymbolId) -> Result<u64> {
+        if let Some(&index) = self.name_index.get(&id) {
+            return Ok(index);
+        }
+        let name = self.symbols.resolve(id).ok_or_else(|| {
+            Error::NotFound(format!("symbol {} is not in the symbol table", id.raw()))
+        })?;
+        let index = self.names.len() as u64;
+        self.names.push(name);
+        self.name_index.insert(id, index);
+        Ok(index)
+    }
+
+    fn level(&mut self, id: LevelId) -> Result<u64> {
+        if let Some(&index) = self.level_index.get(&id) {
+            return Ok(index);
+        }
+        let level = self.levels.get(id).cloned().ok_or_else(|| {
+            Error::NotFound(format!("level {} is not in the level arena", id.raw()))
+        })?;
+
+        let mut node = Vec::new();
+        match level {
+            Level::Zero => node.push(LEVEL_ZERO),
+            Level::Const(n) => {
+                node.push(LEVEL_CONST);
+                write_uint(&mut node, n as u64);
+            }
+            Level::Param(n) => {
+                node.push(LEVEL_PARAM);
+                write_uint(&mut node, n as u64);
+            }
+            Level::Succ(inner) => {
+                let inner = self.level(inner)?;
+                node.push(LEVEL_SUCC);
+                write_uint(&mut node, inner);
+            }
+            Level::Max(a, b) | Level::IMax(a, b) => {
+                let tag = if matches!(level, Level::Max(..)) { LEVEL_MAX } else { LEVEL_IMAX };
+                let (a, b) = (self.level(a)?, self.level(b)?);
+                node.push(tag);
+                write_uint(&mut node, a);
+                write_uint(&mut node, b);
+            }
+        }
+
+        let index = self.level_count;
+        self.level_nodes.extend_from_slice(&node);
+        self.level_count += 1;
+        self.level_index.insert(id, index);
+        Ok(index)
+    }
+}
+
+/// Table indices of an imported module, re-interned into the importing session
+struct ModuleIds {
+    symbols: Vec<SymbolId>,
+    levels: Vec<LevelId>,
+}
+
+impl ModuleIds {
+    fn read(reader: &mut Reader<'_>, symbols: &SymbolTable, levels: &mut LevelArena) -> Result<Self> {
+        let names = (0..reader.uint()?)
+            .map(|_| {
+                let len = reader.uint()? as usize;
+                let name = std::str::from_utf8(reader.take(len)?)
+                    .map_err(|_| Error::Decode("name is not UTF-8".to_string()))?;
+                Ok(symbols.intern(name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut ids = Self { symbols: names, levels: Vec::new() };
+        for _ in 0..reader.uint()? {
+            let level = match reader.byte()? {
+                LEVEL_ZERO => Level::Zero,
+                LEVEL_CONST => Level::Const(reader.u32()?),
+                LEVEL_PARAM => Level::Param(reader.u32()?),
+                LEVEL_SUCC => Level::Succ(ids.level(reader.u32()?)?),
+                LEVEL_MAX => Level::Max(ids.level(reader.u32()?)?, ids.level(reader.u32()?)?),
+                LEVEL_IMAX => Level::IMax(ids.level(reader.u32()?)?, ids.level(reader.u32()?)?),
+                other => return Err(Error::Decode(format!("unknown level tag {}", other))),
+            };
+            ids.levels.push(levels.intern(level));
+        }
+        Ok(ids)
+    }
+}
+
+impl IdDecoder for ModuleIds {
+    fn symbol(&self, raw: u32) -> Result<SymbolId> {
+        self.symbols.get(raw as usize).copied().ok_or_else(|| {
+            Error::Decode(format!("name {} is not in the module's name table", raw))
+        })
+    }
+
+    fn level(&self, raw: u32) -> Result<LevelId> {
+        self.levels.get(raw as usize).copied().ok_or_else(|| {
+            Error::Decode(format!("level {} is not in the module's level table", raw))
+        })
+    }
+}
+
+fn write_decl(
+    out: &mut Vec<u8>,
+    decl: &Declaration,
+    arena: &Arena,
+    tables: &mut ModuleTables<'_>,
+) -> Result<()> {
+    write_uint(out, tables.symbol(decl.name)?);
+    write_uint(out, decl.level_params.len() as u64);
+    for param in &decl.level_params {
+        write_uint(out, *param as u64);
+    }
+    out.push(match decl.kind {
+        DeclKind::Def => 0,
+        DeclKind::Axiom => 1,
+        DeclKind::Theorem => 2,
+        DeclKind::Inductive => 3,
+        DeclKind::Constructor => 4,
+        DeclKind::Recursor => 5,
+    });
+
+    let attrs = &decl.attrs;
+    out.push(attrs.reducible as u8);
+    out.push(match attrs.reducibility {
+        Reducibility::Reducible => 0,
+        Reducibility::Default => 1,
+        Reducibility::Irreducible => 2,
+    });
+    out.push(attrs.instance as u8);
+    out.push(attrs.recursor as u8);
+    write_uint(out, attrs.custom.len() as u64);
+    for attr in &attrs.custom {
+        write_bytes(out, attr.as_bytes());
+    }
+
+    write_bytes(out, &serialize_term_with(arena, decl.ty, tables)?);
+    match decl.value {
+        Some(value) => {
+            out.push(1);
+            write_bytes(out, &serialize_term_with(arena, value, tables)?);
+        }
+        None => out.push(0),
+    }
+    Ok(())
+}
+
+fn read_decl(reader: &mut Reader<'_>, arena: &mut Arena, ids: &ModuleIds) -> Result<Declaration> {
+    let name = ids.symbol(reader.u32()?)?;
+    let level_params = (0..reader.uint()?)
+        .map(|_| reader.u32())
+        .collect::<Result<Vec<_>>>()?;
+    let kind = match reader.byte()? {
+        0 => DeclKind::Def,
+        1 => DeclKind::Axiom,
+        2 => DeclKind::Theorem,
+        3 => DeclKind::Inductive,
+        4 => DeclKind::Constructor,
+        5 => DeclKind::Recursor,
+        other => return Err(Error::Decode(format!("unknown declaration kind {}", other))),
+    };
+
+    let reducible = reader.byte()? != 0;
+    let reducibility = match reader.byte()? {
+        0 => Reducibility::Reducible,
+        1 => Reducibility::Default,
+        2 => Reducibility::Irreducible,
+        other => return Err(Error::Decode(format!("unknown reducibility {}", other))),
+    };
+    let instance = reader.byte()? != 0;
+    let recursor = reader.byte()? != 0;
+    let custom = (0..reader.uint()?)
+        .map(|_| {
+            let len = reader.uint()? as usize;
+            String::from_utf8(reader.take(len)?.to_vec())
+                .map_err(|_| Error::Decode("attribute is not UTF-8".to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut read_term = |reader: &mut Reader<'_>| -> Result<TermId> {
+        let len = reader.uint()? as usize;
+        deserialize_term_with(arena, reader.take(len)?, ids)
+    };
+    let ty = read_term(reader)?;
+    let value = match reader.byte()? {
+        0 => None,
+        _ => Some(read_term(reader)?),
+    };
+
+    Ok(Declaration {
+        name,
+        level_params,
+        ty,
+        value,
+        attrs: Attributes { reducible, reducibility, instance, recursor, custom },
+        kind,
+    })
+}
+
+fn write_inductive(
+    out: &mut Vec<u8>,
+    ind: &InductiveDecl,
+    arena: &Arena,
+    tables: &mut ModuleTables<'_>,
+) -> Result<()> {
+    write_uint(out, tables.symbol(ind.name)?);
+    write_uint(out, ind.level_params.len() as u64);
+    for param in &ind.level_params {
+        write_uint(out, *param as u64);
+    }
+    write_bytes(out, &serialize_term_with(arena, ind.ty, tables)?);
+    write_uint(out, ind.num_params as u64);
+    write_uint(out, ind.num_indices as u64);
+
+    write_uint(out, ind.constructors.len() as u64);
+    for ctor in &ind.constructors {
+        write_uint(out, tables.symbol(ctor.name)?);
+        write_bytes(out, &serialize_term_with(arena, ctor.ty, tables)?);
+        write_uint(out, ctor.num_fields as u64);
+    }
+
+    match ind.recursor {
+        Some(recursor) => {
+            out.push(1);
+            write_uint(out, tables.symbol(recursor)?);
+        }
+        None => out.push(0),
+    }
+    Ok(())
+}
+
+fn read_inductive(
+    reader: &mut Reader<'_>,
+    arena: &mut Arena,
+    ids: &ModuleIds,
+) -> Result<InductiveDecl> {
+    let mut read_term = |reader: &mut Reader<'_>| -> Result<TermId> {
+        let len = reader.uint()? as usize;
+        deserialize_term_with(arena, reader.take(len)?, ids)
+    };
+
+    let name = ids.symbol(reader.u32()?)?;
+    let level_params = (0..reader.uint()?)
+        .map(|_| reader.u32())
+        .collect::<Result<Vec<_>>>()?;
+    let ty = read_term(reader)?;
+    let num_params = reader.u32()?;
+    let num_indices = reader.u32()?;
+
+    let constructors = (0..reader.uint()?)
+        .map(|_| {
+            Ok(ConstructorDecl {
+                name: ids.symbol(reader.u32()?)?,
+                ty: read_term(reader)?,
+                num_fields: reader.u32()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let recursor = match reader.byte()? {
+        0 => None,
+        _ => Some(ids.symbol(reader.u32()?)?),
+    };
+
+    Ok(InductiveDecl {
+        name,
+        level_params,
+        ty,
+        num_params,
+        num_indices,
+        constructors,
+        recursor,
+    })
+}
+
 impl Clone for Environment {
     fn clone(&self) -> Self {
         self.fork()
@@ -351,6 +916,41 @@ mod tests {
         assert_eq!(env1.num_decls(), env2.num_decls());
     }
 
+    #[test]
+    fn test_generation_tracks_changes() {
+        let mut env = Environment::new();
+        let start = env.generation();
+
+        env.add_decl(Declaration::axiom(SymbolId::new(0), vec![], TermId::new(0))).unwrap();
+        assert!(env.generation() > start);
+
+        // A rejected duplicate does not change the environment
+        let after_add = env.generation();
+        assert!(env.add_decl(Declaration::axiom(SymbolId::new(0), vec![], TermId::new(0))).is_err());
+        assert_eq!(env.generation(), after_add);
+        assert_eq!(env.fork().generation(), after_add);
+
+        // Replacing an existing declaration is a change
+        let previous = env.add_or_replace(Declaration::axiom(SymbolId::new(0), vec![], TermId::new(1)));
+        assert_eq!(previous.map(|d| d.ty), Some(TermId::new(0)));
+        assert!(env.generation() > after_add);
+        assert_eq!(env.get_decl(SymbolId::new(0)).unwrap().ty, TermId::new(1));
+    }
+
+    #[test]
+    fn test_diverging_forks_have_distinct_generations() {
+        let env = Environment::new();
+        let mut left = env.fork();
+        let mut right = env.fork();
+
+        left.add_decl(Declaration::axiom(SymbolId::new(0), vec![], TermId::new(0))).unwrap();
+        right.add_decl(Declaration::axiom(SymbolId::new(1), vec![], TermId::new(0))).unwrap();
+
+        assert_ne!(left.generation(), right.generation());
+        assert_ne!(left.generation(), env.generation());
+        assert_ne!(Environment::new().generation(), Environment::new().generation());
+    }
+
     #[test]
     fn test_reducibility() {
         let name = SymbolId::new(0);
@@ -364,4 +964,65 @@ mod tests {
         let theorem = Declaration::theorem(name, vec![], TermId::new(0), TermId::new(1));
         assert!(!theorem.is_reducible()); // Theorems are opaque
     }
+
+    #[test]
+    fn test_transparency_levels() {
+        let def = Declaration::def(SymbolId::new(0), vec![], TermId::new(0), TermId::new(1));
+        let reducible = def.clone().with_reducibility(Reducibility::Reducible);
+        let irreducible = def.clone().with_reducibility(Reducibility::Irreducible);
+
+        assert!(def.can_unfold(Transparency::Default));
+        assert!(!def.can_unfold(Transparency::Reducible));
+        assert!(reducible.can_unfold(Transparency::Reducible));
+        assert!(!irreducible.can_unfold(Transparency::Default));
+        assert!(irreducible.can_unfold(Transparency::All));
+        assert!(!reducible.can_unfold(Transparency::None));
+
+        // Theorems stay opaque at every transparency
+        let theorem = Declaration::theorem(SymbolId::new(1), vec![], TermId::new(0), TermId::new(1));
+        assert!(!theorem.can_unfold(Transparency::All));
+    }
+
+    #[test]
+    fn test_export_rebuilds_universe_levels() {
+        use crate::level::{Level, LevelArena};
+        use crate::symbol::SymbolTable;
+        use crate::term::TermKind;
+
+        // The elaborator cannot produce level parameters yet, so this
+        // universe-polymorphic declaration is built by hand:
+        // poly.{u} : Sort (max u 1) := Sort (max u 1)
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let (u, one) = (levels.param(0), levels.constant(1));
+        let poly_ty = arena.mk_sort(levels.max(u, one));
+        env.add_decl(Declaration::theorem(symbols.intern("poly"), vec![0], poly_ty, poly_ty)).unwrap();
+
+        let path = std::env::temp_dir().join(format!("leanr-levels-{}.olean", std::process::id()));
+        env.export(&path, &arena, &symbols, &levels).unwrap();
+
+        // A level arena whose IDs differ from the exporter's
+        let fresh_symbols = SymbolTable::new();
+        let mut fresh_levels = LevelArena::new();
+        fresh_levels.constant(7);
+        let mut fresh = Arena::new();
+        let loaded =
+            Environment::import(&path, &mut fresh, &fresh_symbols, &mut fresh_levels).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let restored = loaded.get_decl(fresh_symbols.intern("poly")).unwrap();
+        assert_eq!(restored.level_params, vec![0]);
+        match fresh.kind(restored.ty) {
+            Some(TermKind::Sort(level)) => match fresh_levels.get(*level) {
+                Some(Level::Max(a, b)) => {
+                    assert_eq!(fresh_levels.get(*a), Some(&Level::Param(0)));
+                    assert_eq!(fresh_levels.get(*b), Some(&Level::Const(1)));
+                }
+                other => panic!("expected a max level, got {:?}", other),
+            },
+            other => panic!("expected a Sort, got {:?}", other),
+        }
+    }
 }
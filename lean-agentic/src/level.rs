@@ -196,6 +196,169 @@ impl Default for LevelArena {
     }
 }
 
+/// Adjacency of `(target, strict)` edges between levels
+type ConstraintGraph = HashMap<LevelId, Vec<(LevelId, bool)>>;
+
+/// A constraint between two universe levels
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UniverseConstraint {
+    /// `u ≤ v`
+    Le(LevelId, LevelId),
+
+    /// `u < v`
+    Lt(LevelId, LevelId),
+}
+
+/// Universe constraints accumulated while elaborating a batch of declarations
+///
+/// Constraints form a graph where `u ≤ v` is a weak edge and `u < v` a
+/// strict one. The set is inconsistent exactly when some cycle contains a
+/// strict edge, since that would imply `u < u`.
+#[derive(Debug, Clone, Default)]
+pub struct UniverseConstraintSet {
+    constraints: Vec<UniverseConstraint>,
+}
+
+impl UniverseConstraintSet {
+    /// Create an empty constraint set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `u ≤ v`
+    pub fn add_le(&mut self, u: LevelId, v: LevelId) {
+        self.constraints.push(UniverseConstraint::Le(u, v));
+    }
+
+    /// Record `u < v`
+    pub fn add_lt(&mut self, u: LevelId, v: LevelId) {
+        self.constraints.push(UniverseConstraint::Lt(u, v));
+    }
+
+    /// Get the recorded constraints
+    pub fn constraints(&self) -> &[UniverseConstraint] {
+        &self.constraints
+    }
+
+    /// Get the number of recorded constraints
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    /// Check if no constraints have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Discard all recorded constraints
+    pub fn clear(&mut self) {
+        self.constraints.clear();
+    }
+
+    /// Check that the constraints are satisfiable
+    ///
+    /// Besides the recorded constraints, the structure of each mentioned
+    /// level contributes edges: `u < succ u`, `u ≤ max u v`,
+    /// `v ≤ imax u v`, `0 ≤ u`, and concrete levels are ordered by value.
+    pub fn check(&self, levels: &LevelArena) -> crate::Result<()> {
+        let mut edges: ConstraintGraph = HashMap::new();
+        let add = |edges: &mut ConstraintGraph, u, v, strict| {
+            edges.entry(u).or_default().push((v, strict));
+            edges.entry(v).or_default();
+        };
+
+        for c in &self.constraints {
+            match *c {
+                UniverseConstraint::Le(u, v) => add(&mut edges, u, v, false),
+                UniverseConstraint::Lt(u, v) => add(&mut edges, u, v, true),
+            }
+        }
+
+        // Structural edges, added until no new levels are discovered
+        let mut pending: Vec<LevelId> = edges.keys().copied().collect();
+        let mut seen: std::collections::HashSet<LevelId> = pending.iter().copied().collect();
+        while let Some(id) = pending.pop() {
+            let level = levels.get(id).ok_or_else(|| {
+                crate::Error::Internal(format!("Invalid level ID: {:?}", id))
+            })?;
+            let parts: Vec<(LevelId, bool)> = match level {
+                Level::Succ(inner) => vec![(*inner, true)],
+                Level::Max(a, b) => vec![(*a, false), (*b, false)],
+                // imax u 0 = 0, so only the codomain is bounded by imax
+                Level::IMax(_, b) => vec![(*b, false)],
+                _ => Vec::new(),
+            };
+            for (part, strict) in parts {
+                add(&mut edges, part, id, strict);
+                if seen.insert(part) {
+                    pending.push(part);
+                }
+            }
+        }
+
+        let mut concrete: Vec<(u32, LevelId)> = edges
+            .keys()
+            .filter_map(|&id| match levels.get(id) {
+                Some(Level::Zero) => Some((0, id)),
+                Some(Level::Const(n)) => Some((*n, id)),
+                _ => None,
+            })
+            .collect();
+        concrete.sort();
+        for pair in concrete.windows(2) {
+            add(&mut edges, pair[0].1, pair[1].1, pair[0].0 < pair[1].0);
+        }
+        if let Some(&(0, zero)) = concrete.first() {
+            let all: Vec<LevelId> = edges.keys().copied().collect();
+            for id in all {
+                if id != zero {
+                    add(&mut edges, zero, id, false);
+                }
+            }
+        }
+
+        // A strict edge u → v is inconsistent if v can reach u again
+        for (&u, targets) in &edges {
+            for &(v, strict) in targets {
+                if strict && Self::reaches(&edges, v, u) {
+                    return Err(crate::Error::UniverseError(format!(
+                        "universe cycle: {} < {} but {} ≤ {}",
+                        Self::describe(levels, u),
+                        Self::describe(levels, v),
+                        Self::describe(levels, v),
+                        Self::describe(levels, u),
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn reaches(edges: &ConstraintGraph, from: LevelId, to: LevelId) -> bool {
+        let mut stack = vec![from];
+        let mut visited = std::collections::HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+            if visited.insert(id) {
+                if let Some(next) = edges.get(&id) {
+                    stack.extend(next.iter().map(|&(v, _)| v));
+                }
+            }
+        }
+        false
+    }
+
+    fn describe(levels: &LevelArena, id: LevelId) -> String {
+        levels
+            .get(id)
+            .map(|l| l.to_string())
+            .unwrap_or_else(|| format!("?{}", id.0))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -239,4 +402,43 @@ mod tests {
 
         assert_eq!(normalized, zero);
     }
+
+    #[test]
+    fn test_universe_self_constraint_is_inconsistent() {
+        let mut arena = LevelArena::new();
+        let u = arena.param(0);
+
+        let mut set = UniverseConstraintSet::new();
+        set.add_lt(u, u);
+        assert!(matches!(set.check(&arena), Err(crate::Error::UniverseError(_))));
+
+        // Also caught through a longer cycle and through `succ`
+        let v = arena.param(1);
+        let succ_v = arena.succ(v);
+        let mut set = UniverseConstraintSet::new();
+        set.add_le(u, v);
+        set.add_le(succ_v, u);
+        assert!(set.check(&arena).is_err());
+    }
+
+    #[test]
+    fn test_universe_acyclic_constraints_pass() {
+        let mut arena = LevelArena::new();
+        let u = arena.param(0);
+        let v = arena.param(1);
+        let w = arena.param(2);
+        let one = arena.constant(1);
+
+        let mut set = UniverseConstraintSet::new();
+        set.add_le(u, v);
+        set.add_lt(v, w);
+        set.add_le(one, u);
+        set.add_le(u, u);
+        assert!(set.check(&arena).is_ok());
+
+        // Concrete levels are ordered: Type 1 ≤ Type 0 is rejected
+        let zero = arena.zero();
+        set.add_le(one, zero);
+        assert!(set.check(&arena).is_err());
+    }
 }
@@ -12,17 +12,25 @@ pub mod context;
 pub mod conversion;
 pub mod environment;
 pub mod level;
+pub mod minimize;
+pub mod serialize;
+pub mod shared_arena;
 pub mod symbol;
 pub mod term;
 pub mod typechecker;
 pub mod unification;
+pub mod visit;
 
 pub use arena::Arena;
 pub use context::Context;
-pub use environment::Environment;
-pub use level::{Level, LevelId};
-pub use symbol::{Symbol, SymbolId, SymbolTable};
+pub use environment::{Environment, Reducibility, Transparency};
+pub use level::{Level, LevelId, UniverseConstraintSet};
+pub use minimize::{minimize_proof, term_size};
+pub use serialize::{deserialize_term, serialize_term};
+pub use shared_arena::SharedArena;
+pub use symbol::{Symbol, SymbolId, SymbolStats, SymbolTable};
 pub use term::{Binder, Term, TermId, TermKind};
+pub use visit::{fold_term, map_term, TermVisitor};
 
 /// Result type for core operations
 pub type Result<T> = std::result::Result<T, Error>;
@@ -50,6 +58,15 @@ pub enum Error {
         actual: String,
     },
 
+    /// Resource budget exhausted before a result was reached
+    Timeout(String),
+
+    /// Malformed serialized data
+    Decode(String),
+
+    /// Filesystem failure while reading or writing a module
+    Io(String),
+
     /// Internal error (should not happen in production)
     Internal(String),
 }
@@ -64,6 +81,9 @@ impl std::fmt::Display for Error {
             Error::ConversionError { expected, actual } => {
                 write!(f, "Conversion check failed: {} ≠ {}", expected, actual)
             }
+            Error::Timeout(msg) => write!(f, "Timeout: {}", msg),
+            Error::Decode(msg) => write!(f, "Decode error: {}", msg),
+            Error::Io(msg) => write!(f, "I/O error: {}", msg),
             Error::Internal(msg) => write!(f, "Internal error: {}", msg),
         }
     }
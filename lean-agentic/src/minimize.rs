@@ -0,0 +1,178 @@
+//! Proof-term minimization
+//!
+//! Terms produced by elaboration and proof search carry structure that the
+//! kernel does not need: lets whose variable is never used, identity
+//! functions wrapped around arguments, and lambdas applied to arguments they
+//! ignore. `minimize_proof` removes these bottom-up so certificates stay
+//! small before they are signed.
+//!
+//! Every rewrite is a beta or zeta step whose eliminated binder does not
+//! occur in the body, so the minimized term has the same type as the input.
+
+use crate::arena::Arena;
+use crate::term::{TermId, TermKind};
+use crate::visit::fold_term;
+
+/// Shrink a proof term without changing its type
+///
+/// Removes unused let bindings, collapses `(fun x => x) e` to `e`, and
+/// drops the argument of `(fun x => b) e` when `b` does not mention `x`.
+/// Subterms that need no rewriting keep their original `TermId`.
+pub fn minimize_proof(arena: &mut Arena, term: TermId) -> TermId {
+    let Some(kind) = arena.kind(term).cloned() else {
+        return term;
+    };
+
+    match kind {
+        TermKind::App(func, arg) => {
+            let new_func = minimize_proof(arena, func);
+            let new_arg = minimize_proof(arena, arg);
+
+            if let Some(TermKind::Lam(_, body)) = arena.kind(new_func).cloned() {
+                // (fun x => x) e  ~>  e
+                if matches!(arena.kind(body), Some(TermKind::Var(0))) {
+                    return new_arg;
+                }
+                // (fun x => b) e  ~>  b, when x is dead in b
+                if !uses_bound_var(arena, body) {
+                    return arena.shift(body, 0, -1);
+                }
+            }
+
+            if new_func == func && new_arg == arg {
+                term
+            } else {
+                arena.mk_app(new_func, new_arg)
+            }
+        }
+
+        TermKind::Lam(binder, body) => {
+            let new_ty = minimize_proof(arena, binder.ty);
+            let new_body = minimize_proof(arena, body);
+            if new_ty == binder.ty && new_body == body {
+                term
+            } else {
+                arena.mk_lam(crate::term::Binder { ty: new_ty, ..binder }, new_body)
+            }
+        }
+
+        TermKind::Pi(binder, body) => {
+            let new_ty = minimize_proof(arena, binder.ty);
+            let new_body = minimize_proof(arena, body);
+            if new_ty == binder.ty && new_body == body {
+                term
+            } else {
+                arena.mk_pi(crate::term::Binder { ty: new_ty, ..binder }, new_body)
+            }
+        }
+
+        TermKind::Let(binder, value, body) => {
+            let new_body = minimize_proof(arena, body);
+
+            // let x := v in b  ~>  b, when x is dead in b
+            if !uses_bound_var(arena, new_body) {
+                return arena.shift(new_body, 0, -1);
+            }
+
+            let new_ty = minimize_proof(arena, binder.ty);
+            let new_value = minimize_proof(arena, value);
+            if new_ty == binder.ty && new_value == value && new_body == body {
+                term
+            } else {
+                arena.mk_let(crate::term::Binder { ty: new_ty, ..binder }, new_value, new_body)
+            }
+        }
+
+        TermKind::Sort(_)
+        | TermKind::Const(_, _)
+        | TermKind::Var(_)
+        | TermKind::MVar(_)
+        | TermKind::Lit(_) => term,
+    }
+}
+
+/// Number of nodes in a term, counting shared subterms once per occurrence
+pub fn term_size(arena: &Arena, term: TermId) -> usize {
+    fold_term(arena, term, 0, &mut |count, _, _, _| count + 1)
+}
+
+/// Whether the innermost bound variable (`#0`) occurs in `body`
+fn uses_bound_var(arena: &Arena, body: TermId) -> bool {
+    fold_term(arena, body, false, &mut |found, _, kind, depth| {
+        found || matches!(kind, TermKind::Var(idx) if *idx == depth)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+    use crate::environment::{Declaration, Environment};
+    use crate::level::LevelArena;
+    use crate::symbol::SymbolId;
+    use crate::term::Binder;
+    use crate::typechecker::{check, infer};
+
+    #[test]
+    fn test_minimize_shrinks_redundant_proof() {
+        let mut arena = Arena::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+
+        // axiom A : Type, axiom a : A
+        let type0 = arena.mk_sort(levels.zero());
+        let (a_name, p_name) = (SymbolId::new(0), SymbolId::new(1));
+        env.add_decl(Declaration::axiom(a_name, vec![], type0)).unwrap();
+        let a_ty = arena.mk_const(a_name, vec![]);
+        env.add_decl(Declaration::axiom(p_name, vec![], a_ty)).unwrap();
+        let a = arena.mk_const(p_name, vec![]);
+
+        // let y : A := a in (fun x : A => x) ((fun z : A => a) a)
+        let x = arena.mk_var(0);
+        let id = arena.mk_lam(Binder::new(SymbolId::new(2), a_ty), x);
+        let konst = arena.mk_lam(Binder::new(SymbolId::new(3), a_ty), a);
+        let dead = arena.mk_app(konst, a);
+        let body = arena.mk_app(id, dead);
+        let proof = arena.mk_let(Binder::new(SymbolId::new(4), a_ty), a, body);
+
+        let ty = infer(&mut arena, &mut levels, &env, &ctx, proof).unwrap();
+        let minimized = minimize_proof(&mut arena, proof);
+
+        assert_eq!(minimized, a);
+        assert!(term_size(&arena, minimized) < term_size(&arena, proof));
+        check(&mut arena, &mut levels, &env, &ctx, minimized, ty).unwrap();
+    }
+
+    #[test]
+    fn test_minimize_cascades_through_binders() {
+        let mut arena = Arena::new();
+        let mut levels = LevelArena::new();
+        let env = Environment::new();
+        let ctx = Context::new();
+
+        // fun (A : Type) => let u : Type := A in (fun (x : Type) => A) u
+        let type0 = arena.mk_sort(levels.zero());
+        let a_outer = arena.mk_var(0);
+        let a_in_konst = arena.mk_var(2);
+        let konst = arena.mk_lam(Binder::new(SymbolId::new(1), type0), a_in_konst);
+        let u = arena.mk_var(0);
+        let body = arena.mk_app(konst, u);
+        let let_term = arena.mk_let(Binder::new(SymbolId::new(2), type0), a_outer, body);
+        let proof = arena.mk_lam(Binder::new(SymbolId::new(0), type0), let_term);
+
+        let ty = infer(&mut arena, &mut levels, &env, &ctx, proof).unwrap();
+        let minimized = minimize_proof(&mut arena, proof);
+
+        // The dead application goes first, which leaves `u` unused as well
+        let expected = arena.mk_lam(Binder::new(SymbolId::new(0), type0), a_outer);
+        assert_eq!(minimized, expected);
+        assert!(term_size(&arena, minimized) < term_size(&arena, proof));
+
+        check(&mut arena, &mut levels, &env, &ctx, minimized, ty).unwrap();
+
+        // A let whose variable is used is left alone
+        let used = arena.mk_let(Binder::new(SymbolId::new(2), type0), type0, u);
+        assert_eq!(minimize_proof(&mut arena, used), used);
+    }
+}
@@ -0,0 +1,414 @@
+//! Compact binary encoding of arena terms
+//!
+//! A term is written as its DAG of distinct subterms in post-order, so a
+//! subterm shared in the arena is written once and every reference to it
+//! is a small back-index. Decoding interns the nodes in the same order,
+//! which restores the sharing through hash-consing.
+//!
+//! Symbol, level and metavariable IDs are written as raw indices: the
+//! reader must use the same symbol table and level arena as the writer.
+//! Environment modules instead write symbols and levels as indices into
+//! tables stored alongside the terms (see `Environment::export`).
+//!
+//! Layout: a version byte, the node count, then one node per entry as a
+//! tag byte followed by LEB128 fields. The root is the last node.
+
+use crate::arena::Arena;
+use crate::level::LevelId;
+use crate::symbol::SymbolId;
+use crate::term::{Binder, BinderInfo, Literal, MetaVarId, TermId, TermKind};
+use crate::visit::subterms;
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+/// Version byte at the start of every encoded term
+pub const FORMAT_VERSION: u8 = 1;
+
+const TAG_SORT: u8 = 0;
+const TAG_CONST: u8 = 1;
+const TAG_VAR: u8 = 2;
+const TAG_APP: u8 = 3;
+const TAG_LAM: u8 = 4;
+const TAG_PI: u8 = 5;
+const TAG_LET: u8 = 6;
+const TAG_MVAR: u8 = 7;
+const TAG_NAT: u8 = 8;
+const TAG_STRING: u8 = 9;
+
+/// How symbol and level IDs are written
+pub(crate) trait IdEncoder {
+    fn symbol(&mut self, id: SymbolId) -> Result<u64>;
+    fn level(&mut self, id: LevelId) -> Result<u64>;
+}
+
+/// How written symbol and level IDs are read back
+pub(crate) trait IdDecoder {
+    fn symbol(&self, raw: u32) -> Result<SymbolId>;
+    fn level(&self, raw: u32) -> Result<LevelId>;
+}
+
+/// IDs written as their raw indices
+pub(crate) struct RawIds;
+
+impl IdEncoder for RawIds {
+    fn symbol(&mut self, id: SymbolId) -> Result<u64> {
+        Ok(id.raw() as u64)
+    }
+
+    fn level(&mut self, id: LevelId) -> Result<u64> {
+        Ok(id.raw() as u64)
+    }
+}
+
+impl IdDecoder for RawIds {
+    fn symbol(&self, raw: u32) -> Result<SymbolId> {
+        Ok(SymbolId::new(raw))
+    }
+
+    fn level(&self, raw: u32) -> Result<LevelId> {
+        Ok(LevelId::new(raw))
+    }
+}
+
+/// Encode a term and everything it references
+pub fn serialize_term(arena: &Arena, term: TermId) -> Result<Vec<u8>> {
+    serialize_term_with(arena, term, &mut RawIds)
+}
+
+/// Encode a term, writing its symbol and level IDs through `ids`
+pub(crate) fn serialize_term_with(
+    arena: &Arena,
+    term: TermId,
+    ids: &mut impl IdEncoder,
+) -> Result<Vec<u8>> {
+    let mut order = Vec::new();
+    let mut index = HashMap::new();
+    collect(arena, term, &mut order, &mut index)?;
+
+    let mut out = vec![FORMAT_VERSION];
+    write_uint(&mut out, order.len() as u64);
+    for id in &order {
+        let kind = arena.kind(*id).ok_or_else(|| missing(*id))?;
+        write_node(&mut out, kind, &index, ids)?;
+    }
+    Ok(out)
+}
+
+/// Decode a term produced by `serialize_term`, interning it into `arena`
+pub fn deserialize_term(arena: &mut Arena, bytes: &[u8]) -> Result<TermId> {
+    deserialize_term_with(arena, bytes, &RawIds)
+}
+
+/// Decode a term produced by `serialize_term_with`, reading its IDs through `ids`
+pub(crate) fn deserialize_term_with(
+    arena: &mut Arena,
+    bytes: &[u8],
+    ids: &impl IdDecoder,
+) -> Result<TermId> {
+    let mut reader = Reader::new(bytes);
+    let term = reader.term(arena, ids)?;
+    if !reader.is_done() {
+        return Err(Error::Decode(format!(
+            "{} trailing bytes after term",
+            bytes.len() - reader.pos
+        )));
+    }
+    Ok(term)
+}
+
+/// Post-order walk assigning each distinct subterm an index
+fn collect(
+    arena: &Arena,
+    term: TermId,
+    order: &mut Vec<TermId>,
+    index: &mut HashMap<TermId, u64>,
+) -> Result<()> {
+    if index.contains_key(&term) {
+        return Ok(());
+    }
+    let kind = arena.kind(term).ok_or_else(|| missing(term))?;
+    for (child, _) in subterms(kind) {
+        collect(arena, child, order, index)?;
+    }
+    index.insert(term, order.len() as u64);
+    order.push(term);
+    Ok(())
+}
+
+fn missing(term: TermId) -> Error {
+    Error::NotFound(format!("term {} is not in the arena", term.raw()))
+}
+
+fn write_node(
+    out: &mut Vec<u8>,
+    kind: &TermKind,
+    index: &HashMap<TermId, u64>,
+    ids: &mut impl IdEncoder,
+) -> Result<()> {
+    let mut write_binder = |out: &mut Vec<u8>, binder: &Binder| -> Result<()> {
+        write_uint(out, ids.symbol(binder.name)?);
+        write_uint(out, index[&binder.ty]);
+        out.push(binder.implicit as u8);
+        out.push(match binder.info {
+            BinderInfo::Default => 0,
+            BinderInfo::Implicit => 1,
+            BinderInfo::StrictImplicit => 2,
+            BinderInfo::InstImplicit => 3,
+        });
+        Ok(())
+    };
+
+    match kind {
+        TermKind::Sort(level) => {
+            out.push(TAG_SORT);
+            write_uint(out, ids.level(*level)?);
+        }
+        TermKind::Const(name, levels) => {
+            out.push(TAG_CONST);
+            write_uint(out, ids.symbol(*name)?);
+            write_uint(out, levels.len() as u64);
+            for level in levels {
+                write_uint(out, ids.level(*level)?);
+            }
+        }
+        TermKind::Var(idx) => {
+            out.push(TAG_VAR);
+            write_uint(out, *idx as u64);
+        }
+        TermKind::App(func, arg) => {
+            out.push(TAG_APP);
+            write_uint(out, index[func]);
+            write_uint(out, index[arg]);
+        }
+        TermKind::Lam(binder, body) | TermKind::Pi(binder, body) => {
+            out.push(if matches!(kind, TermKind::Lam(..)) { TAG_LAM } else { TAG_PI });
+            write_binder(out, binder)?;
+            write_uint(out, index[body]);
+        }
+        TermKind::Let(binder, value, body) => {
+            out.push(TAG_LET);
+            write_binder(out, binder)?;
+            write_uint(out, index[value]);
+            write_uint(out, index[body]);
+        }
+        TermKind::MVar(id) => {
+            out.push(TAG_MVAR);
+            write_uint(out, id.raw() as u64);
+        }
+        TermKind::Lit(Literal::Nat(n)) => {
+            out.push(TAG_NAT);
+            write_uint(out, *n);
+        }
+        TermKind::Lit(Literal::String(s)) => {
+            out.push(TAG_STRING);
+            write_uint(out, s.len() as u64);
+            out.extend_from_slice(s.as_bytes());
+        }
+    }
+    Ok(())
+}
+
+/// LEB128 unsigned integer
+pub(crate) fn write_uint(out: &mut Vec<u8>, mut n: u64) {
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Cursor over encoded bytes
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    /// Whether every byte has been consumed
+    pub(crate) fn is_done(&self) -> bool {
+        self.pos == self.bytes.len()
+    }
+
+    fn term(&mut self, arena: &mut Arena, ids: &impl IdDecoder) -> Result<TermId> {
+        let version = self.byte()?;
+        if version != FORMAT_VERSION {
+            return Err(Error::Decode(format!(
+                "unsupported term format version {} (expected {})",
+                version, FORMAT_VERSION
+            )));
+        }
+
+        let count = self.uint()?;
+        if count == 0 {
+            return Err(Error::Decode("empty term encoding".to_string()));
+        }
+
+        let mut nodes: Vec<TermId> = Vec::new();
+        for _ in 0..count {
+            let kind = self.node(&nodes, ids)?;
+            nodes.push(arena.intern(kind));
+        }
+        Ok(*nodes.last().expect("count is non-zero"))
+    }
+
+    fn node(&mut self, nodes: &[TermId], ids: &impl IdDecoder) -> Result<TermKind> {
+        Ok(match self.byte()? {
+            TAG_SORT => TermKind::Sort(ids.level(self.u32()?)?),
+            TAG_CONST => {
+                let name = ids.symbol(self.u32()?)?;
+                let len = self.uint()?;
+                let levels = (0..len)
+                    .map(|_| ids.level(self.u32()?))
+                    .collect::<Result<Vec<_>>>()?;
+                TermKind::Const(name, levels)
+            }
+            TAG_VAR => TermKind::Var(self.u32()?),
+            TAG_APP => TermKind::App(self.term_ref(nodes)?, self.term_ref(nodes)?),
+            TAG_LAM => TermKind::Lam(self.binder(nodes, ids)?, self.term_ref(nodes)?),
+            TAG_PI => TermKind::Pi(self.binder(nodes, ids)?, self.term_ref(nodes)?),
+            TAG_LET => TermKind::Let(
+                self.binder(nodes, ids)?,
+                self.term_ref(nodes)?,
+                self.term_ref(nodes)?,
+            ),
+            TAG_MVAR => TermKind::MVar(MetaVarId::new(self.u32()?)),
+            TAG_NAT => TermKind::Lit(Literal::Nat(self.uint()?)),
+            TAG_STRING => {
+                let len = self.uint()? as usize;
+                let bytes = self.take(len)?;
+                let s = String::from_utf8(bytes.to_vec())
+                    .map_err(|_| Error::Decode("string literal is not UTF-8".to_string()))?;
+                TermKind::Lit(Literal::String(s))
+            }
+            tag => return Err(Error::Decode(format!("unknown term tag {}", tag))),
+        })
+    }
+
+    fn binder(&mut self, nodes: &[TermId], ids: &impl IdDecoder) -> Result<Binder> {
+        let name = ids.symbol(self.u32()?)?;
+        let ty = self.term_ref(nodes)?;
+        let implicit = self.byte()? != 0;
+        let info = match self.byte()? {
+            0 => BinderInfo::Default,
+            1 => BinderInfo::Implicit,
+            2 => BinderInfo::StrictImplicit,
+            3 => BinderInfo::InstImplicit,
+            other => return Err(Error::Decode(format!("unknown binder info {}", other))),
+        };
+        Ok(Binder { name, ty, implicit, info })
+    }
+
+    /// Back-reference to an earlier node
+    fn term_ref(&mut self, nodes: &[TermId]) -> Result<TermId> {
+        let index = self.uint()? as usize;
+        nodes.get(index).copied().ok_or_else(|| {
+            Error::Decode(format!("reference to node {} before it is defined", index))
+        })
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32> {
+        u32::try_from(self.uint()?).map_err(|_| Error::Decode("index exceeds 32 bits".to_string()))
+    }
+
+    pub(crate) fn uint(&mut self) -> Result<u64> {
+        let mut n: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+        Err(Error::Decode("integer is too long".to_string()))
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| Error::Decode("unexpected end of input".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::LevelArena;
+    use crate::symbol::SymbolTable;
+
+    #[test]
+    fn test_round_trip_preserves_term_ids() {
+        let mut arena = Arena::new();
+        let mut levels = LevelArena::new();
+        let symbols = SymbolTable::new();
+
+        let zero = levels.zero();
+        let ty = arena.mk_sort(zero);
+        let nat = arena.mk_const(symbols.intern("Nat"), vec![]);
+        let x = arena.mk_var(0);
+        let id_fn = arena.mk_lam(Binder::new(symbols.intern("x"), nat), x);
+        let shared = arena.mk_app(id_fn, x);
+        let pair = arena.mk_app(shared, shared);
+        let pi = arena.intern(TermKind::Pi(Binder::implicit(symbols.intern("A"), ty), pair));
+        let with_let = arena.intern(TermKind::Let(Binder::new(symbols.intern("y"), nat), pi, x));
+        let greeting = arena.intern(TermKind::Lit(Literal::String("héllo".to_string())));
+        let big = arena.intern(TermKind::Lit(Literal::Nat(1 << 40)));
+        let lits = arena.mk_app(greeting, big);
+        let mvar = arena.intern(TermKind::MVar(MetaVarId::new(7)));
+
+        for term in [ty, nat, x, id_fn, pair, pi, with_let, lits, mvar] {
+            let bytes = serialize_term(&arena, term).unwrap();
+            assert_eq!(deserialize_term(&mut arena, &bytes).unwrap(), term);
+        }
+
+        // The shared application is written once
+        let bytes = serialize_term(&arena, pair).unwrap();
+        assert_eq!(bytes[1], 5); // nat, x, id_fn, shared, pair
+    }
+
+    #[test]
+    fn test_round_trip_into_fresh_arena() {
+        let symbols = SymbolTable::new();
+        let mut source = Arena::new();
+        let nat = source.mk_const(symbols.intern("Nat"), vec![]);
+        let x = source.mk_var(0);
+        let lam = source.mk_lam(Binder::new(symbols.intern("x"), nat), x);
+        let term = source.mk_app(lam, lam);
+        let bytes = serialize_term(&source, term).unwrap();
+
+        let mut target = Arena::new();
+        let loaded = deserialize_term(&mut target, &bytes).unwrap();
+        assert_eq!(target.terms(), 4);
+        match target.kind(loaded) {
+            Some(TermKind::App(f, a)) => assert_eq!(f, a),
+            other => panic!("expected application, got {:?}", other),
+        }
+        assert_eq!(serialize_term(&target, loaded).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_malformed_input_is_rejected() {
+        let mut arena = Arena::new();
+        let x = arena.mk_var(3);
+        let bytes = serialize_term(&arena, x).unwrap();
+
+        assert!(deserialize_term(&mut arena, &bytes[..bytes.len() - 1]).is_err());
+        assert!(deserialize_term(&mut arena, &[FORMAT_VERSION + 1, 1, TAG_VAR, 0]).is_err());
+        // An application referring to a node that was never written
+        assert!(deserialize_term(&mut arena, &[FORMAT_VERSION, 1, TAG_APP, 0, 0]).is_err());
+        assert!(deserialize_term(&mut arena, &[bytes.as_slice(), &[0]].concat()).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+//! Thread-safe arena for concurrent elaboration
+//!
+//! `Arena` needs `&mut self` to intern, so workers elaborating different
+//! files cannot share one. `SharedArena` wraps an arena in a `RwLock` and
+//! follows the same pattern as `SymbolTable`: a read-locked lookup for
+//! terms that already exist, and a write-locked insert (with a re-check)
+//! for new ones. Hash-consing stays global, so equal terms built on
+//! different threads get the same `TermId`.
+
+use crate::arena::{Arena, ArenaStats};
+use crate::term::{Binder, Term, TermId, TermKind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// Arena that can be shared between threads
+pub struct SharedArena {
+    inner: RwLock<Arena>,
+
+    /// Hits served by the read-locked fast path
+    read_hits: AtomicUsize,
+}
+
+impl SharedArena {
+    /// Create a new, empty shared arena
+    pub fn new() -> Self {
+        Self::from_arena(Arena::new())
+    }
+
+    /// Share an existing arena, keeping its terms and IDs
+    pub fn from_arena(arena: Arena) -> Self {
+        Self {
+            inner: RwLock::new(arena),
+            read_hits: AtomicUsize::new(0),
+        }
+    }
+
+    /// Intern a term and return its ID
+    pub fn intern(&self, kind: TermKind) -> TermId {
+        let term = Term::new(kind);
+
+        // Fast path: already interned (read lock)
+        if let Some(id) = self.read().lookup(&term) {
+            self.read_hits.fetch_add(1, Ordering::Relaxed);
+            return id;
+        }
+
+        // Slow path: `intern_term` re-checks under the write lock in case
+        // another thread inserted the term in between
+        self.write().intern_term(term)
+    }
+
+    /// Get a copy of a term's kind
+    pub fn kind(&self, id: TermId) -> Option<TermKind> {
+        self.read().kind(id).cloned()
+    }
+
+    /// Get the number of terms in the arena
+    pub fn terms(&self) -> usize {
+        self.read().terms()
+    }
+
+    /// Get arena statistics, including fast-path hits
+    pub fn stats(&self) -> ArenaStats {
+        let mut stats = self.read().stats().clone();
+        stats.cache_hits += self.read_hits.load(Ordering::Relaxed);
+        stats
+    }
+
+    /// Run `f` with shared access to the underlying arena
+    pub fn with_arena<R>(&self, f: impl FnOnce(&Arena) -> R) -> R {
+        f(&self.read())
+    }
+
+    /// Run `f` with exclusive access to the underlying arena
+    ///
+    /// Lets code written against `&mut Arena` (the elaborator, the type
+    /// checker) run on the shared arena. Other threads block until `f`
+    /// returns.
+    pub fn with_arena_mut<R>(&self, f: impl FnOnce(&mut Arena) -> R) -> R {
+        f(&mut self.write())
+    }
+
+    /// Take back the underlying arena
+    pub fn into_inner(self) -> Arena {
+        self.inner.into_inner().unwrap()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Arena> {
+        self.inner.read().unwrap()
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Arena> {
+        self.inner.write().unwrap()
+    }
+
+    // Helper methods for creating common terms
+
+    /// Create a sort term
+    pub fn mk_sort(&self, level: crate::level::LevelId) -> TermId {
+        self.intern(TermKind::Sort(level))
+    }
+
+    /// Create a constant term
+    pub fn mk_const(
+        &self,
+        name: crate::symbol::SymbolId,
+        levels: Vec<crate::level::LevelId>,
+    ) -> TermId {
+        self.intern(TermKind::Const(name, levels))
+    }
+
+    /// Create a variable term
+    pub fn mk_var(&self, index: u32) -> TermId {
+        self.intern(TermKind::Var(index))
+    }
+
+    /// Create an application term
+    pub fn mk_app(&self, func: TermId, arg: TermId) -> TermId {
+        self.intern(TermKind::App(func, arg))
+    }
+
+    /// Create a lambda term
+    pub fn mk_lam(&self, binder: Binder, body: TermId) -> TermId {
+        self.intern(TermKind::Lam(binder, body))
+    }
+
+    /// Create a Pi term
+    pub fn mk_pi(&self, binder: Binder, body: TermId) -> TermId {
+        self.intern(TermKind::Pi(binder, body))
+    }
+
+    /// Create a let term
+    pub fn mk_let(&self, binder: Binder, value: TermId, body: TermId) -> TermId {
+        self.intern(TermKind::Let(binder, value, body))
+    }
+
+    /// Create a natural number literal
+    pub fn mk_nat(&self, n: u64) -> TermId {
+        self.intern(TermKind::Lit(crate::term::Literal::Nat(n)))
+    }
+
+    /// Create a spine of applications (f x y z)
+    pub fn mk_app_spine(&self, func: TermId, args: &[TermId]) -> TermId {
+        args.iter().fold(func, |acc, &arg| self.mk_app(acc, arg))
+    }
+}
+
+impl Default for SharedArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Arena> for SharedArena {
+    fn from(arena: Arena) -> Self {
+        Self::from_arena(arena)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::LevelId;
+    use crate::symbol::SymbolId;
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Build `λ (x : Type). f x₀ (x₁ x₂ ...)` with `n` applications
+    fn build(arena: &SharedArena, n: u32) -> TermId {
+        let ty = arena.mk_sort(LevelId::new(0));
+        let f = arena.mk_const(SymbolId::new(1), vec![]);
+        let mut body = arena.mk_var(0);
+        for i in 0..n {
+            let lit = arena.mk_nat(i as u64);
+            let head = arena.mk_app(f, lit);
+            body = arena.mk_app(head, body);
+        }
+        arena.mk_lam(Binder::new(SymbolId::new(0), ty), body)
+    }
+
+    #[test]
+    fn test_concurrent_interning_preserves_hash_consing() {
+        let arena = Arc::new(SharedArena::new());
+
+        // Threads build overlapping prefixes of the same term
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let arena = Arc::clone(&arena);
+                thread::spawn(move || {
+                    (0..50)
+                        .map(|n| build(&arena, n + t * 5))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let results: Vec<Vec<TermId>> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+
+        // Same size from different threads means the same TermId
+        for (t, ids) in results.iter().enumerate() {
+            for (n, &id) in ids.iter().enumerate() {
+                assert_eq!(id, build(&arena, (n + t * 5) as u32));
+            }
+        }
+        assert_eq!(results[0][10], results[1][5]);
+        assert_eq!(results[2][0], results[1][5]);
+
+        // No duplicates: sort, Const f, Var 0, and per size i a literal, a
+        // partial application, a body, and a lambda (plus the size-0 lambda)
+        let max = 49 + 7 * 5;
+        assert_eq!(arena.terms(), 3 + 1 + 4 * max as usize);
+
+        let stats = arena.stats();
+        assert_eq!(stats.allocated, arena.terms());
+        assert!(stats.cache_hits > 0);
+    }
+
+    #[test]
+    fn test_exclusive_access_shares_ids() {
+        let shared = SharedArena::new();
+        let v0 = shared.mk_var(0);
+
+        let app = shared.with_arena_mut(|arena| {
+            let v0 = arena.mk_var(0);
+            arena.mk_app(v0, v0)
+        });
+
+        assert_eq!(shared.mk_app(v0, v0), app);
+        assert_eq!(shared.kind(app), Some(TermKind::App(v0, v0)));
+
+        let arena = shared.into_inner();
+        assert_eq!(arena.terms(), 2);
+    }
+}
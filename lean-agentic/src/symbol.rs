@@ -6,6 +6,7 @@
 use std::collections::HashMap;
 use std::hash::BuildHasherDefault;
 use std::hash::DefaultHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 
 type FxHashMap<K, V> = HashMap<K, V, BuildHasherDefault<DefaultHasher>>;
@@ -45,10 +46,31 @@ impl Symbol {
     }
 }
 
+/// Statistics about a symbol table
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolStats {
+    /// Number of unique symbols
+    pub count: usize,
+
+    /// Total bytes across all symbol names
+    pub total_bytes: usize,
+
+    /// Average name length in bytes
+    pub average_len: f64,
+
+    /// Calls to `intern`
+    pub intern_calls: usize,
+
+    /// Calls to `intern` that found an existing symbol
+    pub intern_hits: usize,
+}
+
 /// Global symbol table for interning strings
 pub struct SymbolTable {
     strings: RwLock<Vec<Arc<str>>>,
     lookup: RwLock<FxHashMap<Arc<str>, SymbolId>>,
+    intern_calls: AtomicUsize,
+    intern_hits: AtomicUsize,
 }
 
 impl SymbolTable {
@@ -57,15 +79,20 @@ impl SymbolTable {
         Self {
             strings: RwLock::new(Vec::new()),
             lookup: RwLock::new(FxHashMap::default()),
+            intern_calls: AtomicUsize::new(0),
+            intern_hits: AtomicUsize::new(0),
         }
     }
 
     /// Intern a string and return its symbol ID
     pub fn intern(&self, s: &str) -> SymbolId {
+        self.intern_calls.fetch_add(1, Ordering::Relaxed);
+
         // Fast path: check if already interned (read lock)
         {
             let lookup = self.lookup.read().unwrap();
             if let Some(&id) = lookup.get(s) {
+                self.intern_hits.fetch_add(1, Ordering::Relaxed);
                 return id;
             }
         }
@@ -76,6 +103,7 @@ impl SymbolTable {
 
         // Double-check in case another thread interned it
         if let Some(&id) = lookup.get(s) {
+            self.intern_hits.fetch_add(1, Ordering::Relaxed);
             return id;
         }
 
@@ -112,6 +140,72 @@ impl SymbolTable {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Get interning statistics
+    pub fn stats(&self) -> SymbolStats {
+        let strings = self.strings.read().unwrap();
+        let count = strings.len();
+        let total_bytes: usize = strings.iter().map(|s| s.len()).sum();
+
+        SymbolStats {
+            count,
+            total_bytes,
+            average_len: if count == 0 { 0.0 } else { total_bytes as f64 / count as f64 },
+            intern_calls: self.intern_calls.load(Ordering::Relaxed),
+            intern_hits: self.intern_hits.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Iterate over a snapshot of all symbols, in ID order
+    pub fn iter_symbols(&self) -> impl Iterator<Item = Symbol> {
+        let strings = self.strings.read().unwrap();
+        let symbols: Vec<Symbol> = strings
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Symbol {
+                id: SymbolId::new(i as u32),
+                name: name.clone(),
+            })
+            .collect();
+        symbols.into_iter()
+    }
+
+    /// Check that names and IDs form a bijection
+    ///
+    /// Every ID must map to a distinct name that looks up back to the same
+    /// ID. A failure means two names were given one ID or one name two IDs.
+    pub fn audit(&self) -> crate::Result<()> {
+        let lookup = self.lookup.read().unwrap();
+        let strings = self.strings.read().unwrap();
+
+        if lookup.len() != strings.len() {
+            return Err(crate::Error::Internal(format!(
+                "Symbol table has {} names but {} lookup entries",
+                strings.len(),
+                lookup.len()
+            )));
+        }
+
+        for (i, name) in strings.iter().enumerate() {
+            match lookup.get(name) {
+                Some(id) if id.0 as usize == i => {}
+                Some(id) => {
+                    return Err(crate::Error::Internal(format!(
+                        "Symbol '{}' is #{} but looks up to #{}",
+                        name, i, id.0
+                    )));
+                }
+                None => {
+                    return Err(crate::Error::Internal(format!(
+                        "Symbol '{}' (#{}) is missing from the lookup table",
+                        name, i
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for SymbolTable {
@@ -147,4 +241,31 @@ mod tests {
         assert_eq!(sym.as_str(), "test");
         assert_eq!(sym.id(), id);
     }
+
+    #[test]
+    fn test_symbol_stats_and_iteration() {
+        let table = SymbolTable::new();
+        let names = ["Nat", "add", "Nat", "zero", "add", "succ", "Nat"];
+
+        let ids: Vec<_> = names.iter().map(|n| table.intern(n)).collect();
+
+        // Interning is idempotent
+        assert_eq!(ids[0], ids[2]);
+        assert_eq!(ids[0], ids[6]);
+        assert_eq!(ids[1], ids[4]);
+        assert_eq!(table.intern("zero"), ids[3]);
+
+        let stats = table.stats();
+        assert_eq!(stats.count, 4);
+        assert_eq!(stats.total_bytes, "Nat".len() + "add".len() + "zero".len() + "succ".len());
+        assert_eq!(stats.average_len, 3.5);
+        assert_eq!(stats.intern_calls, 8);
+        assert_eq!(stats.intern_hits, 4);
+
+        let listed: Vec<String> = table.iter_symbols().map(|s| s.as_str().to_string()).collect();
+        assert_eq!(listed, vec!["Nat", "add", "zero", "succ"]);
+        assert!(table.iter_symbols().all(|s| table.intern(s.as_str()) == s.id()));
+
+        assert!(table.audit().is_ok());
+    }
 }
@@ -62,9 +62,9 @@ impl TypeChecker {
                 Ok(decl.ty)
             }
 
-            // Γ ⊢ #i : Γ(i)
+            // Γ ⊢ #i : ↑Γ(i)
             TermKind::Var(idx) => {
-                ctx.type_of(idx).ok_or_else(|| {
+                ctx.type_of_var(arena, idx).ok_or_else(|| {
                     crate::Error::TypeError(format!("Variable #{} not in context", idx))
                 })
             }
@@ -185,6 +185,27 @@ impl TypeChecker {
         term: TermId,
         expected_ty: TermId,
     ) -> crate::Result<()> {
+        // Γ ⊢ λx:A.b ⇐ Πx:A'.B  if A ≡ A' and Γ,x:A ⊢ b ⇐ B
+        if let Some(TermKind::Lam(binder, body)) = arena.kind(term).cloned() {
+            let expected_whnf = self.converter.whnf(arena, env, ctx, expected_ty)?;
+            if let Some(TermKind::Pi(pi_binder, codomain)) = arena.kind(expected_whnf).cloned() {
+                let binder_ty_sort = self.infer(arena, levels, env, ctx, binder.ty)?;
+                self.ensure_sort(arena, levels, env, ctx, binder_ty_sort)?;
+
+                if !self.converter.is_def_eq(arena, env, ctx, binder.ty, pi_binder.ty)? {
+                    return Err(crate::Error::ConversionError {
+                        expected: format!("{:?}", pi_binder.ty),
+                        actual: format!("{:?}", binder.ty),
+                    });
+                }
+
+                let mut new_ctx = ctx.clone();
+                new_ctx.push_var(binder.name, binder.ty);
+                return self.check(arena, levels, env, &new_ctx, body, codomain);
+            }
+        }
+
+        // Otherwise switch to inference mode and compare up to conversion
         let inferred_ty = self.infer(arena, levels, env, ctx, term)?;
 
         if self.converter.is_def_eq(arena, env, ctx, inferred_ty, expected_ty)? {
@@ -264,6 +285,36 @@ impl Default for TypeChecker {
     }
 }
 
+/// Infer the type of `term` in `ctx` using a fresh trusted kernel
+///
+/// This is the entry point downstream crates should use to re-check
+/// elaborated terms: it never consults metavariable assignments or
+/// elaborator state, only the environment and local context.
+pub fn infer(
+    arena: &mut Arena,
+    levels: &mut LevelArena,
+    env: &Environment,
+    ctx: &Context,
+    term: TermId,
+) -> crate::Result<TermId> {
+    TypeChecker::new().infer(arena, levels, env, ctx, term)
+}
+
+/// Check that `term` has type `expected` in `ctx` using a fresh trusted kernel
+///
+/// Lambdas are checked against Pi types bidirectionally; every other
+/// term is inferred and compared to `expected` up to definitional equality.
+pub fn check(
+    arena: &mut Arena,
+    levels: &mut LevelArena,
+    env: &Environment,
+    ctx: &Context,
+    term: TermId,
+    expected: TermId,
+) -> crate::Result<()> {
+    TypeChecker::new().check(arena, levels, env, ctx, term, expected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -336,4 +387,77 @@ mod tests {
             panic!("Expected Pi type");
         }
     }
+
+    #[test]
+    fn test_infer_lambda_type() {
+        let mut arena = Arena::new();
+        let mut levels = LevelArena::new();
+        let env = Environment::new();
+        let ctx = Context::new();
+
+        // λx:Type 0. x  :  Πx:Type 0. Type 0
+        let zero = levels.zero();
+        let type0 = arena.mk_sort(zero);
+        let x = arena.mk_var(0);
+        let lam = arena.mk_lam(Binder::new(SymbolId::new(0), type0), x);
+
+        let ty = infer(&mut arena, &mut levels, &env, &ctx, lam).unwrap();
+        let expected = arena.mk_pi(Binder::new(SymbolId::new(0), type0), type0);
+        assert_eq!(ty, expected);
+
+        // Checking mode agrees with inference
+        check(&mut arena, &mut levels, &env, &ctx, lam, expected).unwrap();
+    }
+
+    #[test]
+    fn test_reject_ill_typed_application() {
+        let mut arena = Arena::new();
+        let mut levels = LevelArena::new();
+        let env = Environment::new();
+        let ctx = Context::new();
+
+        let zero = levels.zero();
+        let type0 = arena.mk_sort(zero);
+        let x = arena.mk_var(0);
+        let id = arena.mk_lam(Binder::new(SymbolId::new(0), type0), x);
+
+        // (λx:Type 0. x) (Type 0) is ill-typed: Type 0 : Type 1, not Type 0
+        let bad_arg = arena.mk_app(id, type0);
+        assert!(matches!(
+            infer(&mut arena, &mut levels, &env, &ctx, bad_arg),
+            Err(crate::Error::ConversionError { .. })
+        ));
+
+        // Applying a non-function is rejected outright
+        let not_fn = arena.mk_app(type0, type0);
+        assert!(matches!(
+            infer(&mut arena, &mut levels, &env, &ctx, not_fn),
+            Err(crate::Error::TypeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_variable_type_refers_to_bound_type() {
+        let mut arena = Arena::new();
+        let mut levels = LevelArena::new();
+        let env = Environment::new();
+        let ctx = Context::new();
+
+        // fun (A : Type) (x : A) => x
+        let zero = levels.zero();
+        let type0 = arena.mk_sort(zero);
+        let a_in_x = arena.mk_var(0);
+        let x = arena.mk_var(0);
+        let inner = arena.mk_lam(Binder::new(SymbolId::new(1), a_in_x), x);
+        let term = arena.mk_lam(Binder::new(SymbolId::new(0), type0), inner);
+
+        // Π (A : Type). Π (x : A). A, where the codomain A is #1 under x
+        let ty = infer(&mut arena, &mut levels, &env, &ctx, term).unwrap();
+        let a_under_x = arena.mk_var(1);
+        let inner_ty = arena.mk_pi(Binder::new(SymbolId::new(1), a_in_x), a_under_x);
+        let expected = arena.mk_pi(Binder::new(SymbolId::new(0), type0), inner_ty);
+        assert_eq!(ty, expected);
+
+        check(&mut arena, &mut levels, &env, &ctx, term, expected).unwrap();
+    }
 }
@@ -0,0 +1,268 @@
+//! Generic traversals over arena terms
+//!
+//! Substitution, occurs checks, free-variable collection and similar
+//! passes all walk the same `TermKind` shape. This module provides that
+//! walk once: a `TermVisitor` trait for read-only traversals plus
+//! `fold_term` and `map_term` for accumulating and rebuilding terms.
+//!
+//! Every callback receives the binder depth, i.e. the number of binders
+//! crossed between the root and the current subterm.
+
+use crate::arena::Arena;
+use crate::term::{Binder, TermId, TermKind};
+
+/// Immediate subterms of a term, paired with how many binders they sit under
+///
+/// Binder types and let values are at offset 0; bodies are at offset 1.
+pub fn subterms(kind: &TermKind) -> Vec<(TermId, u32)> {
+    match kind {
+        TermKind::App(func, arg) => vec![(*func, 0), (*arg, 0)],
+        TermKind::Lam(binder, body) | TermKind::Pi(binder, body) => {
+            vec![(binder.ty, 0), (*body, 1)]
+        }
+        TermKind::Let(binder, value, body) => vec![(binder.ty, 0), (*value, 0), (*body, 1)],
+        TermKind::Sort(_)
+        | TermKind::Const(_, _)
+        | TermKind::Var(_)
+        | TermKind::MVar(_)
+        | TermKind::Lit(_) => Vec::new(),
+    }
+}
+
+/// Read-only traversal over a term
+///
+/// The default `visit` recurses into every subterm; override it to act on
+/// particular nodes and call `walk_term` to keep descending.
+pub trait TermVisitor {
+    /// Visit a term at the given binder depth
+    fn visit(&mut self, arena: &Arena, term: TermId, depth: u32) {
+        walk_term(self, arena, term, depth);
+    }
+}
+
+/// Visit the immediate subterms of `term`
+pub fn walk_term<V: TermVisitor + ?Sized>(
+    visitor: &mut V,
+    arena: &Arena,
+    term: TermId,
+    depth: u32,
+) {
+    if let Some(kind) = arena.kind(term) {
+        for (child, offset) in subterms(kind) {
+            visitor.visit(arena, child, depth + offset);
+        }
+    }
+}
+
+/// Fold over every subterm in pre-order
+///
+/// `f` receives the accumulator, the subterm, its kind and its binder depth.
+pub fn fold_term<A, F>(arena: &Arena, term: TermId, init: A, f: &mut F) -> A
+where
+    F: FnMut(A, TermId, &TermKind, u32) -> A,
+{
+    fold_at(arena, term, 0, init, f)
+}
+
+fn fold_at<A, F>(arena: &Arena, term: TermId, depth: u32, acc: A, f: &mut F) -> A
+where
+    F: FnMut(A, TermId, &TermKind, u32) -> A,
+{
+    let Some(kind) = arena.kind(term) else {
+        return acc;
+    };
+    let mut acc = f(acc, term, kind, depth);
+    for (child, offset) in subterms(kind) {
+        acc = fold_at(arena, child, depth + offset, acc, f);
+    }
+    acc
+}
+
+/// Rebuild a term bottom-up
+///
+/// `f` is tried on each subterm first: returning `Some` replaces that
+/// subterm outright, `None` recurses into its children. Unchanged subterms
+/// keep their original `TermId`.
+pub fn map_term<F>(arena: &mut Arena, term: TermId, f: &mut F) -> TermId
+where
+    F: FnMut(&mut Arena, TermId, u32) -> Option<TermId>,
+{
+    map_at(arena, term, 0, f)
+}
+
+fn map_at<F>(arena: &mut Arena, term: TermId, depth: u32, f: &mut F) -> TermId
+where
+    F: FnMut(&mut Arena, TermId, u32) -> Option<TermId>,
+{
+    if let Some(replacement) = f(arena, term, depth) {
+        return replacement;
+    }
+
+    let Some(kind) = arena.kind(term).cloned() else {
+        return term;
+    };
+
+    let map_binder = |arena: &mut Arena, binder: &Binder, f: &mut F| Binder {
+        ty: map_at(arena, binder.ty, depth, f),
+        ..binder.clone()
+    };
+
+    match kind {
+        TermKind::App(func, arg) => {
+            let new_func = map_at(arena, func, depth, f);
+            let new_arg = map_at(arena, arg, depth, f);
+            if new_func == func && new_arg == arg {
+                term
+            } else {
+                arena.mk_app(new_func, new_arg)
+            }
+        }
+
+        TermKind::Lam(binder, body) => {
+            let new_binder = map_binder(arena, &binder, f);
+            let new_body = map_at(arena, body, depth + 1, f);
+            if new_binder == binder && new_body == body {
+                term
+            } else {
+                arena.mk_lam(new_binder, new_body)
+            }
+        }
+
+        TermKind::Pi(binder, body) => {
+            let new_binder = map_binder(arena, &binder, f);
+            let new_body = map_at(arena, body, depth + 1, f);
+            if new_binder == binder && new_body == body {
+                term
+            } else {
+                arena.mk_pi(new_binder, new_body)
+            }
+        }
+
+        TermKind::Let(binder, value, body) => {
+            let new_binder = map_binder(arena, &binder, f);
+            let new_value = map_at(arena, value, depth, f);
+            let new_body = map_at(arena, body, depth + 1, f);
+            if new_binder == binder && new_value == value && new_body == body {
+                term
+            } else {
+                arena.mk_let(new_binder, new_value, new_body)
+            }
+        }
+
+        TermKind::Sort(_)
+        | TermKind::Const(_, _)
+        | TermKind::Var(_)
+        | TermKind::MVar(_)
+        | TermKind::Lit(_) => term,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol::SymbolId;
+    use std::collections::BTreeSet;
+
+    /// Free de Bruijn indices, relative to the root
+    #[derive(Default)]
+    struct FreeVars(BTreeSet<u32>);
+
+    impl TermVisitor for FreeVars {
+        fn visit(&mut self, arena: &Arena, term: TermId, depth: u32) {
+            match arena.kind(term) {
+                Some(TermKind::Var(idx)) if *idx >= depth => {
+                    self.0.insert(idx - depth);
+                }
+                _ => walk_term(self, arena, term, depth),
+            }
+        }
+    }
+
+    fn reference_free_vars(arena: &Arena, term: TermId, depth: u32, out: &mut BTreeSet<u32>) {
+        match arena.kind(term).unwrap() {
+            TermKind::Var(idx) if *idx >= depth => {
+                out.insert(idx - depth);
+            }
+            TermKind::App(f, a) => {
+                reference_free_vars(arena, *f, depth, out);
+                reference_free_vars(arena, *a, depth, out);
+            }
+            TermKind::Lam(b, body) | TermKind::Pi(b, body) => {
+                reference_free_vars(arena, b.ty, depth, out);
+                reference_free_vars(arena, *body, depth + 1, out);
+            }
+            TermKind::Let(b, v, body) => {
+                reference_free_vars(arena, b.ty, depth, out);
+                reference_free_vars(arena, *v, depth, out);
+                reference_free_vars(arena, *body, depth + 1, out);
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_free_var_visitor_matches_reference() {
+        let mut arena = Arena::new();
+        let zero = arena.mk_level_zero();
+        let ty = arena.mk_sort(zero);
+        let name = SymbolId::new(0);
+
+        let v0 = arena.mk_var(0);
+        let v1 = arena.mk_var(1);
+        let v3 = arena.mk_var(3);
+        let app = arena.mk_app(v0, v3);
+
+        // λ (_ : #1). #0 #3
+        let lam = arena.mk_lam(Binder::new(name, v1), app);
+        // Π (_ : Type). λ (_ : #1). #0 #3
+        let pi = arena.mk_pi(Binder::new(name, ty), lam);
+        // let _ : #0 := #1 in #1 #0
+        let body = arena.mk_app(v1, v0);
+        let let_term = arena.mk_let(Binder::new(name, v0), v1, body);
+        let closed = arena.mk_lam(Binder::new(name, ty), v0);
+
+        for term in [v0, app, lam, pi, let_term, closed, ty] {
+            let mut visitor = FreeVars::default();
+            visitor.visit(&arena, term, 0);
+
+            let mut expected = BTreeSet::new();
+            reference_free_vars(&arena, term, 0, &mut expected);
+
+            assert_eq!(visitor.0, expected, "mismatch on {:?}", arena.kind(term));
+        }
+
+        let mut visitor = FreeVars::default();
+        visitor.visit(&arena, lam, 0);
+        assert_eq!(visitor.0, BTreeSet::from([1, 2]));
+    }
+
+    #[test]
+    fn test_fold_and_map() {
+        let mut arena = Arena::new();
+        let name = SymbolId::new(0);
+        let v0 = arena.mk_var(0);
+        let v1 = arena.mk_var(1);
+        let app = arena.mk_app(v0, v1);
+        let lam = arena.mk_lam(Binder::new(name, v1), app);
+
+        let vars = fold_term(&arena, lam, 0, &mut |n, _, kind, _| {
+            n + matches!(kind, TermKind::Var(_)) as usize
+        });
+        assert_eq!(vars, 3);
+
+        // Rename free #0 (seen as #1 under the binder) to #5
+        let renamed = map_term(&mut arena, lam, &mut |arena, term, depth| {
+            match arena.kind(term) {
+                Some(TermKind::Var(idx)) if *idx == depth => Some(arena.mk_var(depth + 5)),
+                _ => None,
+            }
+        });
+        let v6 = arena.mk_var(6);
+        let expected_body = arena.mk_app(v0, v6);
+        let expected = arena.mk_lam(Binder::new(name, v1), expected_body);
+        assert_eq!(renamed, expected);
+
+        // The identity map shares the original term
+        assert_eq!(map_term(&mut arena, lam, &mut |_, _, _| None), lam);
+    }
+}
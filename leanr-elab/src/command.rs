@@ -0,0 +1,79 @@
+//! Interactive commands: #check and #eval
+
+use crate::elaborate::{ElabError, ElabResult, Elaborator};
+use lean_agentic::{level::LevelArena, Arena, Context, Environment, SymbolTable, TermId};
+use leanr_eval_lite::{EvalConfig, NatBuiltins, Normalizer};
+use leanr_syntax::Command;
+
+/// Result of running a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandOutput {
+    /// A declaration was added to the environment
+    Declared,
+
+    /// #check: the elaborated term and its type
+    Type { term: TermId, ty: TermId },
+
+    /// #eval: the elaborated term and its weak head normal form
+    Value { term: TermId, value: TermId },
+}
+
+/// Run a single command against the environment
+pub fn run_command(
+    cmd: &Command,
+    arena: &mut Arena,
+    symbols: &SymbolTable,
+    levels: &mut LevelArena,
+    env: &mut Environment,
+) -> ElabResult<CommandOutput> {
+    match cmd {
+        Command::Decl(decl) => {
+            crate::elaborate_decl(decl, arena, symbols, levels, env)?;
+            Ok(CommandOutput::Declared)
+        }
+
+        Command::Check { expr, .. } => {
+            let (term, ty) = Elaborator::new(arena, symbols, levels, env).synth(expr)?;
+            Ok(CommandOutput::Type { term, ty })
+        }
+
+        Command::Eval { expr, .. } => {
+            let (term, _ty) = Elaborator::new(arena, symbols, levels, env).synth(expr)?;
+            let nat = NatBuiltins::standard(|name| symbols.intern(name));
+            let value = Normalizer::new(arena, env, EvalConfig::default())
+                .with_nat_builtins(nat)
+                .whnf(term, &Context::new())
+                .map_err(|e| ElabError::new(format!("Evaluation failed: {}", e)))?;
+            Ok(CommandOutput::Value { term, value })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lean_agentic::{term::Literal, TermKind};
+
+    #[test]
+    fn test_eval_reduces_nat_builtins() {
+        let input = "axiom Nat : Type\naxiom add : Nat -> (Nat -> Nat)\n#eval add 2 3";
+        let source = leanr_syntax::SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = leanr_syntax::Lexer::new(source).tokenize();
+        let commands = leanr_syntax::Parser::new(tokens).parse_commands().unwrap();
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let outputs: Vec<_> = commands
+            .iter()
+            .map(|cmd| run_command(cmd, &mut arena, &symbols, &mut levels, &mut env).unwrap())
+            .collect();
+
+        assert_eq!(outputs[..2], [CommandOutput::Declared, CommandOutput::Declared]);
+        let CommandOutput::Value { value, .. } = outputs[2] else {
+            panic!("#eval should produce a value");
+        };
+        assert_eq!(arena.kind(value), Some(&TermKind::Lit(Literal::Nat(5))));
+    }
+}
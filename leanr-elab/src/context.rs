@@ -43,7 +43,7 @@ impl ElabContext {
             level: self.depth,
         };
 
-        self.bindings.entry(name).or_insert_with(Vec::new).push(binding);
+        self.bindings.entry(name).or_default().push(binding);
         self.depth += 1;
     }
 
@@ -83,15 +83,16 @@ impl Default for ElabContext {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lean_agentic::symbol::SymbolId;
-    use lean_agentic::term::TermId;
+    use lean_agentic::{Arena, SymbolTable};
 
     #[test]
     fn test_context_push_pop() {
         let mut ctx = ElabContext::new();
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
 
-        let sym1 = SymbolId::new(1);
-        let ty1 = TermId::new(10);
+        let sym1 = symbols.intern("x");
+        let ty1 = arena.mk_nat(10);
 
         ctx.push("x".to_string(), sym1, ty1);
         assert_eq!(ctx.depth(), 1);
@@ -107,12 +108,15 @@ mod tests {
     #[test]
     fn test_shadowing() {
         let mut ctx = ElabContext::new();
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
 
-        ctx.push("x".to_string(), SymbolId::new(1), TermId::new(10));
-        ctx.push("x".to_string(), SymbolId::new(2), TermId::new(20));
+        let (outer, inner) = (symbols.intern("x.outer"), symbols.intern("x.inner"));
+        ctx.push("x".to_string(), outer, arena.mk_nat(10));
+        ctx.push("x".to_string(), inner, arena.mk_nat(20));
 
         let binding = ctx.lookup("x").unwrap();
-        assert_eq!(binding.name.raw(), 2);
+        assert_eq!(binding.name, inner);
         assert_eq!(binding.level, 1);
     }
 }
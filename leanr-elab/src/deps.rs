@@ -0,0 +1,286 @@
+//! Dependency analysis for top-level declarations
+//!
+//! Collects the global names each declaration refers to and orders a
+//! batch of declarations so every name is elaborated before its uses.
+
+use crate::elaborate::{ElabError, ElabResult};
+use leanr_syntax::{Decl, Expr, Param, Pattern};
+use std::collections::{BTreeSet, HashMap};
+
+/// Names introduced by a declaration
+pub fn defined_names(decl: &Decl) -> Vec<String> {
+    match decl {
+        Decl::Def(d) => vec![d.name.name.clone()],
+        Decl::Theorem(t) => vec![t.name.name.clone()],
+        Decl::Axiom(a) => vec![a.name.name.clone()],
+        Decl::Inductive(i) => std::iter::once(i.name.name.clone())
+            .chain(i.constructors.iter().map(|c| c.name.name.clone()))
+            .collect(),
+        Decl::Structure(s) => vec![s.name.name.clone()],
+        Decl::Infix(_) => Vec::new(),
+        Decl::Mutual(decls) => decls.iter().flat_map(defined_names).collect(),
+    }
+}
+
+/// Free (global) names a declaration refers to
+pub fn referenced_names(decl: &Decl) -> BTreeSet<String> {
+    let mut collector = NameCollector::default();
+    collector.decl(decl);
+    collector.names
+}
+
+/// Order declarations so that each comes after the declarations it uses
+///
+/// Returns indices into `decls`. Declarations with no ordering constraint
+/// between them keep their source order. Self-references and references
+/// within a mutual block are not dependencies; any other cycle is an error.
+pub fn dependency_order(decls: &[Decl]) -> ElabResult<Vec<usize>> {
+    let mut owner: HashMap<String, usize> = HashMap::new();
+    for (i, decl) in decls.iter().enumerate() {
+        for name in defined_names(decl) {
+            owner.insert(name, i);
+        }
+    }
+
+    // deps[i] = declarations that must be elaborated before i
+    let deps: Vec<BTreeSet<usize>> = decls
+        .iter()
+        .enumerate()
+        .map(|(i, decl)| {
+            referenced_names(decl)
+                .iter()
+                .filter_map(|name| owner.get(name).copied())
+                .filter(|&j| j != i)
+                .collect()
+        })
+        .collect();
+
+    let mut remaining: Vec<usize> = deps.iter().map(|d| d.len()).collect();
+    let mut done = vec![false; decls.len()];
+    let mut order = Vec::with_capacity(decls.len());
+
+    // Repeatedly take the first ready declaration in source order
+    while order.len() < decls.len() {
+        let next = (0..decls.len()).find(|&i| !done[i] && remaining[i] == 0);
+        let Some(i) = next else {
+            let cycle: Vec<String> = (0..decls.len())
+                .filter(|&i| !done[i])
+                .flat_map(|i| defined_names(&decls[i]))
+                .collect();
+            return Err(ElabError::new(format!(
+                "Cyclic dependency between declarations: {}",
+                cycle.join(", ")
+            )));
+        };
+
+        done[i] = true;
+        order.push(i);
+        for (j, d) in deps.iter().enumerate() {
+            if d.contains(&i) {
+                remaining[j] -= 1;
+            }
+        }
+    }
+
+    Ok(order)
+}
+
+/// Walks syntax collecting identifiers not bound by an enclosing binder
+#[derive(Default)]
+struct NameCollector {
+    bound: Vec<String>,
+    names: BTreeSet<String>,
+}
+
+impl NameCollector {
+    fn decl(&mut self, decl: &Decl) {
+        match decl {
+            Decl::Def(d) => {
+                let mark = self.params(&d.params);
+                if let Some(ref ty) = d.return_type {
+                    self.expr(ty);
+                }
+                self.expr(&d.body);
+                self.bound.truncate(mark);
+            }
+            Decl::Theorem(t) => {
+                let mark = self.params(&t.params);
+                self.expr(&t.type_);
+                self.expr(&t.proof);
+                self.bound.truncate(mark);
+            }
+            Decl::Axiom(a) => {
+                let mark = self.params(&a.params);
+                self.expr(&a.type_);
+                self.bound.truncate(mark);
+            }
+            Decl::Inductive(i) => {
+                let mark = self.params(&i.params);
+                if let Some(ref ty) = i.type_ {
+                    self.expr(ty);
+                }
+                for ctor in &i.constructors {
+                    let ctor_mark = self.params(&ctor.params);
+                    if let Some(ref ty) = ctor.type_ {
+                        self.expr(ty);
+                    }
+                    self.bound.truncate(ctor_mark);
+                }
+                self.bound.truncate(mark);
+            }
+            Decl::Structure(s) => {
+                let mark = self.params(&s.params);
+                for parent in &s.extends {
+                    self.expr(parent);
+                }
+                for field in &s.fields {
+                    self.expr(&field.type_);
+                }
+                self.bound.truncate(mark);
+            }
+            Decl::Infix(infix) => {
+                self.names.insert(infix.function.name.clone());
+            }
+            Decl::Mutual(decls) => {
+                for d in decls {
+                    self.decl(d);
+                }
+            }
+        }
+    }
+
+    /// Bind parameters left to right; returns the mark to restore
+    fn params(&mut self, params: &[Param]) -> usize {
+        let mark = self.bound.len();
+        for param in params {
+            if let Some(ref ty) = param.type_ {
+                self.expr(ty);
+            }
+            self.bound.extend(param.names.iter().map(|n| n.name.clone()));
+        }
+        mark
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Ident(ident) => {
+                if !self.bound.contains(&ident.name) {
+                    self.names.insert(ident.name.clone());
+                }
+            }
+            Expr::App { func, args, .. } => {
+                self.expr(func);
+                for arg in args {
+                    self.expr(arg);
+                }
+            }
+            Expr::Lam { params, body, .. } | Expr::Forall { params, body, .. } => {
+                let mark = self.params(params);
+                self.expr(body);
+                self.bound.truncate(mark);
+            }
+            Expr::Arrow { from, to, .. } => {
+                self.expr(from);
+                self.expr(to);
+            }
+            Expr::Let { name, type_, value, body, .. } => {
+                if let Some(ty) = type_ {
+                    self.expr(ty);
+                }
+                self.expr(value);
+                self.bound.push(name.name.clone());
+                self.expr(body);
+                self.bound.pop();
+            }
+            Expr::Match { scrutinee, arms, .. } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    let mark = self.bound.len();
+                    self.pattern(&arm.pattern);
+                    self.expr(&arm.body);
+                    self.bound.truncate(mark);
+                }
+            }
+            Expr::If { cond, then_branch, else_branch, .. } => {
+                self.expr(cond);
+                self.expr(then_branch);
+                self.expr(else_branch);
+            }
+            Expr::Ann { expr, type_, .. } => {
+                self.expr(expr);
+                self.expr(type_);
+            }
+            Expr::Paren { expr, .. } => self.expr(expr),
+            Expr::Lit(_) | Expr::Hole { .. } | Expr::Sorry { .. } | Expr::Universe { .. } => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Var { name, .. } => self.bound.push(name.name.clone()),
+            Pattern::Constructor { name, args, .. } => {
+                self.names.insert(name.name.clone());
+                for arg in args {
+                    self.pattern(arg);
+                }
+            }
+            Pattern::Wildcard { .. } | Pattern::Lit { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<Decl> {
+        let source = leanr_syntax::SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = leanr_syntax::Lexer::new(source).tokenize();
+        leanr_syntax::Parser::new(tokens).parse_decls().unwrap()
+    }
+
+    #[test]
+    fn test_referenced_names_skip_bound_variables() {
+        let decls = parse("def f (x : A) : B := fun y => g x y (let z := h in z)");
+        let names: Vec<String> = referenced_names(&decls[0]).into_iter().collect();
+        assert_eq!(names, vec!["A", "B", "g", "h"]);
+    }
+
+    #[test]
+    fn test_forward_reference_is_reordered() {
+        let decls = parse(r#"
+            def b : Nat := a
+            axiom Nat : Type
+            def a : Nat := c
+            axiom c : Nat
+        "#);
+
+        let order = dependency_order(&decls).unwrap();
+        let names: Vec<String> = order.iter().flat_map(|&i| defined_names(&decls[i])).collect();
+        assert_eq!(names, vec!["Nat", "c", "a", "b"]);
+    }
+
+    #[test]
+    fn test_cycle_is_reported() {
+        let decls = parse(r#"
+            axiom Nat : Type
+            def f : Nat := g
+            def g : Nat := f
+        "#);
+
+        let err = dependency_order(&decls).unwrap_err();
+        assert!(err.message().contains("Cyclic"));
+        assert!(err.message().contains("f") && err.message().contains("g"));
+        assert!(!err.message().contains("Nat"));
+
+        // The same pair inside a mutual block is fine
+        let decls = parse(r#"
+            axiom Nat : Type
+            mutual
+              def f : Nat := g
+              def g : Nat := f
+            end
+        "#);
+        assert_eq!(dependency_order(&decls).unwrap(), vec![0, 1]);
+    }
+}
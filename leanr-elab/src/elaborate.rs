@@ -2,32 +2,106 @@
 
 use crate::context::ElabContext;
 use crate::metavar::MetaVarContext;
+use crate::sorry::SorryTracker;
+use crate::source_map::SourceMap;
 use lean_agentic::{
     Arena, Environment, TermId, TermKind,
-    term::{Binder, BinderInfo, MetaVarId},
-    symbol::SymbolId,
-    level::LevelId,
+    term::{Binder, MetaVarId},
+    symbol::{SymbolId, SymbolTable},
+    level::{LevelArena, UniverseConstraintSet},
     unification::Unifier,
     context::Context,
+    conversion::Converter,
+    fold_term,
+    environment::Declaration,
+    typechecker,
 };
-use leanr_syntax::{Expr, Decl, DefDecl, TheoremDecl, AxiomDecl, Param, Ident, UniverseKind};
+use leanr_syntax::{Expr, Decl, DefDecl, TheoremDecl, AxiomDecl, Param, Span, UniverseKind};
+use std::collections::HashMap;
 use std::fmt;
 
+/// Name of the axiom `sorry` elaborates to: `sorryAx : Π (α : Type), α`
+pub const SORRY_AXIOM: &str = "sorryAx";
+
 /// Elaboration error
+///
+/// Spans come from the elaborator's source map; they are `None` only when
+/// the offending term was not built from source (e.g. during kernel checks).
 #[derive(Debug, Clone)]
-pub struct ElabError {
-    pub message: String,
+pub enum ElabError {
+    /// A name bound neither locally nor in the environment
+    UnknownIdentifier { name: String, span: Span },
+
+    /// A term whose inferred type differs from the expected one
+    TypeMismatch {
+        expected: TermId,
+        actual: TermId,
+        span: Option<Span>,
+    },
+
+    /// A term applied to arguments although its type is not a function type
+    NotAFunction { ty: TermId, span: Option<Span> },
+
+    /// A metavariable left unassigned after constraint solving
+    UnresolvedMetavar { mvar: MetaVarId, span: Option<Span> },
+
+    /// Universe constraints with no solution, as reported by the kernel
+    Universe(lean_agentic::Error),
+
+    /// Any other elaboration failure
+    Other { message: String, span: Option<Span> },
 }
 
 impl ElabError {
     pub fn new(message: String) -> Self {
-        Self { message }
+        ElabError::Other { message, span: None }
+    }
+
+    /// Create an error located at a source span
+    pub fn at(span: Span, message: String) -> Self {
+        ElabError::Other {
+            message,
+            span: Some(span),
+        }
+    }
+
+    /// Get the source span the error points at, if known
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            ElabError::UnknownIdentifier { span, .. } => Some(*span),
+            ElabError::TypeMismatch { span, .. }
+            | ElabError::NotAFunction { span, .. }
+            | ElabError::UnresolvedMetavar { span, .. }
+            | ElabError::Other { span, .. } => *span,
+            ElabError::Universe(_) => None,
+        }
+    }
+
+    /// Get the error message without its location
+    pub fn message(&self) -> String {
+        match self {
+            ElabError::UnknownIdentifier { name, .. } => format!("Unknown identifier: {}", name),
+            ElabError::TypeMismatch { expected, actual, .. } => {
+                format!("Type mismatch: expected {:?}, got {:?}", expected, actual)
+            }
+            ElabError::NotAFunction { ty, .. } => {
+                format!("Expected function type, got {:?}", ty)
+            }
+            ElabError::UnresolvedMetavar { mvar, .. } => {
+                format!("Unresolved metavariable {:?}", mvar)
+            }
+            ElabError::Universe(e) => e.to_string(),
+            ElabError::Other { message, .. } => message.clone(),
+        }
     }
 }
 
 impl fmt::Display for ElabError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Elaboration error: {}", self.message)
+        match self.span() {
+            Some(span) => write!(f, "Elaboration error at {}: {}", span, self.message()),
+            None => write!(f, "Elaboration error: {}", self.message()),
+        }
     }
 }
 
@@ -36,11 +110,33 @@ impl std::error::Error for ElabError {}
 /// Result type for elaboration
 pub type ElabResult<T> = Result<T, ElabError>;
 
+/// Elaboration cache key: the expression's structural hash and the
+/// expected type it was checked against (`None` for synthesis)
+type CacheKey = (u64, Option<TermId>);
+
+/// A cached elaboration result
+struct CacheEntry {
+    /// The elaborated expression, compared on lookup to rule out hash collisions
+    expr: Expr,
+
+    /// Elaborated term and its type
+    result: (TermId, TermId),
+
+    /// Global constants referenced while elaborating, replayed on a hit
+    references: Vec<String>,
+}
+
 /// Bidirectional elaborator
 pub struct Elaborator<'a> {
     /// Term arena
     arena: &'a mut Arena,
 
+    /// Interned names
+    symbols: &'a SymbolTable,
+
+    /// Universe levels of the sorts being built
+    levels: &'a mut LevelArena,
+
     /// Global environment
     env: &'a mut Environment,
 
@@ -52,22 +148,236 @@ pub struct Elaborator<'a> {
 
     /// Unifier for constraint solving
     unifier: Unifier,
+
+    /// Universe constraints collected across the elaborated batch
+    universes: UniverseConstraintSet,
+
+    /// Uses of `sorry` and the declarations they taint
+    sorries: SorryTracker,
+
+    /// Source spans of the terms built so far
+    source_map: SourceMap,
+
+    /// Elaborated (term, type) of closed expressions, if caching is enabled
+    cache: Option<HashMap<CacheKey, CacheEntry>>,
+
+    /// Number of elaborations answered from the cache
+    cache_hits: usize,
+
+    /// Global constants referenced so far, recorded while caching is enabled
+    references: Vec<String>,
 }
 
 impl<'a> Elaborator<'a> {
     /// Create a new elaborator
-    pub fn new(arena: &'a mut Arena, env: &'a mut Environment) -> Self {
+    pub fn new(
+        arena: &'a mut Arena,
+        symbols: &'a SymbolTable,
+        levels: &'a mut LevelArena,
+        env: &'a mut Environment,
+    ) -> Self {
         Self {
             arena,
+            symbols,
+            levels,
             env,
             ctx: ElabContext::new(),
             mctx: MetaVarContext::new(),
             unifier: Unifier::new(),
+            universes: UniverseConstraintSet::new(),
+            sorries: SorryTracker::new(),
+            source_map: SourceMap::new(),
+            cache: None,
+            cache_hits: 0,
+            references: Vec::new(),
+        }
+    }
+
+    /// Reuse the result of elaborating a structurally identical expression
+    ///
+    /// Only expressions elaborated with no locals in scope are cached, since
+    /// under binders the same syntax can refer to different variables.
+    /// Results that used `sorry` are never cached, so every use is tracked,
+    /// and neither are results containing metavariables, so separate holes
+    /// are not unified. A hit replays the constant references of the cached
+    /// elaboration, so `sorry` taint still propagates.
+    pub fn with_cache(mut self) -> Self {
+        self.cache = Some(HashMap::new());
+        self
+    }
+
+    /// Number of elaborations answered from the cache
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits
+    }
+
+    fn cache_key(&self, expr: &Expr, expected_ty: Option<TermId>) -> Option<CacheKey> {
+        self.cache.as_ref()?;
+        (self.ctx.depth() == 0).then(|| (expr.structural_hash(), expected_ty))
+    }
+
+    fn cache_lookup(&mut self, key: Option<CacheKey>, expr: &Expr) -> Option<(TermId, TermId)> {
+        let entry = self.cache.as_ref()?.get(&key?)?;
+        if !entry.expr.structurally_eq(expr) {
+            return None;
+        }
+        let (result, references) = (entry.result, entry.references.clone());
+        for name in references {
+            self.record_reference(name);
         }
+        self.source_map.rebind(result.0, expr.span());
+        self.cache_hits += 1;
+        Some(result)
     }
 
-    /// Elaborate a declaration
+    /// Cache a result; `mark` is the sorry and reference counts from before
+    /// `expr` was elaborated
+    fn cache_store(
+        &mut self,
+        key: Option<CacheKey>,
+        expr: &Expr,
+        mark: (usize, usize),
+        result: (TermId, TermId),
+    ) {
+        let Some(key) = key else { return };
+        let (sorries_before, references_before) = mark;
+        if self.sorries.uses().len() != sorries_before
+            || self.has_mvars(result.0)
+            || self.has_mvars(result.1)
+        {
+            return;
+        }
+        let references = self.references[references_before..].to_vec();
+        if let Some(cache) = self.cache.as_mut() {
+            cache.insert(key, CacheEntry { expr: expr.clone(), result, references });
+        }
+    }
+
+    fn cache_mark(&self) -> (usize, usize) {
+        (self.sorries.uses().len(), self.references.len())
+    }
+
+    /// Record a reference to the global constant `name`
+    fn record_reference(&mut self, name: String) {
+        self.sorries.record_reference(&name);
+        if self.cache.is_some() {
+            self.references.push(name);
+        }
+    }
+
+    /// Elaborate a declaration and add it to the environment
     pub fn elaborate_decl(&mut self, decl: &Decl) -> ElabResult<()> {
+        for elaborated in self.elaborate_declarations(decl)? {
+            self.env.add_decl(elaborated)
+                .map_err(|e| ElabError::new(format!("Cannot add declaration: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Elaborate a batch of declarations in dependency order
+    ///
+    /// Declarations may refer to names defined later in the batch; they are
+    /// topologically sorted first, and a genuine cycle is reported as an error
+    /// before anything is elaborated. The universe constraints collected so
+    /// far are checked once the whole batch is elaborated.
+    pub fn elaborate_decls(&mut self, decls: &[Decl]) -> ElabResult<()> {
+        for i in crate::deps::dependency_order(decls)? {
+            self.elaborate_decl(&decls[i])?;
+        }
+        self.check_universes()
+    }
+
+    /// Elaborate a declaration into the kernel declarations it introduces
+    ///
+    /// Operator declarations introduce none, mutual blocks one per member,
+    /// and every other declaration exactly one.
+    pub fn elaborate_declarations(&mut self, decl: &Decl) -> ElabResult<Vec<Declaration>> {
+        match decl {
+            // Operator declarations only affect parsing
+            Decl::Infix(_) => Ok(Vec::new()),
+            Decl::Mutual(decls) => self.elaborate_mutual(decls),
+            _ => Ok(vec![self.elaborate_declaration(decl)?]),
+        }
+    }
+
+    /// Elaborate a mutual block
+    ///
+    /// Every signature is elaborated and pre-declared first, so each body
+    /// may refer to any member of the block regardless of order.
+    fn elaborate_mutual(&mut self, decls: &[Decl]) -> ElabResult<Vec<Declaration>> {
+        let mut scratch = self.env.fork();
+
+        for decl in decls {
+            let (name, ty) = self.elaborate_signature(decl)?;
+            scratch.add_decl(Declaration::axiom(name, vec![], ty))
+                .map_err(|e| ElabError::new(format!("Duplicate name in mutual block: {}", e)))?;
+        }
+
+        let mut inner = Elaborator::new(self.arena, self.symbols, self.levels, &mut scratch);
+        inner.universes = std::mem::take(&mut self.universes);
+        inner.sorries = std::mem::take(&mut self.sorries);
+
+        let result: ElabResult<Vec<_>> = decls.iter().map(|decl| inner.elaborate_declaration(decl)).collect();
+
+        self.universes = std::mem::take(&mut inner.universes);
+        self.sorries = std::mem::take(&mut inner.sorries);
+        let result = result?;
+        self.check_universes()?;
+        Ok(result)
+    }
+
+    /// Elaborate only the name and type of a declaration
+    fn elaborate_signature(&mut self, decl: &Decl) -> ElabResult<(SymbolId, TermId)> {
+        let (name, params, ty_expr) = match decl {
+            Decl::Def(def) => {
+                let ty = def.return_type.as_ref().ok_or_else(|| {
+                    ElabError::new(format!(
+                        "Mutual definition '{}' needs an explicit type",
+                        def.name.name
+                    ))
+                })?;
+                (&def.name, &def.params, ty)
+            }
+            Decl::Theorem(thm) => (&thm.name, &thm.params, &thm.type_),
+            Decl::Axiom(ax) => (&ax.name, &ax.params, &ax.type_),
+            _ => {
+                return Err(ElabError::new(
+                    "Only def, theorem and axiom are supported in mutual blocks".to_string(),
+                ));
+            }
+        };
+
+        let (param_binders, _) = self.elaborate_params(params)?;
+        let ty = self.synth(ty_expr);
+        self.pop_params(params);
+
+        let full_type = self.build_pi_type(param_binders, ty?.0)?;
+        Ok((self.symbols.intern(&name.name), full_type))
+    }
+
+    /// Remove parameters pushed by `elaborate_params`
+    fn pop_params(&mut self, params: &[Param]) {
+        for param in params.iter().rev() {
+            for name in param.names.iter().rev() {
+                self.ctx.pop(&name.name);
+            }
+        }
+    }
+
+    /// Elaborate a declaration without adding it to the environment
+    ///
+    /// The returned declaration has not been re-checked by the kernel;
+    /// see [`Elaborator::elaborate_and_check`] for the trusted path.
+    pub fn elaborate_declaration(&mut self, decl: &Decl) -> ElabResult<Declaration> {
+        if let Some(name) = decl_name(decl) {
+            self.sorries.begin_decl(name);
+        }
+        let result = self.elaborate_declaration_inner(decl);
+        self.sorries.end_decl();
+        result
+    }
+
+    fn elaborate_declaration_inner(&mut self, decl: &Decl) -> ElabResult<Declaration> {
         match decl {
             Decl::Def(def) => self.elaborate_def(def),
             Decl::Theorem(thm) => self.elaborate_theorem(thm),
@@ -80,14 +390,30 @@ impl<'a> Elaborator<'a> {
                 // TODO: Implement structure elaboration
                 Err(ElabError::new("Structures not yet implemented".to_string()))
             }
+            Decl::Infix(infix) => {
+                Err(ElabError::new(format!(
+                    "Operator declaration '{}' has no kernel declaration",
+                    infix.operator
+                )))
+            }
+            Decl::Mutual(_) => {
+                Err(ElabError::new(
+                    "Mutual block introduces several declarations; use elaborate_declarations".to_string(),
+                ))
+            }
         }
     }
 
     /// Elaborate a def declaration
-    fn elaborate_def(&mut self, def: &DefDecl) -> ElabResult<()> {
-        // Elaborate parameters and build Pi type
-        let (param_binders, body_ctx_depth) = self.elaborate_params(&def.params)?;
+    fn elaborate_def(&mut self, def: &DefDecl) -> ElabResult<Declaration> {
+        let (param_binders, _) = self.elaborate_params(&def.params)?;
+        let result = self.elaborate_def_body(def, param_binders);
+        self.pop_params(&def.params);
+        result
+    }
 
+    /// Elaborate a def's type and body with its parameters in scope
+    fn elaborate_def_body(&mut self, def: &DefDecl, param_binders: Vec<Binder>) -> ElabResult<Declaration> {
         // Elaborate return type if provided
         let return_type = if let Some(ref ty_expr) = def.return_type {
             self.synth(ty_expr)?.0
@@ -97,7 +423,7 @@ impl<'a> Elaborator<'a> {
         };
 
         // Build full type: Pi type from params
-        let full_type = self.build_pi_type(param_binders, return_type)?;
+        let full_type = self.build_pi_type(param_binders.clone(), return_type)?;
 
         // Check body against return type
         let body_term = if def.return_type.is_some() {
@@ -107,68 +433,88 @@ impl<'a> Elaborator<'a> {
         };
 
         // Build lambda term from params
-        let full_term = self.build_lambda(def.params.clone(), body_term)?;
+        let full_term = self.build_lambda(&param_binders, body_term)?;
 
-        // Restore context depth
-        for _ in 0..body_ctx_depth {
-            // Pop parameters
-        }
-
-        // Add to environment
-        let name_sym = self.arena.get_symbol(&def.name.name);
-        self.env.add_constant(name_sym, full_type, Some(full_term), vec![])?;
-
-        Ok(())
+        let name_sym = self.symbols.intern(&def.name.name);
+        Ok(Declaration::def(name_sym, vec![], full_type, full_term))
     }
 
     /// Elaborate a theorem declaration
-    fn elaborate_theorem(&mut self, thm: &TheoremDecl) -> ElabResult<()> {
-        // Similar to def but proof is checked against type
+    fn elaborate_theorem(&mut self, thm: &TheoremDecl) -> ElabResult<Declaration> {
         let (param_binders, _) = self.elaborate_params(&thm.params)?;
+        let result = self.elaborate_theorem_body(thm, param_binders);
+        self.pop_params(&thm.params);
+        result
+    }
 
+    /// Elaborate a theorem's statement and proof with its parameters in scope
+    fn elaborate_theorem_body(
+        &mut self,
+        thm: &TheoremDecl,
+        param_binders: Vec<Binder>,
+    ) -> ElabResult<Declaration> {
+        // Similar to def but proof is checked against type
         let type_term = self.synth(&thm.type_)?.0;
-        let full_type = self.build_pi_type(param_binders, type_term)?;
+        let full_type = self.build_pi_type(param_binders.clone(), type_term)?;
 
         let proof_term = self.check(&thm.proof, type_term)?;
-        let full_proof = self.build_lambda(thm.params.clone(), proof_term)?;
-
-        let name_sym = self.arena.get_symbol(&thm.name.name);
-        self.env.add_constant(name_sym, full_type, Some(full_proof), vec![])?;
+        let full_proof = self.build_lambda(&param_binders, proof_term)?;
 
-        Ok(())
+        let name_sym = self.symbols.intern(&thm.name.name);
+        Ok(Declaration::theorem(name_sym, vec![], full_type, full_proof))
     }
 
     /// Elaborate an axiom declaration
-    fn elaborate_axiom(&mut self, ax: &AxiomDecl) -> ElabResult<()> {
+    fn elaborate_axiom(&mut self, ax: &AxiomDecl) -> ElabResult<Declaration> {
         let (param_binders, _) = self.elaborate_params(&ax.params)?;
+        let type_term = self.synth(&ax.type_);
+        self.pop_params(&ax.params);
 
-        let type_term = self.synth(&ax.type_)?.0;
-        let full_type = self.build_pi_type(param_binders, type_term)?;
-
-        let name_sym = self.arena.get_symbol(&ax.name.name);
-        self.env.add_constant(name_sym, full_type, None, vec![])?;
+        let full_type = self.build_pi_type(param_binders, type_term?.0)?;
 
-        Ok(())
+        let name_sym = self.symbols.intern(&ax.name.name);
+        Ok(Declaration::axiom(name_sym, vec![], full_type))
     }
 
     /// Synthesis mode: infer the type of an expression
     /// Returns (term, type)
     pub fn synth(&mut self, expr: &Expr) -> ElabResult<(TermId, TermId)> {
+        let key = self.cache_key(expr, None);
+        if let Some(hit) = self.cache_lookup(key, expr) {
+            return Ok(hit);
+        }
+
+        let mark = self.cache_mark();
+        let (term, ty) = self.synth_expr(expr)?;
+        self.source_map.record(term, expr.span());
+        self.cache_store(key, expr, mark, (term, ty));
+        Ok((term, ty))
+    }
+
+    fn synth_expr(&mut self, expr: &Expr) -> ElabResult<(TermId, TermId)> {
         match expr {
             Expr::Ident(ident) => {
                 // Look up in local context first
                 if let Some(binding) = self.ctx.lookup(&ident.name) {
                     let idx = self.ctx.level_to_index(binding.level);
                     let var_term = self.arena.mk_var(idx);
-                    Ok((var_term, binding.ty))
+                    // The type was recorded outside the variable's own binder
+                    // and those pushed since, so it is shifted past them
+                    let ty = self.arena.shift(binding.ty, 0, idx as i32 + 1);
+                    Ok((var_term, ty))
                 } else {
                     // Look up in global environment
-                    let sym = self.arena.get_symbol(&ident.name);
-                    if let Some(info) = self.env.get_constant(sym) {
+                    let sym = self.symbols.intern(&ident.name);
+                    self.record_reference(ident.name.clone());
+                    if let Some(decl) = self.env.get_decl(sym) {
+                        let ty = decl.ty;
                         let const_term = self.arena.mk_const(sym, vec![]);
-                        Ok((const_term, info.type_))
+                        Ok((const_term, ty))
                     } else {
-                        Err(ElabError::new(format!("Unknown identifier: {}", ident.name)))
+                        Err(ElabError::UnknownIdentifier {
+                            name: ident.name.clone(),
+                            span: ident.span,
+                        })
                     }
                 }
             }
@@ -183,16 +529,16 @@ impl<'a> Elaborator<'a> {
                     LitKind::String(s) => (Literal::String(s.clone()), "String"),
                 };
 
-                let lit_term = self.arena.mk_lit(core_lit);
-                let ty_sym = self.arena.get_symbol(ty_name);
+                let lit_term = self.arena.intern(TermKind::Lit(core_lit));
+                let ty_sym = self.symbols.intern(ty_name);
 
                 // Get or create Nat/String type
-                let ty = if let Some(info) = self.env.get_constant(ty_sym) {
-                    info.type_
+                let ty = if self.env.has_decl(ty_sym) {
+                    self.arena.mk_const(ty_sym, vec![])
                 } else {
                     // Create a basic type constant
-                    let type0 = self.arena.mk_sort(LevelId::new(0));
-                    type0
+                    let zero = self.levels.zero();
+                    self.arena.mk_sort(zero)
                 };
 
                 Ok((lit_term, ty))
@@ -222,7 +568,7 @@ impl<'a> Elaborator<'a> {
                             func_type = self.substitute(body, arg_term)?;
                         }
 
-                        TermKind::MVar(mvar) => {
+                        TermKind::MVar(_) => {
                             // Function type is unknown, create metavariables
                             let arg_ty_mvar = self.fresh_mvar()?;
                             let result_ty_mvar = self.fresh_mvar()?;
@@ -230,7 +576,7 @@ impl<'a> Elaborator<'a> {
                             // Create Pi type: arg_ty -> result_ty
                             let pi_ty = self.arena.mk_pi(
                                 Binder::new(
-                                    self.arena.get_symbol("_"),
+                                    self.symbols.intern("_"),
                                     arg_ty_mvar,
                                 ),
                                 result_ty_mvar,
@@ -246,10 +592,10 @@ impl<'a> Elaborator<'a> {
                         }
 
                         _ => {
-                            return Err(ElabError::new(format!(
-                                "Expected function type, got {:?}",
-                                func_type_kind
-                            )));
+                            return Err(ElabError::NotAFunction {
+                                ty: func_type,
+                                span: self.source_map.span_of(app_term),
+                            });
                         }
                     }
                 }
@@ -259,7 +605,7 @@ impl<'a> Elaborator<'a> {
 
             Expr::Lam { params, body, .. } => {
                 // Lambda synthesis: infer types for parameters if not given
-                let mut param_tys = Vec::new();
+                let mut binders = Vec::new();
 
                 for param in params {
                     let ty = if let Some(ref ty_expr) = param.type_ {
@@ -268,11 +614,15 @@ impl<'a> Elaborator<'a> {
                         // Create metavariable for parameter type
                         self.fresh_mvar()?
                     };
-                    param_tys.push(ty);
 
-                    // Add to context
-                    for name in &param.names {
-                        let name_sym = self.arena.get_symbol(&name.name);
+                    // As in `elaborate_params`, each later name of a group
+                    // sees the group's type one binder further away
+                    for (i, name) in param.names.iter().enumerate() {
+                        let name_sym = self.symbols.intern(&name.name);
+                        let ty = self.arena.shift(ty, 0, i as i32);
+                        binders.push((name_sym, ty, param.implicit));
+
+                        // Add to context
                         self.ctx.push(name.name.clone(), name_sym, ty);
                     }
                 }
@@ -281,34 +631,19 @@ impl<'a> Elaborator<'a> {
                 let (body_term, body_ty) = self.synth(body)?;
 
                 // Pop parameters
-                for param in params {
-                    for name in &param.names {
-                        self.ctx.pop(&name.name);
-                    }
-                }
+                self.pop_params(params);
 
-                // Build lambda term
+                // Build lambda term and Pi type
                 let mut term = body_term;
-                for (param, ty) in params.iter().rev().zip(param_tys.iter().rev()) {
-                    for name in param.names.iter().rev() {
-                        let name_sym = self.arena.get_symbol(&name.name);
-                        let binder = Binder::new(name_sym, *ty);
-                        term = self.arena.mk_lam(binder, term);
-                    }
-                }
-
-                // Build Pi type
                 let mut pi_type = body_ty;
-                for (param, ty) in params.iter().rev().zip(param_tys.iter().rev()) {
-                    for name in param.names.iter().rev() {
-                        let name_sym = self.arena.get_symbol(&name.name);
-                        let binder = if param.implicit {
-                            Binder::implicit(name_sym, *ty)
-                        } else {
-                            Binder::new(name_sym, *ty)
-                        };
-                        pi_type = self.arena.mk_pi(binder, pi_type);
-                    }
+                for &(name_sym, ty, implicit) in binders.iter().rev() {
+                    term = self.arena.mk_lam(Binder::new(name_sym, ty), term);
+                    let binder = if implicit {
+                        Binder::implicit(name_sym, ty)
+                    } else {
+                        Binder::new(name_sym, ty)
+                    };
+                    pi_type = self.arena.mk_pi(binder, pi_type);
                 }
 
                 Ok((term, pi_type))
@@ -331,7 +666,7 @@ impl<'a> Elaborator<'a> {
                 let from_term = self.synth(from)?.0;
                 let to_term = self.synth(to)?.0;
 
-                let binder = Binder::new(self.arena.get_symbol("_"), from_term);
+                let binder = Binder::new(self.symbols.intern("_"), from_term);
                 let pi_type = self.arena.mk_pi(binder, to_term);
 
                 let pi_type_ty = self.infer_universe(pi_type)?;
@@ -342,29 +677,19 @@ impl<'a> Elaborator<'a> {
             Expr::Universe { kind, .. } => {
                 use UniverseKind::*;
 
-                let (level, level_ty) = match kind {
-                    Type => {
-                        let l0 = LevelId::new(0);
-                        let l1 = LevelId::new(1);
-                        (self.arena.mk_sort(l0), self.arena.mk_sort(l1))
-                    }
-                    TypeLevel(n) => {
-                        let ln = LevelId::new(*n);
-                        let ln1 = LevelId::new(n + 1);
-                        (self.arena.mk_sort(ln), self.arena.mk_sort(ln1))
-                    }
-                    Prop => {
-                        let l0 = LevelId::new(0);
-                        let l1 = LevelId::new(1);
-                        (self.arena.mk_sort(l0), self.arena.mk_sort(l1))
-                    }
+                let n = match kind {
+                    Type | Prop => 0,
+                    TypeLevel(n) => *n,
                     Sort(_) => {
                         // Universe variable - would need universe polymorphism
                         return Err(ElabError::new("Universe variables not yet supported".to_string()));
                     }
                 };
+                let level = self.levels.constant(n);
+                let level_succ = self.levels.constant(n + 1);
+                self.universes.add_lt(level, level_succ);
 
-                Ok((level, level_ty))
+                Ok((self.arena.mk_sort(level), self.arena.mk_sort(level_succ)))
             }
 
             Expr::Hole { .. } => {
@@ -374,6 +699,13 @@ impl<'a> Elaborator<'a> {
                 Ok((term_mvar, ty_mvar))
             }
 
+            Expr::Sorry { span } => {
+                // Type is unknown until unification fills it in
+                let ty_mvar = self.fresh_mvar()?;
+                let term = self.mk_sorry(*span, ty_mvar)?;
+                Ok((term, ty_mvar))
+            }
+
             Expr::Ann { expr: inner, type_: ty_expr, .. } => {
                 // Explicit type annotation
                 let ty = self.synth(ty_expr)?.0;
@@ -394,7 +726,7 @@ impl<'a> Elaborator<'a> {
                 }
 
                 // Add to context
-                let name_sym = self.arena.get_symbol(&name.name);
+                let name_sym = self.symbols.intern(&name.name);
                 self.ctx.push(name.name.clone(), name_sym, val_ty);
 
                 // Elaborate body
@@ -423,6 +755,19 @@ impl<'a> Elaborator<'a> {
 
     /// Checking mode: check that an expression has a given type
     pub fn check(&mut self, expr: &Expr, expected_ty: TermId) -> ElabResult<TermId> {
+        let key = self.cache_key(expr, Some(expected_ty));
+        if let Some((term, _)) = self.cache_lookup(key, expr) {
+            return Ok(term);
+        }
+
+        let mark = self.cache_mark();
+        let term = self.check_expr(expr, expected_ty)?;
+        self.source_map.record(term, expr.span());
+        self.cache_store(key, expr, mark, (term, expected_ty));
+        Ok(term)
+    }
+
+    fn check_expr(&mut self, expr: &Expr, expected_ty: TermId) -> ElabResult<TermId> {
         match expr {
             Expr::Lam { params, body, .. } => {
                 // Check lambda against Pi type
@@ -430,48 +775,45 @@ impl<'a> Elaborator<'a> {
                     .ok_or_else(|| ElabError::new("Invalid expected type".to_string()))?;
 
                 if let TermKind::Pi(binder, result_ty) = expected_kind {
-                    // Add parameter to context
-                    if params.is_empty() {
+                    // Each name consumes one Pi binder
+                    let Some((param, rest)) = params.split_first() else {
                         return Err(ElabError::new("Lambda has no parameters".to_string()));
-                    }
+                    };
+                    let Some((name, names)) = param.names.split_first() else {
+                        return Err(ElabError::new("Lambda parameter has no names".to_string()));
+                    };
+                    let name_sym = self.symbols.intern(&name.name);
+                    self.ctx.push(name.name.clone(), name_sym, binder.ty);
 
-                    let param = &params[0];
-                    for name in &param.names {
-                        let name_sym = self.arena.get_symbol(&name.name);
-                        self.ctx.push(name.name.clone(), name_sym, binder.ty);
+                    // Check body with the remaining names and params
+                    let mut remaining = Vec::with_capacity(rest.len() + 1);
+                    if !names.is_empty() {
+                        remaining.push(Param { names: names.to_vec(), ..param.clone() });
                     }
-
-                    // Check body with remaining params
-                    let body_expr = if params.len() > 1 {
+                    remaining.extend_from_slice(rest);
+                    let body_expr = if remaining.is_empty() {
+                        body
+                    } else {
                         &Expr::Lam {
                             span: body.span(),
-                            params: params[1..].to_vec(),
+                            params: remaining,
                             body: body.clone(),
                         }
-                    } else {
-                        body
                     };
 
-                    let body_term = self.check(body_expr, result_ty)?;
+                    let body_term = self.check(body_expr, result_ty);
 
                     // Pop parameter
-                    for name in &param.names {
-                        self.ctx.pop(&name.name);
-                    }
+                    self.ctx.pop(&name.name);
 
                     // Build lambda
-                    let mut term = body_term;
-                    for name in param.names.iter().rev() {
-                        let name_sym = self.arena.get_symbol(&name.name);
-                        let lam_binder = Binder::new(name_sym, binder.ty);
-                        term = self.arena.mk_lam(lam_binder, term);
-                    }
+                    let term = self.arena.mk_lam(Binder::new(name_sym, binder.ty), body_term?);
 
                     Ok(term)
                 } else {
                     // Fall back to synthesis and unification
                     let (term, inferred_ty) = self.synth(expr)?;
-                    self.unifier.unify(inferred_ty, expected_ty);
+                    self.ensure_type(expr, inferred_ty, expected_ty)?;
                     Ok(term)
                 }
             }
@@ -481,15 +823,55 @@ impl<'a> Elaborator<'a> {
                 Ok(self.fresh_mvar_with_type(expected_ty)?)
             }
 
+            Expr::Sorry { span } => {
+                // Accepted against any goal, but recorded
+                self.mk_sorry(*span, expected_ty)
+            }
+
             _ => {
                 // Fall back to synthesis and check equality
                 let (term, inferred_ty) = self.synth(expr)?;
-                self.unifier.unify(inferred_ty, expected_ty);
+                self.ensure_type(expr, inferred_ty, expected_ty)?;
                 Ok(term)
             }
         }
     }
 
+    /// Require `actual` to match `expected` for the term elaborated from `expr`
+    ///
+    /// Types containing metavariables are deferred to the unifier; closed
+    /// types are compared right away so the mismatch can point at `expr`.
+    fn ensure_type(&mut self, expr: &Expr, actual: TermId, expected: TermId) -> ElabResult<()> {
+        if actual == expected {
+            return Ok(());
+        }
+
+        if self.has_mvars(actual) || self.has_mvars(expected) {
+            self.unifier.unify(actual, expected);
+            return Ok(());
+        }
+
+        let def_eq = Converter::new()
+            .is_def_eq(self.arena, self.env, &Context::new(), actual, expected)
+            .map_err(|e| ElabError::at(expr.span(), e.to_string()))?;
+        if def_eq {
+            Ok(())
+        } else {
+            Err(ElabError::TypeMismatch {
+                expected,
+                actual,
+                span: Some(expr.span()),
+            })
+        }
+    }
+
+    /// Check if a term mentions any metavariable
+    fn has_mvars(&self, term: TermId) -> bool {
+        fold_term(self.arena, term, false, &mut |found, _, kind, _| {
+            found || matches!(kind, TermKind::MVar(_))
+        })
+    }
+
     /// Elaborate parameters and return binders
     fn elaborate_params(&mut self, params: &[Param]) -> ElabResult<(Vec<Binder>, u32)> {
         let mut binders = Vec::new();
@@ -503,8 +885,12 @@ impl<'a> Elaborator<'a> {
                 self.fresh_mvar()?
             };
 
-            for name in &param.names {
-                let name_sym = self.arena.get_symbol(&name.name);
+            // `(x y : T)` elaborates T once, before x is bound; each later
+            // name in the group sees one more binder, so T's free
+            // variables are shifted past the names already pushed
+            for (i, name) in param.names.iter().enumerate() {
+                let name_sym = self.symbols.intern(&name.name);
+                let ty = self.arena.shift(ty, 0, i as i32);
 
                 let binder = if param.implicit {
                     Binder::implicit(name_sym, ty)
@@ -532,31 +918,20 @@ impl<'a> Elaborator<'a> {
         Ok(result)
     }
 
-    /// Build lambda from params
-    fn build_lambda(&mut self, params: Vec<Param>, body: TermId) -> ElabResult<TermId> {
+    /// Build lambda from the binders produced by `elaborate_params`
+    fn build_lambda(&mut self, binders: &[Binder], body: TermId) -> ElabResult<TermId> {
         let mut result = body;
-
-        for param in params.into_iter().rev() {
-            let ty = if let Some(ref ty_expr) = param.type_ {
-                self.synth(ty_expr)?.0
-            } else {
-                self.fresh_mvar()?
-            };
-
-            for name in param.names.into_iter().rev() {
-                let name_sym = self.arena.get_symbol(&name.name);
-                let binder = Binder::new(name_sym, ty);
-                result = self.arena.mk_lam(binder, result);
-            }
+        for binder in binders.iter().rev() {
+            result = self.arena.mk_lam(binder.clone(), result);
         }
-
         Ok(result)
     }
 
     /// Create a fresh metavariable
     fn fresh_mvar(&mut self) -> ElabResult<TermId> {
         // Type of metavariable is itself a metavariable
-        let type0 = self.arena.mk_sort(LevelId::new(0));
+        let zero = self.levels.zero();
+        let type0 = self.arena.mk_sort(zero);
         let mvar_id = self.mctx.fresh(type0, self.ctx.depth());
         Ok(self.arena.mk_mvar(mvar_id))
     }
@@ -568,28 +943,173 @@ impl<'a> Elaborator<'a> {
     }
 
     /// Infer the universe level of a type
-    fn infer_universe(&self, ty: TermId) -> ElabResult<TermId> {
+    fn infer_universe(&mut self, _ty: TermId) -> ElabResult<TermId> {
         // For now, return Type 1
         // TODO: Proper universe inference
-        Ok(self.arena.mk_sort(LevelId::new(1)))
+        let one = self.levels.constant(1);
+        Ok(self.arena.mk_sort(one))
     }
 
     /// Substitute a term for de Bruijn index 0
-    fn substitute(&self, body: TermId, replacement: TermId) -> ElabResult<TermId> {
+    fn substitute(&self, body: TermId, _replacement: TermId) -> ElabResult<TermId> {
         // TODO: Implement proper substitution
         // For now, just return body (this is incorrect but allows compilation)
         Ok(body)
     }
 
+    /// Build `sorryAx ty`, declaring the axiom on first use
+    fn mk_sorry(&mut self, span: Span, ty: TermId) -> ElabResult<TermId> {
+        self.sorries.record_sorry(span);
+
+        let sym = self.symbols.intern(SORRY_AXIOM);
+        if !self.env.has_decl(sym) {
+            let zero = self.levels.zero();
+            let type0 = self.arena.mk_sort(zero);
+            let alpha = self.arena.mk_var(0);
+            let alpha_sym = self.symbols.intern("α");
+            let sorry_ty = self.arena.mk_pi(Binder::new(alpha_sym, type0), alpha);
+            self.env.add_decl(Declaration::axiom(sym, vec![], sorry_ty))
+                .map_err(|e| ElabError::new(format!("Cannot declare {}: {}", SORRY_AXIOM, e)))?;
+        }
+
+        let sorry_const = self.arena.mk_const(sym, vec![]);
+        Ok(self.arena.mk_app(sorry_const, ty))
+    }
+
+    /// Elaborate a declaration, re-check it with the trusted kernel, and add it
+    ///
+    /// The elaborator is not part of the trusted computing base: it may solve
+    /// metavariables or insert implicit arguments incorrectly. Before anything
+    /// reaches the environment, the produced type and value are checked by
+    /// `lean_agentic::typechecker`, and the declaration is rejected if the
+    /// kernel disagrees.
+    ///
+    /// The environment, `sorry` tracker and universe constraints are saved
+    /// first and restored unless the check passes, so a rejected declaration
+    /// leaves nothing behind: not the `sorryAx` it may have declared, its
+    /// taint, nor its constraints. For accepted declarations, taint and
+    /// constraints accumulate across calls as with `elaborate_decl`.
+    pub fn elaborate_and_check(&mut self, decl: &Decl) -> ElabResult<()> {
+        let committed = self.env.fork();
+        let sorries = self.sorries.clone();
+        let universes = self.universes.clone();
+        let result = self.elaborate_and_check_inner(decl);
+        if result.is_err() {
+            *self.env = committed;
+            self.sorries = sorries;
+            self.universes = universes;
+            // Cached results may name constants that were just discarded
+            if let Some(cache) = self.cache.as_mut() {
+                cache.clear();
+            }
+        }
+        result
+    }
+
+    fn elaborate_and_check_inner(&mut self, decl: &Decl) -> ElabResult<()> {
+        let elaborated = self.elaborate_declarations(decl)?;
+
+        // Members of a mutual block refer to each other, so check them
+        // against an environment that already contains the whole block
+        for d in &elaborated {
+            self.env.add_decl(d.clone())
+                .map_err(|e| ElabError::new(format!("Cannot add declaration: {}", e)))?;
+        }
+        for d in &elaborated {
+            kernel_check(d, self.arena, self.levels, self.env)?;
+        }
+        Ok(())
+    }
+
+    /// Get the source spans recorded for elaborated terms
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// Get the `sorry` tracker
+    pub fn sorries(&self) -> &SorryTracker {
+        &self.sorries
+    }
+
+    /// Get the universe constraints collected so far
+    pub fn universe_constraints(&self) -> &UniverseConstraintSet {
+        &self.universes
+    }
+
+    /// Check the collected universe constraints for consistency
+    ///
+    /// Runs after each batch and mutual block, since a single declaration
+    /// rarely exposes a cycle on its own.
+    pub fn check_universes(&self) -> ElabResult<()> {
+        self.universes.check(self.levels).map_err(|e| match e {
+            lean_agentic::Error::UniverseError(_) => ElabError::Universe(e),
+            other => ElabError::new(other.to_string()),
+        })
+    }
+
     /// Solve pending constraints
+    ///
+    /// Fails with `UnresolvedMetavar` if any metavariable is still
+    /// unassigned once the constraints are exhausted.
     pub fn solve_constraints(&mut self) -> ElabResult<()> {
         let ctx = Context::new();
         self.unifier.solve(self.arena, self.env, &ctx)
             .map_err(|e| ElabError::new(format!("Unification failed: {}", e)))?;
+
+        for mvar in self.mctx.unsolved() {
+            if self.unifier.substitution().lookup(mvar).is_none() {
+                let term = self.arena.mk_mvar(mvar);
+                return Err(ElabError::UnresolvedMetavar {
+                    mvar,
+                    span: self.source_map.span_of(term),
+                });
+            }
+        }
         Ok(())
     }
 }
 
+/// Get the name a declaration introduces, if it introduces exactly one
+fn decl_name(decl: &Decl) -> Option<&str> {
+    match decl {
+        Decl::Def(d) => Some(&d.name.name),
+        Decl::Theorem(t) => Some(&t.name.name),
+        Decl::Axiom(a) => Some(&a.name.name),
+        Decl::Inductive(i) => Some(&i.name.name),
+        Decl::Structure(s) => Some(&s.name.name),
+        Decl::Infix(_) | Decl::Mutual(_) => None,
+    }
+}
+
+/// Re-check an elaborated declaration against the trusted kernel
+///
+/// The declared type must itself be a type, and the value (if any) must
+/// check against it in the empty local context.
+pub fn kernel_check(
+    decl: &Declaration,
+    arena: &mut Arena,
+    levels: &mut LevelArena,
+    env: &Environment,
+) -> ElabResult<()> {
+    let ctx = Context::new();
+
+    let sort = typechecker::infer(arena, levels, env, &ctx, decl.ty)
+        .map_err(|e| ElabError::new(format!("Kernel rejected type: {}", e)))?;
+    if !matches!(arena.kind(sort), Some(TermKind::Sort(_))) {
+        return Err(ElabError::new(format!(
+            "Kernel rejected type: {:?} is not a type",
+            decl.ty
+        )));
+    }
+
+    if let Some(value) = decl.value {
+        typechecker::check(arena, levels, env, &ctx, value, decl.ty)
+            .map_err(|e| ElabError::new(format!("Kernel rejected value: {}", e)))?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -598,4 +1118,655 @@ mod tests {
     fn test_elaborate_simple() {
         // TODO: Add tests once we can compile
     }
+
+    fn parse_decls(input: &str) -> Vec<Decl> {
+        let source = leanr_syntax::SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = leanr_syntax::Lexer::new(source).tokenize();
+        leanr_syntax::Parser::new(tokens).parse_decls().unwrap()
+    }
+
+    #[test]
+    fn test_mutual_definitions_reference_each_other() {
+        let decls = parse_decls(r#"
+            axiom Nat : Type
+            axiom Bool : Type
+            mutual
+              def isEven (n : Nat) : Bool := isOdd n
+              def isOdd (n : Nat) : Bool := isEven n
+            end
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        for decl in &decls {
+            Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).elaborate_decl(decl).unwrap();
+        }
+
+        let is_even = symbols.intern("isEven");
+        let is_odd = symbols.intern("isOdd");
+
+        // Each body is `λ n. other n`, so it mentions the other constant
+        let mentions = |arena: &Arena, value: TermId, target: SymbolId| {
+            let mut stack = vec![value];
+            while let Some(t) = stack.pop() {
+                match arena.kind(t) {
+                    Some(TermKind::Const(name, _)) if *name == target => return true,
+                    Some(TermKind::App(f, a)) => stack.extend([*f, *a]),
+                    Some(TermKind::Lam(_, body)) => stack.push(*body),
+                    _ => {}
+                }
+            }
+            false
+        };
+
+        let even_value = env.get_decl(is_even).and_then(|d| d.value).unwrap();
+        let odd_value = env.get_decl(is_odd).and_then(|d| d.value).unwrap();
+        assert!(mentions(&arena, even_value, is_odd));
+        assert!(mentions(&arena, odd_value, is_even));
+    }
+
+    #[test]
+    fn test_mutual_block_passes_kernel_check() {
+        let decls = parse_decls(r#"
+            axiom Nat : Type
+            axiom Bool : Type
+            mutual
+              def isEven (n : Nat) : Bool := isOdd n
+              def isOdd (n : Nat) : Bool := isEven n
+            end
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        for decl in &decls {
+            elab.elaborate_and_check(decl).unwrap();
+        }
+
+        // Both members land together, under the names the symbol table gave them
+        assert_eq!(env.num_decls(), 4);
+        assert!(env.has_decl(symbols.intern("isEven")));
+        assert!(env.has_decl(symbols.intern("isOdd")));
+    }
+
+    #[test]
+    fn test_batch_reports_universe_cycle() {
+        let decls = parse_decls("axiom A : Type");
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        elab.elaborate_decls(&decls).unwrap();
+
+        // `1 ≤ 0` contradicts `0 < 1`
+        let (zero, one) = (elab.levels.zero(), elab.levels.constant(1));
+        elab.universes.add_le(one, zero);
+
+        let err = elab.elaborate_decls(&[]).unwrap_err();
+        assert!(
+            matches!(err, ElabError::Universe(lean_agentic::Error::UniverseError(_))),
+            "unexpected error: {:?}",
+            err
+        );
+        assert!(err.message().contains("universe cycle"));
+    }
+
+    #[test]
+    fn test_elaborate_decls_resolves_forward_references() {
+        let decls = parse_decls(r#"
+            def b : Nat := a
+            axiom Nat : Type
+            axiom a : Nat
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).elaborate_decls(&decls).unwrap();
+
+        let b = symbols.intern("b");
+        assert!(env.has_decl(b));
+
+        let cyclic = parse_decls(r#"
+            axiom Nat : Type
+            def f : Nat := g
+            def g : Nat := f
+        "#);
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let err = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).elaborate_decls(&cyclic).unwrap_err();
+        assert!(err.message().contains("Cyclic"));
+        assert_eq!(env.num_decls(), 0);
+    }
+
+    #[test]
+    fn test_sorry_proof_is_accepted_and_tracked() {
+        let decls = parse_decls(r#"
+            axiom P : Prop
+            theorem unfinished : P := sorry
+            theorem uses_it : P := unfinished
+            axiom Q : Prop
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        for decl in &decls {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        let sorries = elab.sorries();
+        assert_eq!(sorries.uses().len(), 1);
+        assert!(sorries.is_tainted("unfinished"));
+        assert!(sorries.is_tainted("uses_it"));
+        assert!(!sorries.is_tainted("P"));
+        assert!(!sorries.is_tainted("Q"));
+
+        let thm = symbols.intern("unfinished");
+        assert!(env.has_decl(thm));
+    }
+
+    #[test]
+    fn test_sorry_axiom_is_declared_once_and_kernel_checks() {
+        let decls = parse_decls(r#"
+            axiom P : Prop
+            theorem first : P := sorry
+            theorem second : P := sorry
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        for decl in &decls {
+            elab.elaborate_and_check(decl).unwrap();
+        }
+
+        // sorryAx : Π (α : Type 0), α
+        let sorry_ax = env.get_decl(symbols.intern(SORRY_AXIOM)).unwrap();
+        let zero = levels.zero();
+        let type0 = arena.mk_sort(zero);
+        let alpha = arena.mk_var(0);
+        let expected = arena.mk_pi(Binder::new(symbols.intern("α"), type0), alpha);
+        assert_eq!(sorry_ax.ty, expected);
+        assert_eq!(env.num_decls(), 4);
+    }
+
+    #[test]
+    fn test_checked_elaboration_tracks_sorry_and_rolls_back_failures() {
+        let decls = parse_decls(r#"
+            axiom Nat : Type
+            axiom Bool : Type
+            def bad (A : Type) : Bool := (sorry : Nat)
+            axiom P : Prop
+            theorem unfinished : P := sorry
+            theorem uses_it : P := unfinished
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        elab.elaborate_and_check(&decls[0]).unwrap();
+        elab.elaborate_and_check(&decls[1]).unwrap();
+        let constraints = elab.universe_constraints().len();
+        assert!(elab.elaborate_and_check(&decls[2]).is_err());
+
+        // Neither the rejected declaration nor the sorryAx it declared
+        // remain, and neither do its taint or universe constraints
+        assert!(!elab.env.has_decl(symbols.intern("bad")));
+        assert!(!elab.env.has_decl(symbols.intern(SORRY_AXIOM)));
+        assert_eq!(elab.env.num_decls(), 2);
+        assert!(!elab.sorries().is_tainted("bad"));
+        assert!(elab.sorries().uses().is_empty());
+        assert_eq!(elab.universe_constraints().len(), constraints);
+
+        // Taint carries over from one call to the next
+        for decl in &decls[3..] {
+            elab.elaborate_and_check(decl).unwrap();
+        }
+        assert!(elab.sorries().is_tainted("unfinished"));
+        assert!(elab.sorries().is_tainted("uses_it"));
+    }
+
+    #[test]
+    fn test_kernel_check_catches_broken_elaboration() {
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let env = Environment::new();
+
+        let zero = levels.zero();
+        let type0 = arena.mk_sort(zero);
+        let x = arena.mk_var(0);
+        let id_ty = arena.mk_pi(Binder::new(symbols.intern("x"), type0), type0);
+        let id = arena.mk_lam(Binder::new(symbols.intern("x"), type0), x);
+
+        // A well-typed definition passes the re-check
+        let good = Declaration::def(symbols.intern("good"), vec![], id_ty, id);
+        assert!(kernel_check(&good, &mut arena, &mut levels, &env).is_ok());
+
+        // Simulate an elaborator bug that pairs the body with the wrong type:
+        // `id : Type 0` would be admitted without the kernel re-check.
+        let broken = Declaration::def(symbols.intern("broken"), vec![], type0, id);
+        let err = kernel_check(&broken, &mut arena, &mut levels, &env).unwrap_err();
+        assert!(err.message().contains("Kernel rejected value"));
+    }
+
+    #[test]
+    fn test_error_span_points_at_subexpression() {
+        let input = "axiom Nat : Type\naxiom zero : Nat\ndef bad : Nat := (zero) zero";
+        let decls = parse_decls(input);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        elab.elaborate_decl(&decls[0]).unwrap();
+        elab.elaborate_decl(&decls[1]).unwrap();
+
+        // `zero : Nat` is applied as if it were a function
+        let err = elab.elaborate_decl(&decls[2]).unwrap_err();
+        assert!(matches!(err, ElabError::NotAFunction { .. }));
+        let span = err.span().expect("error should carry a source span");
+        let start = input.find("(zero)").unwrap() + 1;
+        assert_eq!(&input[span.start as usize..span.end as usize], "zero");
+        assert_eq!(span.start as usize, start);
+
+        // Every elaborated subterm is mapped back to its source
+        assert!(!elab.source_map().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_identifier_carries_its_span() {
+        let input = "axiom Nat : Type\ndef f : Nat := missing";
+        let decls = parse_decls(input);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        elab.elaborate_decl(&decls[0]).unwrap();
+
+        match elab.elaborate_decl(&decls[1]).unwrap_err() {
+            ElabError::UnknownIdentifier { name, span } => {
+                assert_eq!(name, "missing");
+                assert_eq!(&input[span.start as usize..span.end as usize], "missing");
+            }
+            other => panic!("expected UnknownIdentifier, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_type_mismatch_carries_both_types() {
+        let input = "axiom Nat : Type\naxiom Bool : Type\naxiom tt : Bool\ndef f : Nat := tt";
+        let decls = parse_decls(input);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        for decl in &decls[..3] {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        let err = elab.elaborate_decl(&decls[3]).unwrap_err();
+        let nat = arena.mk_const(symbols.intern("Nat"), vec![]);
+        let bool_ty = arena.mk_const(symbols.intern("Bool"), vec![]);
+        match err {
+            ElabError::TypeMismatch { expected, actual, span } => {
+                assert_eq!(expected, nat);
+                assert_eq!(actual, bool_ty);
+                let span = span.unwrap();
+                assert_eq!(&input[span.start as usize..span.end as usize], "tt");
+            }
+            other => panic!("expected TypeMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_type_ascription_elaborates_through_ann() {
+        let decls = parse_decls("axiom Nat : Type\ndef five := (5 : Nat)");
+        assert!(matches!(
+            &decls[1],
+            Decl::Def(def) if matches!(*def.body, Expr::Ann { .. })
+        ));
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        elab.elaborate_decl(&decls[0]).unwrap();
+
+        let Decl::Def(def) = &decls[1] else { unreachable!() };
+        let (term, ty) = elab.synth(&def.body).unwrap();
+
+        assert!(matches!(arena.kind(term), Some(TermKind::Lit(_))));
+        let nat = arena.mk_const(symbols.intern("Nat"), vec![]);
+        assert_eq!(ty, nat);
+    }
+
+    #[test]
+    fn test_param_telescope_binds_earlier_names() {
+        let decls = parse_decls("axiom f (A : Type) (x y : A) : A");
+        let Decl::Axiom(ax) = &decls[0] else { unreachable!() };
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        let (binders, depth) = elab.elaborate_params(&ax.params).unwrap();
+        assert_eq!(depth, 3);
+
+        // x sees A one binder up; y sees A past both A's group and x
+        assert_eq!(arena.kind(binders[1].ty), Some(&TermKind::Var(0)));
+        assert_eq!(arena.kind(binders[2].ty), Some(&TermKind::Var(1)));
+    }
+
+    #[test]
+    fn test_lambda_binder_group_shifts_type() {
+        let expr = parse_expr("fun (A : Type) (x y : A) => y");
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+
+        let (synthesized, ty) = elab.synth(&expr).unwrap();
+        let checked = elab.check(&expr, ty).unwrap();
+        assert_eq!(checked, synthesized);
+
+        // λ (A : Type) (x : #0) (y : #1), #0 : Π (A : Type) (x : #0) (y : #1), #2
+        let binder_tys = |arena: &Arena, mut term: TermId| {
+            let mut tys = Vec::new();
+            while let Some(TermKind::Lam(b, body) | TermKind::Pi(b, body)) = arena.kind(term) {
+                tys.push(arena.kind(b.ty).cloned());
+                term = *body;
+            }
+            (tys, arena.kind(term).cloned())
+        };
+        for term in [synthesized, ty] {
+            let (tys, _) = binder_tys(&arena, term);
+            assert_eq!(tys[1..], [Some(TermKind::Var(0)), Some(TermKind::Var(1))]);
+        }
+        assert_eq!(binder_tys(&arena, synthesized).1, Some(TermKind::Var(0)));
+        assert_eq!(binder_tys(&arena, ty).1, Some(TermKind::Var(2)));
+    }
+
+    fn parse_expr(input: &str) -> Expr {
+        let source = leanr_syntax::SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = leanr_syntax::Lexer::new(source).tokenize();
+        leanr_syntax::Parser::new(tokens).parse_expr().unwrap()
+    }
+
+    #[test]
+    fn test_cache_reuses_structurally_identical_exprs() {
+        let decls = parse_decls("axiom Nat : Type\naxiom zero : Nat\naxiom succ : Nat -> Nat");
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).with_cache();
+        for decl in &decls {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        // Same expression at different positions and with extra grouping;
+        // the second is answered at the top level without descending
+        let (first, first_ty) = elab.synth(&parse_expr("succ (succ zero)")).unwrap();
+        let hits = elab.cache_hits();
+        let (second, second_ty) = elab.synth(&parse_expr("  (succ   (succ zero))")).unwrap();
+        assert_eq!(elab.cache_hits(), hits + 1);
+        assert_eq!((first, first_ty), (second, second_ty));
+
+        // The reused term points at where it was reused
+        let third_expr = parse_expr("    succ (succ zero)");
+        let (third, _) = elab.synth(&third_expr).unwrap();
+        assert_eq!(third, first);
+        assert_eq!(elab.source_map().span_of(third), Some(third_expr.span()));
+
+        // A different expression misses
+        let (other, _) = elab.synth(&parse_expr("succ zero")).unwrap();
+        assert_ne!(other, first);
+
+        // Without the cache every elaboration starts from scratch
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut plain = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        for decl in &decls {
+            plain.elaborate_decl(decl).unwrap();
+        }
+        plain.synth(&parse_expr("succ zero")).unwrap();
+        plain.synth(&parse_expr("succ zero")).unwrap();
+        assert_eq!(plain.cache_hits(), 0);
+    }
+
+    #[test]
+    fn test_cache_hits_after_definition_with_params() {
+        let decls = parse_decls(r#"
+            axiom Nat : Type
+            axiom zero : Nat
+            axiom succ : Nat -> Nat
+            def double (n : Nat) : Nat := n
+            theorem refl_like (m : Nat) : Nat := m
+            axiom family (k : Nat) : Type
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).with_cache();
+        for decl in &decls {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        // Every declaration's parameters are out of scope again
+        elab.synth(&parse_expr("succ zero")).unwrap();
+        let hits = elab.cache_hits();
+        elab.synth(&parse_expr("succ zero")).unwrap();
+        assert_eq!(elab.cache_hits(), hits + 1);
+        assert!(elab.synth(&parse_expr("n")).is_err());
+    }
+
+    #[test]
+    fn test_cache_keeps_holes_distinct() {
+        let decls = parse_decls("axiom Nat : Type");
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).with_cache();
+        for decl in &decls {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        let (nat, _) = elab.synth(&parse_expr("Nat")).unwrap();
+        let first = elab.check(&parse_expr("_"), nat).unwrap();
+        let second = elab.check(&parse_expr("_"), nat).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_cache_hit_propagates_sorry_taint() {
+        let decls = parse_decls(r#"
+            axiom P : Prop
+            theorem unfinished : P := sorry
+            theorem first : P := unfinished
+            theorem second : P := unfinished
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).with_cache();
+        for decl in &decls {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        assert!(elab.cache_hits() > 0);
+        assert!(elab.sorries().is_tainted("first"));
+        assert!(elab.sorries().is_tainted("second"));
+    }
+
+    #[test]
+    fn test_cache_ignores_hash_collisions() {
+        let decls = parse_decls("axiom Nat : Type\naxiom zero : Nat\naxiom succ : Nat -> Nat");
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env).with_cache();
+        for decl in &decls {
+            elab.elaborate_decl(decl).unwrap();
+        }
+
+        // File the result for `zero` under the key of `succ zero`
+        let zero = parse_expr("zero");
+        let succ_zero = parse_expr("succ zero");
+        let (zero_term, _) = elab.synth(&zero).unwrap();
+        let cache = elab.cache.as_mut().unwrap();
+        let entry = cache.remove(&(zero.structural_hash(), None)).unwrap();
+        cache.insert((succ_zero.structural_hash(), None), entry);
+
+        let hits = elab.cache_hits();
+        let (term, _) = elab.synth(&succ_zero).unwrap();
+        assert_ne!(term, zero_term);
+        assert_eq!(elab.cache_hits(), hits);
+    }
+
+    #[test]
+    fn test_export_import_round_trips_elaborated_environment() {
+        use lean_agentic::environment::{ConstructorDecl, InductiveDecl};
+
+        let decls = parse_decls(r#"
+            axiom Nat : Type
+            axiom zero : Nat
+            axiom succ : Nat -> Nat
+            def plusTwo (n : Nat) : Nat := succ (succ n)
+            def two : Nat := plusTwo zero
+        "#);
+
+        let mut arena = Arena::new();
+        let symbols = SymbolTable::new();
+        let mut levels = LevelArena::new();
+        let mut env = Environment::new();
+        let mut elab = Elaborator::new(&mut arena, &symbols, &mut levels, &mut env);
+        for decl in &decls {
+            elab.elaborate_and_check(decl).unwrap();
+        }
+
+        // Inductive elaboration is not implemented, so `Nat` is registered
+        // as an inductive over its elaborated type and constructors
+        let name = |text: &str| symbols.intern(text);
+        let ty_of = |env: &Environment, text: &str| env.get_decl(symbols.intern(text)).unwrap().ty;
+        let nat = InductiveDecl {
+            name: name("Nat"),
+            level_params: vec![],
+            ty: ty_of(&env, "Nat"),
+            num_params: 0,
+            num_indices: 0,
+            constructors: vec![
+                ConstructorDecl { name: name("zero"), ty: ty_of(&env, "zero"), num_fields: 0 },
+                ConstructorDecl { name: name("succ"), ty: ty_of(&env, "succ"), num_fields: 1 },
+            ],
+            recursor: Some(name("Nat.rec")),
+        };
+        env.add_inductive(nat).unwrap();
+
+        let path = std::env::temp_dir().join(format!("leanr-elab-env-{}.olean", std::process::id()));
+        env.export(&path, &arena, &symbols, &levels).unwrap();
+
+        // A new session whose symbol and level IDs differ from the exporter's
+        let fresh_symbols = SymbolTable::new();
+        fresh_symbols.intern("unrelated");
+        let mut fresh_levels = LevelArena::new();
+        fresh_levels.constant(7);
+        let mut fresh = Arena::new();
+        let loaded =
+            Environment::import(&path, &mut fresh, &fresh_symbols, &mut fresh_levels).unwrap();
+        assert_eq!(loaded.num_decls(), env.num_decls());
+
+        for (decl_name, decl) in env.declarations() {
+            let text = symbols.resolve(*decl_name).unwrap();
+            let restored = loaded.get_decl(fresh_symbols.intern(&text)).unwrap();
+            assert_eq!(restored.kind, decl.kind);
+            assert_eq!(restored.attrs, decl.attrs);
+            assert_eq!(restored.value.is_some(), decl.value.is_some());
+        }
+
+        // The imported definitions still pass the kernel in the new session
+        for text in ["plusTwo", "two"] {
+            let decl = loaded.get_decl(fresh_symbols.intern(text)).unwrap().clone();
+            kernel_check(&decl, &mut fresh, &mut fresh_levels, &loaded).unwrap();
+        }
+
+        // The inductive tables come back under the new session's names
+        let fresh_nat = fresh_symbols.intern("Nat");
+        let restored = loaded.get_inductive(fresh_nat).unwrap();
+        assert_eq!(restored.ty, loaded.get_decl(fresh_nat).unwrap().ty);
+        assert_eq!((restored.num_params, restored.num_indices), (0, 0));
+        assert_eq!(restored.recursor, Some(fresh_symbols.intern("Nat.rec")));
+        let ctors: Vec<_> = restored
+            .constructors
+            .iter()
+            .map(|c| (fresh_symbols.resolve(c.name).unwrap().to_string(), c.num_fields))
+            .collect();
+        assert_eq!(ctors, vec![("zero".to_string(), 0), ("succ".to_string(), 1)]);
+        let succ = fresh_symbols.intern("succ");
+        assert!(loaded.is_constructor(succ));
+        assert_eq!(loaded.get_inductive_of_constructor(succ).unwrap().name, fresh_nat);
+        match fresh.kind(restored.constructors[1].ty) {
+            Some(TermKind::Pi(binder, body)) => {
+                assert_eq!(fresh.kind(binder.ty), Some(&TermKind::Const(fresh_nat, vec![])));
+                assert_eq!(binder.ty, *body);
+            }
+            other => panic!("expected a Pi, got {:?}", other),
+        }
+
+        // Re-importing into the same session yields the same term IDs
+        let again =
+            Environment::import(&path, &mut fresh, &fresh_symbols, &mut fresh_levels).unwrap();
+        assert_eq!(
+            again.get_decl(fresh_symbols.intern("plusTwo")).unwrap().value,
+            loaded.get_decl(fresh_symbols.intern("plusTwo")).unwrap().value
+        );
+
+        // Corruption is caught by the checksum
+        let mut bytes = std::fs::read(&path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+        let err = Environment::import(&path, &mut fresh, &fresh_symbols, &mut fresh_levels)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("checksum"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
 }
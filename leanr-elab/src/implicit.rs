@@ -2,7 +2,7 @@
 //!
 //! Automatically inserts metavariables for implicit parameters.
 
-use lean_agentic::{Arena, TermId, term::{BinderInfo, MetaVarId}};
+use lean_agentic::{Arena, TermId};
 use crate::metavar::MetaVarContext;
 use crate::ElabResult;
 
@@ -21,11 +21,11 @@ impl ImplicitHandler {
     /// Insert implicit arguments for a function application
     pub fn insert_implicits(
         &mut self,
-        func_type: TermId,
-        arena: &Arena,
-        depth: u32,
+        _func_type: TermId,
+        _arena: &Arena,
+        _depth: u32,
     ) -> ElabResult<Vec<TermId>> {
-        let mut implicits = Vec::new();
+        let implicits = Vec::new();
 
         // TODO: Walk through Pi type and insert metavariables for implicit params
         // For now, return empty vec
@@ -51,8 +51,11 @@ mod tests {
     #[test]
     fn test_implicit_insertion() {
         let mctx = MetaVarContext::new();
-        let handler = ImplicitHandler::new(mctx);
+        let mut handler = ImplicitHandler::new(mctx);
+        let mut arena = Arena::new();
+        let func_type = arena.mk_nat(0);
 
-        // TODO: Add tests
+        // TODO: Exercise implicit Pi binders once insertion is implemented
+        assert!(handler.insert_implicits(func_type, &arena, 0).unwrap().is_empty());
     }
 }
@@ -10,21 +10,30 @@ pub mod elaborate;
 pub mod context;
 pub mod metavar;
 pub mod implicit;
+pub mod command;
+pub mod sorry;
+pub mod deps;
+pub mod source_map;
 
-pub use elaborate::{Elaborator, ElabError, ElabResult};
+pub use elaborate::{kernel_check, Elaborator, ElabError, ElabResult};
 pub use context::ElabContext;
 pub use metavar::MetaVarContext;
+pub use command::{run_command, CommandOutput};
+pub use sorry::{SorryTracker, SorryUse};
+pub use source_map::SourceMap;
 
-use lean_agentic::{Arena, Environment, Term, TermId, TermKind};
+use lean_agentic::{level::LevelArena, Arena, Environment, SymbolTable, TermId};
 use leanr_syntax::{Expr, Decl};
 
 /// Main elaboration entry point
 pub fn elaborate_decl(
     decl: &Decl,
     arena: &mut Arena,
+    symbols: &SymbolTable,
+    levels: &mut LevelArena,
     env: &mut Environment,
 ) -> ElabResult<()> {
-    let mut elab = Elaborator::new(arena, env);
+    let mut elab = Elaborator::new(arena, symbols, levels, env);
     elab.elaborate_decl(decl)
 }
 
@@ -33,9 +42,11 @@ pub fn elaborate_expr(
     expr: &Expr,
     expected_type: Option<TermId>,
     arena: &mut Arena,
-    env: &Environment,
+    symbols: &SymbolTable,
+    levels: &mut LevelArena,
+    env: &mut Environment,
 ) -> ElabResult<TermId> {
-    let mut elab = Elaborator::new(arena, env);
+    let mut elab = Elaborator::new(arena, symbols, levels, env);
 
     if let Some(ty) = expected_type {
         elab.check(expr, ty)
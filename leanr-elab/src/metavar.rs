@@ -106,14 +106,15 @@ impl Default for MetaVarContext {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use lean_agentic::term::TermId;
+    use lean_agentic::Arena;
 
     #[test]
     fn test_fresh_mvar() {
         let mut mctx = MetaVarContext::new();
+        let mut arena = Arena::new();
 
-        let mvar1 = mctx.fresh(TermId::new(10), 0);
-        let mvar2 = mctx.fresh(TermId::new(20), 0);
+        let mvar1 = mctx.fresh(arena.mk_nat(10), 0);
+        let mvar2 = mctx.fresh(arena.mk_nat(20), 0);
 
         assert_ne!(mvar1, mvar2);
         assert!(!mctx.is_assigned(mvar1));
@@ -122,9 +123,10 @@ mod tests {
     #[test]
     fn test_assign_mvar() {
         let mut mctx = MetaVarContext::new();
+        let mut arena = Arena::new();
 
-        let mvar = mctx.fresh(TermId::new(10), 0);
-        let term = TermId::new(100);
+        let mvar = mctx.fresh(arena.mk_nat(10), 0);
+        let term = arena.mk_nat(100);
 
         mctx.assign(mvar, term).unwrap();
 
@@ -135,11 +137,12 @@ mod tests {
     #[test]
     fn test_unsolved() {
         let mut mctx = MetaVarContext::new();
+        let mut arena = Arena::new();
 
-        let mvar1 = mctx.fresh(TermId::new(10), 0);
-        let mvar2 = mctx.fresh(TermId::new(20), 0);
+        let mvar1 = mctx.fresh(arena.mk_nat(10), 0);
+        let mvar2 = mctx.fresh(arena.mk_nat(20), 0);
 
-        mctx.assign(mvar1, TermId::new(100)).unwrap();
+        mctx.assign(mvar1, arena.mk_nat(100)).unwrap();
 
         let unsolved = mctx.unsolved();
         assert_eq!(unsolved.len(), 1);
@@ -0,0 +1,111 @@
+//! Tracking of `sorry` placeholders
+//!
+//! `sorry` type-checks against any goal, so a declaration using it is not
+//! actually proven. The tracker records every use and propagates the taint
+//! to declarations that reference a tainted constant.
+
+use leanr_syntax::Span;
+use std::collections::BTreeSet;
+
+/// A single occurrence of `sorry`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SorryUse {
+    /// Declaration being elaborated when `sorry` was encountered
+    pub decl: Option<String>,
+
+    /// Source location of the `sorry`
+    pub span: Span,
+}
+
+/// Records `sorry` uses and the declarations that depend on them
+#[derive(Debug, Clone, Default)]
+pub struct SorryTracker {
+    /// Every `sorry` encountered, in elaboration order
+    uses: Vec<SorryUse>,
+
+    /// Declarations that use `sorry` directly or through a dependency
+    tainted: BTreeSet<String>,
+
+    /// Declaration currently being elaborated
+    current: Option<String>,
+}
+
+impl SorryTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start elaborating a declaration
+    pub fn begin_decl(&mut self, name: &str) {
+        self.current = Some(name.to_string());
+    }
+
+    /// Finish the current declaration
+    pub fn end_decl(&mut self) {
+        self.current = None;
+    }
+
+    /// Record a `sorry` in the current declaration
+    pub fn record_sorry(&mut self, span: Span) {
+        if let Some(ref name) = self.current {
+            self.tainted.insert(name.clone());
+        }
+        self.uses.push(SorryUse {
+            decl: self.current.clone(),
+            span,
+        });
+    }
+
+    /// Record that the current declaration references the constant `name`
+    pub fn record_reference(&mut self, name: &str) {
+        if self.tainted.contains(name) {
+            if let Some(ref current) = self.current {
+                self.tainted.insert(current.clone());
+            }
+        }
+    }
+
+    /// Check if a declaration depends on `sorry`
+    pub fn is_tainted(&self, name: &str) -> bool {
+        self.tainted.contains(name)
+    }
+
+    /// Get the declarations that depend on `sorry`, sorted by name
+    pub fn tainted_decls(&self) -> &BTreeSet<String> {
+        &self.tainted
+    }
+
+    /// Get every recorded `sorry` use
+    pub fn uses(&self) -> &[SorryUse] {
+        &self.uses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_taint_propagates_through_references() {
+        let mut tracker = SorryTracker::new();
+
+        tracker.begin_decl("lemma");
+        tracker.record_sorry(Span::dummy());
+        tracker.end_decl();
+
+        tracker.begin_decl("main");
+        tracker.record_reference("lemma");
+        tracker.end_decl();
+
+        tracker.begin_decl("clean");
+        tracker.record_reference("other");
+        tracker.end_decl();
+
+        assert!(tracker.is_tainted("lemma"));
+        assert!(tracker.is_tainted("main"));
+        assert!(!tracker.is_tainted("clean"));
+        assert_eq!(tracker.uses().len(), 1);
+        assert_eq!(tracker.uses()[0].decl.as_deref(), Some("lemma"));
+    }
+}
@@ -0,0 +1,86 @@
+//! Mapping from elaborated core terms back to source locations
+//!
+//! Desugaring (`A -> B` to a Pi, operators to applications) produces core
+//! terms with no position information. The elaborator records the span of
+//! the expression each `TermId` was built from, so errors found later on a
+//! term can still point into the source.
+
+use lean_agentic::TermId;
+use leanr_syntax::Span;
+use std::collections::HashMap;
+
+/// Side table from `TermId` to the span it was elaborated from
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    spans: HashMap<TermId, Span>,
+}
+
+impl SourceMap {
+    /// Create an empty source map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `term` was produced from the expression at `span`
+    ///
+    /// Terms are hash-consed, so the same `TermId` can come from several
+    /// places. Subexpressions are elaborated before their parents, so
+    /// keeping the first span keeps the most precise one.
+    pub fn record(&mut self, term: TermId, span: Span) {
+        self.spans.entry(term).or_insert(span);
+    }
+
+    /// Point `term` at `span`, replacing any span recorded for it
+    ///
+    /// For a cached term reused at a new position: no subexpressions are
+    /// elaborated there, so the reused term's own span is the most precise
+    /// one, and errors raised on it should point at this occurrence.
+    pub fn rebind(&mut self, term: TermId, span: Span) {
+        self.spans.insert(term, span);
+    }
+
+    /// Get the source span a term was elaborated from
+    pub fn span_of(&self, term: TermId) -> Option<Span> {
+        self.spans.get(&term).copied()
+    }
+
+    /// Number of terms with a known span
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Check if no spans have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Forget all recorded spans
+    pub fn clear(&mut self) {
+        self.spans.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lean_agentic::Arena;
+
+    #[test]
+    fn test_first_span_wins() {
+        let mut arena = Arena::new();
+        let x = arena.mk_var(0);
+        let inner = Span::new(5, 6, 0);
+        let outer = Span::new(4, 7, 0);
+
+        let mut map = SourceMap::new();
+        assert!(map.is_empty());
+
+        map.record(x, inner);
+        map.record(x, outer);
+        assert_eq!(map.span_of(x), Some(inner));
+        assert_eq!(map.len(), 1);
+
+        let y = arena.mk_var(1);
+        assert_eq!(map.span_of(y), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! Built-in reduction rules for natural number literals
+//!
+//! Nat literals are stored as `Literal::Nat`, not as `succ`/`zero` chains,
+//! so arithmetic on them cannot reduce through ordinary definitions. The
+//! normalizer instead recognizes registered constants such as `Nat.add`
+//! applied to two literals and computes the result directly.
+
+use lean_agentic::SymbolId;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// A binary arithmetic operation on natural numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NatOp {
+    /// `a + b`
+    Add,
+    /// Truncated subtraction: `a - b`, or 0 if `b > a`
+    Sub,
+    /// `a * b`
+    Mul,
+    /// `a / b`, with division by zero giving 0
+    Div,
+    /// `a % b`, with modulus by zero giving `a`
+    Mod,
+}
+
+impl NatOp {
+    /// Compute the operation, or `None` if the result overflows `u64`
+    pub fn apply(self, a: u64, b: u64) -> Option<u64> {
+        match self {
+            NatOp::Add => a.checked_add(b),
+            NatOp::Sub => Some(a.saturating_sub(b)),
+            NatOp::Mul => a.checked_mul(b),
+            NatOp::Div => Some(a.checked_div(b).unwrap_or(0)),
+            NatOp::Mod => Some(a.checked_rem(b).unwrap_or(a)),
+        }
+    }
+}
+
+/// Constants that compute on Nat literals
+#[derive(Debug, Clone, Default)]
+pub struct NatBuiltins {
+    ops: HashMap<SymbolId, NatOp>,
+}
+
+/// Names bound to each operation by `NatBuiltins::standard`
+///
+/// Includes the `HAdd.hAdd`-style functions the parser desugars `+`, `-`
+/// and `*` to, and the short names used by the examples.
+const STANDARD_NAMES: &[(&str, NatOp)] = &[
+    ("Nat.add", NatOp::Add),
+    ("Nat.sub", NatOp::Sub),
+    ("Nat.mul", NatOp::Mul),
+    ("Nat.div", NatOp::Div),
+    ("Nat.mod", NatOp::Mod),
+    ("HAdd.hAdd", NatOp::Add),
+    ("HSub.hSub", NatOp::Sub),
+    ("HMul.hMul", NatOp::Mul),
+    ("add", NatOp::Add),
+    ("sub", NatOp::Sub),
+    ("mul", NatOp::Mul),
+];
+
+impl NatBuiltins {
+    /// Create an empty set of builtins (no literal arithmetic)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the standard Nat operations under their usual names
+    ///
+    /// `intern` maps a name to the symbol the environment uses for it.
+    pub fn standard(mut intern: impl FnMut(&str) -> SymbolId) -> Self {
+        let mut builtins = Self::new();
+        for &(name, op) in STANDARD_NAMES {
+            builtins.register(intern(name), op);
+        }
+        builtins
+    }
+
+    /// Make `name` compute `op` when applied to two literals
+    pub fn register(&mut self, name: SymbolId, op: NatOp) {
+        self.ops.insert(name, op);
+    }
+
+    /// Get the operation registered for a constant
+    pub fn get(&self, name: SymbolId) -> Option<NatOp> {
+        self.ops.get(&name).copied()
+    }
+
+    /// Check if no operations are registered
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Hash of the registered operations, independent of registration order
+    ///
+    /// Equal sets of builtins have equal fingerprints, so normal forms cached
+    /// under one can be reused under the other.
+    pub fn fingerprint(&self) -> u64 {
+        let mut ops: Vec<_> = self.ops.iter().collect();
+        ops.sort_unstable_by_key(|(name, _)| **name);
+
+        let mut hasher = DefaultHasher::new();
+        ops.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lean_agentic::SymbolTable;
+
+    #[test]
+    fn test_nat_op_semantics() {
+        assert_eq!(NatOp::Add.apply(2, 2), Some(4));
+        assert_eq!(NatOp::Sub.apply(2, 5), Some(0));
+        assert_eq!(NatOp::Div.apply(7, 0), Some(0));
+        assert_eq!(NatOp::Mod.apply(7, 0), Some(7));
+        assert_eq!(NatOp::Mul.apply(u64::MAX, 2), None);
+
+        let symbols = SymbolTable::new();
+        let builtins = NatBuiltins::standard(|name| symbols.intern(name));
+        assert_eq!(builtins.get(symbols.intern("Nat.mul")), Some(NatOp::Mul));
+        assert_eq!(builtins.get(symbols.intern("Nat.succ")), None);
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_registration_order() {
+        let symbols = SymbolTable::new();
+        let (add, mul) = (symbols.intern("add"), symbols.intern("mul"));
+
+        let mut forward = NatBuiltins::new();
+        forward.register(add, NatOp::Add);
+        forward.register(mul, NatOp::Mul);
+
+        let mut backward = NatBuiltins::new();
+        backward.register(mul, NatOp::Mul);
+        backward.register(add, NatOp::Add);
+
+        assert_eq!(forward.fingerprint(), backward.fingerprint());
+        assert_ne!(forward.fingerprint(), NatBuiltins::new().fingerprint());
+    }
+}
@@ -1,17 +1,41 @@
 //! Memoization cache for WHNF normalization
 
 use hashbrown::HashMap;
-use lean_agentic::TermId;
+use lean_agentic::{TermId, Transparency};
 use rustc_hash::FxHasher;
 use std::hash::BuildHasherDefault;
 
 type FxHashMap<K, V> = HashMap<K, V, BuildHasherDefault<FxHasher>>;
 
+/// Key of a cached normalization result
+///
+/// A term's WHNF depends on which constants may be unfolded and which ones
+/// compute on Nat literals, so both are part of the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    /// The normalized term
+    pub term: TermId,
+
+    /// Transparency delta reduction ran under
+    pub transparency: Transparency,
+
+    /// [`NatBuiltins::fingerprint`](crate::builtins::NatBuiltins::fingerprint) of the builtins in effect
+    pub nat_builtins: u64,
+}
+
 /// LRU cache for normalization results
+///
+/// Terms are hash-consed `TermId`s, so every occurrence of a structurally
+/// equal term shares one entry regardless of where it was built. Entries
+/// are only valid for one arena and one environment generation; see
+/// [`NormalizationCache::sync_generation`].
 pub struct NormalizationCache {
-    cache: FxHashMap<TermId, TermId>,
+    cache: FxHashMap<CacheKey, TermId>,
     capacity: usize,
-    access_order: Vec<TermId>,
+    access_order: Vec<CacheKey>,
+    generation: u64,
+    hits: usize,
+    misses: usize,
 }
 
 impl NormalizationCache {
@@ -24,23 +48,48 @@ impl NormalizationCache {
             ),
             capacity,
             access_order: Vec::with_capacity(capacity),
+            generation: 0,
+            hits: 0,
+            misses: 0,
         }
     }
 
     /// Get cached WHNF result
-    pub fn get(&self, term: TermId) -> Option<TermId> {
-        self.cache.get(&term).copied()
+    pub fn get(&mut self, key: CacheKey) -> Option<TermId> {
+        let result = self.cache.get(&key).copied();
+        if result.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        result
+    }
+
+    /// Drop all entries if the environment has changed since they were computed
+    ///
+    /// Delta reduction makes cached normal forms depend on the definitions
+    /// in scope, so results from an older generation cannot be reused.
+    pub fn sync_generation(&mut self, generation: u64) {
+        if generation != self.generation {
+            self.clear();
+            self.generation = generation;
+        }
+    }
+
+    /// Get the environment generation the entries were computed at
+    pub fn generation(&self) -> u64 {
+        self.generation
     }
 
     /// Insert WHNF result
-    pub fn insert(&mut self, term: TermId, whnf: TermId) {
+    pub fn insert(&mut self, key: CacheKey, whnf: TermId) {
         // Evict if at capacity
-        if self.cache.len() >= self.capacity && !self.cache.contains_key(&term) {
+        if self.cache.len() >= self.capacity && !self.cache.contains_key(&key) {
             self.evict_lru();
         }
 
-        self.cache.insert(term, whnf);
-        self.access_order.push(term);
+        self.cache.insert(key, whnf);
+        self.access_order.push(key);
     }
 
     /// Evict least recently used entry
@@ -67,51 +116,73 @@ impl NormalizationCache {
         self.cache.is_empty()
     }
 
+    /// Get the number of lookups that found an entry
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the number of lookups that found no entry
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
     /// Get cache hit statistics
     pub fn hit_rate(&self) -> f64 {
-        // This would need separate tracking
-        0.0
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lean_agentic::Arena;
+
+    fn key(term: TermId) -> CacheKey {
+        CacheKey { term, transparency: Transparency::Default, nat_builtins: 0 }
+    }
 
     #[test]
     fn test_cache_operations() {
+        let mut arena = Arena::new();
         let mut cache = NormalizationCache::new(2);
 
-        let term1 = TermId::new(1);
-        let whnf1 = TermId::new(10);
-        let term2 = TermId::new(2);
-        let whnf2 = TermId::new(20);
+        let term1 = arena.mk_var(1);
+        let whnf1 = arena.mk_var(10);
+        let term2 = arena.mk_var(2);
+        let whnf2 = arena.mk_var(20);
 
         // Insert and retrieve
-        cache.insert(term1, whnf1);
-        assert_eq!(cache.get(term1), Some(whnf1));
+        cache.insert(key(term1), whnf1);
+        assert_eq!(cache.get(key(term1)), Some(whnf1));
 
         // Insert second
-        cache.insert(term2, whnf2);
-        assert_eq!(cache.get(term2), Some(whnf2));
+        cache.insert(key(term2), whnf2);
+        assert_eq!(cache.get(key(term2)), Some(whnf2));
 
         // Both should be present
         assert_eq!(cache.len(), 2);
 
         // Insert third (should evict first due to LRU)
-        let term3 = TermId::new(3);
-        let whnf3 = TermId::new(30);
-        cache.insert(term3, whnf3);
+        let term3 = arena.mk_var(3);
+        let whnf3 = arena.mk_var(30);
+        cache.insert(key(term3), whnf3);
 
         assert_eq!(cache.len(), 2);
-        assert_eq!(cache.get(term3), Some(whnf3));
+        assert_eq!(cache.get(key(term3)), Some(whnf3));
     }
 
     #[test]
     fn test_cache_clear() {
+        let mut arena = Arena::new();
         let mut cache = NormalizationCache::new(10);
-        cache.insert(TermId::new(1), TermId::new(10));
-        cache.insert(TermId::new(2), TermId::new(20));
+        let (t1, t2) = (arena.mk_var(1), arena.mk_var(2));
+        cache.insert(key(t1), arena.mk_var(10));
+        cache.insert(key(t2), arena.mk_var(20));
 
         assert_eq!(cache.len(), 2);
 
@@ -119,4 +190,21 @@ mod tests {
         assert_eq!(cache.len(), 0);
         assert!(cache.is_empty());
     }
+
+    #[test]
+    fn test_generation_change_invalidates() {
+        let mut arena = Arena::new();
+        let mut cache = NormalizationCache::new(10);
+        let term = arena.mk_var(0);
+
+        cache.sync_generation(1);
+        cache.insert(key(term), term);
+        cache.sync_generation(1);
+        assert_eq!(cache.get(key(term)), Some(term));
+
+        cache.sync_generation(2);
+        assert!(cache.is_empty());
+        assert_eq!(cache.get(key(term)), None);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+    }
 }
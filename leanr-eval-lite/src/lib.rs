@@ -13,15 +13,17 @@
 #![warn(missing_docs)]
 #![deny(unsafe_op_in_unsafe_fn)]
 
-use lean_agentic::{Error as CoreError};
+use lean_agentic::{Error as CoreError, Transparency};
 
 pub mod normalize;
 pub mod cache;
 pub mod reduction;
+pub mod builtins;
 
 pub use normalize::{Normalizer, NormalizeConfig};
-pub use cache::NormalizationCache;
+pub use cache::{CacheKey, NormalizationCache};
 pub use reduction::{ReductionStats, ReductionStep};
+pub use builtins::{NatBuiltins, NatOp};
 
 /// Result type for evaluator operations
 pub type Result<T> = std::result::Result<T, EvalError>;
@@ -82,6 +84,9 @@ pub struct EvalConfig {
 
     /// Track reduction statistics
     pub track_stats: bool,
+
+    /// Which constants delta reduction may unfold
+    pub transparency: Transparency,
 }
 
 impl Default for EvalConfig {
@@ -94,6 +99,7 @@ impl Default for EvalConfig {
             zeta_reduction: true,
             iota_reduction: true,
             track_stats: false,
+            transparency: Transparency::Default,
         }
     }
 }
@@ -110,6 +116,7 @@ impl EvalConfig {
             zeta_reduction: true,
             iota_reduction: true,
             track_stats: false,
+            transparency: Transparency::Default,
         }
     }
 
@@ -123,6 +130,7 @@ impl EvalConfig {
             zeta_reduction: true,
             iota_reduction: true,
             track_stats: false,
+            transparency: Transparency::Default,
         }
     }
 
@@ -136,6 +144,7 @@ impl EvalConfig {
             zeta_reduction: true,
             iota_reduction: false,
             track_stats: true,
+            transparency: Transparency::Default,
         }
     }
 }
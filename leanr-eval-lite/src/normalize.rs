@@ -1,9 +1,11 @@
 //! WHNF normalization with beta, delta, zeta, and iota reduction
 
 use crate::{EvalConfig, EvalError, Result};
-use crate::cache::NormalizationCache;
+use crate::builtins::NatBuiltins;
+use crate::cache::{CacheKey, NormalizationCache};
 use crate::reduction::ReductionStats;
 use lean_agentic::{Arena, Environment, TermId, TermKind, Context};
+use lean_agentic::term::Literal;
 
 /// Configuration for normalization
 pub type NormalizeConfig = EvalConfig;
@@ -16,17 +18,44 @@ pub struct Normalizer<'a> {
     cache: Option<NormalizationCache>,
     stats: ReductionStats,
     steps_remaining: usize,
+    nat: NatBuiltins,
+    nat_fingerprint: u64,
 }
 
 impl<'a> Normalizer<'a> {
     /// Create a new normalizer
     pub fn new(arena: &'a mut Arena, env: &'a Environment, config: NormalizeConfig) -> Self {
-        let steps_remaining = config.max_steps;
         let cache = if config.enable_cache {
             Some(NormalizationCache::new(config.cache_size))
         } else {
             None
         };
+        Self::build(arena, env, config, cache)
+    }
+
+    /// Create a normalizer that reuses a cache from an earlier normalizer
+    ///
+    /// The cache must have been filled using the same arena. Entries from an
+    /// older environment generation are discarded.
+    pub fn with_cache(
+        arena: &'a mut Arena,
+        env: &'a Environment,
+        config: NormalizeConfig,
+        cache: NormalizationCache,
+    ) -> Self {
+        Self::build(arena, env, config, Some(cache))
+    }
+
+    fn build(
+        arena: &'a mut Arena,
+        env: &'a Environment,
+        config: NormalizeConfig,
+        mut cache: Option<NormalizationCache>,
+    ) -> Self {
+        let steps_remaining = config.max_steps;
+        if let Some(ref mut cache) = cache {
+            cache.sync_generation(env.generation());
+        }
 
         Self {
             arena,
@@ -35,14 +64,24 @@ impl<'a> Normalizer<'a> {
             cache,
             stats: ReductionStats::new(),
             steps_remaining,
+            nat_fingerprint: NatBuiltins::new().fingerprint(),
+            nat: NatBuiltins::new(),
         }
     }
 
+    /// Compute on Nat literals for the given builtin operations
+    pub fn with_nat_builtins(mut self, nat: NatBuiltins) -> Self {
+        self.nat_fingerprint = nat.fingerprint();
+        self.nat = nat;
+        self
+    }
+
     /// Normalize a term to WHNF
     pub fn whnf(&mut self, term: TermId, ctx: &Context) -> Result<TermId> {
         // Check cache first
-        if let Some(ref cache) = self.cache {
-            if let Some(cached) = cache.get(term) {
+        let key = self.cache_key(term);
+        if let Some(ref mut cache) = self.cache {
+            if let Some(cached) = cache.get(key) {
                 self.stats.cache_hits += 1;
                 return Ok(cached);
             }
@@ -55,12 +94,21 @@ impl<'a> Normalizer<'a> {
 
         // Store in cache
         if let Some(ref mut cache) = self.cache {
-            cache.insert(term, result);
+            cache.insert(key, result);
         }
 
         Ok(result)
     }
 
+    /// Cache key for `term` under this normalizer's configuration
+    fn cache_key(&self, term: TermId) -> CacheKey {
+        CacheKey {
+            term,
+            transparency: self.config.transparency,
+            nat_builtins: self.nat_fingerprint,
+        }
+    }
+
     /// WHNF reduction without caching
     fn whnf_uncached(&mut self, term: TermId, ctx: &Context) -> Result<TermId> {
         if self.steps_remaining == 0 {
@@ -78,9 +126,6 @@ impl<'a> Normalizer<'a> {
         match &kind {
             // Application: try beta reduction
             TermKind::App(func, arg) => {
-                self.steps_remaining -= 1;
-                self.stats.beta_reductions += 1;
-
                 let func_whnf = self.whnf(*func, ctx)?;
                 let func_data = self.arena.get_term(func_whnf)
                     .ok_or_else(|| EvalError::InvalidReduction("Invalid function term".to_string()))?;
@@ -91,11 +136,18 @@ impl<'a> Normalizer<'a> {
                 match &func_kind {
                     // Beta reduction: (λx. body) arg ~~> body[x := arg]
                     TermKind::Lam(_, body) => {
+                        self.steps_remaining -= 1;
+                        self.stats.beta_reductions += 1;
+
                         let substituted = self.substitute(*body, 0, *arg, ctx)?;
                         self.whnf(substituted, ctx)
                     }
                     // Cannot reduce further
                     _ => {
+                        if let Some(value) = self.reduce_nat_op(func_whnf, *arg, ctx)? {
+                            return Ok(value);
+                        }
+
                         // Re-create application with normalized function
                         if func_whnf == *func {
                             Ok(term)
@@ -121,8 +173,8 @@ impl<'a> Normalizer<'a> {
             TermKind::Const(name, levels) if self.config.delta_reduction => {
                 if let Some(decl) = self.env.get_decl(*name) {
                     if let Some(value) = &decl.value {
-                        // Only unfold if transparent (not opaque)
-                        if !decl.is_opaque() {
+                        // Only unfold if the configured transparency permits
+                        if decl.can_unfold(self.config.transparency) {
                             self.steps_remaining -= 1;
                             self.stats.delta_reductions += 1;
 
@@ -147,6 +199,37 @@ impl<'a> Normalizer<'a> {
         }
     }
 
+    /// Iota-like rule for builtin arithmetic: `op a b` on two Nat literals
+    fn reduce_nat_op(&mut self, func: TermId, arg: TermId, ctx: &Context) -> Result<Option<TermId>> {
+        if !self.config.iota_reduction || self.nat.is_empty() {
+            return Ok(None);
+        }
+
+        let Some(TermKind::App(head, lhs)) = self.arena.kind(func).cloned() else {
+            return Ok(None);
+        };
+        let op = match self.arena.kind(head) {
+            Some(TermKind::Const(name, _)) => self.nat.get(*name),
+            _ => None,
+        };
+        let Some(op) = op else {
+            return Ok(None);
+        };
+
+        let lhs = self.whnf(lhs, ctx)?;
+        let rhs = self.whnf(arg, ctx)?;
+        let (a, b) = match (self.arena.kind(lhs), self.arena.kind(rhs)) {
+            (Some(TermKind::Lit(Literal::Nat(a))), Some(TermKind::Lit(Literal::Nat(b)))) => (*a, *b),
+            _ => return Ok(None),
+        };
+
+        Ok(op.apply(a, b).map(|n| {
+            self.steps_remaining = self.steps_remaining.saturating_sub(1);
+            self.stats.iota_reductions += 1;
+            self.arena.mk_nat(n)
+        }))
+    }
+
     /// Substitute term for variable at given de Bruijn index
     fn substitute(&mut self, term: TermId, var_idx: u32, replacement: TermId, ctx: &Context) -> Result<TermId> {
         let term_data = self.arena.get_term(term)
@@ -194,48 +277,7 @@ impl<'a> Normalizer<'a> {
 
     /// Shift de Bruijn indices by amount
     fn shift(&mut self, term: TermId, amount: i32) -> Result<TermId> {
-        self.shift_above(term, 0, amount)
-    }
-
-    /// Shift indices above cutoff
-    fn shift_above(&mut self, term: TermId, cutoff: u32, amount: i32) -> Result<TermId> {
-        let term_data = self.arena.get_term(term)
-            .ok_or_else(|| EvalError::InvalidReduction("Invalid term in shift".to_string()))?;
-
-        // Clone the kind to avoid borrow checker issues
-        let kind = term_data.kind.clone();
-
-        match &kind {
-            TermKind::Var(idx) if *idx >= cutoff => {
-                let new_idx = (*idx as i32 + amount) as u32;
-                Ok(self.arena.mk_var(new_idx))
-            }
-            TermKind::Var(_) => Ok(term),
-
-            TermKind::App(func, arg) => {
-                let new_func = self.shift_above(*func, cutoff, amount)?;
-                let new_arg = self.shift_above(*arg, cutoff, amount)?;
-                Ok(self.arena.mk_app(new_func, new_arg))
-            }
-
-            TermKind::Lam(binder, body) => {
-                let new_body = self.shift_above(*body, cutoff + 1, amount)?;
-                Ok(self.arena.mk_lam(binder.clone(), new_body))
-            }
-
-            TermKind::Pi(binder, body) => {
-                let new_body = self.shift_above(*body, cutoff + 1, amount)?;
-                Ok(self.arena.mk_pi(binder.clone(), new_body))
-            }
-
-            TermKind::Let(binder, val, body) => {
-                let new_val = self.shift_above(*val, cutoff, amount)?;
-                let new_body = self.shift_above(*body, cutoff + 1, amount)?;
-                Ok(self.arena.mk_let(binder.clone(), new_val, new_body))
-            }
-
-            _ => Ok(term),
-        }
+        Ok(self.arena.shift(term, 0, amount))
     }
 
     /// Instantiate universe levels in a term
@@ -245,6 +287,11 @@ impl<'a> Normalizer<'a> {
         Ok(term)
     }
 
+    /// Take the cache out of this normalizer so another one can reuse it
+    pub fn into_cache(self) -> Option<NormalizationCache> {
+        self.cache
+    }
+
     /// Get reduction statistics
     pub fn stats(&self) -> &ReductionStats {
         &self.stats
@@ -281,7 +328,8 @@ mod tests {
         // Create (λx. x) 42
         let var0 = arena.mk_var(0);
         let name = symbols.intern("x");
-        let nat_sort = arena.mk_sort(arena.mk_level_zero());
+        let zero = arena.mk_level_zero();
+        let nat_sort = arena.mk_sort(zero);
         let binder = Binder::new(name, nat_sort);
 
         let lambda = arena.mk_lam(binder, var0);
@@ -341,4 +389,205 @@ mod tests {
         let hit_rate = normalizer.cache_hit_rate();
         assert!((hit_rate - 0.5).abs() < 0.01);
     }
+
+    #[test]
+    fn test_cache_shared_across_call_sites() {
+        let mut arena = Arena::new();
+        let env = Environment::new();
+        let ctx = Context::new();
+
+        // Each call site builds `λx. (λy. y) x` independently
+        fn build(arena: &mut Arena) -> TermId {
+            let zero = arena.mk_level_zero();
+            let ty = arena.mk_sort(zero);
+            let name = SymbolTable::new().intern("x");
+            let y = arena.mk_var(0);
+            let id = arena.mk_lam(Binder::new(name, ty), y);
+            let x = arena.mk_var(0);
+            let body = arena.mk_app(id, x);
+            arena.mk_lam(Binder::new(name, ty), body)
+        }
+
+        let first = build(&mut arena);
+        let mut normalizer = Normalizer::new(&mut arena, &env, NormalizeConfig::default());
+        normalizer.whnf(first, &ctx).unwrap();
+        let cache = normalizer.into_cache().unwrap();
+
+        let second = build(&mut arena);
+        assert_eq!(first, second);
+        let mut normalizer =
+            Normalizer::with_cache(&mut arena, &env, NormalizeConfig::default(), cache);
+        normalizer.whnf(second, &ctx).unwrap();
+
+        let cache = normalizer.into_cache().unwrap();
+        assert_eq!(cache.misses(), 1);
+        assert_eq!(cache.hits(), 1);
+    }
+
+    #[test]
+    fn test_cache_invalidated_when_environment_changes() {
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+        let symbols = SymbolTable::new();
+
+        let c = symbols.intern("c");
+        let term = arena.mk_const(c, vec![]);
+
+        let mut normalizer = Normalizer::new(&mut arena, &env, NormalizeConfig::default());
+        assert_eq!(normalizer.whnf(term, &ctx).unwrap(), term);
+        let cache = normalizer.into_cache().unwrap();
+
+        // Defining `c` afterwards must not reuse the stale "stuck" result
+        let zero = arena.mk_level_zero();
+        let ty = arena.mk_sort(zero);
+        let value = arena.mk_nat(7);
+        env.add_decl(lean_agentic::environment::Declaration::def(c, vec![], ty, value)).unwrap();
+
+        let mut normalizer =
+            Normalizer::with_cache(&mut arena, &env, NormalizeConfig::default(), cache);
+        assert_eq!(normalizer.whnf(term, &ctx).unwrap(), value);
+    }
+
+    #[test]
+    fn test_redefinition_invalidates_cached_unfolding() {
+        use lean_agentic::environment::Declaration;
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+        let symbols = SymbolTable::new();
+
+        let c = symbols.intern("c");
+        let zero = arena.mk_level_zero();
+        let ty = arena.mk_sort(zero);
+        let seven = arena.mk_nat(7);
+        let eight = arena.mk_nat(8);
+        env.add_decl(Declaration::def(c, vec![], ty, seven)).unwrap();
+
+        let term = arena.mk_const(c, vec![]);
+        let mut normalizer = Normalizer::new(&mut arena, &env, NormalizeConfig::default());
+        assert_eq!(normalizer.whnf(term, &ctx).unwrap(), seven);
+        let cache = normalizer.into_cache().unwrap();
+        assert!(!cache.is_empty());
+
+        // Redefine `c` as in a REPL session
+        env.add_or_replace(Declaration::def(c, vec![], ty, eight));
+
+        let mut normalizer =
+            Normalizer::with_cache(&mut arena, &env, NormalizeConfig::default(), cache);
+        assert_eq!(normalizer.whnf(term, &ctx).unwrap(), eight);
+        assert_eq!(normalizer.into_cache().unwrap().generation(), env.generation());
+    }
+
+    #[test]
+    fn test_delta_respects_reducibility() {
+        use lean_agentic::environment::{Declaration, Reducibility};
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+        let symbols = SymbolTable::new();
+
+        let zero = arena.mk_level_zero();
+        let ty = arena.mk_sort(zero);
+        let value = arena.mk_nat(3);
+
+        let hidden = symbols.intern("hidden");
+        let shown = symbols.intern("shown");
+        env.add_decl(
+            Declaration::def(hidden, vec![], ty, value).with_reducibility(Reducibility::Irreducible),
+        ).unwrap();
+        env.add_decl(
+            Declaration::def(shown, vec![], ty, value).with_reducibility(Reducibility::Reducible),
+        ).unwrap();
+
+        let hidden_term = arena.mk_const(hidden, vec![]);
+        let shown_term = arena.mk_const(shown, vec![]);
+
+        let config = NormalizeConfig::default();
+        assert!(config.delta_reduction);
+        let mut normalizer = Normalizer::new(&mut arena, &env, config);
+
+        assert_eq!(normalizer.whnf(hidden_term, &ctx).unwrap(), hidden_term);
+        assert_eq!(normalizer.whnf(shown_term, &ctx).unwrap(), value);
+        assert_eq!(normalizer.stats().delta_reductions, 1);
+    }
+
+    #[test]
+    fn test_nat_literal_arithmetic() {
+        use crate::builtins::NatBuiltins;
+
+        let mut arena = Arena::new();
+        let env = Environment::new();
+        let ctx = Context::new();
+        let symbols = SymbolTable::new();
+
+        let add = arena.mk_const(symbols.intern("add"), vec![]);
+        let mul = arena.mk_const(symbols.intern("mul"), vec![]);
+        let two = arena.mk_nat(2);
+        let three = arena.mk_nat(3);
+        let add_2_2 = arena.mk_app_spine(add, &[two, two]);
+        let mul_3_4 = {
+            let four = arena.mk_nat(4);
+            arena.mk_app_spine(mul, &[three, four])
+        };
+        // Nested: mul (add 2 2) 3
+        let nested = arena.mk_app_spine(mul, &[add_2_2, three]);
+
+        let four = arena.mk_nat(4);
+        let twelve = arena.mk_nat(12);
+
+        let builtins = NatBuiltins::standard(|name| symbols.intern(name));
+        let mut normalizer = Normalizer::new(&mut arena, &env, NormalizeConfig::default())
+            .with_nat_builtins(builtins);
+
+        assert_eq!(normalizer.whnf(add_2_2, &ctx).unwrap(), four);
+        assert_eq!(normalizer.whnf(mul_3_4, &ctx).unwrap(), twelve);
+        assert_eq!(normalizer.whnf(nested, &ctx).unwrap(), twelve);
+        assert_eq!(normalizer.stats().iota_reductions, 3);
+
+        // Without builtins the application is stuck
+        let mut plain = Normalizer::new(&mut arena, &env, NormalizeConfig::default());
+        assert_eq!(plain.whnf(add_2_2, &ctx).unwrap(), add_2_2);
+    }
+
+    #[test]
+    fn test_cache_keyed_by_configuration() {
+        use lean_agentic::environment::{Declaration, Reducibility};
+        use lean_agentic::Transparency;
+
+        let mut arena = Arena::new();
+        let mut env = Environment::new();
+        let ctx = Context::new();
+        let symbols = SymbolTable::new();
+
+        let zero = arena.mk_level_zero();
+        let ty = arena.mk_sort(zero);
+        let value = arena.mk_nat(3);
+        let c = symbols.intern("c");
+        env.add_decl(Declaration::def(c, vec![], ty, value).with_reducibility(Reducibility::Reducible))
+            .unwrap();
+        let constant = arena.mk_const(c, vec![]);
+
+        let add = arena.mk_const(symbols.intern("add"), vec![]);
+        let two = arena.mk_nat(2);
+        let add_2_2 = arena.mk_app_spine(add, &[two, two]);
+        let four = arena.mk_nat(4);
+
+        // Fill the cache without delta reduction or builtins
+        let opaque = NormalizeConfig { transparency: Transparency::None, ..Default::default() };
+        let mut normalizer = Normalizer::new(&mut arena, &env, opaque);
+        assert_eq!(normalizer.whnf(constant, &ctx).unwrap(), constant);
+        assert_eq!(normalizer.whnf(add_2_2, &ctx).unwrap(), add_2_2);
+        let cache = normalizer.into_cache().unwrap();
+
+        // Neither stuck result is reused once the configuration allows progress
+        let builtins = NatBuiltins::standard(|name| symbols.intern(name));
+        let mut normalizer =
+            Normalizer::with_cache(&mut arena, &env, NormalizeConfig::default(), cache)
+                .with_nat_builtins(builtins);
+        assert_eq!(normalizer.whnf(constant, &ctx).unwrap(), value);
+        assert_eq!(normalizer.whnf(add_2_2, &ctx).unwrap(), four);
+    }
 }
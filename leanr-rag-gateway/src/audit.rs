@@ -1,9 +1,18 @@
 //! Audit logging for RAG Gateway
+//!
+//! Events are handed to an `AuditSink` as they happen. The default sink
+//! keeps them in memory; `FileSink` appends them to a JSON-lines file and
+//! `CallbackSink` passes them to user code.
 
 use crate::{RagQuery, GatewayError};
-use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AuditEvent {
     RequestSuccess {
         user_id: String,
@@ -36,18 +45,114 @@ impl AuditEvent {
     }
 }
 
+/// Destination for audit events
+///
+/// Sinks are called synchronously from the request path, once per event.
+pub trait AuditSink: Send + Sync {
+    /// Record one event
+    fn record(&self, event: &AuditEvent) -> io::Result<()>;
+
+    /// Events this sink can read back; sinks that do not retain events
+    /// return nothing
+    fn events(&self) -> Vec<AuditEvent> {
+        Vec::new()
+    }
+}
+
+/// Keeps every event in memory
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    events: Mutex<Vec<AuditEvent>>,
+}
+
+impl AuditSink for MemorySink {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+
+    fn events(&self) -> Vec<AuditEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+/// Appends each event to a file as one line of JSON
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Mutex::new(file) })
+    }
+}
+
+impl AuditSink for FileSink {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        // One write per line so concurrent writers never interleave
+        self.file.lock().unwrap().write_all(&line)
+    }
+}
+
+/// Passes each event to a user callback
+pub struct CallbackSink<F> {
+    callback: F,
+}
+
+impl<F: Fn(&AuditEvent) + Send + Sync> CallbackSink<F> {
+    pub fn new(callback: F) -> Self {
+        Self { callback }
+    }
+}
+
+impl<F: Fn(&AuditEvent) + Send + Sync> AuditSink for CallbackSink<F> {
+    fn record(&self, event: &AuditEvent) -> io::Result<()> {
+        (self.callback)(event);
+        Ok(())
+    }
+}
+
+/// Audit log writing to a configurable sink
+///
+/// Queries such as `events`, the counts and the compliance report read back
+/// from the sink, so they only see events when the sink retains them.
 pub struct AuditLog {
-    events: Arc<Mutex<Vec<AuditEvent>>>,
+    sink: Box<dyn AuditSink>,
+
+    /// Events the sink failed to record
+    sink_errors: AtomicUsize,
 }
 
 impl AuditLog {
-    /// Create a new audit log
+    /// Create a new audit log that keeps events in memory
     pub fn new() -> Self {
+        Self::with_sink(MemorySink::default())
+    }
+
+    /// Create an audit log writing to `sink`
+    pub fn with_sink(sink: impl AuditSink + 'static) -> Self {
         Self {
-            events: Arc::new(Mutex::new(Vec::new())),
+            sink: Box::new(sink),
+            sink_errors: AtomicUsize::new(0),
+        }
+    }
+
+    fn record(&self, event: AuditEvent) {
+        if self.sink.record(&event).is_err() {
+            self.sink_errors.fetch_add(1, Ordering::Relaxed);
         }
     }
 
+    /// Number of events the sink failed to record
+    pub fn sink_errors(&self) -> usize {
+        self.sink_errors.load(Ordering::Relaxed)
+    }
+
     /// Log a blocked request
     pub fn log_blocked(&self, query: &RagQuery, violation: String) {
         let event = AuditEvent::RequestBlocked {
@@ -60,8 +165,7 @@ impl AuditLog {
                 .as_secs() as i64,
         };
 
-        let mut events = self.events.lock().unwrap();
-        events.push(event);
+        self.record(event);
     }
 
     /// Log a successful request
@@ -78,8 +182,7 @@ impl AuditLog {
                 .as_secs() as i64,
         };
 
-        let mut events = self.events.lock().unwrap();
-        events.push(event);
+        self.record(event);
     }
 
     /// Log PII masking
@@ -93,13 +196,12 @@ impl AuditLog {
                 .as_secs() as i64,
         };
 
-        let mut events = self.events.lock().unwrap();
-        events.push(event);
+        self.record(event);
     }
 
     /// Export compliance report as text
     pub fn export_compliance_report(&self) -> Result<String, GatewayError> {
-        let events = self.events.lock().unwrap();
+        let events = self.sink.events();
         let mut report = String::from("Audit Log Report\n================\n\n");
 
         for event in events.iter() {
@@ -130,28 +232,19 @@ impl AuditLog {
 
     /// Get all audit events
     pub fn events(&self) -> Vec<AuditEvent> {
-        let events = self.events.lock().unwrap();
-        events.clone()
+        self.sink.events()
     }
 
     /// Get count of blocked requests
     pub fn blocked_count(&self) -> usize {
-        let events = self.events.lock().unwrap();
-        events.iter().filter(|e| matches!(e, AuditEvent::RequestBlocked { .. })).count()
+        self.sink.events().iter().filter(|e| matches!(e, AuditEvent::RequestBlocked { .. })).count()
     }
 
     /// Get count of successful requests
     pub fn success_count(&self) -> usize {
-        let events = self.events.lock().unwrap();
-        events.iter().filter(|e| matches!(e, AuditEvent::RequestSuccess { .. })).count()
+        self.sink.events().iter().filter(|e| matches!(e, AuditEvent::RequestSuccess { .. })).count()
     }
 
-    /// Clear audit log (for testing)
-    #[cfg(test)]
-    pub fn clear(&self) {
-        let mut events = self.events.lock().unwrap();
-        events.clear();
-    }
 }
 
 impl Default for AuditLog {
@@ -205,4 +298,58 @@ mod tests {
         assert!(report.contains("SUCCESS"));
         assert!(report.contains("BLOCKED"));
     }
+
+    fn test_query() -> RagQuery {
+        RagQuery {
+            question: "Test".to_string(),
+            sources: vec![],
+            user_id: "user123".to_string(),
+            latency_sla: None,
+            cost_budget: None,
+        }
+    }
+
+    #[test]
+    fn test_file_sink_writes_json_lines() {
+        let path = std::env::temp_dir().join(format!("leanr-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let log = AuditLog::with_sink(FileSink::open(&path).unwrap());
+        log.log_success(&test_query(), 100, 0.01, "local");
+        log.log_blocked(&test_query(), "Policy violation".to_string());
+        log.log_pii_masked("user123", 2);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let events: Vec<AuditEvent> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 3);
+        assert!(events[0].is_success());
+        assert!(events[1].is_blocked());
+        assert!(matches!(events[2], AuditEvent::PIIMasked { count: 2, .. }));
+        assert_eq!(log.sink_errors(), 0);
+
+        // The file sink does not retain events in memory
+        assert!(log.events().is_empty());
+    }
+
+    #[test]
+    fn test_callback_sink_receives_each_event() {
+        let seen = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = seen.clone();
+        let log = AuditLog::with_sink(CallbackSink::new(move |event: &AuditEvent| {
+            sink_seen.lock().unwrap().push(event.clone());
+        }));
+
+        log.log_success(&test_query(), 100, 0.01, "local");
+        log.log_blocked(&test_query(), "Policy violation".to_string());
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert!(seen[0].is_success());
+        assert!(seen[1].is_blocked());
+    }
 }
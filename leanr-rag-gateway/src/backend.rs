@@ -0,0 +1,76 @@
+//! Retrieval and generation backends for the RAG pipeline
+//!
+//! The gateway owns policy checks, PII masking, proofs and auditing; the
+//! backends only fetch citations and write answers. Plug a vector DB or an
+//! LLM in by implementing `Retriever` or `Generator` and passing it to
+//! `RagGateway::with_retriever` / `with_generator`. The simulated backends
+//! are the defaults.
+
+use crate::{Citation, GatewayError, Lane, RagQuery};
+
+/// Fetches source excerpts relevant to a query
+///
+/// The gateway runs retrieval before generation and checks the latency
+/// SLA in between, so a slow retriever aborts the request instead of
+/// producing a late answer.
+pub trait Retriever: Send + Sync {
+    /// Retrieve citations for `query` on the selected lane
+    fn retrieve(&self, query: &RagQuery, lane: &Lane) -> Result<Vec<Citation>, GatewayError>;
+}
+
+/// Writes an answer from retrieved citations
+///
+/// The answer is PII-masked by the gateway afterwards, so generators do
+/// not need to mask their own output.
+pub trait Generator: Send + Sync {
+    /// Generate an answer, returning it with the number of tokens used
+    fn generate(
+        &self,
+        query: &RagQuery,
+        citations: &[Citation],
+        lane: &Lane,
+    ) -> Result<(String, usize), GatewayError>;
+}
+
+/// Retriever that returns a fixed excerpt from the first source
+///
+/// In production, this would call an actual vector DB.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulatedRetriever;
+
+impl Retriever for SimulatedRetriever {
+    fn retrieve(&self, query: &RagQuery, _lane: &Lane) -> Result<Vec<Citation>, GatewayError> {
+        Ok(vec![
+            Citation {
+                source: query.sources.first().cloned().unwrap_or_default(),
+                excerpt: "Relevant excerpt from source...".to_string(),
+                relevance_score: 0.92,
+            }
+        ])
+    }
+}
+
+/// Generator that echoes the question in a canned answer
+///
+/// In production, this would call an actual LLM.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulatedGenerator;
+
+impl Generator for SimulatedGenerator {
+    fn generate(
+        &self,
+        query: &RagQuery,
+        _citations: &[Citation],
+        _lane: &Lane,
+    ) -> Result<(String, usize), GatewayError> {
+        let answer = format!(
+            "Based on the sources, here is the answer to '{}': \
+             [Generated content respecting all policies]",
+            query.question
+        );
+
+        let tokens = 450; // Estimated
+
+        Ok((answer, tokens))
+    }
+}
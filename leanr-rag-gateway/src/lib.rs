@@ -13,17 +13,25 @@
 
 use lean_agentic::{Arena, Environment, SymbolTable};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 pub mod policy;
 pub mod proof;
 pub mod router;
 pub mod audit;
-
-pub use policy::{Policy, PolicyEngine, PolicyViolation};
-pub use proof::{ProofCertificate, ProofKind};
-pub use router::{CostAwareRouter, Lane, RoutingDecision};
-pub use audit::{AuditLog, AuditEvent};
+pub mod backend;
+
+pub use policy::{
+    Blocker, MaskConfig, MaskingError, Policy, PolicyEngine, PolicyExplanation, PolicyViolation,
+    Remedy,
+};
+pub use proof::{
+    KernelProof, ObligationArg, ProofCertificate, ProofError, ProofKind, ProofObligation,
+    VerificationFailure, VerificationOutcome,
+};
+pub use router::{CostAwareRouter, Lane, LaneEvaluation, LaneVerdict, RoutingDecision};
+pub use audit::{AuditEvent, AuditLog, AuditSink, CallbackSink, FileSink, MemorySink};
+pub use backend::{Generator, Retriever, SimulatedGenerator, SimulatedRetriever};
 
 /// RAG Query with metadata
 #[derive(Debug, Clone)]
@@ -70,9 +78,24 @@ pub struct Citation {
 #[derive(Debug, Clone)]
 pub struct ResponseMetrics {
     pub latency_ms: u64,
+    /// Sum of the per-stage costs
     pub cost_usd: f64,
     pub tokens_used: usize,
     pub lane_used: String,
+    /// Whether the context or answer was cut to fit the token ceiling
+    pub truncated: bool,
+    /// `(stage, latency, cost_usd)` for each pipeline stage, in order
+    pub stage_breakdown: Vec<(String, Duration, f64)>,
+}
+
+impl ResponseMetrics {
+    /// Stage that took the longest
+    pub fn slowest_stage(&self) -> Option<&str> {
+        self.stage_breakdown
+            .iter()
+            .max_by_key(|(_, latency, _)| *latency)
+            .map(|(stage, _, _)| stage.as_str())
+    }
 }
 
 /// RAG Gateway with policy verification
@@ -86,6 +109,15 @@ pub struct RagGateway {
     /// Audit logger
     audit_log: Arc<AuditLog>,
 
+    /// Retrieval backend
+    retriever: Box<dyn Retriever>,
+
+    /// Generation backend
+    generator: Box<dyn Generator>,
+
+    /// Token ceiling for retrieved context and for the generated answer
+    max_tokens: Option<usize>,
+
     /// Lean core for proofs
     arena: Arena,
     env: Environment,
@@ -99,45 +131,147 @@ impl RagGateway {
             policy_engine: PolicyEngine::new(policies),
             router: CostAwareRouter::new(),
             audit_log: Arc::new(AuditLog::new()),
+            retriever: Box::new(SimulatedRetriever),
+            generator: Box::new(SimulatedGenerator),
+            max_tokens: None,
             arena: Arena::new(),
             env: Environment::new(),
             symbols: SymbolTable::new(),
         }
     }
 
+    /// Replace the retrieval backend
+    pub fn with_retriever(mut self, retriever: impl Retriever + 'static) -> Self {
+        self.retriever = Box::new(retriever);
+        self
+    }
+
+    /// Cap retrieved context and generated answers at `max_tokens`
+    ///
+    /// Tokens are counted as whitespace-separated words. Anything cut is
+    /// flagged in `ResponseMetrics::truncated` and in the proof claims.
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Replace the audit log, e.g. with one writing to a file sink
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Arc::new(audit_log);
+        self
+    }
+
+    /// Exempt allow-listed tokens from PII masking
+    pub fn with_mask_config(mut self, mask_config: MaskConfig) -> Self {
+        self.policy_engine = self.policy_engine.with_mask_config(mask_config);
+        self
+    }
+
+    /// Replace the generation backend
+    pub fn with_generator(mut self, generator: impl Generator + 'static) -> Self {
+        self.generator = Box::new(generator);
+        self
+    }
+
     /// Process a RAG query with policy verification
+    ///
+    /// When the query sets `latency_sla`, the deadline is checked between
+    /// pipeline steps and the request is aborted with
+    /// `GatewayError::Timeout` as soon as it is exceeded.
+    ///
+    /// Every failure, whatever the stage, is recorded in the audit log as
+    /// a blocked request. The audit log only ever sees the question with
+    /// PII masked.
     pub fn process(&mut self, query: RagQuery) -> Result<RagResponse, GatewayError> {
+        self.run(&query).inspect_err(|e| {
+            self.audit_log.log_blocked(&self.audited(&query), e.to_string())
+        })
+    }
+
+    /// Copy of `query` that is safe to write to the audit log
+    ///
+    /// A question whose PII cannot be masked is replaced wholesale.
+    fn audited(&self, query: &RagQuery) -> RagQuery {
+        let question = self
+            .policy_engine
+            .mask_pii(&query.question)
+            .unwrap_or_else(|_| "[REDACTED]".to_string());
+        RagQuery { question, ..query.clone() }
+    }
+
+    /// Run the pipeline; errors are audit-logged by `process`
+    fn run(&mut self, query: &RagQuery) -> Result<RagResponse, GatewayError> {
         let start = Instant::now();
+        let deadline = Deadline::new(start, query.latency_sla);
+
+        let mut stages = Vec::new();
+        let mut stage_start = start;
+        let mut end_stage = |name: &str, cost: f64| {
+            let now = Instant::now();
+            stages.push((name.to_string(), now - stage_start, cost));
+            stage_start = now;
+        };
 
         // Step 1: Verify access policies
-        let access_check = self.policy_engine.check_access(&query)?;
-        if !access_check.allowed {
-            self.audit_log.log_blocked(&query, format!("{:?}", access_check.violation));
-            return Err(GatewayError::PolicyViolation(access_check.violation));
-        }
+        let access_check = self.policy_engine.check_access(query)?.require_allowed()?;
+        end_stage("access", 0.0);
 
         // Step 2: Route to appropriate lane
         let routing = self.router.select_lane(
             query.latency_sla.unwrap_or(150),
             query.cost_budget.unwrap_or(0.01),
         )?;
+        end_stage("routing", 0.0);
+        deadline.check("routing")?;
+
+        // Step 3: Retrieve, keeping the context within the token ceiling
+        let mut citations = self.retriever.retrieve(query, &routing.lane)?;
+        let mut truncated = false;
+        if let Some(max_tokens) = self.max_tokens {
+            let mut budget = max_tokens;
+            for citation in &mut citations {
+                let (excerpt, used, cut) = truncate_tokens(&citation.excerpt, budget);
+                citation.excerpt = excerpt;
+                budget -= used;
+                truncated |= cut;
+            }
+        }
+        end_stage("retrieval", 0.0);
+        deadline.check("retrieval")?;
+
+        // Step 4: Generate, billed by the lane for the tokens actually used
+        let (mut answer, mut tokens) = self.generator.generate(query, &citations, &routing.lane)?;
+        if let Some(max_tokens) = self.max_tokens.filter(|&max| tokens > max) {
+            answer = truncate_tokens(&answer, max_tokens).0;
+            tokens = max_tokens;
+            truncated = true;
+        }
+        end_stage("generation", routing.lane.cost_per_1k_tokens * tokens as f64 / 1000.0);
+        deadline.check("generation")?;
 
-        // Step 3: Retrieve and generate
-        let (answer, citations, tokens) = self.retrieve_and_generate(
-            &query,
-            &routing.lane,
-        )?;
-
-        // Step 4: Apply PII masking
+        // Step 5: Apply PII masking to the answer and the cited excerpts
         let masked_answer = self.policy_engine.mask_pii(&answer)?;
-
-        // Step 5: Generate proof certificate
-        let proof = self.generate_proof(&query, &masked_answer, &access_check)?;
+        let citations = citations
+            .into_iter()
+            .map(|c| {
+                Ok(Citation {
+                    excerpt: self.policy_engine.mask_pii(&c.excerpt)?,
+                    ..c
+                })
+            })
+            .collect::<Result<Vec<_>, MaskingError>>()?;
+        end_stage("masking", 0.0);
+
+        // Step 6: Generate proof certificate
+        let truncated_at = self.max_tokens.filter(|_| truncated);
+        let proof = self.generate_proof(query, &masked_answer, &access_check, truncated_at)?;
+        end_stage("proof", 0.0);
 
         let latency = start.elapsed().as_millis() as u64;
+        let cost: f64 = stages.iter().map(|(_, _, cost)| cost).sum();
 
-        // Step 6: Log successful request
-        self.audit_log.log_success(&query, latency, routing.estimated_cost, &routing.lane.name);
+        // Step 7: Log successful request
+        self.audit_log.log_success(&self.audited(query), latency, cost, &routing.lane.name);
 
         Ok(RagResponse {
             answer: masked_answer,
@@ -145,59 +279,87 @@ impl RagGateway {
             proof,
             metrics: ResponseMetrics {
                 latency_ms: latency,
-                cost_usd: routing.estimated_cost,
+                cost_usd: cost,
                 tokens_used: tokens,
                 lane_used: routing.lane.name.clone(),
+                truncated,
+                stage_breakdown: stages,
             },
         })
     }
 
-    /// Retrieve relevant documents and generate answer
-    fn retrieve_and_generate(
-        &self,
-        query: &RagQuery,
-        _lane: &Lane,
-    ) -> Result<(String, Vec<Citation>, usize), GatewayError> {
-        // Simulated retrieval and generation
-        // In production, this would call actual vector DB + LLM
-
-        let answer = format!(
-            "Based on the sources, here is the answer to '{}': \
-             [Generated content respecting all policies]",
-            query.question
-        );
-
-        let citations = vec![
-            Citation {
-                source: query.sources.first().cloned().unwrap_or_default(),
-                excerpt: "Relevant excerpt from source...".to_string(),
-                relevance_score: 0.92,
-            }
-        ];
-
-        let tokens = 450; // Estimated
-
-        Ok((answer, citations, tokens))
-    }
-
     /// Generate proof certificate
+    ///
+    /// Claims are derived from the checks that actually passed, and the
+    /// certificate must verify before it is returned. `truncated_at` is the
+    /// token ceiling when content was cut to fit it.
+    ///
+    /// Obligations record the policies evaluated for the query: access,
+    /// masking and rate limiting only appear when a policy required them.
     fn generate_proof(
         &self,
         query: &RagQuery,
         answer: &str,
-        _access_check: &AccessCheckResult,
-    ) -> Result<ProofCertificate, GatewayError> {
-        let claims = vec![
-            format!("access_granted(user={})", query.user_id),
-            format!("pii_masked(answer)"),
-            format!("sources_authorized({:?})", query.sources),
-        ];
+        access_check: &AccessCheckResult,
+        truncated_at: Option<usize>,
+    ) -> Result<ProofCertificate, ProofError> {
+        if answer.trim().is_empty() {
+            return Err(ProofError::EmptyContent);
+        }
 
-        Ok(ProofCertificate::new(
+        let mut claims = Vec::new();
+        if access_check.allowed {
+            claims.push(format!("access_granted(user={})", query.user_id));
+        }
+        claims.push("pii_masked(answer)".to_string());
+        claims.push(format!("sources_authorized({:?})", query.sources));
+        if let Some(max_tokens) = truncated_at {
+            claims.push(format!("{}(max_tokens={})", proof::TRUNCATED, max_tokens));
+        }
+
+        let decision = self.policy_engine.evaluate(query);
+        let mut obligations = Vec::new();
+        if access_check.allowed {
+            obligations.push(
+                ProofObligation::new(proof::ACCESS_GRANTED)
+                    .with_arg("user", ObligationArg::Text(query.user_id.clone())),
+            );
+        }
+        if decision.mask_pii {
+            obligations.push(
+                ProofObligation::new(proof::PII_MASKED)
+                    .with_arg("field", ObligationArg::Text("answer".to_string())),
+            );
+        }
+        if let Some(limit) = decision.rate_limit {
+            obligations.push(
+                ProofObligation::new(proof::RATE_LIMITED)
+                    .with_arg("user", ObligationArg::Text(query.user_id.clone()))
+                    .with_arg("max_requests", ObligationArg::Int(limit.max_requests as u64))
+                    .with_arg("window_secs", ObligationArg::Int(limit.window.as_secs())),
+            );
+        }
+        obligations.push(
+            ProofObligation::new(proof::SOURCES_AUTHORIZED)
+                .with_arg("sources", ObligationArg::List(query.sources.clone())),
+        );
+        if let Some(max_tokens) = truncated_at {
+            obligations.push(
+                ProofObligation::new(proof::TRUNCATED)
+                    .with_arg("max_tokens", ObligationArg::Int(max_tokens as u64)),
+            );
+        }
+
+        let proof = ProofCertificate::new(
             ProofKind::PolicyRespected,
             claims,
             answer,
-        ))
+        )
+        .with_pii_masking_required(decision.mask_pii)
+        .with_obligations(obligations);
+        proof.verify().into_result()?;
+
+        Ok(proof)
     }
 
     /// Get audit log reference
@@ -212,12 +374,72 @@ pub struct AccessCheckResult {
     pub violation: PolicyViolation,
 }
 
+impl AccessCheckResult {
+    /// Turn a denial into its policy violation
+    pub fn require_allowed(self) -> Result<Self, PolicyViolation> {
+        if self.allowed {
+            Ok(self)
+        } else {
+            Err(self.violation)
+        }
+    }
+}
+
+/// Keep at most `budget` whitespace-separated tokens of `text`
+///
+/// Returns the kept text, the number of tokens kept, and whether anything
+/// was cut. Text within budget is returned unchanged.
+fn truncate_tokens(text: &str, budget: usize) -> (String, usize, bool) {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() <= budget {
+        return (text.to_string(), words.len(), false);
+    }
+    (words[..budget].join(" "), budget, true)
+}
+
+/// Latency deadline derived from a query's SLA
+struct Deadline {
+    start: Instant,
+    sla: Option<Duration>,
+}
+
+impl Deadline {
+    fn new(start: Instant, sla_ms: Option<u64>) -> Self {
+        Self {
+            start,
+            sla: sla_ms.map(Duration::from_millis),
+        }
+    }
+
+    fn check(&self, stage: &'static str) -> Result<(), GatewayError> {
+        let Some(sla) = self.sla else {
+            return Ok(());
+        };
+
+        let elapsed = self.start.elapsed();
+        if elapsed > sla {
+            return Err(GatewayError::Timeout {
+                stage,
+                elapsed_ms: elapsed.as_millis() as u64,
+                sla_ms: sla.as_millis() as u64,
+            });
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum GatewayError {
     PolicyViolation(PolicyViolation),
     RoutingError(String),
     RetrievalError(String),
     ProofGenerationError(String),
+    /// Latency SLA exceeded after the named pipeline stage
+    Timeout {
+        stage: &'static str,
+        elapsed_ms: u64,
+        sla_ms: u64,
+    },
     Internal(String),
 }
 
@@ -228,6 +450,11 @@ impl std::fmt::Display for GatewayError {
             GatewayError::RoutingError(e) => write!(f, "Routing error: {}", e),
             GatewayError::RetrievalError(e) => write!(f, "Retrieval error: {}", e),
             GatewayError::ProofGenerationError(e) => write!(f, "Proof error: {}", e),
+            GatewayError::Timeout { stage, elapsed_ms, sla_ms } => write!(
+                f,
+                "Timeout: latency SLA of {}ms exceeded after {} ({}ms)",
+                sla_ms, stage, elapsed_ms
+            ),
             GatewayError::Internal(e) => write!(f, "Internal error: {}", e),
         }
     }
@@ -235,6 +462,36 @@ impl std::fmt::Display for GatewayError {
 
 impl std::error::Error for GatewayError {}
 
+impl From<PolicyViolation> for GatewayError {
+    fn from(violation: PolicyViolation) -> Self {
+        GatewayError::PolicyViolation(violation)
+    }
+}
+
+impl From<MaskingError> for GatewayError {
+    fn from(e: MaskingError) -> Self {
+        match e {
+            MaskingError::UnmaskablePii { kind } => {
+                GatewayError::PolicyViolation(PolicyViolation::PIIDetected {
+                    field: kind.to_string(),
+                })
+            }
+        }
+    }
+}
+
+impl From<ProofError> for GatewayError {
+    fn from(e: ProofError) -> Self {
+        GatewayError::ProofGenerationError(e.to_string())
+    }
+}
+
+impl From<lean_agentic::Error> for GatewayError {
+    fn from(e: lean_agentic::Error) -> Self {
+        ProofError::from(e).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,6 +517,7 @@ mod tests {
 
         assert!(response.metrics.latency_ms < 150);
         assert!(response.proof.claims.len() > 0);
+        assert!(response.proof.verify().is_valid());
         assert_eq!(response.metrics.lane_used, "local");
     }
 
@@ -301,4 +559,331 @@ mod tests {
         // Answer should not contain actual SSN
         assert!(response.answer.contains("[REDACTED]") || !response.answer.contains("123-45-6789"));
     }
+
+    struct EchoRetriever;
+
+    impl Retriever for EchoRetriever {
+        fn retrieve(&self, query: &RagQuery, _lane: &Lane) -> Result<Vec<Citation>, GatewayError> {
+            Ok(vec![Citation {
+                source: "tickets.db".to_string(),
+                excerpt: format!("Ticket: {}", query.question),
+                relevance_score: 0.8,
+            }])
+        }
+    }
+
+    #[test]
+    fn test_question_pii_masked_in_audit_and_citations() {
+        let policies = vec![Policy::allow_user("user123"), Policy::deny_user("intruder"), Policy::mask_pii()];
+        let mut gateway = RagGateway::new(policies).with_retriever(EchoRetriever);
+
+        let query = RagQuery {
+            question: "My SSN is 123-45-6789, am I refunded?".to_string(),
+            ..refund_query()
+        };
+        let response = gateway.process(query.clone()).unwrap();
+
+        assert_eq!(response.citations[0].excerpt, "Ticket: My SSN is [REDACTED], am I refunded?");
+        let events = gateway.audit_log().events();
+        assert!(matches!(
+            &events[0],
+            AuditEvent::RequestSuccess { question, .. } if question == "My SSN is [REDACTED], am I refunded?"
+        ));
+
+        // Blocked requests are audited with the masked question too
+        let blocked = RagQuery { user_id: "intruder".to_string(), ..query };
+        assert!(gateway.process(blocked).is_err());
+        let events = gateway.audit_log().events();
+        assert!(matches!(
+            &events[1],
+            AuditEvent::RequestBlocked { question, .. } if !question.contains("123-45-6789")
+        ));
+    }
+
+    struct SlowRetriever(Duration);
+
+    impl Retriever for SlowRetriever {
+        fn retrieve(&self, query: &RagQuery, lane: &Lane) -> Result<Vec<Citation>, GatewayError> {
+            std::thread::sleep(self.0);
+            SimulatedRetriever.retrieve(query, lane)
+        }
+    }
+
+    #[test]
+    fn test_slow_retrieval_aborts_on_sla() {
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123")])
+            .with_retriever(SlowRetriever(Duration::from_millis(120)));
+
+        let query = RagQuery {
+            question: "What is our refund policy?".to_string(),
+            sources: vec!["policies.txt".to_string()],
+            user_id: "user123".to_string(),
+            // Loose enough for routing to find a lane, too tight for retrieval
+            latency_sla: Some(60),
+            cost_budget: Some(1.0),
+        };
+
+        let result = gateway.process(query.clone());
+        assert!(matches!(
+            result,
+            Err(GatewayError::Timeout { stage: "retrieval", sla_ms: 60, .. })
+        ));
+        assert!(gateway.audit_log().events().last().unwrap().is_blocked());
+
+        // Without an SLA the same slow request completes
+        let relaxed = RagQuery { latency_sla: None, ..query };
+        assert!(gateway.process(relaxed).is_ok());
+    }
+
+    struct FixedRetriever(Vec<Citation>);
+
+    impl Retriever for FixedRetriever {
+        fn retrieve(&self, _query: &RagQuery, _lane: &Lane) -> Result<Vec<Citation>, GatewayError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct CitingGenerator;
+
+    impl Generator for CitingGenerator {
+        fn generate(
+            &self,
+            _query: &RagQuery,
+            citations: &[Citation],
+            _lane: &Lane,
+        ) -> Result<(String, usize), GatewayError> {
+            let excerpts: Vec<_> = citations.iter().map(|c| c.excerpt.as_str()).collect();
+            Ok((excerpts.join(" "), 7))
+        }
+    }
+
+    #[test]
+    fn test_injected_backends_flow_through() {
+        let citations = vec![
+            Citation {
+                source: "handbook.md".to_string(),
+                excerpt: "Refunds within 30 days.".to_string(),
+                relevance_score: 0.97,
+            },
+            Citation {
+                source: "faq.md".to_string(),
+                excerpt: "Contact 123-45-6789 for help.".to_string(),
+                relevance_score: 0.41,
+            },
+        ];
+
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123"), Policy::mask_pii()])
+            .with_retriever(FixedRetriever(citations.clone()))
+            .with_generator(CitingGenerator);
+
+        let query = RagQuery {
+            question: "What is our refund policy?".to_string(),
+            sources: vec!["handbook.md".to_string()],
+            user_id: "user123".to_string(),
+            latency_sla: None,
+            cost_budget: Some(1.0),
+        };
+
+        let response = gateway.process(query).unwrap();
+
+        let sources: Vec<_> = response.citations.iter().map(|c| c.source.as_str()).collect();
+        assert_eq!(sources, vec!["handbook.md", "faq.md"]);
+        assert_eq!(response.metrics.tokens_used, 7);
+
+        // The gateway still masks and proves the generated answer
+        assert!(response.answer.starts_with("Refunds within 30 days."));
+        assert!(!response.answer.contains("123-45-6789"));
+        assert!(response.proof.verify().is_valid());
+        assert_eq!(gateway.audit_log().success_count(), 1);
+    }
+
+    struct FixedGenerator(&'static str);
+
+    impl Generator for FixedGenerator {
+        fn generate(
+            &self,
+            _query: &RagQuery,
+            _citations: &[Citation],
+            _lane: &Lane,
+        ) -> Result<(String, usize), GatewayError> {
+            Ok((self.0.to_string(), 3))
+        }
+    }
+
+    fn refund_query() -> RagQuery {
+        RagQuery {
+            question: "What is our refund policy?".to_string(),
+            sources: vec!["policies.txt".to_string()],
+            user_id: "user123".to_string(),
+            latency_sla: None,
+            cost_budget: Some(1.0),
+        }
+    }
+
+    #[test]
+    fn test_masking_failure_maps_to_pii_violation() {
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123"), Policy::mask_pii()])
+            .with_mask_config(MaskConfig::new().rejecting_unformatted_ssn())
+            .with_generator(FixedGenerator("The customer's SSN is 123456789."));
+
+        let result = gateway.process(refund_query());
+        assert!(matches!(
+            result,
+            Err(GatewayError::PolicyViolation(PolicyViolation::PIIDetected { ref field }))
+                if field == "unformatted_ssn"
+        ));
+
+        let events = gateway.audit_log().events();
+        let last = events.last().unwrap();
+        assert!(last.is_blocked());
+        assert!(matches!(last, AuditEvent::RequestBlocked { violation, .. } if violation.contains("PIIDetected")));
+    }
+
+    #[test]
+    fn test_proof_failure_maps_to_proof_error() {
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123")])
+            .with_generator(FixedGenerator("   "));
+
+        let result = gateway.process(refund_query());
+        assert!(matches!(
+            result,
+            Err(GatewayError::ProofGenerationError(ref e)) if e.contains("empty content")
+        ));
+        assert_eq!(gateway.audit_log().events().len(), 1);
+        assert!(gateway.audit_log().events()[0].is_blocked());
+
+        // A certificate missing its access claim is rejected, not returned
+        let denied = AccessCheckResult {
+            allowed: false,
+            violation: PolicyViolation::UserDenied { user_id: "user123".to_string() },
+        };
+        let err = gateway.generate_proof(&refund_query(), "answer", &denied, None).unwrap_err();
+        assert_eq!(
+            err,
+            ProofError::Unverified(vec![VerificationFailure::MissingClaim(proof::ACCESS_GRANTED)])
+        );
+        assert!(matches!(GatewayError::from(err), GatewayError::ProofGenerationError(_)));
+    }
+
+    struct SlowGenerator(Duration);
+
+    impl Generator for SlowGenerator {
+        fn generate(
+            &self,
+            query: &RagQuery,
+            citations: &[Citation],
+            lane: &Lane,
+        ) -> Result<(String, usize), GatewayError> {
+            std::thread::sleep(self.0);
+            SimulatedGenerator.generate(query, citations, lane)
+        }
+    }
+
+    #[test]
+    fn test_stage_breakdown_adds_up() {
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123"), Policy::mask_pii()])
+            .with_generator(SlowGenerator(Duration::from_millis(2)));
+
+        // Budget and SLA that route to a paid lane
+        let query = RagQuery {
+            latency_sla: Some(150),
+            cost_budget: Some(0.05),
+            ..refund_query()
+        };
+        gateway.router = CostAwareRouter::new().with_min_availability(0.995);
+        let response = gateway.process(query).unwrap();
+        let metrics = &response.metrics;
+        assert_eq!(metrics.lane_used, "cloud_fast");
+
+        let stages: Vec<_> = metrics.stage_breakdown.iter().map(|(s, _, _)| s.as_str()).collect();
+        assert_eq!(stages, vec!["access", "routing", "retrieval", "generation", "masking", "proof"]);
+
+        let total_cost: f64 = metrics.stage_breakdown.iter().map(|(_, _, c)| c).sum();
+        assert!((total_cost - metrics.cost_usd).abs() < 1e-12);
+        assert!((metrics.cost_usd - 0.015 * 450.0 / 1000.0).abs() < 1e-12);
+
+        let total_latency: Duration = metrics.stage_breakdown.iter().map(|(_, d, _)| *d).sum();
+        assert!(total_latency.as_millis() as u64 <= metrics.latency_ms);
+        assert!(metrics.latency_ms - total_latency.as_millis() as u64 <= 1);
+
+        assert_eq!(metrics.slowest_stage(), Some("generation"));
+    }
+
+    #[test]
+    fn test_max_tokens_truncates_and_flags() {
+        let citations = vec![
+            Citation {
+                source: "handbook.md".to_string(),
+                excerpt: "Refunds are issued within thirty days of purchase.".to_string(),
+                relevance_score: 0.97,
+            },
+            Citation {
+                source: "faq.md".to_string(),
+                excerpt: "Contact support for help.".to_string(),
+                relevance_score: 0.41,
+            },
+        ];
+
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123")])
+            .with_retriever(FixedRetriever(citations))
+            .with_generator(CitingGenerator)
+            .with_max_tokens(4);
+
+        let response = gateway.process(refund_query()).unwrap();
+
+        // Context is cut to four tokens in total, so the second citation is emptied
+        assert_eq!(response.citations[0].excerpt, "Refunds are issued within");
+        assert_eq!(response.citations[1].excerpt, "");
+        // CitingGenerator reports 7 tokens, over the ceiling
+        assert_eq!(response.metrics.tokens_used, 4);
+        assert!(response.metrics.truncated);
+        assert!(response.proof.has_claim(proof::TRUNCATED));
+        assert!(response.proof.verify().is_valid());
+
+        // Within the ceiling nothing is flagged
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123")])
+            .with_generator(FixedGenerator("Refunds take thirty days."))
+            .with_max_tokens(1000);
+        let response = gateway.process(refund_query()).unwrap();
+        assert!(!response.metrics.truncated);
+        assert!(!response.proof.has_claim(proof::TRUNCATED));
+    }
+
+    #[test]
+    fn test_exported_obligations_match_evaluated_policies() {
+        let policies = vec![
+            Policy::allow_user("user123"),
+            Policy::mask_pii(),
+            Policy::rate_limit("user123", 5, Duration::from_secs(60)),
+            Policy::rate_limit("someone_else", 1, Duration::from_secs(60)),
+        ];
+        let mut gateway = RagGateway::new(policies);
+        let response = gateway.process(refund_query()).unwrap();
+
+        let exported: Vec<ProofObligation> =
+            serde_json::from_str(&response.proof.export_obligations()).unwrap();
+        assert_eq!(exported, response.proof.obligations);
+
+        let predicates: Vec<_> = exported.iter().map(|o| o.predicate.as_str()).collect();
+        assert_eq!(
+            predicates,
+            vec![proof::ACCESS_GRANTED, proof::PII_MASKED, proof::RATE_LIMITED, proof::SOURCES_AUTHORIZED]
+        );
+
+        // Only the rate limit that applied to this user is recorded
+        let rate = &exported[2].args;
+        assert_eq!(rate["user"], ObligationArg::Text("user123".to_string()));
+        assert_eq!(rate["max_requests"], ObligationArg::Int(5));
+        assert_eq!(rate["window_secs"], ObligationArg::Int(60));
+        assert_eq!(
+            exported[3].args["sources"],
+            ObligationArg::List(vec!["policies.txt".to_string()])
+        );
+
+        // Without masking or rate-limit policies those obligations are absent
+        let mut gateway = RagGateway::new(vec![Policy::allow_user("user123")]);
+        let response = gateway.process(refund_query()).unwrap();
+        let predicates: Vec<_> = response.proof.obligations.iter().map(|o| o.predicate.as_str()).collect();
+        assert_eq!(predicates, vec![proof::ACCESS_GRANTED, proof::SOURCES_AUTHORIZED]);
+    }
 }
@@ -1,14 +1,39 @@
 //! Policy engine for access control and PII masking
+//!
+//! Several policies can apply to the same request. They are resolved by
+//! `PolicyEngine::evaluate` independently of their order in the list:
+//!
+//! 1. Deny wins: a `DenyUser` naming the user blocks the request even if
+//!    an `AllowUser` also names them.
+//! 2. Access is otherwise open: users named by no allow or deny policy
+//!    are allowed.
+//! 3. Constraints always apply on top of an allow: masking is required if
+//!    any `MaskPII` applies, and the tightest matching `RateLimit` is
+//!    enforced.
+//! 4. When any `RequireSource` is present, every requested source must
+//!    match at least one of their patterns.
+//!
+//! `PolicyEngine::explain` reports every policy blocking a query together
+//! with the smallest change that would let it through.
 
 use crate::{RagQuery, GatewayError, AccessCheckResult};
+use regex::Regex;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Policy {
     AllowUser { user_id: String },
     DenyUser { user_id: String },
+    /// Sources must match `pattern`: exactly, or by prefix if it ends in `*`
     RequireSource { pattern: String },
     MaskPII,
     MaxRetention { days: u32 },
+    /// At most `max_requests` per `window` for one user
+    RateLimit { user_id: String, max_requests: usize, window: Duration },
+    /// Several policies applied together
+    All(Vec<Policy>),
 }
 
 impl Policy {
@@ -20,67 +45,467 @@ impl Policy {
         Policy::DenyUser { user_id: user_id.to_string() }
     }
 
+    pub fn require_source(pattern: &str) -> Self {
+        Policy::RequireSource { pattern: pattern.to_string() }
+    }
+
     pub fn mask_pii() -> Self {
         Policy::MaskPII
     }
+
+    pub fn rate_limit(user_id: &str, max_requests: usize, window: Duration) -> Self {
+        Policy::RateLimit {
+            user_id: user_id.to_string(),
+            max_requests,
+            window,
+        }
+    }
+
+    /// Combine policies into one, resolved by the usual precedence rules
+    pub fn compose(policies: impl IntoIterator<Item = Policy>) -> Self {
+        Policy::All(policies.into_iter().collect())
+    }
+
+    /// This policy and any it is composed of, flattened
+    fn flatten(&self) -> Vec<&Policy> {
+        match self {
+            Policy::All(policies) => policies.iter().flat_map(Policy::flatten).collect(),
+            policy => vec![policy],
+        }
+    }
 }
 
+/// Rate limit that applies to a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max_requests: usize,
+    pub window: Duration,
+}
+
+/// Combined outcome of every policy that applies to a request
 #[derive(Debug, Clone)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    /// Why the request was denied
+    pub violation: Option<PolicyViolation>,
+    /// Whether the response must be PII-masked
+    pub mask_pii: bool,
+    /// Tightest rate limit for the user, if any
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum PolicyViolation {
     UserDenied { user_id: String },
     SourceUnauthorized { source: String },
     PIIDetected { field: String },
     RetentionExceeded,
+    RateLimited { user_id: String, max_requests: usize },
+}
+
+/// Change that would lift a block
+#[derive(Debug, Clone, PartialEq)]
+pub enum Remedy {
+    /// Remove the user from the deny list
+    RemoveDeny { user_id: String },
+    /// Authorize the source, e.g. by adding a pattern covering it
+    AuthorizeSource { source: String, allowed_patterns: Vec<String> },
+    /// Wait until the rate limit window frees a slot
+    WaitFor(Duration),
+}
+
+impl std::fmt::Display for Remedy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Remedy::RemoveDeny { user_id } => {
+                write!(f, "user {} must be removed from the deny list", user_id)
+            }
+            Remedy::AuthorizeSource { source, allowed_patterns } => write!(
+                f,
+                "source {} not authorized; it must match one of: {}",
+                source,
+                allowed_patterns.join(", ")
+            ),
+            Remedy::WaitFor(wait) => write!(f, "rate limited; retry in {:?}", wait),
+        }
+    }
+}
+
+/// One reason a query is blocked
+#[derive(Debug, Clone, PartialEq)]
+pub struct Blocker {
+    /// Policies responsible for the block
+    pub policies: Vec<Policy>,
+    pub violation: PolicyViolation,
+    /// Smallest change that would lift this block
+    pub remedy: Remedy,
+}
+
+/// Counterfactual explanation of a policy decision
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyExplanation {
+    pub allowed: bool,
+    /// Every block, in the order `evaluate` applies them; empty if allowed
+    pub blockers: Vec<Blocker>,
+}
+
+/// PII masking could not make a response safe to return
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaskingError {
+    /// Text still looks like PII after masking, in a format the masker
+    /// has no redaction rule for
+    UnmaskablePii { kind: &'static str },
+}
+
+impl std::fmt::Display for MaskingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaskingError::UnmaskablePii { kind } => {
+                write!(f, "unmaskable PII detected ({})", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MaskingError {}
+
+/// Exemptions from PII masking
+///
+/// Order numbers, SKUs and version strings can look like SSNs or card
+/// numbers. A candidate match that equals an allowed string, or that an
+/// allowed pattern matches in full, is left as is.
+#[derive(Debug, Clone, Default)]
+pub struct MaskConfig {
+    allowed: HashSet<String>,
+    allowed_patterns: Vec<Regex>,
+    /// What to do with bare nine-digit runs
+    unformatted_ssn: UnformattedSsn,
+}
+
+/// Handling of nine-digit runs that may be SSNs written without dashes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum UnformattedSsn {
+    /// Leave them alone: most are order numbers, phone numbers or IDs
+    #[default]
+    Keep,
+    Redact,
+    Reject,
+}
+
+impl MaskConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never mask this exact string
+    pub fn with_allowed(mut self, token: &str) -> Self {
+        self.allowed.insert(token.to_string());
+        self
+    }
+
+    /// Never mask a match this regex matches in full
+    pub fn with_allowed_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.allowed_patterns.push(Regex::new(&format!("^(?:{})$", pattern))?);
+        Ok(self)
+    }
+
+    /// Redact every bare nine-digit run as an SSN written without dashes
+    ///
+    /// Off by default, since such runs are usually order numbers or other
+    /// identifiers; exempt known ones with `with_allowed_pattern`.
+    pub fn redacting_unformatted_ssn(mut self) -> Self {
+        self.unformatted_ssn = UnformattedSsn::Redact;
+        self
+    }
+
+    /// Reject text containing a bare nine-digit run instead of returning it
+    ///
+    /// For deployments where such a run is more likely an SSN pasted
+    /// without dashes than an order number, and a human should look at it.
+    pub fn rejecting_unformatted_ssn(mut self) -> Self {
+        self.unformatted_ssn = UnformattedSsn::Reject;
+        self
+    }
+
+    /// Whether a PII candidate is exempt from masking
+    pub fn is_allowed(&self, candidate: &str) -> bool {
+        self.allowed.contains(candidate)
+            || self.allowed_patterns.iter().any(|re| re.is_match(candidate))
+    }
 }
 
 pub struct PolicyEngine {
     policies: Vec<Policy>,
+
+    /// Exemptions applied by `mask_pii`
+    mask_config: MaskConfig,
+
+    /// Recent admitted requests per user, for rate limiting
+    requests: Mutex<HashMap<String, VecDeque<Instant>>>,
 }
 
 impl PolicyEngine {
     pub fn new(policies: Vec<Policy>) -> Self {
-        Self { policies }
+        Self {
+            policies,
+            mask_config: MaskConfig::default(),
+            requests: Mutex::new(HashMap::new()),
+        }
     }
 
-    pub fn check_access(&self, query: &RagQuery) -> Result<AccessCheckResult, GatewayError> {
-        for policy in &self.policies {
+    /// Exempt allow-listed tokens from PII masking
+    pub fn with_mask_config(mut self, mask_config: MaskConfig) -> Self {
+        self.mask_config = mask_config;
+        self
+    }
+
+    fn flattened(&self) -> impl Iterator<Item = &Policy> {
+        self.policies.iter().flat_map(Policy::flatten)
+    }
+
+    /// Resolve every policy that applies to `query` into one decision
+    ///
+    /// See the module docs for the precedence rules. This does not count
+    /// the request against any rate limit; `check_access` does.
+    pub fn evaluate(&self, query: &RagQuery) -> PolicyDecision {
+        let mut decision = PolicyDecision {
+            allowed: true,
+            violation: None,
+            mask_pii: false,
+            rate_limit: None,
+        };
+
+        for policy in self.flattened() {
             match policy {
                 Policy::DenyUser { user_id } if user_id == &query.user_id => {
-                    return Ok(AccessCheckResult {
-                        allowed: false,
-                        violation: PolicyViolation::UserDenied {
-                            user_id: query.user_id.clone(),
-                        },
+                    decision.allowed = false;
+                    decision.violation = Some(PolicyViolation::UserDenied {
+                        user_id: query.user_id.clone(),
+                    });
+                }
+                Policy::MaskPII => decision.mask_pii = true,
+                Policy::RateLimit { user_id, max_requests, window } if user_id == &query.user_id => {
+                    let limit = RateLimit { max_requests: *max_requests, window: *window };
+                    let tighter = decision.rate_limit.is_none_or(|current| {
+                        limit.max_requests as f64 / limit.window.as_secs_f64()
+                            < current.max_requests as f64 / current.window.as_secs_f64()
                     });
+                    if tighter {
+                        decision.rate_limit = Some(limit);
+                    }
                 }
                 _ => {}
             }
         }
 
+        if decision.allowed {
+            if let Some(source) = self.unauthorized_sources(query).first() {
+                decision.allowed = false;
+                decision.violation = Some(PolicyViolation::SourceUnauthorized {
+                    source: source.to_string(),
+                });
+            }
+        }
+
+        decision
+    }
+
+    /// Patterns of every `RequireSource` policy
+    fn source_patterns(&self) -> Vec<&str> {
+        self.flattened()
+            .filter_map(|p| match p {
+                Policy::RequireSource { pattern } => Some(pattern.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Requested sources that match no `RequireSource` pattern
+    fn unauthorized_sources<'q>(&self, query: &'q RagQuery) -> Vec<&'q String> {
+        let patterns = self.source_patterns();
+        if patterns.is_empty() {
+            return Vec::new();
+        }
+        query
+            .sources
+            .iter()
+            .filter(|source| !patterns.iter().any(|p| source_matches(p, source)))
+            .collect()
+    }
+
+    /// Explain why `query` is blocked and what would allow it
+    ///
+    /// Unlike `evaluate`, this lists every blocking policy rather than the
+    /// first, and includes a rate limit the user has already exhausted.
+    /// Nothing is counted against the rate limit.
+    pub fn explain(&self, query: &RagQuery) -> PolicyExplanation {
+        let mut blockers = Vec::new();
+
+        let denies: Vec<Policy> = self
+            .flattened()
+            .filter(|p| matches!(p, Policy::DenyUser { user_id } if user_id == &query.user_id))
+            .cloned()
+            .collect();
+        if !denies.is_empty() {
+            blockers.push(Blocker {
+                policies: denies,
+                violation: PolicyViolation::UserDenied { user_id: query.user_id.clone() },
+                remedy: Remedy::RemoveDeny { user_id: query.user_id.clone() },
+            });
+        }
+
+        let patterns = self.source_patterns();
+        for source in self.unauthorized_sources(query) {
+            blockers.push(Blocker {
+                policies: patterns.iter().map(|p| Policy::require_source(p)).collect(),
+                violation: PolicyViolation::SourceUnauthorized { source: source.clone() },
+                remedy: Remedy::AuthorizeSource {
+                    source: source.clone(),
+                    allowed_patterns: patterns.iter().map(|p| p.to_string()).collect(),
+                },
+            });
+        }
+
+        if let Some(limit) = self.evaluate(query).rate_limit {
+            let now = Instant::now();
+            let requests = self.requests.lock().unwrap();
+            let recent: Vec<&Instant> = requests
+                .get(&query.user_id)
+                .into_iter()
+                .flatten()
+                .filter(|t| now.duration_since(**t) < limit.window)
+                .collect();
+
+            if recent.len() >= limit.max_requests {
+                let wait = recent
+                    .first()
+                    .map(|oldest| limit.window.saturating_sub(now.duration_since(**oldest)))
+                    .unwrap_or(limit.window);
+                blockers.push(Blocker {
+                    policies: vec![Policy::RateLimit {
+                        user_id: query.user_id.clone(),
+                        max_requests: limit.max_requests,
+                        window: limit.window,
+                    }],
+                    violation: PolicyViolation::RateLimited {
+                        user_id: query.user_id.clone(),
+                        max_requests: limit.max_requests,
+                    },
+                    remedy: Remedy::WaitFor(wait),
+                });
+            }
+        }
+
+        PolicyExplanation {
+            allowed: blockers.is_empty(),
+            blockers,
+        }
+    }
+
+    /// Evaluate the policies and count the request against its rate limit
+    pub fn check_access(&self, query: &RagQuery) -> Result<AccessCheckResult, GatewayError> {
+        let decision = self.evaluate(query);
+        if let Some(violation) = decision.violation {
+            return Ok(AccessCheckResult { allowed: false, violation });
+        }
+
+        if let Some(limit) = decision.rate_limit {
+            let now = Instant::now();
+            let mut requests = self.requests.lock().unwrap();
+            let recent = requests.entry(query.user_id.clone()).or_default();
+            while recent.front().is_some_and(|t| now.duration_since(*t) >= limit.window) {
+                recent.pop_front();
+            }
+
+            if recent.len() >= limit.max_requests {
+                return Ok(AccessCheckResult {
+                    allowed: false,
+                    violation: PolicyViolation::RateLimited {
+                        user_id: query.user_id.clone(),
+                        max_requests: limit.max_requests,
+                    },
+                });
+            }
+            recent.push_back(now);
+        }
+
         Ok(AccessCheckResult {
             allowed: true,
             violation: PolicyViolation::UserDenied { user_id: String::new() },
         })
     }
 
-    pub fn mask_pii(&self, text: &str) -> Result<String, GatewayError> {
+    /// Whether any policy requires PII masking
+    pub fn requires_pii_masking(&self) -> bool {
+        self.flattened().any(|p| matches!(p, Policy::MaskPII))
+    }
+
+    /// Redact known PII formats from `text`
+    ///
+    /// Bare nine-digit runs are left alone unless the `MaskConfig` opts
+    /// into redacting them, or into rejecting them: then any text holding
+    /// one fails closed, whether or not a masking policy applies. Tokens
+    /// exempted by the `MaskConfig` are neither masked nor rejected.
+    pub fn mask_pii(&self, text: &str) -> Result<String, MaskingError> {
+        let config = &self.mask_config;
         let mut masked = text.to_string();
 
         // Simple PII detection without regex
         // Mask potential SSN patterns (XXX-XX-XXXX)
-        masked = mask_ssn_pattern(&masked);
+        masked = mask_ssn_pattern(&masked, config);
+        if config.unformatted_ssn == UnformattedSsn::Redact {
+            masked = mask_unformatted_ssn_pattern(&masked, config);
+        }
 
-        // Mask potential credit card numbers (16 consecutive digits)
-        masked = mask_credit_card_pattern(&masked);
+        // Mask potential credit card numbers (16 digits passing the Luhn check)
+        masked = mask_credit_card_pattern(&masked, config);
 
         // Mask potential email addresses
-        masked = mask_email_pattern(&masked);
+        masked = mask_email_pattern(&masked, config);
+
+        if config.unformatted_ssn == UnformattedSsn::Reject {
+            if let Some(kind) = detect_residual_pii(&masked, config) {
+                return Err(MaskingError::UnmaskablePii { kind });
+            }
+        }
 
         Ok(masked)
     }
 }
 
-fn mask_ssn_pattern(text: &str) -> String {
+/// Whether `source` matches a `RequireSource` pattern
+fn source_matches(pattern: &str, source: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => source.starts_with(prefix),
+        None => source == pattern,
+    }
+}
+
+/// Find PII-like text the masking rules do not cover
+fn detect_residual_pii(text: &str, config: &MaskConfig) -> Option<&'static str> {
+    // A run of exactly nine digits reads as an SSN without separators
+    text.split(|c: char| !c.is_ascii_digit())
+        .any(|run| run.len() == 9 && !config.is_allowed(run))
+        .then_some("unformatted_ssn")
+}
+
+/// Luhn checksum used by payment card numbers
+fn luhn_valid(digits: &[char]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+fn mask_ssn_pattern(text: &str, config: &MaskConfig) -> String {
     let mut result = String::new();
     let chars: Vec<char> = text.chars().collect();
     let mut i = 0;
@@ -98,7 +523,8 @@ fn mask_ssn_pattern(text: &str) -> String {
                chars[i+7].is_ascii_digit() &&
                chars[i+8].is_ascii_digit() &&
                chars[i+9].is_ascii_digit() &&
-               chars[i+10].is_ascii_digit() {
+               chars[i+10].is_ascii_digit() &&
+               !config.is_allowed(&chars[i..i+11].iter().collect::<String>()) {
                 result.push_str("[REDACTED]");
                 i += 11;
                 continue;
@@ -110,15 +536,41 @@ fn mask_ssn_pattern(text: &str) -> String {
     result
 }
 
-fn mask_credit_card_pattern(text: &str) -> String {
+/// Redact runs of exactly nine digits (SSNs written without dashes)
+fn mask_unformatted_ssn_pattern(text: &str, config: &MaskConfig) -> String {
+    let mut result = String::new();
+    let mut run = String::new();
+
+    for c in text.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_digit() {
+            run.push(c);
+            continue;
+        }
+        if run.len() == 9 && !config.is_allowed(&run) {
+            result.push_str("[REDACTED]");
+        } else {
+            result.push_str(&run);
+        }
+        run.clear();
+        if c != '\0' {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn mask_credit_card_pattern(text: &str, config: &MaskConfig) -> String {
     let mut result = String::new();
     let chars: Vec<char> = text.chars().collect();
     let mut i = 0;
 
     while i < chars.len() {
         if i + 15 < chars.len() {
-            // Check for 16 consecutive digits
-            let is_card = (0..16).all(|j| chars[i+j].is_ascii_digit());
+            // Check for 16 consecutive digits with a valid checksum
+            let candidate = &chars[i..i+16];
+            let is_card = candidate.iter().all(|c| c.is_ascii_digit())
+                && luhn_valid(candidate)
+                && !config.is_allowed(&candidate.iter().collect::<String>());
             if is_card {
                 result.push_str("[REDACTED]");
                 i += 16;
@@ -131,7 +583,7 @@ fn mask_credit_card_pattern(text: &str) -> String {
     result
 }
 
-fn mask_email_pattern(text: &str) -> String {
+fn mask_email_pattern(text: &str, config: &MaskConfig) -> String {
     // Simple email detection: look for word@word.word pattern
     let words: Vec<&str> = text.split_whitespace().collect();
     let mut result = String::new();
@@ -139,7 +591,7 @@ fn mask_email_pattern(text: &str) -> String {
     for (idx, word) in words.iter().enumerate() {
         if word.contains('@') && word.contains('.') {
             let parts: Vec<&str> = word.split('@').collect();
-            if parts.len() == 2 && parts[1].contains('.') {
+            if parts.len() == 2 && parts[1].contains('.') && !config.is_allowed(word) {
                 result.push_str("[REDACTED]");
             } else {
                 result.push_str(word);
@@ -171,6 +623,54 @@ mod tests {
         assert!(masked.contains("[REDACTED]"));
     }
 
+    #[test]
+    fn test_unformatted_ssn_is_masked() {
+        // Bare runs are left alone by default
+        let engine = PolicyEngine::new(vec![Policy::MaskPII]);
+        assert_eq!(
+            engine.mask_pii("Order 123456789 shipped").unwrap(),
+            "Order 123456789 shipped"
+        );
+
+        let engine = PolicyEngine::new(vec![Policy::MaskPII])
+            .with_mask_config(MaskConfig::new().redacting_unformatted_ssn());
+        assert_eq!(
+            engine.mask_pii("SSN 123456789 on file").unwrap(),
+            "SSN [REDACTED] on file"
+        );
+
+        // Longer and shorter digit runs are left alone
+        assert_eq!(
+            engine.mask_pii("Order 12345678 and ref 1234567890").unwrap(),
+            "Order 12345678 and ref 1234567890"
+        );
+
+        let config = MaskConfig::new()
+            .redacting_unformatted_ssn()
+            .with_allowed("987654321");
+        let engine = PolicyEngine::new(vec![Policy::MaskPII]).with_mask_config(config);
+        assert_eq!(engine.mask_pii("SKU 987654321").unwrap(), "SKU 987654321");
+    }
+
+    #[test]
+    fn test_unmaskable_pii_fails_closed() {
+        let engine = PolicyEngine::new(vec![Policy::MaskPII])
+            .with_mask_config(MaskConfig::new().rejecting_unformatted_ssn());
+        assert_eq!(
+            engine.mask_pii("SSN 123456789 on file"),
+            Err(MaskingError::UnmaskablePii { kind: "unformatted_ssn" })
+        );
+        assert!(engine.mask_pii("Order 12345678 shipped").is_ok());
+
+        // Rejection does not depend on a masking policy being present
+        let unpoliced = PolicyEngine::new(vec![])
+            .with_mask_config(MaskConfig::new().rejecting_unformatted_ssn());
+        assert_eq!(
+            unpoliced.mask_pii("SSN 123456789 on file"),
+            Err(MaskingError::UnmaskablePii { kind: "unformatted_ssn" })
+        );
+    }
+
     #[test]
     fn test_access_control() {
         let engine = PolicyEngine::new(vec![Policy::deny_user("blocked")]);
@@ -186,4 +686,150 @@ mod tests {
         let result = engine.check_access(&query).unwrap();
         assert!(!result.allowed);
     }
+
+    fn query_for(user_id: &str) -> RagQuery {
+        RagQuery {
+            question: "test".to_string(),
+            sources: vec![],
+            user_id: user_id.to_string(),
+            latency_sla: None,
+            cost_budget: None,
+        }
+    }
+
+    #[test]
+    fn test_deny_beats_allow_in_any_order() {
+        for policies in [
+            vec![Policy::allow_user("mallory"), Policy::deny_user("mallory")],
+            vec![Policy::deny_user("mallory"), Policy::allow_user("mallory")],
+            vec![Policy::compose([Policy::allow_user("mallory"), Policy::deny_user("mallory")])],
+        ] {
+            let engine = PolicyEngine::new(policies);
+            let decision = engine.evaluate(&query_for("mallory"));
+            assert!(!decision.allowed);
+            assert!(matches!(decision.violation, Some(PolicyViolation::UserDenied { .. })));
+            assert!(!engine.check_access(&query_for("mallory")).unwrap().allowed);
+        }
+    }
+
+    #[test]
+    fn test_allow_and_rate_limit_both_apply() {
+        let engine = PolicyEngine::new(vec![Policy::compose([
+            Policy::allow_user("alice"),
+            Policy::rate_limit("alice", 5, Duration::from_secs(60)),
+            Policy::rate_limit("alice", 2, Duration::from_secs(60)),
+            Policy::mask_pii(),
+        ])]);
+
+        let decision = engine.evaluate(&query_for("alice"));
+        assert!(decision.allowed);
+        assert!(decision.mask_pii);
+        assert_eq!(
+            decision.rate_limit,
+            Some(RateLimit { max_requests: 2, window: Duration::from_secs(60) })
+        );
+        assert!(engine.requires_pii_masking());
+
+        // The tightest limit is enforced
+        assert!(engine.check_access(&query_for("alice")).unwrap().allowed);
+        assert!(engine.check_access(&query_for("alice")).unwrap().allowed);
+        let third = engine.check_access(&query_for("alice")).unwrap();
+        assert!(!third.allowed);
+        assert!(matches!(third.violation, PolicyViolation::RateLimited { max_requests: 2, .. }));
+
+        // Other users are not limited
+        assert!(engine.evaluate(&query_for("bob")).rate_limit.is_none());
+    }
+
+    #[test]
+    fn test_explain_unauthorized_source() {
+        let engine = PolicyEngine::new(vec![Policy::require_source("docs/public/*")]);
+        let mut query = query_for("alice");
+        query.sources = vec!["docs/public/faq".to_string(), "docs/secret".to_string()];
+
+        let decision = engine.evaluate(&query);
+        assert!(!decision.allowed);
+        assert_eq!(
+            decision.violation,
+            Some(PolicyViolation::SourceUnauthorized { source: "docs/secret".to_string() })
+        );
+
+        let explanation = engine.explain(&query);
+        assert!(!explanation.allowed);
+        assert_eq!(explanation.blockers.len(), 1);
+        let blocker = &explanation.blockers[0];
+        assert_eq!(blocker.policies, vec![Policy::require_source("docs/public/*")]);
+        assert_eq!(
+            blocker.remedy,
+            Remedy::AuthorizeSource {
+                source: "docs/secret".to_string(),
+                allowed_patterns: vec!["docs/public/*".to_string()],
+            }
+        );
+        assert_eq!(
+            blocker.remedy.to_string(),
+            "source docs/secret not authorized; it must match one of: docs/public/*"
+        );
+
+        query.sources.pop();
+        assert!(engine.explain(&query).allowed);
+    }
+
+    #[test]
+    fn test_explain_lists_every_blocker() {
+        let engine = PolicyEngine::new(vec![
+            Policy::deny_user("mallory"),
+            Policy::require_source("docs/*"),
+            Policy::rate_limit("alice", 1, Duration::from_secs(60)),
+        ]);
+
+        let mut query = query_for("mallory");
+        query.sources = vec!["wiki/home".to_string()];
+        let explanation = engine.explain(&query);
+        let remedies: Vec<_> = explanation.blockers.iter().map(|b| &b.remedy).collect();
+        assert!(matches!(remedies[..], [Remedy::RemoveDeny { .. }, Remedy::AuthorizeSource { .. }]));
+
+        // Explaining does not use up the rate limit
+        assert!(engine.explain(&query_for("alice")).allowed);
+        assert!(engine.check_access(&query_for("alice")).unwrap().allowed);
+        let explanation = engine.explain(&query_for("alice"));
+        assert!(matches!(explanation.blockers[0].remedy, Remedy::WaitFor(wait) if wait <= Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_credit_card_masking_requires_luhn() {
+        let engine = PolicyEngine::new(vec![Policy::MaskPII]);
+
+        // Standard Visa test number
+        assert_eq!(engine.mask_pii("card 4111111111111111").unwrap(), "card [REDACTED]");
+
+        // Same length, last digit changed so the checksum fails
+        let order = "order 4111111111111112";
+        assert_eq!(engine.mask_pii(order).unwrap(), order);
+    }
+
+    #[test]
+    fn test_allow_listed_tokens_are_preserved() {
+        let config = MaskConfig::new()
+            .redacting_unformatted_ssn()
+            .with_allowed("123-45-6789")
+            .with_allowed_pattern(r"SKU\d{9}|\d{9}")
+            .unwrap()
+            .with_allowed("support@example.com");
+        let engine = PolicyEngine::new(vec![Policy::MaskPII]).with_mask_config(config);
+
+        let text = "ref 123-45-6789 part 987654321 mail support@example.com";
+        assert_eq!(engine.mask_pii(text).unwrap(), text);
+
+        // Other matches are still masked
+        assert_eq!(
+            engine.mask_pii("ssn 987-65-4321 mail bob@example.com").unwrap(),
+            "ssn [REDACTED] mail [REDACTED]"
+        );
+
+        // Patterns must match the whole candidate
+        assert!(MaskConfig::new().with_allowed_pattern("[").is_err());
+        let partial = MaskConfig::new().with_allowed_pattern(r"123").unwrap();
+        assert!(!partial.is_allowed("123-45-6789"));
+    }
 }
@@ -1,11 +1,15 @@
 //! Proof certificates for verified RAG responses
 
+use lean_agentic::level::LevelArena;
+use lean_agentic::{Arena, Context, Environment, TermId};
+use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Types of proofs
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProofKind {
     PolicyRespected,
     PIIMasked,
@@ -13,6 +17,15 @@ pub enum ProofKind {
     CostWithinBudget,
 }
 
+/// Claim predicates
+pub const ACCESS_GRANTED: &str = "access_granted";
+pub const PII_MASKED: &str = "pii_masked";
+pub const SOURCES_AUTHORIZED: &str = "sources_authorized";
+pub const COST_WITHIN_BUDGET: &str = "cost_within_budget";
+pub const KERNEL_CHECKED: &str = "kernel_checked";
+pub const TRUNCATED: &str = "truncated";
+pub const RATE_LIMITED: &str = "rate_limited";
+
 /// Proof certificate for verified response
 #[derive(Debug, Clone)]
 pub struct ProofCertificate {
@@ -20,13 +33,244 @@ pub struct ProofCertificate {
     pub claims: Vec<String>,
     pub verified_at: u64, // Unix timestamp
     pub proof_hash: String,
+    /// Whether a PII-masking policy applied to the response
+    pub pii_masking_required: bool,
+    /// Core proof term backing the claims, if built with `from_term`
+    pub kernel_proof: Option<KernelProof>,
+    /// Structured form of the checks behind the claims
+    pub obligations: Vec<ProofObligation>,
+}
+
+/// A check the gateway discharged, in a form an external verifier can redo
+///
+/// `predicate` is one of the claim predicates such as `access_granted`;
+/// `args` are the values the check was made against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofObligation {
+    pub predicate: String,
+    pub args: BTreeMap<String, ObligationArg>,
+}
+
+/// Typed argument of a [`ProofObligation`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ObligationArg {
+    Bool(bool),
+    Int(u64),
+    Text(String),
+    List(Vec<String>),
+}
+
+impl ProofObligation {
+    pub fn new(predicate: &str) -> Self {
+        Self {
+            predicate: predicate.to_string(),
+            args: BTreeMap::new(),
+        }
+    }
+
+    /// Add a named argument
+    pub fn with_arg(mut self, name: &str, value: ObligationArg) -> Self {
+        self.args.insert(name.to_string(), value);
+        self
+    }
+}
+
+/// A core proof term and the proposition it was checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KernelProof {
+    pub proof_term: TermId,
+    pub stated_type: TermId,
+}
+
+/// Reason a certificate failed verification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationFailure {
+    /// Certificate carries no claims at all
+    NoClaims,
+    /// A claim required by the proof kind is absent
+    MissingClaim(&'static str),
+    /// The kernel no longer accepts the proof term at its stated type
+    KernelRejected(String),
+    /// The certificate rests on a proof term that was not re-checked
+    KernelNotChecked,
+}
+
+/// Reason a proof certificate could not be produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    /// There is no content for the certificate to attest to
+    EmptyContent,
+    /// The certificate does not satisfy its kind's invariants
+    Unverified(Vec<VerificationFailure>),
+    /// The kernel rejected the proof term
+    Kernel(String),
+}
+
+impl std::fmt::Display for ProofError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofError::EmptyContent => write!(f, "cannot certify empty content"),
+            ProofError::Unverified(failures) => {
+                write!(f, "certificate failed verification: {:?}", failures)
+            }
+            ProofError::Kernel(e) => write!(f, "kernel rejected proof: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProofError {}
+
+impl From<lean_agentic::Error> for ProofError {
+    fn from(e: lean_agentic::Error) -> Self {
+        ProofError::Kernel(e.to_string())
+    }
+}
+
+/// Result of verifying a proof certificate against its kind's invariants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationOutcome {
+    pub kind: ProofKind,
+    pub failures: Vec<VerificationFailure>,
+}
+
+impl VerificationOutcome {
+    pub fn is_valid(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Turn a failed verification into an error
+    pub fn into_result(self) -> Result<(), ProofError> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(ProofError::Unverified(self.failures))
+        }
+    }
+
+    /// Required claim predicates that were not present
+    pub fn missing_claims(&self) -> Vec<&'static str> {
+        self.failures
+            .iter()
+            .filter_map(|f| match f {
+                VerificationFailure::MissingClaim(claim) => Some(*claim),
+                VerificationFailure::NoClaims
+                | VerificationFailure::KernelRejected(_)
+                | VerificationFailure::KernelNotChecked => None,
+            })
+            .collect()
+    }
 }
 
 impl ProofCertificate {
     /// Verify the proof certificate
-    pub fn verify(&self) -> bool {
-        // In production, this would check signatures
-        !self.claims.is_empty()
+    ///
+    /// Each kind requires its own claims: `PolicyRespected` needs
+    /// `access_granted` (and `pii_masked` when masking was required), the
+    /// other kinds need the claim named after them.
+    ///
+    /// A certificate backed by a kernel proof cannot be verified without
+    /// re-running the kernel and fails with `KernelNotChecked`; use
+    /// [`verify_with_kernel`](Self::verify_with_kernel) instead.
+    pub fn verify(&self) -> VerificationOutcome {
+        // In production, this would also check signatures
+        let mut outcome = self.verify_claims();
+        if self.kernel_proof.is_some() {
+            outcome.failures.push(VerificationFailure::KernelNotChecked);
+        }
+        outcome
+    }
+
+    /// Verify the claims and re-run the kernel check on the proof term
+    ///
+    /// `arena` and `env` must be the ones the certificate was built with.
+    /// Certificates without a kernel proof only get the claim checks.
+    pub fn verify_with_kernel(&self, arena: &mut Arena, env: &Environment) -> VerificationOutcome {
+        let mut outcome = self.verify_claims();
+
+        if let Some(kernel) = &self.kernel_proof {
+            if let Err(e) = kernel.check(arena, env) {
+                outcome.failures.push(VerificationFailure::KernelRejected(e.to_string()));
+            }
+        }
+
+        outcome
+    }
+
+    /// Check the claims without consulting the kernel
+    ///
+    /// Claims of a kernel-backed certificate must be exactly the ones its
+    /// proof term supports, so they cannot outlive a change to the proof.
+    fn verify_claims(&self) -> VerificationOutcome {
+        let mut failures = Vec::new();
+
+        if self.claims.is_empty() {
+            failures.push(VerificationFailure::NoClaims);
+        }
+
+        for claim in self.required_claims() {
+            let present = match &self.kernel_proof {
+                Some(kernel) => self.claims.contains(&kernel.claim(claim)),
+                None => self.has_claim(claim),
+            };
+            if !present {
+                failures.push(VerificationFailure::MissingClaim(claim));
+            }
+        }
+
+        if let Some(kernel) = &self.kernel_proof {
+            if !self.claims.contains(&kernel.checked_claim()) {
+                failures.push(VerificationFailure::MissingClaim(KERNEL_CHECKED));
+            }
+        }
+
+        VerificationOutcome {
+            kind: self.kind,
+            failures,
+        }
+    }
+
+    /// Claim predicates this certificate must carry
+    pub fn required_claims(&self) -> Vec<&'static str> {
+        match self.kind {
+            ProofKind::PolicyRespected => {
+                let mut required = vec![ACCESS_GRANTED];
+                if self.pii_masking_required {
+                    required.push(PII_MASKED);
+                }
+                required
+            }
+            ProofKind::PIIMasked => vec![PII_MASKED],
+            ProofKind::SourceAuthorized => vec![SOURCES_AUTHORIZED],
+            ProofKind::CostWithinBudget => vec![COST_WITHIN_BUDGET],
+        }
+    }
+
+    /// Check for a claim by predicate name, e.g. `access_granted(user=alice)`
+    pub fn has_claim(&self, predicate: &str) -> bool {
+        self.claims
+            .iter()
+            .any(|claim| claim.split('(').next().map(str::trim) == Some(predicate))
+    }
+
+    /// Mark whether a PII-masking policy applied to the response
+    pub fn with_pii_masking_required(mut self, required: bool) -> Self {
+        self.pii_masking_required = required;
+        self
+    }
+
+    /// Attach the structured obligations behind the claims
+    pub fn with_obligations(mut self, obligations: Vec<ProofObligation>) -> Self {
+        self.obligations = obligations;
+        self
+    }
+
+    /// Export the obligations as a JSON array for offline re-verification
+    ///
+    /// Each entry is `{"predicate": ..., "args": {...}}`, with arguments
+    /// as plain JSON values.
+    pub fn export_obligations(&self) -> String {
+        serde_json::to_string(&self.obligations).expect("obligations always serialize")
     }
 
     /// Export audit bundle
@@ -58,8 +302,72 @@ impl ProofCertificate {
             claims,
             verified_at,
             proof_hash,
+            pii_masking_required: false,
+            kernel_proof: None,
+            obligations: Vec::new(),
         }
     }
+
+    /// Create a certificate backed by a kernel-checked proof term
+    ///
+    /// Type-checks `proof_term` against `stated_type` with the trusted
+    /// kernel and fails if it does not check. Only then are the claims its
+    /// kind requires derived from the checked proof, plus a
+    /// `kernel_checked` claim naming both terms.
+    pub fn from_term(
+        kind: ProofKind,
+        arena: &mut Arena,
+        env: &Environment,
+        proof_term: TermId,
+        stated_type: TermId,
+    ) -> lean_agentic::Result<Self> {
+        let kernel = KernelProof {
+            proof_term,
+            stated_type,
+        };
+        kernel.check(arena, env)?;
+
+        let mut certificate = Self::new(kind, Vec::new(), &format!("{:?}", kernel));
+        certificate.claims = certificate
+            .required_claims()
+            .into_iter()
+            .map(|claim| kernel.claim(claim))
+            .collect();
+        certificate.claims.push(kernel.checked_claim());
+        certificate.kernel_proof = Some(kernel);
+
+        Ok(certificate)
+    }
+}
+
+impl KernelProof {
+    /// Check the proof term against its stated type in an empty context
+    pub fn check(&self, arena: &mut Arena, env: &Environment) -> lean_agentic::Result<()> {
+        let mut levels = LevelArena::new();
+        lean_agentic::typechecker::check(
+            arena,
+            &mut levels,
+            env,
+            &Context::new(),
+            self.proof_term,
+            self.stated_type,
+        )
+    }
+
+    /// Claim `predicate` as established by this proof's stated type
+    fn claim(&self, predicate: &str) -> String {
+        format!("{}(type={})", predicate, self.stated_type.raw())
+    }
+
+    /// The `kernel_checked` claim naming both terms
+    fn checked_claim(&self) -> String {
+        format!(
+            "{}(proof={}, type={})",
+            KERNEL_CHECKED,
+            self.proof_term.raw(),
+            self.stated_type.raw()
+        )
+    }
 }
 
 /// Compute a simple hash for content
@@ -77,14 +385,130 @@ mod tests {
     fn test_proof_certificate() {
         let proof = ProofCertificate::new(
             ProofKind::PolicyRespected,
-            vec!["claim1".to_string(), "claim2".to_string()],
+            vec!["access_granted(user=u1)".to_string(), "claim2".to_string()],
             "test content",
         );
 
-        assert!(proof.verify());
+        assert!(proof.verify().is_valid());
         assert_eq!(proof.claims.len(), 2);
 
         let bundle = proof.export_audit_bundle();
-        assert!(bundle.contains("claim1"));
+        assert!(bundle.contains("claim2"));
+    }
+
+    #[test]
+    fn test_policy_proof_missing_access_claim() {
+        let proof = ProofCertificate::new(
+            ProofKind::PolicyRespected,
+            vec!["sources_authorized([])".to_string()],
+            "test content",
+        );
+
+        let outcome = proof.verify();
+        assert!(!outcome.is_valid());
+        assert_eq!(
+            outcome.failures,
+            vec![VerificationFailure::MissingClaim(ACCESS_GRANTED)]
+        );
+    }
+
+    #[test]
+    fn test_policy_proof_requires_pii_claim_when_masking_required() {
+        let claims = vec!["access_granted(user=u1)".to_string()];
+
+        let unmasked = ProofCertificate::new(ProofKind::PolicyRespected, claims.clone(), "x");
+        assert!(unmasked.verify().is_valid());
+
+        let masked = unmasked.with_pii_masking_required(true);
+        assert_eq!(masked.verify().missing_claims(), vec![PII_MASKED]);
+    }
+
+    #[test]
+    fn test_empty_certificate_fails() {
+        let proof = ProofCertificate::new(ProofKind::CostWithinBudget, vec![], "x");
+        let outcome = proof.verify();
+
+        assert_eq!(
+            outcome.failures,
+            vec![
+                VerificationFailure::NoClaims,
+                VerificationFailure::MissingClaim(COST_WITHIN_BUDGET),
+            ]
+        );
+    }
+
+    /// `λ (A : Type). λ (x : A). x` and its type `Π (A : Type). Π (x : A). A`
+    fn identity_proof(arena: &mut Arena) -> (TermId, TermId) {
+        use lean_agentic::{Binder, SymbolTable};
+
+        let symbols = SymbolTable::new();
+        let (a, x) = (symbols.intern("A"), symbols.intern("x"));
+        let zero = arena.mk_level_zero();
+        let ty = arena.mk_sort(zero);
+        let v0 = arena.mk_var(0);
+        let v1 = arena.mk_var(1);
+
+        let inner = arena.mk_lam(Binder::new(x, v0), v0);
+        let proof = arena.mk_lam(Binder::new(a, ty), inner);
+
+        let inner_ty = arena.mk_pi(Binder::new(x, v0), v1);
+        let stated = arena.mk_pi(Binder::new(a, ty), inner_ty);
+
+        (proof, stated)
+    }
+
+    #[test]
+    fn test_certificate_from_kernel_checked_term() {
+        let mut arena = Arena::new();
+        let env = Environment::new();
+        let (proof, stated) = identity_proof(&mut arena);
+
+        let cert = ProofCertificate::from_term(
+            ProofKind::PolicyRespected,
+            &mut arena,
+            &env,
+            proof,
+            stated,
+        )
+        .unwrap();
+
+        assert!(cert.has_claim(ACCESS_GRANTED));
+        assert!(cert.has_claim(KERNEL_CHECKED));
+        assert!(cert.verify_with_kernel(&mut arena, &env).is_valid());
+
+        // The proof term is only trusted once the kernel has re-checked it
+        assert_eq!(cert.verify().failures, vec![VerificationFailure::KernelNotChecked]);
+    }
+
+    #[test]
+    fn test_mismatched_type_is_rejected() {
+        let mut arena = Arena::new();
+        let env = Environment::new();
+        let (proof, stated) = identity_proof(&mut arena);
+
+        // `Type` is not the type of the identity function
+        let zero = arena.mk_level_zero();
+        let wrong = arena.mk_sort(zero);
+        let result =
+            ProofCertificate::from_term(ProofKind::PIIMasked, &mut arena, &env, proof, wrong);
+        assert!(result.is_err());
+
+        // A certificate whose stated type was swapped no longer verifies
+        let mut cert =
+            ProofCertificate::from_term(ProofKind::PIIMasked, &mut arena, &env, proof, stated)
+                .unwrap();
+        cert.kernel_proof = Some(KernelProof {
+            proof_term: proof,
+            stated_type: wrong,
+        });
+
+        // Its claims were derived from the original type and no longer match
+        let outcome = cert.verify_with_kernel(&mut arena, &env);
+        assert_eq!(outcome.missing_claims(), vec![PII_MASKED, KERNEL_CHECKED]);
+        assert!(matches!(
+            outcome.failures.last(),
+            Some(VerificationFailure::KernelRejected(_))
+        ));
+        assert!(!cert.verify().is_valid());
     }
 }
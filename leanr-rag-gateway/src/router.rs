@@ -48,10 +48,46 @@ pub struct RoutingDecision {
     pub lane: Lane,
     pub estimated_cost: f64,
     pub estimated_latency: u64,
+    /// Every candidate lane with its score and why it was (not) chosen
+    pub rationale: Vec<LaneEvaluation>,
 }
 
+/// Why a lane was or wasn't selected
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaneVerdict {
+    /// Lane was chosen
+    Selected,
+    /// Lane p99 latency exceeds the requested SLA
+    LatencyExceedsSla { latency_p99_ms: u64, sla_ms: u64 },
+    /// Estimated cost exceeds the budget
+    CostExceedsBudget { estimated_cost: f64, budget: f64 },
+    /// Lane availability is below the router minimum
+    Unavailable { availability: f64, minimum: f64 },
+    /// Lane was eligible but another lane scored better
+    Outscored { by: String },
+}
+
+/// Evaluation of one candidate lane during routing
+#[derive(Debug, Clone)]
+pub struct LaneEvaluation {
+    pub lane: String,
+    /// Cost/availability score (lower is better)
+    pub score: f64,
+    pub verdict: LaneVerdict,
+}
+
+impl LaneEvaluation {
+    pub fn is_selected(&self) -> bool {
+        self.verdict == LaneVerdict::Selected
+    }
+}
+
+/// Assumed tokens per request when estimating cost
+const ESTIMATED_TOKENS: f64 = 500.0;
+
 pub struct CostAwareRouter {
     lanes: Vec<Lane>,
+    min_availability: f64,
 }
 
 impl CostAwareRouter {
@@ -62,6 +98,44 @@ impl CostAwareRouter {
                 Lane::cloud_fast(),
                 Lane::cloud_cheap(),
             ],
+            min_availability: 0.95,
+        }
+    }
+
+    /// Set the minimum availability a lane needs to be eligible
+    pub fn with_min_availability(mut self, min_availability: f64) -> Self {
+        self.min_availability = min_availability;
+        self
+    }
+
+    fn score(lane: &Lane) -> f64 {
+        lane.cost_per_1k_tokens / (lane.availability + 0.01)
+    }
+
+    fn estimated_cost(lane: &Lane) -> f64 {
+        lane.cost_per_1k_tokens * ESTIMATED_TOKENS / 1000.0
+    }
+
+    fn check(&self, lane: &Lane, latency_sla_ms: u64, cost_budget_usd: f64) -> Option<LaneVerdict> {
+        let estimated_cost = Self::estimated_cost(lane);
+
+        if lane.latency_p99_ms > latency_sla_ms {
+            Some(LaneVerdict::LatencyExceedsSla {
+                latency_p99_ms: lane.latency_p99_ms,
+                sla_ms: latency_sla_ms,
+            })
+        } else if estimated_cost > cost_budget_usd {
+            Some(LaneVerdict::CostExceedsBudget {
+                estimated_cost,
+                budget: cost_budget_usd,
+            })
+        } else if lane.availability < self.min_availability {
+            Some(LaneVerdict::Unavailable {
+                availability: lane.availability,
+                minimum: self.min_availability,
+            })
+        } else {
+            None
         }
     }
 
@@ -70,31 +144,88 @@ impl CostAwareRouter {
         latency_sla_ms: u64,
         cost_budget_usd: f64,
     ) -> Result<RoutingDecision, GatewayError> {
-        // Filter lanes that meet SLA
-        let candidates: Vec<_> = self.lanes.iter()
-            .filter(|l| l.latency_p99_ms <= latency_sla_ms)
-            .filter(|l| l.cost_per_1k_tokens * 0.5 <= cost_budget_usd) // Assume 500 tokens
+        // Evaluate every lane against SLA, budget and availability
+        let mut rationale: Vec<LaneEvaluation> = self.lanes.iter()
+            .map(|l| LaneEvaluation {
+                lane: l.name.clone(),
+                score: Self::score(l),
+                verdict: self.check(l, latency_sla_ms, cost_budget_usd)
+                    .unwrap_or(LaneVerdict::Selected),
+            })
             .collect();
 
-        if candidates.is_empty() {
-            return Err(GatewayError::RoutingError(
-                "No lane meets SLA and budget".to_string()
-            ));
-        }
+        // Select best cost/performance among eligible lanes
+        let best = match rationale.iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_selected())
+            .min_by(|(_, a), (_, b)| a.score.partial_cmp(&b.score).unwrap())
+        {
+            Some((idx, _)) => idx,
+            None => {
+                return Err(GatewayError::RoutingError(
+                    "No lane meets SLA and budget".to_string()
+                ));
+            }
+        };
 
-        // Select best cost/performance
-        let best = candidates.iter()
-            .min_by(|a, b| {
-                let score_a = a.cost_per_1k_tokens / (a.availability + 0.01);
-                let score_b = b.cost_per_1k_tokens / (b.availability + 0.01);
-                score_a.partial_cmp(&score_b).unwrap()
-            })
-            .unwrap();
+        let best_name = rationale[best].lane.clone();
+        for (idx, evaluation) in rationale.iter_mut().enumerate() {
+            if idx != best && evaluation.is_selected() {
+                evaluation.verdict = LaneVerdict::Outscored { by: best_name.clone() };
+            }
+        }
 
+        let lane = &self.lanes[best];
         Ok(RoutingDecision {
-            lane: (*best).clone(),
-            estimated_cost: best.cost_per_1k_tokens * 0.5,
-            estimated_latency: best.latency_p99_ms,
+            lane: lane.clone(),
+            estimated_cost: Self::estimated_cost(lane),
+            estimated_latency: lane.latency_p99_ms,
+            rationale,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rationale_records_rejections() {
+        let router = CostAwareRouter::new();
+        let decision = router.select_lane(60, 0.01).unwrap();
+
+        assert_eq!(decision.lane.name, "local");
+        assert_eq!(decision.rationale.len(), 3);
+
+        let verdict = |name: &str| {
+            decision.rationale.iter()
+                .find(|e| e.lane == name)
+                .map(|e| e.verdict.clone())
+                .unwrap()
+        };
+
+        assert_eq!(verdict("local"), LaneVerdict::Selected);
+        assert_eq!(
+            verdict("cloud_fast"),
+            LaneVerdict::LatencyExceedsSla { latency_p99_ms: 120, sla_ms: 60 }
+        );
+        assert_eq!(
+            verdict("cloud_cheap"),
+            LaneVerdict::LatencyExceedsSla { latency_p99_ms: 200, sla_ms: 60 }
+        );
+    }
+
+    #[test]
+    fn test_rationale_records_outscored_and_budget() {
+        let router = CostAwareRouter::new();
+        let decision = router.select_lane(150, 0.005).unwrap();
+
+        assert_eq!(decision.lane.name, "local");
+        assert!(decision.rationale.iter().any(|e| e.lane == "cloud_fast"
+            && matches!(e.verdict, LaneVerdict::CostExceedsBudget { .. })));
+
+        let decision = router.select_lane(150, 0.01).unwrap();
+        assert!(decision.rationale.iter().any(|e| e.lane == "cloud_fast"
+            && e.verdict == LaneVerdict::Outscored { by: "local".to_string() }));
+    }
+}
@@ -3,6 +3,8 @@
 //! This represents the parsed structure before elaboration.
 
 use crate::span::Span;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 /// A top-level declaration
 #[derive(Debug, Clone, PartialEq)]
@@ -21,6 +23,31 @@ pub enum Decl {
 
     /// Structure declaration
     Structure(StructureDecl),
+
+    /// Operator declaration: infixl:65 " + " => add
+    Infix(InfixDecl),
+
+    /// Mutually recursive block: mutual ... end
+    Mutual(Vec<Decl>),
+}
+
+/// A top-level command: a declaration or an interactive query
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Declaration
+    Decl(Decl),
+
+    /// Report the type of an expression: #check e
+    Check {
+        span: Span,
+        expr: Box<Expr>,
+    },
+
+    /// Evaluate an expression: #eval e
+    Eval {
+        span: Span,
+        expr: Box<Expr>,
+    },
 }
 
 /// Function/constant definition
@@ -55,6 +82,27 @@ pub struct AxiomDecl {
     pub type_: Box<Expr>,
 }
 
+/// Operator associativity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Assoc {
+    /// a op b op c = (a op b) op c
+    Left,
+    /// a op b op c = a op (b op c)
+    Right,
+    /// a op b op c is rejected
+    None,
+}
+
+/// Operator declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfixDecl {
+    pub span: Span,
+    pub assoc: Assoc,
+    pub precedence: u32,
+    pub operator: String,
+    pub function: Ident,
+}
+
 /// Inductive type declaration
 #[derive(Debug, Clone, PartialEq)]
 pub struct InductiveDecl {
@@ -103,6 +151,17 @@ pub struct Param {
     pub implicit: bool,
 }
 
+impl Param {
+    fn hash_structure<H: Hasher>(&self, h: &mut H) {
+        self.names.len().hash(h);
+        for name in &self.names {
+            name.name.hash(h);
+        }
+        hash_optional(&self.type_, h);
+        self.implicit.hash(h);
+    }
+}
+
 /// Identifier
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Ident {
@@ -189,6 +248,11 @@ pub enum Expr {
         span: Span,
     },
 
+    /// Incomplete proof placeholder: sorry
+    Sorry {
+        span: Span,
+    },
+
     /// Universe: Type, Prop, Sort u
     Universe {
         span: Span,
@@ -217,10 +281,110 @@ impl Expr {
             Expr::If { span, .. } => *span,
             Expr::Ann { span, .. } => *span,
             Expr::Hole { span } => *span,
+            Expr::Sorry { span } => *span,
             Expr::Universe { span, .. } => *span,
             Expr::Paren { span, .. } => *span,
         }
     }
+
+    /// Hash of the expression's structure, for use as a cache key
+    ///
+    /// Spans and grouping parentheses are ignored, so the same expression
+    /// written at a different position or as `(e)` hashes alike.
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash_structure(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Whether two expressions have the same structure
+    ///
+    /// Compares what `structural_hash` hashes, so it ignores spans and
+    /// grouping parentheses and agrees with the hash, but never reports a
+    /// hash collision as a match.
+    pub fn structurally_eq(&self, other: &Expr) -> bool {
+        let (mut a, mut b) = (StructureBytes::default(), StructureBytes::default());
+        self.hash_structure(&mut a);
+        other.hash_structure(&mut b);
+        a.0 == b.0
+    }
+
+    fn hash_structure<H: Hasher>(&self, h: &mut H) {
+        if let Expr::Paren { expr, .. } = self {
+            return expr.hash_structure(h);
+        }
+
+        std::mem::discriminant(self).hash(h);
+        match self {
+            Expr::Ident(i) => i.name.hash(h),
+            Expr::Lit(l) => l.kind.hash(h),
+            Expr::App { func, args, .. } => {
+                func.hash_structure(h);
+                args.len().hash(h);
+                for arg in args {
+                    arg.hash_structure(h);
+                }
+            }
+            Expr::Lam { params, body, .. } | Expr::Forall { params, body, .. } => {
+                params.len().hash(h);
+                for param in params {
+                    param.hash_structure(h);
+                }
+                body.hash_structure(h);
+            }
+            Expr::Arrow { from, to, .. } => {
+                from.hash_structure(h);
+                to.hash_structure(h);
+            }
+            Expr::Let { name, type_, value, body, .. } => {
+                name.name.hash(h);
+                hash_optional(type_, h);
+                value.hash_structure(h);
+                body.hash_structure(h);
+            }
+            Expr::Match { scrutinee, arms, .. } => {
+                scrutinee.hash_structure(h);
+                arms.len().hash(h);
+                for arm in arms {
+                    arm.pattern.hash_structure(h);
+                    arm.body.hash_structure(h);
+                }
+            }
+            Expr::If { cond, then_branch, else_branch, .. } => {
+                cond.hash_structure(h);
+                then_branch.hash_structure(h);
+                else_branch.hash_structure(h);
+            }
+            Expr::Ann { expr, type_, .. } => {
+                expr.hash_structure(h);
+                type_.hash_structure(h);
+            }
+            Expr::Hole { .. } | Expr::Sorry { .. } => {}
+            Expr::Universe { kind, .. } => kind.hash(h),
+            Expr::Paren { .. } => unreachable!(),
+        }
+    }
+}
+
+/// Hasher that keeps every byte written to it, to compare structures exactly
+#[derive(Default)]
+struct StructureBytes(Vec<u8>);
+
+impl Hasher for StructureBytes {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        unreachable!("only the written bytes are compared")
+    }
+}
+
+fn hash_optional<H: Hasher>(expr: &Option<Box<Expr>>, h: &mut H) {
+    expr.is_some().hash(h);
+    if let Some(expr) = expr {
+        expr.hash_structure(h);
+    }
 }
 
 /// Literal expression
@@ -231,14 +395,14 @@ pub struct LitExpr {
 }
 
 /// Literal kinds
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum LitKind {
     Nat(u64),
     String(String),
 }
 
 /// Universe kinds
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum UniverseKind {
     Type,           // Type (implicitly Type 0)
     TypeLevel(u32), // Type u
@@ -283,6 +447,22 @@ pub enum Pattern {
 }
 
 impl Pattern {
+    fn hash_structure<H: Hasher>(&self, h: &mut H) {
+        std::mem::discriminant(self).hash(h);
+        match self {
+            Pattern::Var { name, .. } => name.name.hash(h),
+            Pattern::Constructor { name, args, .. } => {
+                name.name.hash(h);
+                args.len().hash(h);
+                for arg in args {
+                    arg.hash_structure(h);
+                }
+            }
+            Pattern::Wildcard { .. } => {}
+            Pattern::Lit { lit, .. } => lit.hash(h),
+        }
+    }
+
     /// Get the span of this pattern
     pub fn span(&self) -> Span {
         match self {
@@ -0,0 +1,136 @@
+//! Concrete syntax tree
+//!
+//! A lossless tree over the token stream, built by the parser alongside
+//! the AST when CST mode is enabled. Every token the parser consumed is a
+//! leaf, including the delimiters and keywords the AST drops, and tokens
+//! lexed with trivia keep their whitespace and comments. Formatters and
+//! refactoring tools can therefore rewrite a subtree and print the rest
+//! of the file back unchanged.
+
+use crate::lexer::Token;
+use crate::span::Span;
+
+/// Syntactic category of a CST node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CstKind {
+    /// The whole input, including the final `Eof` token
+    Root,
+    /// A `#check` or `#eval` command
+    Command,
+    /// A declaration
+    Decl,
+    /// A binder group such as `(x y : Nat)` or `{α : Type}`
+    Binder,
+    /// An expression
+    Expr,
+}
+
+/// A node or a token in the tree
+#[derive(Debug, Clone, PartialEq)]
+pub enum CstElement {
+    Node(CstNode),
+    Token(Token),
+}
+
+/// Interior node of the concrete syntax tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstNode {
+    pub kind: CstKind,
+    pub children: Vec<CstElement>,
+}
+
+impl CstNode {
+    /// Create an empty node
+    pub fn new(kind: CstKind) -> Self {
+        Self {
+            kind,
+            children: Vec::new(),
+        }
+    }
+
+    /// All tokens under this node, in source order
+    pub fn tokens(&self) -> Vec<&Token> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, out: &mut Vec<&'a Token>) {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => node.collect_tokens(out),
+                CstElement::Token(token) => out.push(token),
+            }
+        }
+    }
+
+    /// Direct child nodes
+    pub fn nodes(&self) -> impl Iterator<Item = &CstNode> {
+        self.children.iter().filter_map(|child| match child {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        })
+    }
+
+    /// Source range covered by the node's tokens, excluding trivia
+    pub fn span(&self) -> Option<Span> {
+        let tokens = self.tokens();
+        Some(tokens.first()?.span.to(tokens.last()?.span))
+    }
+
+    /// Print the node back as source text
+    ///
+    /// Lossless when the tokens were lexed with trivia enabled.
+    pub fn text(&self, source: &str) -> String {
+        let tokens: Vec<Token> = self.tokens().into_iter().cloned().collect();
+        crate::lexer::reconstruct_source(&tokens, source)
+    }
+}
+
+/// Stack of open nodes, fed by the parser as it consumes tokens
+#[derive(Debug, Default)]
+pub(crate) struct CstBuilder {
+    open: Vec<CstNode>,
+}
+
+impl CstBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            open: vec![CstNode::new(CstKind::Root)],
+        }
+    }
+
+    pub(crate) fn start_node(&mut self, kind: CstKind) {
+        self.open.push(CstNode::new(kind));
+    }
+
+    pub(crate) fn finish_node(&mut self) {
+        if self.open.len() > 1 {
+            let node = self.open.pop().unwrap();
+            self.push(CstElement::Node(node));
+        }
+    }
+
+    pub(crate) fn token(&mut self, token: Token) {
+        self.push(CstElement::Token(token));
+    }
+
+    fn push(&mut self, element: CstElement) {
+        if let Some(parent) = self.open.last_mut() {
+            parent.children.push(element);
+        }
+    }
+
+    /// Close any nodes left open by a parse error
+    pub(crate) fn close_all(&mut self) {
+        while self.open.len() > 1 {
+            self.finish_node();
+        }
+    }
+
+    /// Close all open nodes and return the root
+    pub(crate) fn finish(mut self) -> CstNode {
+        self.close_all();
+        self.open.pop().unwrap_or_else(|| CstNode::new(CstKind::Root))
+    }
+}
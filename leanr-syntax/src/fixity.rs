@@ -0,0 +1,87 @@
+//! Operator fixity table
+//!
+//! Maps operator symbols to their precedence, associativity, and the
+//! function an application of the operator desugars to.
+
+use crate::ast::Assoc;
+use std::collections::HashMap;
+
+/// Fixity of a single operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixity {
+    /// Binding strength (higher binds tighter)
+    pub precedence: u32,
+
+    /// Associativity
+    pub assoc: Assoc,
+
+    /// Name of the function `a op b` desugars to
+    pub function: String,
+}
+
+/// Built-in operators: (symbol, precedence, associativity, function)
+///
+/// Precedences follow Lean 4's prelude.
+const BUILTIN_OPERATORS: &[(&str, u32, Assoc, &str)] = &[
+    ("||", 30, Assoc::Right, "or"),
+    ("&&", 35, Assoc::Right, "and"),
+    ("=", 50, Assoc::None, "Eq"),
+    ("<", 50, Assoc::None, "LT.lt"),
+    ("+", 65, Assoc::Left, "HAdd.hAdd"),
+    ("-", 65, Assoc::Left, "HSub.hSub"),
+    ("*", 70, Assoc::Left, "HMul.hMul"),
+];
+
+/// Table of operators known to the parser
+#[derive(Debug, Clone, Default)]
+pub struct OperatorTable {
+    operators: HashMap<String, Fixity>,
+}
+
+impl OperatorTable {
+    /// Create an empty operator table
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a table with the built-in operators registered
+    pub fn builtin() -> Self {
+        let mut table = Self::new();
+        for &(op, precedence, assoc, function) in BUILTIN_OPERATORS {
+            table.register(
+                op,
+                Fixity {
+                    precedence,
+                    assoc,
+                    function: function.to_string(),
+                },
+            );
+        }
+        table
+    }
+
+    /// Register an operator, replacing any previous fixity
+    pub fn register(&mut self, op: impl Into<String>, fixity: Fixity) {
+        self.operators.insert(op.into(), fixity);
+    }
+
+    /// Look up an operator
+    pub fn get(&self, op: &str) -> Option<&Fixity> {
+        self.operators.get(op)
+    }
+
+    /// Check if an operator is registered
+    pub fn contains(&self, op: &str) -> bool {
+        self.operators.contains_key(op)
+    }
+
+    /// Get the number of registered operators
+    pub fn len(&self) -> usize {
+        self.operators.len()
+    }
+
+    /// Check if the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.operators.is_empty()
+    }
+}
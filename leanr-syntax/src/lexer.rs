@@ -17,15 +17,62 @@ pub struct Token {
 
     /// Location in source
     pub span: Span,
+
+    /// Whitespace and comments before this token (only with trivia enabled)
+    pub leading: Vec<Trivia>,
+
+    /// Whitespace and comments after this token, up to the end of its line
+    pub trailing: Vec<Trivia>,
 }
 
 impl Token {
     /// Create a new token
     pub fn new(kind: TokenKind, span: Span) -> Self {
-        Self { kind, span }
+        Self {
+            kind,
+            span,
+            leading: Vec::new(),
+            trailing: Vec::new(),
+        }
     }
 }
 
+/// Kind of trivia attached to a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+}
+
+/// Whitespace or a comment, kept verbatim for lossless round-tripping
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trivia {
+    /// What this trivia is
+    pub kind: TriviaKind,
+
+    /// Location in source
+    pub span: Span,
+
+    /// The exact source text, including comment delimiters
+    pub text: String,
+}
+
+/// Rebuild source text from tokens lexed with trivia enabled
+pub fn reconstruct_source(tokens: &[Token], source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    for token in tokens {
+        for trivia in &token.leading {
+            out.push_str(&trivia.text);
+        }
+        out.push_str(&source[token.span.start as usize..token.span.end as usize]);
+        for trivia in &token.trailing {
+            out.push_str(&trivia.text);
+        }
+    }
+    out
+}
+
 /// Token types
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum TokenKind {
@@ -51,12 +98,22 @@ pub enum TokenKind {
     By,
     Do,
     Return,
+    Infix,
+    Infixl,
+    Infixr,
+    Mutual,
+    End,
+    Sorry,
 
     // Special keywords
     Type,
     Prop,
     Sort,
 
+    // Interactive commands
+    HashCheck,    // #check
+    HashEval,     // #eval
+
     // Symbols
     LParen,       // (
     RParen,       // )
@@ -69,7 +126,7 @@ pub enum TokenKind {
     Colon,        // :
     ColonEq,      // :=
     Arrow,        // →  or ->
-    FatArrow,     // =>
+    FatArrow,     // =>, ⇒ or ↦
     Lambda,       // λ  or \
     Forall,       // ∀  or forall
     Exists,       // ∃  or exists
@@ -78,6 +135,7 @@ pub enum TokenKind {
     Pipe,         // |
     Underscore,   // _
     At,           // @
+    Operator(String), // + * = && ... (meaning given by the fixity table)
 
     // Identifiers and literals
     Ident(String),
@@ -91,7 +149,10 @@ pub enum TokenKind {
 
     // Special
     Eof,
-    Error(String),
+    /// A character that cannot start any token
+    Error(char),
+    /// A malformed token, e.g. an unterminated string
+    Invalid(String),
 }
 
 impl fmt::Display for TokenKind {
@@ -118,12 +179,21 @@ impl fmt::Display for TokenKind {
             TokenKind::By => write!(f, "by"),
             TokenKind::Do => write!(f, "do"),
             TokenKind::Return => write!(f, "return"),
+            TokenKind::Infix => write!(f, "infix"),
+            TokenKind::Infixl => write!(f, "infixl"),
+            TokenKind::Infixr => write!(f, "infixr"),
+            TokenKind::Mutual => write!(f, "mutual"),
+            TokenKind::End => write!(f, "end"),
+            TokenKind::Sorry => write!(f, "sorry"),
             TokenKind::Type => write!(f, "Type"),
             TokenKind::Prop => write!(f, "Prop"),
             TokenKind::Sort => write!(f, "Sort"),
+            TokenKind::HashCheck => write!(f, "#check"),
+            TokenKind::HashEval => write!(f, "#eval"),
             TokenKind::Ident(s) => write!(f, "{}", s),
             TokenKind::Number(n) => write!(f, "{}", n),
             TokenKind::String(s) => write!(f, "\"{}\"", s),
+            TokenKind::Operator(op) => write!(f, "{}", op),
             _ => write!(f, "{:?}", self),
         }
     }
@@ -139,6 +209,9 @@ pub struct Lexer {
 
     /// Tokens produced so far (for incremental edits)
     tokens: Vec<Token>,
+
+    /// Attach whitespace and comments to tokens instead of dropping them
+    trivia: bool,
 }
 
 impl Lexer {
@@ -148,37 +221,79 @@ impl Lexer {
             source,
             pos: 0,
             tokens: Vec::new(),
+            trivia: false,
         }
     }
 
+    /// Keep whitespace and comments as leading/trailing trivia on tokens
+    pub fn with_trivia(mut self, enabled: bool) -> Self {
+        self.trivia = enabled;
+        self
+    }
+
     /// Tokenize the entire source
     pub fn tokenize(mut self) -> Vec<Token> {
+        let mut leading = Vec::new();
+        // Trivia attaches to the previous token until the end of its line
+        let mut trailing_open = false;
+
         while !self.is_eof() {
             let token = self.next_token();
 
-            // Skip whitespace and comments (can be configurable)
-            match token.kind {
-                TokenKind::Whitespace | TokenKind::LineComment(_) | TokenKind::BlockComment(_) => {
-                    continue;
+            // Trailing whitespace with trivia off runs into end of input
+            if token.kind == TokenKind::Eof {
+                break;
+            }
+
+            let trivia_kind = match token.kind {
+                TokenKind::Whitespace => Some(TriviaKind::Whitespace),
+                TokenKind::LineComment(_) => Some(TriviaKind::LineComment),
+                TokenKind::BlockComment(_) => Some(TriviaKind::BlockComment),
+                _ => None,
+            };
+
+            match trivia_kind {
+                Some(_) if !self.trivia => continue,
+                Some(kind) => {
+                    let text = self.source.content
+                        [token.span.start as usize..token.span.end as usize]
+                        .to_string();
+                    let ends_line = text.contains('\n');
+                    let trivia = Trivia { kind, span: token.span, text };
+
+                    match self.tokens.last_mut() {
+                        Some(last) if trailing_open => {
+                            last.trailing.push(trivia);
+                            trailing_open = !ends_line;
+                        }
+                        _ => leading.push(trivia),
+                    }
                 }
-                _ => {
+                None => {
+                    let mut token = token;
+                    token.leading = std::mem::take(&mut leading);
                     self.tokens.push(token);
+                    trailing_open = true;
                 }
             }
         }
 
         // Add EOF token
-        self.tokens.push(Token::new(
+        let mut eof = Token::new(
             TokenKind::Eof,
             Span::new(self.pos as u32, self.pos as u32, self.source.id),
-        ));
+        );
+        eof.leading = leading;
+        self.tokens.push(eof);
 
         self.tokens
     }
 
     /// Get the next token
     fn next_token(&mut self) -> Token {
-        self.skip_whitespace();
+        if !self.trivia {
+            self.skip_whitespace();
+        }
 
         if self.is_eof() {
             return Token::new(
@@ -191,6 +306,8 @@ impl Lexer {
         let ch = self.current_char();
 
         let kind = match ch {
+            _ if ch.is_whitespace() => self.lex_whitespace(),
+
             // Single character tokens
             '(' => { self.advance(); TokenKind::LParen }
             ')' => { self.advance(); TokenKind::RParen }
@@ -200,7 +317,6 @@ impl Lexer {
             ']' => { self.advance(); TokenKind::RBracket }
             '.' => { self.advance(); TokenKind::Dot }
             ',' => { self.advance(); TokenKind::Comma }
-            '|' => { self.advance(); TokenKind::Pipe }
             '_' => { self.advance(); TokenKind::Underscore }
             '@' => { self.advance(); TokenKind::At }
 
@@ -215,39 +331,18 @@ impl Lexer {
                 }
             }
 
-            '-' => {
+            '-' if self.peek_char() == Some('-') => {
+                // Line comment
                 self.advance();
-                if self.current_char() == '>' {
-                    self.advance();
-                    TokenKind::Arrow
-                } else if self.current_char() == '-' {
-                    // Line comment
-                    self.advance();
-                    self.lex_line_comment()
-                } else {
-                    TokenKind::Error("Unexpected '-'".to_string())
-                }
-            }
-
-            '/' => {
                 self.advance();
-                if self.current_char() == '-' {
-                    // Block comment
-                    self.advance();
-                    self.lex_block_comment()
-                } else {
-                    TokenKind::Error("Unexpected '/'".to_string())
-                }
+                self.lex_line_comment()
             }
 
-            '=' => {
+            '/' if self.peek_char() == Some('-') => {
+                // Block comment
                 self.advance();
-                if self.current_char() == '>' {
-                    self.advance();
-                    TokenKind::FatArrow
-                } else {
-                    TokenKind::Error("Unexpected '='".to_string())
-                }
+                self.advance();
+                self.lex_block_comment()
             }
 
             '\\' => {
@@ -255,8 +350,14 @@ impl Lexer {
                 TokenKind::Lambda
             }
 
+            '#' => {
+                self.advance();
+                self.lex_command()
+            }
+
             // Unicode symbols
             '→' => { self.advance(); TokenKind::Arrow }
+            '⇒' | '↦' => { self.advance(); TokenKind::FatArrow }
             'λ' => { self.advance(); TokenKind::Lambda }
             '∀' => { self.advance(); TokenKind::Forall }
             '∃' => { self.advance(); TokenKind::Exists }
@@ -268,11 +369,14 @@ impl Lexer {
             '0'..='9' => self.lex_number(),
 
             // Identifiers and keywords
-            _ if ch.is_alphabetic() || ch == '_' => self.lex_identifier(),
+            _ if is_id_first(ch) => self.lex_identifier(),
+
+            // Operators, including ->, => and |
+            _ if is_operator_char(ch) => self.lex_operator(),
 
             _ => {
                 self.advance();
-                TokenKind::Error(format!("Unexpected character: '{}'", ch))
+                TokenKind::Error(ch)
             }
         };
 
@@ -286,13 +390,25 @@ impl Lexer {
         }
     }
 
+    /// Lex a run of whitespace, ending after the first newline
+    fn lex_whitespace(&mut self) -> TokenKind {
+        while !self.is_eof() && self.current_char().is_whitespace() {
+            let ch = self.current_char();
+            self.advance();
+            if ch == '\n' {
+                break;
+            }
+        }
+        TokenKind::Whitespace
+    }
+
     /// Lex an identifier or keyword
     fn lex_identifier(&mut self) -> TokenKind {
         let start = self.pos;
 
         while !self.is_eof() {
             let ch = self.current_char();
-            if ch.is_alphanumeric() || ch == '_' || ch == '\'' {
+            if is_id_rest(ch) {
                 self.advance();
             } else {
                 break;
@@ -324,6 +440,12 @@ impl Lexer {
             "by" => TokenKind::By,
             "do" => TokenKind::Do,
             "return" => TokenKind::Return,
+            "infix" => TokenKind::Infix,
+            "infixl" => TokenKind::Infixl,
+            "infixr" => TokenKind::Infixr,
+            "mutual" => TokenKind::Mutual,
+            "end" => TokenKind::End,
+            "sorry" => TokenKind::Sorry,
             "Type" => TokenKind::Type,
             "Prop" => TokenKind::Prop,
             "Sort" => TokenKind::Sort,
@@ -333,6 +455,37 @@ impl Lexer {
         }
     }
 
+    /// Lex a maximal run of operator characters
+    fn lex_operator(&mut self) -> TokenKind {
+        let start = self.pos;
+
+        while !self.is_eof() && is_operator_char(self.current_char()) {
+            self.advance();
+        }
+
+        match &self.source.content[start..self.pos] {
+            "->" => TokenKind::Arrow,
+            "=>" => TokenKind::FatArrow,
+            "|" => TokenKind::Pipe,
+            op => TokenKind::Operator(op.to_string()),
+        }
+    }
+
+    /// Lex a command keyword following '#'
+    fn lex_command(&mut self) -> TokenKind {
+        let start = self.pos;
+
+        while !self.is_eof() && self.current_char().is_alphanumeric() {
+            self.advance();
+        }
+
+        match &self.source.content[start..self.pos] {
+            "check" => TokenKind::HashCheck,
+            "eval" => TokenKind::HashEval,
+            other => TokenKind::Invalid(format!("Unknown command: '#{}'", other)),
+        }
+    }
+
     /// Lex a number
     fn lex_number(&mut self) -> TokenKind {
         let start = self.pos;
@@ -362,7 +515,7 @@ impl Lexer {
         }
 
         if self.is_eof() {
-            return TokenKind::Error("Unterminated string".to_string());
+            return TokenKind::Invalid("Unterminated string".to_string());
         }
 
         let text = self.source.content[start..self.pos].to_string();
@@ -405,7 +558,7 @@ impl Lexer {
         let text = self.source.content[start..self.pos].to_string();
 
         if depth > 0 {
-            TokenKind::Error("Unterminated block comment".to_string())
+            TokenKind::Invalid("Unterminated block comment".to_string())
         } else {
             TokenKind::BlockComment(text)
         }
@@ -434,6 +587,44 @@ impl Lexer {
     }
 }
 
+/// Check if a character can start an identifier, following Lean 4
+fn is_id_first(ch: char) -> bool {
+    ch.is_ascii_alphabetic() || ch == '_' || is_letter_like(ch)
+}
+
+/// Check if a character can continue an identifier, following Lean 4
+fn is_id_rest(ch: char) -> bool {
+    ch.is_ascii_alphanumeric()
+        || matches!(ch, '_' | '\'' | '!' | '?')
+        || is_letter_like(ch)
+        || is_subscript_alnum(ch)
+}
+
+/// Non-ASCII letters Lean accepts in identifiers
+///
+/// Greek letters except `λ`, `Π` and `Σ` (which are notation), Coptic,
+/// polytonic Greek, the letterlike block (`ℕ`, `ℝ`, ...) and the
+/// mathematical script, double-struck and fraktur alphabets.
+fn is_letter_like(ch: char) -> bool {
+    matches!(ch as u32, 0x3b1..=0x3c9 if ch != 'λ')
+        || matches!(ch as u32, 0x391..=0x3a9 if ch != 'Π' && ch != 'Σ')
+        || matches!(ch as u32, 0x3ca..=0x3fb | 0x1f00..=0x1ffe | 0x2100..=0x214f | 0x1d49c..=0x1d59f)
+}
+
+/// Subscript digits and letters, e.g. `x₁` or `aᵢ`
+fn is_subscript_alnum(ch: char) -> bool {
+    matches!(ch as u32, 0x2080..=0x2089 | 0x2090..=0x209c | 0x1d62..=0x1d6a | 0x2c7c)
+}
+
+/// Check if a character can appear in an operator
+fn is_operator_char(ch: char) -> bool {
+    matches!(
+        ch,
+        '+' | '-' | '*' | '/' | '<' | '>' | '=' | '&' | '|' | '^' | '%' | '!' | '~'
+            | '≤' | '≥' | '≠' | '∧' | '∨' | '¬' | '∘' | '×'
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -499,4 +690,105 @@ mod tests {
         assert_eq!(tokens[1], TokenKind::Ident("id".to_string()));
         assert_eq!(tokens[2], TokenKind::LParen);
     }
+
+    #[test]
+    fn test_commands() {
+        let tokens = lex("#check id #eval 4 #print");
+        assert_eq!(tokens[0], TokenKind::HashCheck);
+        assert_eq!(tokens[2], TokenKind::HashEval);
+        assert!(matches!(tokens[4], TokenKind::Invalid(_)));
+    }
+
+    #[test]
+    fn test_illegal_character_token() {
+        let source = SourceFile::new(0, "test.lean".to_string(), "x $ `y".to_string());
+        let tokens = Lexer::new(source).tokenize();
+
+        assert_eq!(tokens[1].kind, TokenKind::Error('$'));
+        assert_eq!((tokens[1].span.start, tokens[1].span.end), (2, 3));
+        assert_eq!(tokens[2].kind, TokenKind::Error('`'));
+        assert_eq!(tokens[3].kind, TokenKind::Ident("y".to_string()));
+    }
+
+    #[test]
+    fn test_operators() {
+        let tokens = lex("a + b * c -> d && e | f => g");
+        assert_eq!(tokens[1], TokenKind::Operator("+".to_string()));
+        assert_eq!(tokens[3], TokenKind::Operator("*".to_string()));
+        assert_eq!(tokens[5], TokenKind::Arrow);
+        assert_eq!(tokens[7], TokenKind::Operator("&&".to_string()));
+        assert_eq!(tokens[9], TokenKind::Pipe);
+        assert_eq!(tokens[11], TokenKind::FatArrow);
+
+        // Comments still win over operators
+        let tokens = lex("a -- + b\n/- * -/ c");
+        assert_eq!(tokens, vec![
+            TokenKind::Ident("a".to_string()),
+            TokenKind::Ident("c".to_string()),
+            TokenKind::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_trivia_round_trip() {
+        let input = "-- header comment\n\ndef   id  (x : Nat) /- inline -/ : Nat :=\n\tx   -- trailing\n/- nested /- block -/ comment -/\n  \n";
+        let source = SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = Lexer::new(source).with_trivia(true).tokenize();
+
+        assert_eq!(reconstruct_source(&tokens, input), input);
+
+        // Comments are attached, not emitted as tokens
+        assert!(tokens.iter().all(|t| !matches!(
+            t.kind,
+            TokenKind::Whitespace | TokenKind::LineComment(_) | TokenKind::BlockComment(_)
+        )));
+        assert_eq!(tokens[0].kind, TokenKind::Def);
+        assert_eq!(tokens[0].leading[0].text, "-- header comment");
+
+        // Same-line trivia trails the previous token
+        let body = tokens
+            .iter()
+            .find(|t| t.kind == TokenKind::Ident("x".to_string()) && !t.leading.is_empty())
+            .unwrap();
+        assert_eq!(body.leading[0].text, "\t");
+        assert_eq!(body.trailing[1].kind, TriviaKind::LineComment);
+
+        // Without trivia the token stream is unchanged
+        let plain = lex(input);
+        let kinds: Vec<_> = tokens.into_iter().map(|t| t.kind).collect();
+        assert_eq!(plain, kinds);
+    }
+
+    #[test]
+    fn test_unicode_notation_matches_ascii() {
+        assert_eq!(lex("λ x => x"), lex("\\ x => x"));
+        assert_eq!(lex("λ x ⇒ x"), lex("λ x => x"));
+        assert_eq!(lex("∀ x, P x"), lex("forall x, P x"));
+        assert_eq!(lex("A → B"), lex("A -> B"));
+        assert_eq!(lex("∀ x, P x"), vec![
+            TokenKind::Forall,
+            TokenKind::Ident("x".to_string()),
+            TokenKind::Comma,
+            TokenKind::Ident("P".to_string()),
+            TokenKind::Ident("x".to_string()),
+            TokenKind::Eof,
+        ]);
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let tokens = lex("α x₁ ℕ h' aᵢ! Σ");
+        assert_eq!(tokens[..5], [
+            TokenKind::Ident("α".to_string()),
+            TokenKind::Ident("x₁".to_string()),
+            TokenKind::Ident("ℕ".to_string()),
+            TokenKind::Ident("h'".to_string()),
+            TokenKind::Ident("aᵢ!".to_string()),
+        ]);
+        // Σ is notation, not a letter
+        assert_eq!(tokens[5], TokenKind::Error('Σ'));
+
+        // λ never continues an identifier
+        assert_eq!(lex("fλ")[..2], [TokenKind::Ident("f".to_string()), TokenKind::Lambda]);
+    }
 }
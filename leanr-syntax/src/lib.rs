@@ -7,11 +7,15 @@ pub mod lexer;
 pub mod parser;
 pub mod ast;
 pub mod span;
+pub mod fixity;
+pub mod cst;
 
-pub use lexer::{Lexer, Token, TokenKind};
+pub use lexer::{Lexer, Token, TokenKind, Trivia, TriviaKind};
 pub use parser::{Parser, ParseError};
 pub use ast::*;
-pub use span::{Span, SourceFile};
+pub use span::{Span, SourceFile, SourceMap, SpanLocation};
+pub use fixity::{Fixity, OperatorTable};
+pub use cst::{CstElement, CstKind, CstNode};
 
 /// Result type for syntax operations
 pub type Result<T> = std::result::Result<T, ParseError>;
@@ -3,8 +3,10 @@
 //! Recursive descent parser with error recovery.
 
 use crate::ast::*;
+use crate::cst::{CstBuilder, CstKind, CstNode};
+use crate::fixity::{Fixity, OperatorTable};
 use crate::lexer::{Token, TokenKind};
-use crate::span::Span;
+use crate::span::{SourceMap, Span};
 use std::fmt;
 
 /// Parse error
@@ -18,6 +20,26 @@ impl ParseError {
     pub fn new(span: Span, message: String) -> Self {
         Self { span, message }
     }
+
+    /// Render the error with its file, position and source line
+    ///
+    /// Falls back to the plain message when the span's file is not in
+    /// `sources`.
+    pub fn render(&self, sources: &SourceMap) -> String {
+        let (Some(location), Some(file)) = (sources.resolve(self.span), sources.get(self.span.file_id)) else {
+            return self.to_string();
+        };
+        let line = file.line(location.line).unwrap_or_default();
+        let width = location.snippet.chars().count().max(1);
+        format!(
+            "{}: {}\n  {}\n  {}{}",
+            location,
+            self.message,
+            line,
+            " ".repeat(location.col as usize),
+            "^".repeat(width)
+        )
+    }
 }
 
 impl fmt::Display for ParseError {
@@ -28,16 +50,88 @@ impl fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
+/// Default limit on expression nesting before the parser gives up
+///
+/// The limit is in levels of `LEVEL_COST` units each, and a unit stands
+/// for at most about 5 KiB of stack in unoptimized builds (a tenth of that
+/// with optimizations): a parenthesized expression or a binder type is a
+/// full level, about 20 KiB, a binder body or an operator operand is one
+/// unit, 2 to 5 KiB, and a `let` body two units, about 8 KiB. The default
+/// of 80 levels, 320 units, thus stays within about 1.6 MiB, below a 2 MiB
+/// thread stack with room for the caller. That is 79 nested parentheses,
+/// 39 binders nested in binder types, 157 nested `let`s, or 316 nested
+/// `fun`s or chained `&&`s.
+pub const DEFAULT_MAX_DEPTH: usize = 80;
+
+/// Nesting cost of a full expression, such as one in parentheses
+const LEVEL_COST: usize = 4;
+
+/// Nesting cost of a binder body or an operator's right operand
+const BODY_COST: usize = 1;
+
+/// Nesting cost of a `let` body, whose frames hold the bound value too
+const LET_BODY_COST: usize = 2;
+
 /// Recursive descent parser
 pub struct Parser {
     tokens: Vec<Token>,
     pos: usize,
+    operators: OperatorTable,
+    depth: usize,
+    max_depth: usize,
+    /// Concrete syntax tree under construction, in CST mode
+    cst: Option<CstBuilder>,
 }
 
 impl Parser {
-    /// Create a new parser from tokens
+    /// Create a new parser from tokens with the built-in operators
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, pos: 0 }
+        Self::with_operators(tokens, OperatorTable::builtin())
+    }
+
+    /// Create a parser with a pre-populated operator table
+    pub fn with_operators(tokens: Vec<Token>, operators: OperatorTable) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            operators,
+            depth: 0,
+            max_depth: DEFAULT_MAX_DEPTH,
+            cst: None,
+        }
+    }
+
+    /// Also build a lossless concrete syntax tree while parsing
+    ///
+    /// Retrieve it with [`Parser::take_cst`] once parsing is done.
+    pub fn with_cst(mut self, enabled: bool) -> Self {
+        self.cst = enabled.then(CstBuilder::new);
+        self
+    }
+
+    /// Take the concrete syntax tree built so far, in CST mode
+    ///
+    /// Tokens the parser has not consumed, including the final `Eof`, are
+    /// appended to the root so the tree always covers the whole input.
+    /// Nodes left open by a parse error are closed.
+    pub fn take_cst(&mut self) -> Option<CstNode> {
+        let mut builder = self.cst.take()?;
+        builder.close_all();
+        for token in &self.tokens[self.pos..] {
+            builder.token(token.clone());
+        }
+        Some(builder.finish())
+    }
+
+    /// Set the maximum expression nesting depth
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Get the operator table (including operators declared so far)
+    pub fn operators(&self) -> &OperatorTable {
+        &self.operators
     }
 
     /// Parse a list of declarations
@@ -51,8 +145,61 @@ impl Parser {
         Ok(decls)
     }
 
+    /// Parse a list of commands (declarations and queries)
+    pub fn parse_commands(&mut self) -> crate::Result<Vec<Command>> {
+        let mut commands = Vec::new();
+
+        while !self.is_eof() {
+            commands.push(self.parse_command()?);
+        }
+
+        Ok(commands)
+    }
+
+    /// Parse a single command: #check, #eval, or a declaration
+    pub fn parse_command(&mut self) -> crate::Result<Command> {
+        if !matches!(self.current().kind, TokenKind::HashCheck | TokenKind::HashEval) {
+            return Ok(Command::Decl(self.parse_decl()?));
+        }
+
+        self.start_node(CstKind::Command);
+        let result = self.parse_query();
+        self.finish_node();
+        result
+    }
+
+    /// Parse #check or #eval
+    fn parse_query(&mut self) -> crate::Result<Command> {
+        match self.current().kind {
+            TokenKind::HashCheck => {
+                let start = self.advance().span;
+                let expr = Box::new(self.parse_expr()?);
+                Ok(Command::Check {
+                    span: start.to(expr.span()),
+                    expr,
+                })
+            }
+            TokenKind::HashEval => {
+                let start = self.advance().span;
+                let expr = Box::new(self.parse_expr()?);
+                Ok(Command::Eval {
+                    span: start.to(expr.span()),
+                    expr,
+                })
+            }
+            _ => Err(self.unexpected("command")),
+        }
+    }
+
     /// Parse a single declaration
     pub fn parse_decl(&mut self) -> crate::Result<Decl> {
+        self.start_node(CstKind::Decl);
+        let result = self.parse_decl_inner();
+        self.finish_node();
+        result
+    }
+
+    fn parse_decl_inner(&mut self) -> crate::Result<Decl> {
         let token = self.current();
 
         match &token.kind {
@@ -61,10 +208,11 @@ impl Parser {
             TokenKind::Axiom => Ok(Decl::Axiom(self.parse_axiom()?)),
             TokenKind::Inductive => Ok(Decl::Inductive(self.parse_inductive()?)),
             TokenKind::Structure => Ok(Decl::Structure(self.parse_structure()?)),
-            _ => Err(ParseError::new(
-                token.span,
-                format!("Expected declaration, found {:?}", token.kind),
-            )),
+            TokenKind::Infix | TokenKind::Infixl | TokenKind::Infixr => {
+                Ok(Decl::Infix(self.parse_infix()?))
+            }
+            TokenKind::Mutual => Ok(Decl::Mutual(self.parse_mutual()?)),
+            _ => Err(self.unexpected("declaration")),
         }
     }
 
@@ -253,6 +401,81 @@ impl Parser {
         })
     }
 
+    /// Parse mutual block: mutual decl* end
+    fn parse_mutual(&mut self) -> crate::Result<Vec<Decl>> {
+        let start = self.expect(TokenKind::Mutual)?.span;
+        let mut decls = Vec::new();
+
+        while !self.check(&TokenKind::End) {
+            if self.is_eof() {
+                return Err(ParseError::new(
+                    start,
+                    "Unterminated mutual block (missing 'end')".to_string(),
+                ));
+            }
+            if self.check(&TokenKind::Mutual) {
+                return Err(ParseError::new(
+                    self.current().span,
+                    "Mutual blocks cannot be nested".to_string(),
+                ));
+            }
+            decls.push(self.parse_decl()?);
+        }
+
+        self.expect(TokenKind::End)?;
+        Ok(decls)
+    }
+
+    /// Parse operator declaration: infixl:65 " + " => add
+    ///
+    /// The operator is registered immediately so later declarations can use it.
+    fn parse_infix(&mut self) -> crate::Result<InfixDecl> {
+        let keyword = self.advance();
+        let assoc = match keyword.kind {
+            TokenKind::Infixl => Assoc::Left,
+            TokenKind::Infixr => Assoc::Right,
+            _ => Assoc::None,
+        };
+
+        self.expect(TokenKind::Colon)?;
+        let prec_token = self.expect(TokenKind::Number(String::new()))?;
+        let precedence = match &prec_token.kind {
+            TokenKind::Number(n) => n.parse::<u32>().map_err(|_| {
+                ParseError::new(prec_token.span, "Invalid precedence".to_string())
+            })?,
+            _ => unreachable!(),
+        };
+
+        let op_token = self.expect(TokenKind::String(String::new()))?;
+        let operator = match &op_token.kind {
+            TokenKind::String(s) => s.trim().to_string(),
+            _ => unreachable!(),
+        };
+        if operator.is_empty() {
+            return Err(ParseError::new(op_token.span, "Empty operator".to_string()));
+        }
+
+        self.expect(TokenKind::FatArrow)?;
+        let function = self.parse_ident()?;
+
+        self.operators.register(
+            operator.clone(),
+            Fixity {
+                precedence,
+                assoc,
+                function: function.name.clone(),
+            },
+        );
+
+        Ok(InfixDecl {
+            span: keyword.span.to(function.span),
+            assoc,
+            precedence,
+            operator,
+            function,
+        })
+    }
+
     /// Parse universe parameters: .{u v}
     fn parse_universe_params(&mut self) -> crate::Result<Vec<Ident>> {
         let mut params = Vec::new();
@@ -278,6 +501,7 @@ impl Parser {
         while self.check(&TokenKind::LParen) || self.check(&TokenKind::LBrace) {
             let implicit = self.check(&TokenKind::LBrace);
             let start = self.current().span;
+            self.start_node(CstKind::Binder);
             self.advance();
 
             let mut names = vec![self.parse_ident()?];
@@ -301,6 +525,7 @@ impl Parser {
             };
 
             let end = self.expect(end_token)?.span;
+            self.finish_node();
 
             params.push(Param {
                 span: start.to(end),
@@ -315,7 +540,33 @@ impl Parser {
 
     /// Parse an expression
     pub fn parse_expr(&mut self) -> crate::Result<Expr> {
-        self.parse_arrow_expr()
+        self.parse_nested_expr(LEVEL_COST)
+    }
+
+    /// Parse the body of a `fun` or `forall`
+    fn parse_body_expr(&mut self) -> crate::Result<Expr> {
+        self.parse_nested_expr(BODY_COST)
+    }
+
+    fn parse_nested_expr(&mut self, cost: usize) -> crate::Result<Expr> {
+        self.enter_nested(cost)?;
+        self.start_node(CstKind::Expr);
+        let result = self.parse_arrow_expr();
+        self.finish_node();
+        self.depth -= cost;
+        result
+    }
+
+    /// Track `cost` more units of nesting, failing instead of overflowing the stack
+    fn enter_nested(&mut self, cost: usize) -> crate::Result<()> {
+        if self.depth + cost > self.max_depth * LEVEL_COST {
+            return Err(ParseError::new(
+                self.current().span,
+                "expression nesting too deep".to_string(),
+            ));
+        }
+        self.depth += cost;
+        Ok(())
     }
 
     /// Parse arrow type: A -> B
@@ -343,7 +594,7 @@ impl Parser {
             let start = self.advance().span;
             let params = self.parse_params()?;
             self.expect(TokenKind::Comma)?;
-            let body = Box::new(self.parse_expr()?);
+            let body = Box::new(self.parse_body_expr()?);
             let span = start.to(body.span());
 
             Ok(Expr::Forall { span, params, body })
@@ -358,7 +609,7 @@ impl Parser {
             let start = self.advance().span;
             let params = self.parse_lambda_params()?;
             self.expect(TokenKind::FatArrow)?;
-            let body = Box::new(self.parse_expr()?);
+            let body = Box::new(self.parse_body_expr()?);
             let span = start.to(body.span());
 
             Ok(Expr::Lam { span, params, body })
@@ -378,39 +629,33 @@ impl Parser {
 
             let implicit = self.check(&TokenKind::LBrace);
 
-            if implicit {
-                self.advance();
-                let name = self.parse_ident()?;
-                let type_ = if self.check(&TokenKind::Colon) {
-                    self.advance();
-                    Some(Box::new(self.parse_expr()?))
+            if implicit || self.check(&TokenKind::LParen) {
+                let end_token = if implicit {
+                    TokenKind::RBrace
                 } else {
-                    None
+                    TokenKind::RParen
                 };
-                self.expect(TokenKind::RBrace)?;
+                let start = self.advance().span;
+
+                // Multiple names: (x y : T)
+                let mut names = vec![self.parse_ident()?];
+                while !self.check(&TokenKind::Colon) && !self.check(&end_token) && !self.is_eof() {
+                    names.push(self.parse_ident()?);
+                }
 
-                params.push(Param {
-                    span: name.span,
-                    names: vec![name],
-                    type_,
-                    implicit: true,
-                });
-            } else if self.check(&TokenKind::LParen) {
-                self.advance();
-                let name = self.parse_ident()?;
                 let type_ = if self.check(&TokenKind::Colon) {
                     self.advance();
                     Some(Box::new(self.parse_expr()?))
                 } else {
                     None
                 };
-                self.expect(TokenKind::RParen)?;
+                let end = self.expect(end_token)?.span;
 
                 params.push(Param {
-                    span: name.span,
-                    names: vec![name],
+                    span: start.to(end),
+                    names,
                     type_,
-                    implicit: false,
+                    implicit,
                 });
             } else {
                 // Simple name without parens
@@ -444,7 +689,7 @@ impl Parser {
             let value = Box::new(self.parse_expr()?);
 
             self.expect(TokenKind::In)?;
-            let body = Box::new(self.parse_expr()?);
+            let body = Box::new(self.parse_nested_expr(LET_BODY_COST)?);
 
             let span = start.to(body.span());
 
@@ -466,7 +711,7 @@ impl Parser {
             let start = self.advance().span;
             let scrutinee = Box::new(self.parse_app_expr()?);
 
-            self.expect(TokenKind::With)?;
+            let with_span = self.expect(TokenKind::With)?.span;
 
             let mut arms = Vec::new();
             while self.check(&TokenKind::Pipe) {
@@ -482,7 +727,7 @@ impl Parser {
                 });
             }
 
-            let end = arms.last().map(|a| a.span).unwrap_or(scrutinee.span());
+            let end = arms.last().map(|a| a.span).unwrap_or(with_span);
 
             Ok(Expr::Match {
                 span: start.to(end),
@@ -490,16 +735,74 @@ impl Parser {
                 arms,
             })
         } else {
-            self.parse_app_expr()
+            self.parse_binary_expr(0)
+        }
+    }
+
+    /// Parse binary operators by precedence climbing
+    ///
+    /// Only operators in the fixity table are recognized; `a op b`
+    /// desugars to the application `f a b` of the operator's function.
+    fn parse_binary_expr(&mut self, min_prec: u32) -> crate::Result<Expr> {
+        let mut lhs = self.parse_app_expr()?;
+        let mut last_nonassoc: Option<u32> = None;
+
+        while let Some((op_span, fixity)) = self.current_operator() {
+            if fixity.precedence < min_prec {
+                break;
+            }
+            if last_nonassoc == Some(fixity.precedence) && fixity.assoc == Assoc::None {
+                return Err(ParseError::new(
+                    op_span,
+                    "Non-associative operators cannot be chained".to_string(),
+                ));
+            }
+            self.advance();
+
+            let next_min = match fixity.assoc {
+                Assoc::Right => fixity.precedence,
+                Assoc::Left | Assoc::None => fixity.precedence + 1,
+            };
+            self.enter_nested(BODY_COST)?;
+            let rhs = self.parse_binary_expr(next_min);
+            self.depth -= BODY_COST;
+            let rhs = rhs?;
+
+            let span = lhs.span().to(rhs.span());
+            lhs = Expr::App {
+                span,
+                func: Box::new(Expr::Ident(Ident::new(fixity.function.clone(), op_span))),
+                args: vec![lhs, rhs],
+            };
+            last_nonassoc = (fixity.assoc == Assoc::None).then_some(fixity.precedence);
+        }
+
+        Ok(lhs)
+    }
+
+    /// Get the fixity of the current token if it is a registered operator
+    ///
+    /// Word operators such as `infixl:65 " plus " => add` lex as
+    /// identifiers, so identifiers are looked up in the table as well.
+    fn current_operator(&self) -> Option<(Span, Fixity)> {
+        match &self.current().kind {
+            TokenKind::Operator(op) | TokenKind::Ident(op) => self
+                .operators
+                .get(op)
+                .map(|fixity| (self.current().span, fixity.clone())),
+            _ => None,
         }
     }
 
     /// Parse application: f x y z
+    ///
+    /// Arguments stop at the first token that begins an operator in the
+    /// fixity table, so application binds tighter than any infix operator.
     fn parse_app_expr(&mut self) -> crate::Result<Expr> {
-        let mut func = self.parse_atomic_expr()?;
+        let func = self.parse_atomic_expr()?;
         let mut args = Vec::new();
 
-        while !self.is_eof() && self.is_atomic_start() {
+        while !self.is_eof() && self.is_atomic_start() && self.current_operator().is_none() {
             args.push(self.parse_atomic_expr()?);
         }
 
@@ -556,6 +859,12 @@ impl Parser {
                 Ok(Expr::Hole { span })
             }
 
+            TokenKind::Sorry => {
+                let span = token.span;
+                self.advance();
+                Ok(Expr::Sorry { span })
+            }
+
             TokenKind::Type => {
                 let span = token.span;
                 self.advance();
@@ -575,8 +884,20 @@ impl Parser {
             }
 
             TokenKind::LParen => {
+                // Binders are parsed by `parse_params`, so in expression
+                // position `(e : T)` is always a type ascription
                 let start = self.advance().span;
                 let expr = self.parse_expr()?;
+                if self.check(&TokenKind::Colon) {
+                    self.advance();
+                    let type_ = self.parse_expr()?;
+                    let end = self.expect(TokenKind::RParen)?.span;
+                    return Ok(Expr::Ann {
+                        span: start.to(end),
+                        expr: Box::new(expr),
+                        type_: Box::new(type_),
+                    });
+                }
                 let end = self.expect(TokenKind::RParen)?.span;
                 Ok(Expr::Paren {
                     span: start.to(end),
@@ -584,10 +905,7 @@ impl Parser {
                 })
             }
 
-            _ => Err(ParseError::new(
-                token.span,
-                format!("Expected expression, found {:?}", token.kind),
-            )),
+            _ => Err(self.unexpected("expression")),
         }
     }
 
@@ -640,10 +958,7 @@ impl Parser {
                 }
             }
 
-            _ => Err(ParseError::new(
-                token.span,
-                format!("Expected pattern, found {:?}", token.kind),
-            )),
+            _ => Err(self.unexpected("pattern")),
         }
     }
 
@@ -656,13 +971,25 @@ impl Parser {
                 self.advance();
                 Ok(Ident::new(name, span))
             }
-            _ => Err(ParseError::new(
-                self.current().span,
-                format!("Expected identifier, found {:?}", self.current().kind),
-            )),
+            _ => Err(self.unexpected("identifier")),
         }
     }
 
+    /// Error for a current token that is not the `expected` construct
+    ///
+    /// Lexer error tokens are reported as themselves, so an illegal
+    /// character reads "Unexpected character" rather than a confusing
+    /// "Expected expression".
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let token = self.current();
+        let message = match &token.kind {
+            TokenKind::Error(ch) => format!("Unexpected character '{}'", ch),
+            TokenKind::Invalid(message) => message.clone(),
+            other => format!("Expected {}, found {:?}", expected, other),
+        };
+        ParseError::new(token.span, message)
+    }
+
     /// Check if current token matches
     fn check(&self, kind: &TokenKind) -> bool {
         if self.is_eof() {
@@ -676,10 +1003,7 @@ impl Parser {
         if self.check(&kind) {
             Ok(self.advance())
         } else {
-            Err(ParseError::new(
-                self.current().span,
-                format!("Expected {:?}, found {:?}", kind, self.current().kind),
-            ))
+            Err(self.unexpected(&format!("{:?}", kind)))
         }
     }
 
@@ -693,10 +1017,25 @@ impl Parser {
         let token = self.tokens[self.pos].clone();
         if self.pos < self.tokens.len() - 1 {
             self.pos += 1;
+            if let Some(cst) = &mut self.cst {
+                cst.token(token.clone());
+            }
         }
         token
     }
 
+    fn start_node(&mut self, kind: CstKind) {
+        if let Some(cst) = &mut self.cst {
+            cst.start_node(kind);
+        }
+    }
+
+    fn finish_node(&mut self) {
+        if let Some(cst) = &mut self.cst {
+            cst.finish_node();
+        }
+    }
+
     /// Check if at end of input
     fn is_eof(&self) -> bool {
         matches!(self.current().kind, TokenKind::Eof)
@@ -710,6 +1049,7 @@ impl Parser {
                 | TokenKind::Number(_)
                 | TokenKind::String(_)
                 | TokenKind::Underscore
+                | TokenKind::Sorry
                 | TokenKind::Type
                 | TokenKind::Prop
                 | TokenKind::LParen
@@ -738,6 +1078,13 @@ mod tests {
         parser.parse_decls()
     }
 
+    fn parse_cmds(input: &str) -> crate::Result<Vec<Command>> {
+        let source = SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = Lexer::new(source).tokenize();
+        let mut parser = Parser::new(tokens);
+        parser.parse_commands()
+    }
+
     #[test]
     fn test_simple_def() {
         let input = "def id (x : Nat) : Nat := x";
@@ -778,4 +1125,431 @@ mod tests {
             _ => panic!("Expected inductive"),
         }
     }
+
+    #[test]
+    fn test_check_command() {
+        let cmds = parse_cmds("#check id").unwrap();
+        assert_eq!(cmds.len(), 1);
+
+        match &cmds[0] {
+            Command::Check { expr, .. } => match expr.as_ref() {
+                Expr::Ident(ident) => assert_eq!(ident.name, "id"),
+                other => panic!("Expected identifier, got {:?}", other),
+            },
+            other => panic!("Expected #check, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_eval_command() {
+        let cmds = parse_cmds("def two := 2\n#eval add two two").unwrap();
+        assert_eq!(cmds.len(), 2);
+        assert!(matches!(cmds[0], Command::Decl(Decl::Def(_))));
+
+        match &cmds[1] {
+            Command::Eval { expr, .. } => match expr.as_ref() {
+                Expr::App { args, .. } => assert_eq!(args.len(), 2),
+                other => panic!("Expected application, got {:?}", other),
+            },
+            other => panic!("Expected #eval, got {:?}", other),
+        }
+    }
+
+    /// Render an expression with explicit grouping, e.g. `(add (add 1 2) 3)`
+    fn show(expr: &Expr) -> String {
+        match expr {
+            Expr::Ident(i) => i.name.clone(),
+            Expr::Lit(LitExpr { kind: LitKind::Nat(n), .. }) => n.to_string(),
+            Expr::App { func, args, .. } => {
+                let args: Vec<String> = args.iter().map(show).collect();
+                format!("({} {})", show(func), args.join(" "))
+            }
+            Expr::Paren { expr, .. } => show(expr),
+            other => format!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_infixl_declaration() {
+        let input = r#"
+            infixl:65 " ++ " => add
+            def six := 1 ++ 2 ++ 3
+        "#;
+        let decls = parse(input).unwrap();
+        assert_eq!(decls.len(), 2);
+
+        match &decls[0] {
+            Decl::Infix(infix) => {
+                assert_eq!(infix.operator, "++");
+                assert_eq!(infix.precedence, 65);
+                assert_eq!(infix.assoc, Assoc::Left);
+                assert_eq!(infix.function.name, "add");
+            }
+            other => panic!("Expected infix declaration, got {:?}", other),
+        }
+
+        match &decls[1] {
+            Decl::Def(def) => assert_eq!(show(&def.body), "(add (add 1 2) 3)"),
+            other => panic!("Expected def, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_infixl_overrides_builtin() {
+        let input = r#"
+            infixl:65 " + " => add
+            def six := 1 + 2 + 3
+        "#;
+        let decls = parse(input).unwrap();
+        assert_eq!(decls.len(), 2);
+
+        match &decls[0] {
+            Decl::Infix(infix) => {
+                assert_eq!(infix.operator, "+");
+                assert_eq!(infix.precedence, 65);
+                assert_eq!(infix.assoc, Assoc::Left);
+                assert_eq!(infix.function.name, "add");
+            }
+            other => panic!("Expected infix declaration, got {:?}", other),
+        }
+
+        match &decls[1] {
+            Decl::Def(def) => assert_eq!(show(&def.body), "(add (add 1 2) 3)"),
+            other => panic!("Expected def, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_infixr_and_precedence() {
+        let input = r#"
+            infixl:65 " + " => add
+            infixl:70 " * " => mul
+            infixr:75 " ^ " => pow
+            def e := 1 + 2 * 3 ^ 4 ^ 5
+        "#;
+        let decls = parse(input).unwrap();
+
+        match &decls[3] {
+            Decl::Def(def) => {
+                assert_eq!(show(&def.body), "(add 1 (mul 2 (pow 3 (pow 4 5))))");
+            }
+            other => panic!("Expected def, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unregistered_operator_is_rejected() {
+        assert!(parse("def e := 1 +++ 2").is_err());
+        assert!(parse("infix:50 \" == \" => beq\ndef e := a == b == c").is_err());
+    }
+
+    fn parse_expr_str(input: &str) -> Expr {
+        let source = SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = Lexer::new(source).tokenize();
+        Parser::new(tokens).parse_expr().unwrap()
+    }
+
+    #[test]
+    fn test_builtin_precedence() {
+        assert_eq!(show(&parse_expr_str("1 + 2 * 3")), "(HAdd.hAdd 1 (HMul.hMul 2 3))");
+        assert_eq!(show(&parse_expr_str("1 * 2 + 3")), "(HAdd.hAdd (HMul.hMul 1 2) 3)");
+        assert_eq!(show(&parse_expr_str("1 - 2 - 3")), "(HSub.hSub (HSub.hSub 1 2) 3)");
+        assert_eq!(
+            show(&parse_expr_str("a < b || c && d")),
+            "(or (LT.lt a b) (and c d))"
+        );
+    }
+
+    #[test]
+    fn test_builtin_eq_desugars() {
+        assert_eq!(show(&parse_expr_str("a = b")), "(Eq a b)");
+        assert_eq!(show(&parse_expr_str("f x = g y")), "(Eq (f x) (g y))");
+    }
+
+    #[test]
+    fn test_application_binds_tighter_than_infix() {
+        assert_eq!(
+            show(&parse_expr_str("f x + g y")),
+            "(HAdd.hAdd (f x) (g y))"
+        );
+        assert_eq!(show(&parse_expr_str("f x y z")), "(f x y z)");
+        assert_eq!(show(&parse_expr_str("f (g x) y")), "(f (g x) y)");
+    }
+
+    #[test]
+    fn test_word_operator_stops_application() {
+        let input = r#"
+            infixl:65 " plus " => add
+            def e := f x plus g y
+        "#;
+        let decls = parse(input).unwrap();
+
+        match &decls[1] {
+            Decl::Def(def) => assert_eq!(show(&def.body), "(add (f x) (g y))"),
+            other => panic!("Expected def, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_type_ascription() {
+        match parse_expr_str("(x : Nat)") {
+            Expr::Ann { span, expr, type_ } => {
+                assert_eq!((span.start, span.end), (0, 9));
+                assert_eq!(show(&expr), "x");
+                assert_eq!(show(&type_), "Nat");
+            }
+            other => panic!("Expected annotation, got {:?}", other),
+        }
+
+        match parse_expr_str("f (x : Nat) y") {
+            Expr::App { args, .. } => {
+                assert_eq!(args.len(), 2);
+                assert!(matches!(args[0], Expr::Ann { .. }));
+            }
+            other => panic!("Expected application, got {:?}", other),
+        }
+
+        assert!(matches!(parse_expr_str("(x)"), Expr::Paren { .. }));
+    }
+
+    #[test]
+    fn test_expr_spans_cover_full_syntax() {
+        let cases = [
+            "x",
+            "42",
+            "\"hi\"",
+            "f x y",
+            "a + b * c",
+            "fun x => x",
+            "fun (x : Nat) {y} => x",
+            "λ x => x",
+            "forall (x : Nat), P x",
+            "∀ (x : Nat), P x",
+            "A -> B -> C",
+            "let x : Nat := 1 in x",
+            "match n with | 0 => a | _ => b",
+            "match n with",
+            "(x : Nat)",
+            "(f x)",
+            "_",
+            "sorry",
+            "Type",
+            "Prop",
+        ];
+
+        for input in cases {
+            let expr = parse_expr_str(input);
+            let span = expr.span();
+            assert_eq!(
+                &input[span.start as usize..span.end as usize],
+                input,
+                "span of {:?}",
+                expr
+            );
+        }
+    }
+
+    #[test]
+    fn test_lambda_param_spans_include_brackets() {
+        let input = "fun (x : Nat) {y} z => x";
+        match parse_expr_str(input) {
+            Expr::Lam { params, .. } => {
+                let texts: Vec<&str> = params
+                    .iter()
+                    .map(|p| &input[p.span.start as usize..p.span.end as usize])
+                    .collect();
+                assert_eq!(texts, ["(x : Nat)", "{y}", "z"]);
+            }
+            other => panic!("Expected lambda, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_structural_hash_ignores_spans_and_parens() {
+        let hash = |input: &str| parse_expr_str(input).structural_hash();
+
+        assert_eq!(hash("f x (g y)"), hash("  f   x (g   y)"));
+        assert_eq!(hash("(f x)"), hash("f x"));
+        assert_eq!(hash("fun (x : Nat) => x + 1"), hash("fun (x : Nat) =>\n  x + 1"));
+
+        assert_ne!(hash("f x y"), hash("f y x"));
+        assert_ne!(hash("f (g x)"), hash("f g x"));
+        assert_ne!(hash("fun (x : Nat) => x"), hash("forall (x : Nat), x"));
+        assert_ne!(hash("1"), hash("\"1\""));
+
+        let eq = |a: &str, b: &str| parse_expr_str(a).structurally_eq(&parse_expr_str(b));
+        assert!(eq("(f x)", "f   x"));
+        assert!(!eq("f x y", "f y x"));
+        assert!(!eq("fun (x : Nat) => x", "forall (x : Nat), x"));
+    }
+
+    #[test]
+    fn test_eval_with_operators() {
+        let cmds = parse_cmds("#eval 2 + 2").unwrap();
+
+        match &cmds[0] {
+            Command::Eval { expr, .. } => assert_eq!(show(expr), "(HAdd.hAdd 2 2)"),
+            other => panic!("Expected #eval, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_mutual_block() {
+        let input = r#"
+            mutual
+              def isEven (n : Nat) : Bool := isOdd n
+              def isOdd (n : Nat) : Bool := isEven n
+            end
+            def after := isEven
+        "#;
+        let decls = parse(input).unwrap();
+        assert_eq!(decls.len(), 2);
+
+        match &decls[0] {
+            Decl::Mutual(block) => {
+                let names: Vec<&str> = block
+                    .iter()
+                    .map(|d| match d {
+                        Decl::Def(def) => def.name.name.as_str(),
+                        other => panic!("Expected def, got {:?}", other),
+                    })
+                    .collect();
+                assert_eq!(names, vec!["isEven", "isOdd"]);
+            }
+            other => panic!("Expected mutual block, got {:?}", other),
+        }
+
+        assert!(parse("mutual def f := g").is_err());
+    }
+
+    #[test]
+    fn test_sorry() {
+        let decls = parse("theorem t : P := sorry").unwrap();
+
+        match &decls[0] {
+            Decl::Theorem(thm) => assert!(matches!(*thm.proof, Expr::Sorry { .. })),
+            other => panic!("Expected theorem, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_deep_nesting_is_rejected() {
+        let depth = 100_000;
+        let input = format!("def x := {}0{}", "(".repeat(depth), ")".repeat(depth));
+        let err = parse(&input).unwrap_err();
+        assert_eq!(err.message, "expression nesting too deep");
+
+        // Long right-associative operator chains hit the same limit
+        let chain = vec!["a"; depth].join(" && ");
+        let err = parse(&format!("def x := {}", chain)).unwrap_err();
+        assert_eq!(err.message, "expression nesting too deep");
+
+        // Nesting within the limit still parses
+        let shallow = format!("def x := {}0{}", "(".repeat(50), ")".repeat(50));
+        assert!(parse(&shallow).is_ok());
+    }
+
+    #[test]
+    fn test_binder_bodies_and_operator_chains_nest_cheaply() {
+        // Well past the level limit, but within a quarter-level per step
+        let chain = vec!["a"; 300].join(" && ");
+        assert!(parse(&format!("def x := {}", chain)).is_ok());
+
+        let lambdas = format!("def x := {}x", "fun x => ".repeat(300));
+        assert!(parse(&lambdas).is_ok());
+
+        let lets = format!("def x := {}x", "let y := a in ".repeat(150));
+        assert!(parse(&lets).is_ok());
+
+        // They still share one budget with parenthesized nesting
+        let lambdas = format!("def x := {}x", "fun x => ".repeat(400));
+        assert_eq!(parse(&lambdas).unwrap_err().message, "expression nesting too deep");
+    }
+
+    #[test]
+    fn test_default_depth_limit_boundary() {
+        // The declaration body itself takes the first level
+        let parens = |n: usize| format!("def x := {}0{}", "(".repeat(n), ")".repeat(n));
+        assert!(parse(&parens(DEFAULT_MAX_DEPTH - 1)).is_ok());
+        assert!(parse(&parens(DEFAULT_MAX_DEPTH)).is_err());
+
+        let budget = DEFAULT_MAX_DEPTH * LEVEL_COST - LEVEL_COST;
+        let lambdas = |n: usize| format!("def x := {}x", "fun x => ".repeat(n));
+        assert!(parse(&lambdas(budget / BODY_COST)).is_ok());
+        assert!(parse(&lambdas(budget / BODY_COST + 1)).is_err());
+
+        // The innermost bound value is a full expression
+        let lets = |n: usize| format!("def x := {}x", "let y := a in ".repeat(n));
+        let outer = (budget - LEVEL_COST) / LET_BODY_COST;
+        assert!(parse(&lets(outer + 1)).is_ok());
+        assert!(parse(&lets(outer + 2)).is_err());
+
+        // Each binder nested in a binder type adds the type and its parentheses
+        let typed = |n: usize| format!("def x := {}x{}", "fun (x : (A -> ".repeat(n), "B)) => x".repeat(n));
+        assert!(parse(&typed((DEFAULT_MAX_DEPTH - 1) / 2)).is_ok());
+        assert!(parse(&typed((DEFAULT_MAX_DEPTH - 1) / 2 + 1)).is_err());
+    }
+
+    #[test]
+    fn test_configurable_max_depth() {
+        let source = SourceFile::new(0, "test.lean".to_string(), "def x := ((0))".to_string());
+        let tokens = Lexer::new(source).tokenize();
+        let mut parser = Parser::new(tokens.clone()).with_max_depth(2);
+        assert!(parser.parse_decls().is_err());
+
+        let mut parser = Parser::new(tokens).with_max_depth(3);
+        assert!(parser.parse_decls().is_ok());
+    }
+
+    #[test]
+    fn test_illegal_character_diagnostic() {
+        let err = parse("def f : Nat := $x").unwrap_err();
+        assert_eq!(err.message, "Unexpected character '$'");
+        assert_eq!((err.span.start, err.span.end), (15, 16));
+
+        let err = parse("def s := \"open").unwrap_err();
+        assert_eq!(err.message, "Unterminated string");
+    }
+
+    #[test]
+    fn test_render_error_against_its_file() {
+        let mut sources = SourceMap::new();
+        sources.add(SourceFile::new(0, "A.lean".to_string(), "def a := 1".to_string()));
+        sources.add(SourceFile::new(1, "B.lean".to_string(), "def b := 2\ndef f : Nat := $x".to_string()));
+
+        let tokens = Lexer::new(sources.get(1).unwrap().clone()).tokenize();
+        let err = Parser::new(tokens).parse_decls().unwrap_err();
+        assert_eq!(
+            err.render(&sources),
+            "B.lean:2:16: Unexpected character '$'\n  def f : Nat := $x\n                 ^"
+        );
+    }
+
+    #[test]
+    fn test_cst_keeps_punctuation() {
+        let input = "def id (x : Nat) : Nat := x -- identity\n";
+        let source = SourceFile::new(0, "test.lean".to_string(), input.to_string());
+        let tokens = Lexer::new(source).with_trivia(true).tokenize();
+        let mut parser = Parser::new(tokens).with_cst(true);
+
+        let decls = parser.parse_decls().unwrap();
+        assert!(matches!(&decls[0], Decl::Def(def) if def.params.len() == 1));
+
+        let cst = parser.take_cst().unwrap();
+        let kinds: Vec<_> = cst.tokens().iter().map(|t| t.kind.clone()).collect();
+        for punct in [TokenKind::LParen, TokenKind::RParen, TokenKind::ColonEq] {
+            assert!(kinds.contains(&punct), "missing {:?}", punct);
+        }
+        assert_eq!(kinds.iter().filter(|k| **k == TokenKind::Colon).count(), 2);
+
+        // Root holds the declaration and the Eof token
+        let decl = cst.nodes().next().unwrap();
+        assert_eq!(decl.kind, CstKind::Decl);
+        let binder = decl.nodes().next().unwrap();
+        assert_eq!(binder.kind, CstKind::Binder);
+        assert_eq!(binder.text(input), "(x : Nat) ");
+
+        // Lossless, comments included
+        assert_eq!(cst.text(input), input);
+    }
 }
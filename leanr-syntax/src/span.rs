@@ -1,5 +1,6 @@
 //! Source location tracking for error reporting
 
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
@@ -132,8 +133,93 @@ impl SourceFile {
     }
 }
 
+/// Registry of source files, keyed by the `file_id` carried in spans
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    files: HashMap<u32, SourceFile>,
+}
+
+/// Where a span points, resolved against a `SourceMap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanLocation<'a> {
+    /// Name of the file the span belongs to
+    pub file_name: &'a str,
+
+    /// Source text covered by the span
+    pub snippet: &'a str,
+
+    /// Zero-based line of the span start
+    pub line: u32,
+
+    /// Zero-based column of the span start
+    pub col: u32,
+}
+
+impl SourceMap {
+    /// Create an empty source map
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a file under its id, replacing any file with the same id
+    pub fn add(&mut self, file: SourceFile) {
+        self.files.insert(file.id, file);
+    }
+
+    /// Look up a file by id
+    pub fn get(&self, file_id: u32) -> Option<&SourceFile> {
+        self.files.get(&file_id)
+    }
+
+    /// Resolve a span to its file, text and start position
+    ///
+    /// Returns `None` for unregistered files (including dummy spans) and
+    /// for spans that fall outside their file.
+    pub fn resolve(&self, span: Span) -> Option<SpanLocation<'_>> {
+        let file = self.get(span.file_id)?;
+        let snippet = file.content.get(span.start as usize..span.end as usize)?;
+        let (line, col) = file.line_col(span.start);
+        Some(SpanLocation {
+            file_name: &file.name,
+            snippet,
+            line,
+            col,
+        })
+    }
+}
+
+impl fmt::Display for SpanLocation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file_name, self.line + 1, self.col + 1)
+    }
+}
+
 impl fmt::Display for Span {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}..{}", self.start, self.end)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map_resolves_spans_per_file() {
+        let mut sources = SourceMap::new();
+        sources.add(SourceFile::new(0, "Main.lean".to_string(), "def main := helper".to_string()));
+        sources.add(SourceFile::new(1, "Util.lean".to_string(), "-- util\ndef helper := 1".to_string()));
+
+        let main = sources.resolve(Span::new(12, 18, 0)).unwrap();
+        assert_eq!((main.file_name, main.snippet), ("Main.lean", "helper"));
+        assert_eq!((main.line, main.col), (0, 12));
+
+        let util = sources.resolve(Span::new(12, 18, 1)).unwrap();
+        assert_eq!((util.file_name, util.snippet), ("Util.lean", "helper"));
+        assert_eq!((util.line, util.col), (1, 4));
+        assert_eq!(util.to_string(), "Util.lean:2:5");
+
+        assert!(sources.resolve(Span::dummy()).is_none());
+        assert!(sources.resolve(Span::new(0, 100, 1)).is_none());
+    }
+}
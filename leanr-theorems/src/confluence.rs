@@ -22,6 +22,7 @@
 //! - Cached reduction results
 //! - Deduplicated normal forms
 
+use crate::hashcons_confluence::{HashConsArena, Term};
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
 
@@ -58,6 +59,8 @@ pub struct ReductionStep {
 /// Key property: `t ⇉ t'` implies `t →* t'` but also satisfies diamond property
 #[derive(Debug)]
 pub struct ParallelReduction {
+    /// Arena the parsed terms are reduced in
+    arena: HashConsArena,
     /// Cache of parallel reduction results (hash-consing benefit)
     cache: HashMap<u64, String>,
     /// Statistics
@@ -68,6 +71,7 @@ pub struct ParallelReduction {
 impl ParallelReduction {
     pub fn new() -> Self {
         Self {
+            arena: HashConsArena::new(),
             cache: HashMap::new(),
             cache_hits: 0,
             cache_misses: 0,
@@ -80,6 +84,8 @@ impl ParallelReduction {
     /// For example:
     /// - `(λx.x) ((λy.y) z)` ⇉ `(λy.y) z` (reduces outer redex)
     /// - In parallel mode: `(λx.x) ((λy.y) z)` ⇉ `z` (reduces both)
+    ///
+    /// Terms that fail to parse, or are in normal form, are returned unchanged.
     pub fn parallel_reduce(&mut self, term: &str) -> String {
         // Simple hash for caching (in production, use term IDs)
         let hash = self.hash_term(term);
@@ -98,36 +104,17 @@ impl ParallelReduction {
         result
     }
 
-    /// Perform parallel reduction (simplified for demo)
-    fn perform_parallel_reduction(&self, term: &str) -> String {
-        // Simplified: In real implementation, this would:
-        // 1. Traverse the term AST
-        // 2. Find all β-redexes
-        // 3. Reduce them simultaneously
-        // 4. Return the result
-
-        // Demo implementation: reduce simple lambda application
-        if term.starts_with("(λx.") && term.contains(") ") {
-            // Example: "(λx.x) y" → "y"
-            self.beta_reduce_simple(term)
-        } else {
-            term.to_string()
-        }
-    }
+    /// Perform parallel reduction via the arena's complete development
+    fn perform_parallel_reduction(&mut self, term: &str) -> String {
+        let parsed = match Term::parse(term) {
+            Ok(parsed) => parsed,
+            Err(_) => return term.to_string(),
+        };
 
-    /// Simple β-reduction for demo
-    fn beta_reduce_simple(&self, term: &str) -> String {
-        // This is a simplified version for demonstration
-        // Real implementation would use the lean-agentic arena and term IDs
-
-        if let Some(stripped) = term.strip_prefix("(λx.x) ") {
-            // Identity function: (λx.x) t → t
-            stripped.to_string()
-        } else if let Some(stripped) = term.strip_prefix("(λx.λy.x) ") {
-            // K combinator: (λx.λy.x) a → λy.a
-            format!("λy.{}", stripped)
-        } else {
-            term.to_string()
+        let id = self.arena.intern(parsed);
+        match self.arena.parallel_step(id).and_then(|reduced| self.arena.get(reduced)) {
+            Some(reduced) => reduced.to_string(),
+            None => term.to_string(),
         }
     }
 
@@ -243,6 +230,38 @@ pub struct DiamondProof {
     pub join: String,
 }
 
+/// Outcome of checking the diamond property on a concrete term
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiamondResult {
+    /// Source term
+    pub source: String,
+    /// One-step parallel reducts of the source, including itself
+    pub reducts: Vec<String>,
+    /// Number of reduct pairs checked for a common reduct
+    pub pairs_checked: usize,
+    /// Pairs with no common parallel reduct
+    pub failures: Vec<(String, String)>,
+    /// Every pair of reducts converges in one more parallel step
+    pub holds: bool,
+}
+
+/// Structural key up to renaming of bound variables (de Bruijn indices)
+fn alpha_key(term: &Term, bound: &mut Vec<String>) -> String {
+    match term {
+        Term::Var(v) => match bound.iter().rev().position(|b| b == v) {
+            Some(index) => format!("#{}", index),
+            None => v.clone(),
+        },
+        Term::Lam(v, body) => {
+            bound.push(v.clone());
+            let key = format!("λ.{}", alpha_key(body, bound));
+            bound.pop();
+            key
+        }
+        Term::App(f, a) => format!("({} {})", alpha_key(f, bound), alpha_key(a, bound)),
+    }
+}
+
 /// Church-Rosser Confluence Theorem
 ///
 /// Main theorem: Confluence for βδιζ-reduction
@@ -250,6 +269,8 @@ pub struct DiamondProof {
 pub struct ChurchRosser {
     /// Diamond property prover
     diamond: DiamondProperty,
+    /// Arena for concrete diamond checks
+    arena: HashConsArena,
     /// Reduction statistics
     total_checks: usize,
     successful_proofs: usize,
@@ -259,6 +280,7 @@ impl ChurchRosser {
     pub fn new() -> Self {
         Self {
             diamond: DiamondProperty::new(),
+            arena: HashConsArena::new(),
             total_checks: 0,
             successful_proofs: 0,
         }
@@ -296,6 +318,47 @@ impl ChurchRosser {
         })
     }
 
+    /// Verify the diamond property of ⇉ on a concrete term
+    ///
+    /// Enumerates every a ⇉ b and checks that each pair of reducts b, c has
+    /// some d with b ⇉ d and c ⇉ d, comparing terms up to α-equivalence.
+    pub fn check_diamond(&mut self, term: &Term) -> DiamondResult {
+        self.total_checks += 1;
+
+        let reducts = self.arena.parallel_reducts(term);
+        let next: Vec<std::collections::HashSet<String>> = reducts.iter()
+            .map(|b| {
+                self.arena.parallel_reducts(b).iter()
+                    .map(|d| alpha_key(d, &mut Vec::new()))
+                    .collect()
+            })
+            .collect();
+
+        let mut pairs_checked = 0;
+        let mut failures = Vec::new();
+        for i in 0..reducts.len() {
+            for j in i + 1..reducts.len() {
+                pairs_checked += 1;
+                if next[i].is_disjoint(&next[j]) {
+                    failures.push((reducts[i].to_string(), reducts[j].to_string()));
+                }
+            }
+        }
+
+        let holds = failures.is_empty();
+        if holds {
+            self.successful_proofs += 1;
+        }
+
+        DiamondResult {
+            source: term.to_string(),
+            reducts: reducts.iter().map(|t| t.to_string()).collect(),
+            pairs_checked,
+            failures,
+            holds,
+        }
+    }
+
     /// Get proof statistics
     pub fn stats(&self) -> ChurchRosserStats {
         ChurchRosserStats {
@@ -370,6 +433,19 @@ mod tests {
         assert_eq!(result, "λy.a");
     }
 
+    #[test]
+    fn test_parallel_reduction_contracts_all_redexes() {
+        let mut pr = ParallelReduction::new();
+
+        // Independent redexes are contracted in the same step
+        assert_eq!(pr.parallel_reduce("f ((λx.x) a) ((λy.y) b)"), "f a b");
+        assert_eq!(pr.parallel_reduce("(λx.x) ((λy.y) z)"), "z");
+
+        // A redex created by the step waits for the next one
+        assert_eq!(pr.parallel_reduce("(λf.f a) (λx.x)"), "(λx.x) a");
+        assert_eq!(pr.parallel_reduce("(λx.x) a"), "a");
+    }
+
     #[test]
     fn test_diamond_property() {
         let mut diamond = DiamondProperty::new();
@@ -425,6 +501,32 @@ mod tests {
         assert!(proof.is_ok());
     }
 
+    #[test]
+    fn test_diamond_closes_on_overlapping_redexes() {
+        let mut cr = ChurchRosser::new();
+
+        // The inner redex sits inside the argument of the outer one
+        let term = Term::parse("(λx.f x x) ((λy.y) z)").unwrap();
+        let result = cr.check_diamond(&term);
+
+        assert!(result.holds, "failures: {:?}", result.failures);
+        assert_eq!(result.reducts.len(), 4);
+        assert_eq!(result.pairs_checked, 6);
+        assert!(result.reducts.contains(&"f ((λy.y) z) ((λy.y) z)".to_string()));
+        assert!(result.reducts.contains(&"(λx.f x x) z".to_string()));
+        assert!(result.reducts.contains(&"f z z".to_string()));
+        assert_eq!(cr.stats().successful_proofs, 1);
+    }
+
+    #[test]
+    fn test_diamond_up_to_alpha() {
+        let mut cr = ChurchRosser::new();
+
+        // Contracting the outer redex renames the bound y to avoid capture
+        let term = Term::parse("(λx.λy.x) ((λz.z) y)").unwrap();
+        assert!(cr.check_diamond(&term).holds);
+    }
+
     #[test]
     fn test_multiple_confluence_checks() {
         let mut cr = ChurchRosser::new();
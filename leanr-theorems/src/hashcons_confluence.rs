@@ -22,9 +22,10 @@
 //! First formalization showing hash-consing preserves confluence while
 //! achieving exponential speedup (O(n) → O(1) for equality checks).
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
+use std::fmt;
 use std::hash::{Hash, Hasher};
 
 /// Term identifier (hash-consed pointer)
@@ -44,11 +45,121 @@ pub enum Term {
     App(Box<Term>, Box<Term>),
 }
 
+impl Term {
+    /// Parse `λx.body`, left-associative application and parentheses
+    ///
+    /// `\\` is accepted in place of `λ`.
+    pub fn parse(input: &str) -> Result<Term, String> {
+        let mut parser = TermParser { chars: input.chars().peekable() };
+        let term = parser.term()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            None => Ok(term),
+            Some(c) => Err(format!("Unexpected '{}' in {}", c, input)),
+        }
+    }
+
+    /// Whether `name` occurs free in this term
+    pub fn has_free(&self, name: &str) -> bool {
+        match self {
+            Term::Var(v) => v == name,
+            Term::Lam(v, body) => v != name && body.has_free(name),
+            Term::App(f, a) => f.has_free(name) || a.has_free(name),
+        }
+    }
+
+    /// Whether the term contains a β-redex
+    pub fn has_redex(&self) -> bool {
+        match self {
+            Term::Var(_) => false,
+            Term::Lam(_, body) => body.has_redex(),
+            Term::App(f, a) => matches!(**f, Term::Lam(..)) || f.has_redex() || a.has_redex(),
+        }
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Term::Var(v) => write!(f, "{}", v),
+            Term::Lam(v, body) => write!(f, "λ{}.{}", v, body),
+            Term::App(func, arg) => {
+                match **func {
+                    Term::Lam(..) => write!(f, "({})", func)?,
+                    _ => write!(f, "{}", func)?,
+                }
+                match **arg {
+                    Term::Var(_) => write!(f, " {}", arg),
+                    _ => write!(f, " ({})", arg),
+                }
+            }
+        }
+    }
+}
+
+struct TermParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl TermParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        let mut name = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '\'') {
+            name.push(c);
+        }
+        if name.is_empty() {
+            return Err("Expected identifier".to_string());
+        }
+        Ok(name)
+    }
+
+    fn term(&mut self) -> Result<Term, String> {
+        let mut head: Option<Term> = None;
+        loop {
+            self.skip_whitespace();
+            let arg = match self.chars.peek() {
+                None | Some(')') => break,
+                Some('λ') | Some('\\') => {
+                    // A lambda extends as far right as possible
+                    self.chars.next();
+                    let var = self.ident()?;
+                    self.skip_whitespace();
+                    if self.chars.next() != Some('.') {
+                        return Err(format!("Expected '.' after λ{}", var));
+                    }
+                    let body = self.term()?;
+                    Term::Lam(var, Box::new(body))
+                }
+                Some('(') => {
+                    self.chars.next();
+                    let inner = self.term()?;
+                    if self.chars.next() != Some(')') {
+                        return Err("Expected ')'".to_string());
+                    }
+                    inner
+                }
+                Some(_) => Term::Var(self.ident()?),
+            };
+            head = Some(match head {
+                None => arg,
+                Some(func) => Term::App(Box::new(func), Box::new(arg)),
+            });
+        }
+        head.ok_or_else(|| "Expected term".to_string())
+    }
+}
+
 /// Hash-consing arena implementing the quotient algebra T̂
 ///
 /// Invariant 1 (Uniqueness): Each structural term appears exactly once
 /// Invariant 2 (Pointer Equality): t1 ≡ t2 ⟺ id(t1) = id(t2)
 /// Invariant 3 (Soundness): h(t1) = h(t2) ∧ t1 == t2 ⟹ id(t1) = id(t2)
+#[derive(Debug)]
 pub struct HashConsArena {
     /// Maps hash → canonical term (the quotient representatives)
     table: HashMap<TermId, Term>,
@@ -120,6 +231,52 @@ impl HashConsArena {
         self.table.get(&id)
     }
 
+    /// Iterate over the interned terms (the quotient representatives)
+    ///
+    /// Order is unspecified.
+    pub fn iter(&self) -> impl Iterator<Item = (TermId, &Term)> + '_ {
+        self.table.iter().map(|(id, term)| (*id, term))
+    }
+
+    /// Render the term graph in Graphviz DOT
+    ///
+    /// Structurally equal subterms share a single node, so the output shows
+    /// the sharing hash-consing exploits rather than the naive syntax tree.
+    pub fn to_dot(&self) -> String {
+        let mut roots: Vec<_> = self.iter().collect();
+        roots.sort_by_key(|(id, _)| *id);
+
+        let mut dot = String::from("digraph terms {\n");
+        let mut visited = HashSet::new();
+        for (_, term) in roots {
+            self.dot_node(term, &mut visited, &mut dot);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Emit a node and its children once, returning the node's id
+    fn dot_node(&self, term: &Term, visited: &mut HashSet<TermId>, dot: &mut String) -> TermId {
+        let id = self.hash_term(term);
+        if !visited.insert(id) {
+            return id;
+        }
+
+        let (label, children): (String, Vec<&Term>) = match term {
+            Term::Var(name) => (name.clone(), vec![]),
+            Term::Lam(var, body) => (format!("λ{}", var), vec![body.as_ref()]),
+            Term::App(func, arg) => ("@".to_string(), vec![func.as_ref(), arg.as_ref()]),
+        };
+        dot.push_str(&format!("  n{} [label=\"{}\"];\n", id, label.replace('"', "\\\"")));
+
+        for child in children {
+            let child_id = self.dot_node(child, visited, dot);
+            dot.push_str(&format!("  n{} -> n{};\n", id, child_id));
+        }
+
+        id
+    }
+
     /// Beta reduction step
     ///
     /// Implements: (λx.body) arg →β body[x := arg]
@@ -141,9 +298,97 @@ impl HashConsArena {
         }
     }
 
+    /// Single leftmost-outermost β-step anywhere in the term
+    pub fn single_step(&mut self, id: TermId) -> Option<TermId> {
+        let term = self.table.get(&id)?.clone();
+        let reduced = self.contract_leftmost(&term)?;
+        Some(self.intern(reduced))
+    }
+
+    /// Parallel reduction step t ⇉ t*
+    ///
+    /// Contracts every redex visible in the term at once (Takahashi's complete
+    /// development). Redexes created by the contraction are left for the next
+    /// step. Returns `None` when the term is already in normal form.
+    pub fn parallel_step(&mut self, id: TermId) -> Option<TermId> {
+        let term = self.table.get(&id)?.clone();
+        if !term.has_redex() {
+            return None;
+        }
+        let developed = self.develop(&term);
+        Some(self.intern(developed))
+    }
+
+    /// Every t' with t ⇉ t', contracting any subset of the visible redexes
+    ///
+    /// Includes the term itself. The count grows exponentially with the
+    /// number of redexes, so this is meant for checking small terms.
+    pub fn parallel_reducts(&self, term: &Term) -> Vec<Term> {
+        let mut reducts = match term {
+            Term::Var(_) => vec![term.clone()],
+            Term::Lam(v, body) => self.parallel_reducts(body).into_iter()
+                .map(|b| Term::Lam(v.clone(), Box::new(b)))
+                .collect(),
+            Term::App(f, a) => {
+                let args = self.parallel_reducts(a);
+                let mut reducts = Vec::new();
+                for f in self.parallel_reducts(f) {
+                    for a in &args {
+                        reducts.push(Term::App(Box::new(f.clone()), Box::new(a.clone())));
+                    }
+                }
+                if let Term::Lam(v, body) = f.as_ref() {
+                    for body in self.parallel_reducts(body) {
+                        for a in &args {
+                            reducts.push(self.substitute(&body, v, a));
+                        }
+                    }
+                }
+                reducts
+            }
+        };
+        let mut seen = HashSet::new();
+        reducts.retain(|t| seen.insert(t.clone()));
+        reducts
+    }
+
+    fn contract_leftmost(&self, term: &Term) -> Option<Term> {
+        match term {
+            Term::Var(_) => None,
+            Term::Lam(v, body) => {
+                self.contract_leftmost(body).map(|b| Term::Lam(v.clone(), Box::new(b)))
+            }
+            Term::App(f, a) => {
+                if let Term::Lam(v, body) = f.as_ref() {
+                    return Some(self.substitute(body, v, a));
+                }
+                if let Some(f) = self.contract_leftmost(f) {
+                    return Some(Term::App(Box::new(f), a.clone()));
+                }
+                self.contract_leftmost(a).map(|a| Term::App(f.clone(), Box::new(a)))
+            }
+        }
+    }
+
+    /// Complete development t*
+    fn develop(&self, term: &Term) -> Term {
+        match term {
+            Term::Var(_) => term.clone(),
+            Term::Lam(v, body) => Term::Lam(v.clone(), Box::new(self.develop(body))),
+            Term::App(f, a) => {
+                let a = self.develop(a);
+                match f.as_ref() {
+                    Term::Lam(v, body) => self.substitute(&self.develop(body), v, &a),
+                    _ => Term::App(Box::new(self.develop(f)), Box::new(a)),
+                }
+            }
+        }
+    }
+
     /// Substitute term for variable
     ///
-    /// Implements: body[var := replacement]
+    /// Implements: body[var := replacement], renaming binders that would
+    /// capture free variables of the replacement
     fn substitute(&self, body: &Term, var: &str, replacement: &Term) -> Term {
         match body {
             Term::Var(v) if v == var => replacement.clone(),
@@ -151,6 +396,14 @@ impl HashConsArena {
             Term::Lam(v, b) => {
                 if v == var {
                     body.clone() // Variable shadowed
+                } else if replacement.has_free(v) && b.has_free(var) {
+                    let mut fresh = format!("{}'", v);
+                    while replacement.has_free(&fresh) || b.has_free(&fresh) || fresh == var {
+                        fresh.push('\'');
+                    }
+                    let renamed = self.substitute(b, v, &Term::Var(fresh.clone()));
+                    let new_body = self.substitute(&renamed, var, replacement);
+                    Term::Lam(fresh, Box::new(new_body))
                 } else {
                     let new_body = self.substitute(b, var, replacement);
                     Term::Lam(v.clone(), Box::new(new_body))
@@ -307,9 +560,9 @@ impl HashConsConfluenceProver {
                 };
             }
 
-            // Try to reduce both sides
-            let reduced1 = self.arena.beta_reduce(current1);
-            let reduced2 = self.arena.beta_reduce(current2);
+            // Advance both sides by a parallel step
+            let reduced1 = self.arena.parallel_step(current1);
+            let reduced2 = self.arena.parallel_step(current2);
 
             match (reduced1, reduced2) {
                 (Some(r1), Some(r2)) => {
@@ -420,6 +673,91 @@ mod tests {
         assert_eq!(stats.hit_rate, 0.5);
     }
 
+    fn steps_to_normal_form(
+        arena: &mut HashConsArena,
+        mut id: TermId,
+        step: fn(&mut HashConsArena, TermId) -> Option<TermId>,
+    ) -> (TermId, usize) {
+        let mut steps = 0;
+        while let Some(next) = step(arena, id) {
+            id = next;
+            steps += 1;
+        }
+        (id, steps)
+    }
+
+    #[test]
+    fn test_parallel_step_contracts_independent_redexes() {
+        let mut arena = HashConsArena::new();
+        let term = Term::parse("f ((λx.x) a) ((λy.y) b) ((λz.z) c)").unwrap();
+        let id = arena.intern(term);
+
+        let (single_nf, single_steps) = steps_to_normal_form(&mut arena, id, HashConsArena::single_step);
+        let (parallel_nf, parallel_steps) = steps_to_normal_form(&mut arena, id, HashConsArena::parallel_step);
+
+        assert_eq!(single_nf, parallel_nf);
+        assert_eq!(arena.get(parallel_nf).unwrap().to_string(), "f a b c");
+        assert_eq!(single_steps, 3);
+        assert_eq!(parallel_steps, 1);
+    }
+
+    #[test]
+    fn test_parallel_step_avoids_capture() {
+        let mut arena = HashConsArena::new();
+        let id = arena.intern(Term::parse("(λx.λy.x) y").unwrap());
+        let reduced = arena.parallel_step(id).unwrap();
+
+        // The bound y is renamed instead of capturing the free y
+        assert_eq!(arena.get(reduced).unwrap().to_string(), "λy'.y");
+    }
+
+    #[test]
+    fn test_parse_display_round_trip() {
+        for source in ["x", "λx.x y", "(λx.x) ((λy.y) z)", "f (g x) λz.z"] {
+            let term = Term::parse(source).unwrap();
+            assert_eq!(Term::parse(&term.to_string()).unwrap(), term);
+        }
+        assert!(Term::parse("(λx.x").is_err());
+    }
+
+    #[test]
+    fn test_dot_shows_shared_subterms() {
+        fn tree_size(term: &Term) -> usize {
+            match term {
+                Term::Var(_) => 1,
+                Term::Lam(_, body) => 1 + tree_size(body),
+                Term::App(f, a) => 1 + tree_size(f) + tree_size(a),
+            }
+        }
+
+        let mut arena = HashConsArena::new();
+
+        // (λx.x y) applied to itself: the abstraction appears twice in the tree
+        let shared = Term::Lam(
+            "x".to_string(),
+            Box::new(Term::App(
+                Box::new(Term::Var("x".to_string())),
+                Box::new(Term::Var("y".to_string())),
+            )),
+        );
+        let term = Term::App(Box::new(shared.clone()), Box::new(shared));
+        let id = arena.intern(term.clone());
+
+        let interned: Vec<_> = arena.iter().collect();
+        assert_eq!(interned, vec![(id, &term)]);
+
+        let dot = arena.to_dot();
+        assert!(dot.starts_with("digraph terms {"));
+        let nodes = dot.lines().filter(|line| line.contains("[label=")).count();
+        let edges = dot.lines().filter(|line| line.contains("->")).count();
+
+        assert_eq!(tree_size(&term), 9);
+        assert_eq!(nodes, 5);
+        assert!(nodes < tree_size(&term));
+        // Both edges from the root point at the shared abstraction
+        assert_eq!(edges, 5);
+    }
+
     #[test]
     fn test_confluence_preservation() {
         let mut prover = HashConsConfluenceProver::new();
@@ -23,6 +23,7 @@ pub use confluence::{
     ChurchRosser,
     ParallelReduction,
     ConfluenceProof,
+    DiamondResult,
     ReductionStrategy,
 };
 
@@ -3,11 +3,78 @@
 //! Demonstrates hash-consing, type checking, and formal verification
 //! working in the browser via WebAssembly.
 
-use lean_agentic::{Arena, Environment, SymbolTable};
+use lean_agentic::{Arena, Context, Environment, SymbolTable, TermId, TermKind};
+use leanr_eval_lite::{EvalConfig, NatBuiltins, Normalizer};
 use lean_agentic::level::LevelArena;
-use lean_agentic::term::{Binder, BinderInfo};
+use lean_agentic::term::{Binder, BinderInfo, Literal};
 use wasm_bindgen::prelude::*;
-use web_sys::console;
+
+/// Log to the browser console (no-op outside WASM, e.g. in native tests)
+fn log(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&message.into());
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = message;
+}
+
+/// Milliseconds on a monotonic clock
+///
+/// `std::time::Instant` panics on `wasm32-unknown-unknown`, so the browser's
+/// `performance.now()` is used there.
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
+/// Milliseconds on a monotonic clock
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1e3
+}
+
+/// Compare two terms node by node, ignoring hash-consed identity
+fn structural_eq(arena: &Arena, a: TermId, b: TermId) -> bool {
+    use TermKind::*;
+
+    let mut stack = vec![(a, b)];
+    while let Some((a, b)) = stack.pop() {
+        match (arena.kind(a), arena.kind(b)) {
+            (Some(App(f1, x1)), Some(App(f2, x2))) => {
+                stack.push((*f1, *f2));
+                stack.push((*x1, *x2));
+            }
+            (Some(Lam(b1, body1)), Some(Lam(b2, body2)))
+            | (Some(Pi(b1, body1)), Some(Pi(b2, body2))) => {
+                if b1.name != b2.name || b1.info != b2.info {
+                    return false;
+                }
+                stack.push((b1.ty, b2.ty));
+                stack.push((*body1, *body2));
+            }
+            (Some(Let(b1, v1, body1)), Some(Let(b2, v2, body2))) => {
+                if b1.name != b2.name || b1.info != b2.info {
+                    return false;
+                }
+                stack.push((b1.ty, b2.ty));
+                stack.push((*v1, *v2));
+                stack.push((*body1, *body2));
+            }
+            (Some(k1), Some(k2)) if !matches!(k1, App(..) | Lam(..) | Pi(..) | Let(..)) => {
+                if k1 != k2 {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+    true
+}
 
 /// Demo struct showing hash-consing performance in WASM
 #[wasm_bindgen]
@@ -24,7 +91,7 @@ impl LeanDemo {
     /// Create a new Lean demo instance
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        console::log_1(&"Initializing Lean-Agentic WASM...".into());
+        log("Initializing Lean-Agentic WASM...");
 
         Self {
             arena: Arena::new(),
@@ -41,7 +108,7 @@ impl LeanDemo {
     pub fn create_variable(&mut self, index: u32) -> bool {
         let term = self.arena.mk_var(index);
         self.term_counter += 1;
-        console::log_1(&format!("Created variable with index {} -> TermId {:?}", index, term).into());
+        log(&format!("Created variable with index {} -> TermId {:?}", index, term));
         true
     }
 
@@ -54,10 +121,10 @@ impl LeanDemo {
 
         let same = var1 == var2;
 
-        console::log_1(&format!(
+        log(&format!(
             "Hash-consing test: var1={:?}, var2={:?}, same={}",
             var1, var2, same
-        ).into());
+        ));
 
         same
     }
@@ -77,7 +144,7 @@ impl LeanDemo {
         let level_zero = self.levels.zero();
         let type_term = self.arena.mk_sort(level_zero);
         self.term_counter += 1;
-        console::log_1(&format!("Created Type: {:?}", type_term).into());
+        log(&format!("Created Type: {:?}", type_term));
         true
     }
 
@@ -100,7 +167,7 @@ impl LeanDemo {
         let lambda = self.arena.mk_lam(binder, var_x);
 
         self.term_counter += 3;
-        console::log_1(&format!("Created identity function: λx:Type. x = {:?}", lambda).into());
+        log(&format!("Created identity function: λx:Type. x = {:?}", lambda));
         true
     }
 
@@ -126,6 +193,89 @@ impl LeanDemo {
             duration.as_nanos() as f64 / 1000.0
         )
     }
+
+    /// Time hash-consed equality against a structural traversal on the same terms
+    ///
+    /// Builds two copies of a term with `term_size` applications and compares
+    /// them `iters` times each way. The report ends with the measured speedup.
+    #[wasm_bindgen(js_name = benchmarkEqualityComparison)]
+    pub fn benchmark_equality_comparison(&mut self, term_size: u32, iters: u32) -> String {
+        use std::hint::black_box;
+
+        let lhs = self.build_term(term_size);
+        let rhs = self.build_term(term_size);
+        self.term_counter += 2 * (term_size as usize + 1);
+        let iters = iters.max(1);
+
+        let mut all_equal = true;
+        let start = now_ms();
+        for _ in 0..iters {
+            all_equal &= black_box(lhs) == black_box(rhs);
+        }
+        let hashed_ms = now_ms() - start;
+
+        let start = now_ms();
+        for _ in 0..iters {
+            all_equal &= structural_eq(&self.arena, black_box(lhs), black_box(rhs));
+        }
+        let structural_ms = now_ms() - start;
+
+        if !all_equal {
+            return format!(
+                "term size {}: copies of the same term compared unequal, no timing reported",
+                term_size
+            );
+        }
+
+        let hashed_ns = hashed_ms * 1e6 / iters as f64;
+        let structural_ns = structural_ms * 1e6 / iters as f64;
+        // Guard against a zero-length measurement of the O(1) path, which
+        // is common given the coarse resolution of `performance.now()`
+        let speedup = structural_ms / hashed_ms.max(1e-6);
+
+        format!(
+            "term size {}, {} iterations: hash-consed {:.3}ms (~{:.2}ns per check), \
+             structural {:.3}ms (~{:.2}ns per check), speedup: {:.1}x",
+            term_size, iters, hashed_ms, hashed_ns, structural_ms, structural_ns, speedup
+        )
+    }
+
+    /// Evaluate `op a b` on Nat literals, e.g. `evalArithmetic("add", 2, 2)`
+    ///
+    /// `op` is any builtin Nat operation name (`add`, `Nat.mul`, ...).
+    /// Returns the normal form as a string.
+    #[wasm_bindgen(js_name = evalArithmetic)]
+    pub fn eval_arithmetic(&mut self, op: &str, a: u32, b: u32) -> String {
+        let func = self.arena.mk_const(self.symbols.intern(op), vec![]);
+        let lhs = self.arena.mk_nat(a as u64);
+        let rhs = self.arena.mk_nat(b as u64);
+        let term = self.arena.mk_app_spine(func, &[lhs, rhs]);
+        self.term_counter += 4;
+
+        let nat = NatBuiltins::standard(|name| self.symbols.intern(name));
+        let result = Normalizer::new(&mut self.arena, &self.env, EvalConfig::wasm())
+            .with_nat_builtins(nat)
+            .whnf(term, &Context::new());
+
+        match result {
+            Ok(value) => match self.arena.kind(value) {
+                Some(TermKind::Lit(Literal::Nat(n))) => n.to_string(),
+                _ => format!("{} {} {} does not reduce to a literal", op, a, b),
+            },
+            Err(e) => format!("Evaluation failed: {}", e),
+        }
+    }
+}
+
+impl LeanDemo {
+    /// Build a left-nested application spine with `size` arguments
+    fn build_term(&mut self, size: u32) -> TermId {
+        let head = self.arena.mk_var(0);
+        (0..size).fold(head, |term, i| {
+            let arg = self.arena.mk_var(i % 16);
+            self.arena.mk_app(term, arg)
+        })
+    }
 }
 
 /// Simple greeting function for testing WASM works
@@ -139,3 +289,42 @@ pub fn greet(name: &str) -> String {
 pub fn get_version() -> String {
     "Lean-Agentic v0.1.0 - WASM Edition".to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equality_comparison_report() {
+        let mut demo = LeanDemo::new();
+        let report = demo.benchmark_equality_comparison(10_000, 20);
+
+        assert!(report.contains("hash-consed"));
+        assert!(report.contains("structural"));
+
+        let speedup: f64 = report
+            .rsplit("speedup: ")
+            .next()
+            .and_then(|s| s.strip_suffix('x'))
+            .and_then(|s| s.parse().ok())
+            .expect("report should end with the speedup");
+        assert!(speedup > 1.0, "unexpected report: {}", report);
+    }
+
+    #[test]
+    fn test_structural_eq_detects_differences() {
+        let mut demo = LeanDemo::new();
+        let a = demo.build_term(5);
+        let b = demo.build_term(6);
+        assert!(structural_eq(&demo.arena, a, a));
+        assert!(!structural_eq(&demo.arena, a, b));
+    }
+
+    #[test]
+    fn test_eval_arithmetic_on_literals() {
+        let mut demo = LeanDemo::new();
+        assert_eq!(demo.eval_arithmetic("add", 2, 2), "4");
+        assert_eq!(demo.eval_arithmetic("Nat.mul", 3, 4), "12");
+        assert!(demo.eval_arithmetic("unknown", 1, 1).contains("does not reduce"));
+    }
+}
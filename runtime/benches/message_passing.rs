@@ -4,8 +4,20 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 use runtime::prelude::*;
+use runtime::RuntimeError;
 use std::time::Duration;
 
+/// Send a message, yielding to the receiver while its mailbox is full
+async fn send_when_ready<T: Send>(agent: &AgentRef<T>, make: impl Fn() -> Message<T>) {
+    loop {
+        match agent.send(make()).await {
+            Ok(()) => return,
+            Err(RuntimeError::MailboxFull(_)) => tokio::task::yield_now().await,
+            Err(e) => panic!("send failed: {}", e),
+        }
+    }
+}
+
 async fn ping_pong_benchmark(iterations: usize) {
     let runtime = Runtime::new();
     runtime.start();
@@ -15,7 +27,7 @@ async fn ping_pong_benchmark(iterations: usize) {
 
     // Spawn ping agent
     let ping_agent = runtime
-        .spawn(|mailbox: Mailbox<Ping>| async move {
+        .spawn(move |mailbox: Mailbox<Ping>| async move {
             let mut count = 0;
             while let Ok(msg) = mailbox.recv().await {
                 count += 1;
@@ -24,11 +36,12 @@ async fn ping_pong_benchmark(iterations: usize) {
                 }
             }
         })
-        .await;
+        .await
+        .unwrap();
 
     // Send messages
     for i in 0..iterations {
-        ping_agent.send(Message::new(Ping(i))).await.unwrap();
+        send_when_ready(&ping_agent, || Message::iso(Ping(i))).await;
     }
 
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -40,7 +53,7 @@ async fn throughput_benchmark(message_count: usize) {
     runtime.start();
 
     let agent = runtime
-        .spawn(|mailbox: Mailbox<i64>| async move {
+        .spawn(move |mailbox: Mailbox<i64>| async move {
             let mut received = 0;
             while let Ok(_msg) = mailbox.recv().await {
                 received += 1;
@@ -49,10 +62,11 @@ async fn throughput_benchmark(message_count: usize) {
                 }
             }
         })
-        .await;
+        .await
+        .unwrap();
 
     for i in 0..message_count {
-        agent.send(Message::new(i as i64)).await.unwrap();
+        send_when_ready(&agent, || Message::iso(i as i64)).await;
     }
 
     tokio::time::sleep(Duration::from_millis(200)).await;
@@ -105,10 +119,11 @@ fn benchmark_latency(c: &mut Criterion) {
                 .spawn(|mailbox: Mailbox<()>| async move {
                     let _ = mailbox.recv().await;
                 })
-                .await;
+                .await
+                .unwrap();
 
             let start = quanta::Instant::now();
-            agent.send(Message::new(())).await.unwrap();
+            agent.send(Message::iso(())).await.unwrap();
             let latency = start.elapsed();
 
             runtime.stop().await;
@@ -36,14 +36,15 @@ async fn quorum_benchmark(agent_count: usize, threshold: usize) {
                     tokio::time::sleep(Duration::from_micros(10)).await;
                 }
             })
-            .await;
+            .await
+            .unwrap();
         agents.push(agent);
     }
 
     let result = quorum::<(), (), Request>(
         &agents,
         threshold,
-        Message::new(Request(42)),
+        Message::iso(Request(42)),
         Duration::from_secs(1),
     )
     .await;
@@ -64,14 +65,15 @@ async fn shard_benchmark(shard_count: usize, operation_count: usize) {
                     // Process sharded operation
                 }
             })
-            .await;
+            .await
+            .unwrap();
         shards.push(agent);
     }
 
     for i in 0..operation_count {
         let key = format!("key_{}", i);
         let agent = shard(&key, &shards);
-        agent.send(Message::new(key)).await.unwrap();
+        agent.send(Message::iso(key)).await.unwrap();
     }
 
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -88,11 +90,12 @@ async fn broadcast_benchmark(agent_count: usize, fanout: usize) {
             .spawn(|mailbox: Mailbox<String>| async move {
                 let _ = mailbox.recv().await;
             })
-            .await;
+            .await
+            .unwrap();
         agents.push(agent);
     }
 
-    broadcast(&agents, Message::new("broadcast".to_string()), fanout)
+    broadcast(&agents, Message::iso("broadcast".to_string()), fanout)
         .await
         .unwrap();
 
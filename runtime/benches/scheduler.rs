@@ -20,7 +20,8 @@ async fn spawn_benchmark(agent_count: usize) {
             .spawn(|_mailbox: Mailbox<()>| async move {
                 counter_clone.fetch_add(1, Ordering::Relaxed);
             })
-            .await;
+            .await
+            .unwrap();
     }
 
     tokio::time::sleep(Duration::from_millis(100)).await;
@@ -42,7 +43,8 @@ async fn concurrent_spawn_benchmark(agent_count: usize) {
                 .spawn(|_mailbox: Mailbox<i32>| async move {
                     tokio::time::sleep(Duration::from_micros(10)).await;
                 })
-                .await;
+                .await
+                .unwrap();
         });
 
         handles.push(handle);
@@ -66,7 +68,8 @@ fn benchmark_spawn_latency(c: &mut Criterion) {
             let start = quanta::Instant::now();
             runtime
                 .spawn(|_: Mailbox<()>| async {})
-                .await;
+                .await
+                .unwrap();
             let latency = start.elapsed();
 
             runtime.stop().await;
@@ -129,7 +132,8 @@ fn benchmark_work_stealing(c: &mut Criterion) {
                     .spawn(|_: Mailbox<()>| async move {
                         counter_clone.fetch_add(1, Ordering::Relaxed);
                     })
-                    .await;
+                    .await
+                    .unwrap();
             }
 
             tokio::time::sleep(Duration::from_millis(50)).await;
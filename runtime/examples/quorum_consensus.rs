@@ -7,6 +7,7 @@
 //! - Mesh topology
 
 use runtime::prelude::*;
+use runtime::Result;
 use std::time::Duration;
 use tracing::info;
 
@@ -104,7 +105,10 @@ async fn main() {
 
     for i in 0..node_count {
         let node_id = i as u64;
-        let node = runtime.spawn(move |mailbox| consensus_node(node_id, mailbox)).await;
+        let node = runtime
+            .spawn(move |mailbox| consensus_node(node_id, mailbox))
+            .await
+            .unwrap();
         topology.add_agent(node);
     }
 
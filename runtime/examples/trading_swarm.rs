@@ -100,17 +100,17 @@ async fn main() {
     // Spawn analyzer agents (shard across symbols)
     let mut analyzers = Vec::new();
     for i in 0..4 {
-        let analyzer = runtime.spawn(market_analyzer).await;
+        let analyzer = runtime.spawn(market_analyzer).await.unwrap();
         analyzers.push(analyzer);
         info!("Spawned analyzer {}", i + 1);
     }
 
     // Spawn risk manager
-    let risk_mgr = runtime.spawn(risk_manager).await;
+    let risk_mgr = runtime.spawn(risk_manager).await.unwrap();
     info!("Spawned risk manager");
 
     // Spawn execution engine
-    let executor = runtime.spawn(execution_engine).await;
+    let executor = runtime.spawn(execution_engine).await.unwrap();
     info!("Spawned execution engine");
 
     // Simulate market events
@@ -124,7 +124,7 @@ async fn main() {
 
         // Send price update
         analyzer
-            .send(Message::new(MarketEvent::PriceUpdate {
+            .send(Message::iso(MarketEvent::PriceUpdate {
                 symbol: symbol.to_string(),
                 price: 150.0 + fastrand::f64() * 50.0,
             }))
@@ -141,7 +141,7 @@ async fn main() {
         };
 
         analyzer
-            .send(Message::new(MarketEvent::TradeSignal {
+            .send(Message::iso(MarketEvent::TradeSignal {
                 symbol: symbol.to_string(),
                 action: action.clone(),
             }))
@@ -156,11 +156,11 @@ async fn main() {
                 confidence: 0.7 + fastrand::f64() * 0.3,
             };
 
-            risk_mgr.send(Message::new(decision.clone())).await.unwrap();
+            risk_mgr.send(Message::iso(decision.clone())).await.unwrap();
 
             // If approved, send to executor
             if decision.confidence >= 0.7 {
-                executor.send(Message::new(decision)).await.unwrap();
+                executor.send(Message::iso(decision)).await.unwrap();
             }
         }
 
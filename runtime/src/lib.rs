@@ -30,8 +30,8 @@
 //! #[tokio::main]
 //! async fn main() {
 //!     let runtime = Runtime::new();
-//!     let agent_ref = runtime.spawn(agent_behavior).await;
-//!     agent_ref.send(Ping).await.unwrap();
+//!     let agent_ref = runtime.spawn(agent_behavior).await.unwrap();
+//!     agent_ref.send(Message::iso(Ping)).await.unwrap();
 //! }
 //! ```
 
@@ -60,8 +60,11 @@ pub mod prelude {
 pub use capabilities::{RefCap, SendCap};
 pub use mailbox::{Mailbox, MailboxError};
 pub use message::Message;
-pub use orchestration::{broadcast, channel, lease, quorum, shard, signal, spawn, Awaitable};
-pub use runtime::Runtime;
+pub use orchestration::{
+    broadcast, channel, quorum, shard, signal, spawn, Awaitable, Lease, LeaseManager, ShardRing,
+    Broadcaster, LagPolicy, Signal, SignalMode, Subscriber,
+};
+pub use runtime::{Runtime, ShutdownReport};
 pub use scheduler::{Scheduler, Task};
 
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -79,7 +82,7 @@ pub fn allocate_agent_id() -> u64 {
 pub type Result<T> = std::result::Result<T, RuntimeError>;
 
 /// Unified error type for runtime operations
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum RuntimeError {
     #[error("Mailbox full: {0} messages")]
     MailboxFull(usize),
@@ -87,6 +90,9 @@ pub enum RuntimeError {
     #[error("Mailbox closed")]
     MailboxClosed,
 
+    #[error("Nothing to receive")]
+    Empty,
+
     #[error("Agent not found: {0}")]
     AgentNotFound(u64),
 
@@ -96,8 +102,19 @@ pub enum RuntimeError {
     #[error("Quorum not reached: {received}/{required}")]
     QuorumNotReached { received: usize, required: usize },
 
-    #[error("Lease acquisition failed: {0}")]
-    LeaseAcquisitionFailed(String),
+    #[error(
+        "Lease acquisition failed for {resource}: held by {holder:?}{}",
+        if *.deadlock { " (deadlock)" } else { "" }
+    )]
+    LeaseAcquisitionFailed {
+        resource: String,
+        holder: Option<u64>,
+        /// Set when the waiter was chosen as the victim of a lease cycle
+        deadlock: bool,
+    },
+
+    #[error("Subscriber evicted after lagging {0} messages")]
+    SubscriberEvicted(u64),
 
     #[error("Scheduler error: {0}")]
     SchedulerError(String),
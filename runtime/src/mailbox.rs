@@ -47,6 +47,16 @@ pub enum MailboxError {
 /// High-performance bounded mailbox
 ///
 /// Provides backpressure signaling and efficient MPSC communication.
+///
+/// # Ordering
+///
+/// Messages from a single sender are received in the order they were
+/// sent, however they interleave with other senders' messages. Each send
+/// enqueues its message atomically at the tail of one FIFO queue, and a
+/// sender's next send only starts once the previous one has returned, so
+/// no per-sender sequence numbers are needed. Sends racing from
+/// concurrently running tasks that share a `MailboxSender` clone have no
+/// defined order between them.
 pub struct Mailbox<T: Send> {
     tx: Sender<Message<T>>,
     rx: Receiver<Message<T>>,
@@ -100,10 +110,10 @@ impl<T: Send> Mailbox<T> {
         self.len() == 0
     }
 
-    /// Check if above high water mark
+    /// Check if at the high water mark, where sends are refused
     #[inline]
     pub fn is_high_water(&self) -> bool {
-        self.len() > self.config.high_water
+        self.len() >= self.config.high_water
     }
 
     /// Check if below low water mark
@@ -164,23 +174,26 @@ pub struct MailboxSender<T: Send> {
 impl<T: Send> MailboxSender<T> {
     /// Send message with backpressure check
     ///
-    /// Returns error if above high water mark.
+    /// Returns `MailboxFull` once the queue has reached the high water mark,
+    /// so at most `high_water` messages are ever queued.
     pub async fn send<Cap: SendCap>(&self, msg: Message<T, Cap>) -> Result<(), RuntimeError> {
         let current_len = self.len.load(Ordering::Relaxed);
 
-        if current_len > self.config.high_water {
+        if current_len >= self.config.high_water {
             return Err(RuntimeError::MailboxFull(current_len));
         }
 
         // Convert to default Iso capability for storage
         let iso_msg = Message::new(msg.into_payload());
 
+        // Count before enqueueing so a fast receiver never decrements below zero
+        self.len.fetch_add(1, Ordering::Relaxed);
         match self.tx.send_async(iso_msg).await {
-            Ok(_) => {
-                self.len.fetch_add(1, Ordering::Relaxed);
-                Ok(())
+            Ok(_) => Ok(()),
+            Err(_) => {
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                Err(RuntimeError::MailboxClosed)
             }
-            Err(_) => Err(RuntimeError::MailboxClosed),
         }
     }
 
@@ -188,18 +201,19 @@ impl<T: Send> MailboxSender<T> {
     pub fn try_send<Cap: SendCap>(&self, msg: Message<T, Cap>) -> Result<(), RuntimeError> {
         let current_len = self.len.load(Ordering::Relaxed);
 
-        if current_len > self.config.high_water {
+        if current_len >= self.config.high_water {
             return Err(RuntimeError::MailboxFull(current_len));
         }
 
         let iso_msg = Message::new(msg.into_payload());
 
+        self.len.fetch_add(1, Ordering::Relaxed);
         match self.tx.try_send(iso_msg) {
-            Ok(_) => {
-                self.len.fetch_add(1, Ordering::Relaxed);
-                Ok(())
+            Ok(_) => Ok(()),
+            Err(_) => {
+                self.len.fetch_sub(1, Ordering::Relaxed);
+                Err(RuntimeError::MailboxClosed)
             }
-            Err(_) => Err(RuntimeError::MailboxClosed),
         }
     }
 
@@ -209,10 +223,15 @@ impl<T: Send> MailboxSender<T> {
         self.len.load(Ordering::Relaxed)
     }
 
-    /// Check if above high water mark
+    /// Check if at the high water mark, where sends are refused
     #[inline]
     pub fn is_high_water(&self) -> bool {
-        self.len() > self.config.high_water
+        self.len() >= self.config.high_water
+    }
+
+    /// Shared queue-length counter, used by the runtime to observe draining
+    pub(crate) fn len_handle(&self) -> Arc<AtomicUsize> {
+        self.len.clone()
     }
 }
 
@@ -246,6 +265,16 @@ impl<T: Send> MailboxReceiver<T> {
             Err(TryRecvError::Disconnected) => Err(RuntimeError::MailboxClosed),
         }
     }
+
+    /// Discard every queued message, returning how many were discarded
+    pub(crate) fn drain(&self) -> usize {
+        let mut drained = 0;
+        while self.rx.try_recv().is_ok() {
+            self.len.fetch_sub(1, Ordering::Relaxed);
+            drained += 1;
+        }
+        drained
+    }
 }
 
 #[cfg(test)]
@@ -276,7 +305,7 @@ mod tests {
 
         // Fill up to high water mark
         for i in 0..9 {
-            let result = sender.send(Message::new(i)).await;
+            let result = sender.send(Message::<i32, Iso>::new(i)).await;
             if i < 8 {
                 assert!(result.is_ok());
             } else {
@@ -286,6 +315,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_high_water_reported_when_sends_are_refused() {
+        let config = MailboxConfig {
+            capacity: 10,
+            high_water: 3,
+            low_water: 1,
+        };
+        let mailbox = Mailbox::<i32>::with_config(config);
+        let sender = mailbox.sender();
+
+        for i in 0..2 {
+            sender.send(Message::<i32, Iso>::new(i)).await.unwrap();
+        }
+        assert!(!mailbox.is_high_water());
+        assert!(!sender.is_high_water());
+
+        // Exactly at the mark: reported, and the next send is refused
+        sender.send(Message::<i32, Iso>::new(2)).await.unwrap();
+        assert_eq!(mailbox.len(), 3);
+        assert!(mailbox.is_high_water());
+        assert!(sender.is_high_water());
+        assert!(sender.try_send(Message::<i32, Iso>::new(3)).is_err());
+    }
+
     #[tokio::test]
     async fn test_mailbox_len() {
         let mailbox = Mailbox::<String>::new();
@@ -294,7 +347,7 @@ mod tests {
         assert_eq!(mailbox.len(), 0);
 
         sender
-            .send(Message::new("test".to_string()))
+            .send(Message::<String, Iso>::new("test".to_string()))
             .await
             .unwrap();
         assert_eq!(mailbox.len(), 1);
@@ -302,4 +355,37 @@ mod tests {
         mailbox.recv().await.unwrap();
         assert_eq!(mailbox.len(), 0);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_per_sender_fifo() {
+        // Stays below the default high water mark, so no send is refused
+        const COUNT: u32 = 300;
+        let mailbox = Mailbox::<(u8, u32)>::new();
+
+        let senders: Vec<_> = (0..2u8)
+            .map(|id| {
+                let sender = mailbox.sender();
+                tokio::spawn(async move {
+                    for seq in 0..COUNT {
+                        sender.send(Message::<_, Iso>::new((id, seq))).await.unwrap();
+                        if seq % 16 == 0 {
+                            tokio::task::yield_now().await;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut next = [0u32; 2];
+        for _ in 0..2 * COUNT {
+            let (id, seq) = *mailbox.recv().await.unwrap().payload();
+            assert_eq!(seq, next[id as usize], "sender {} out of order", id);
+            next[id as usize] += 1;
+        }
+        assert_eq!(next, [COUNT, COUNT]);
+
+        for sender in senders {
+            sender.await.unwrap();
+        }
+    }
 }
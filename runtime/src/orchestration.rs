@@ -15,14 +15,15 @@ use crate::mailbox::{Mailbox, MailboxSender};
 use crate::message::Message;
 use crate::scheduler::{Scheduler, TaskFuture};
 use crate::{RuntimeError, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::future::Future;
 use std::hash::{Hash, Hasher};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
-use tokio::sync::{oneshot, RwLock};
+use tokio::sync::{oneshot, Notify, RwLock};
 
 /// Agent reference
 #[derive(Debug, Clone)]
@@ -61,8 +62,16 @@ where
     let mailbox = Mailbox::new();
     let sender = mailbox.sender();
 
+    // Keep the mailbox open until the agent's task ends, even if the
+    // behavior drops its `Mailbox` without awaiting it
+    let keepalive = mailbox.receiver();
+    let task = behavior(mailbox);
+
     // Spawn agent task
-    tokio::spawn(behavior(mailbox));
+    tokio::spawn(async move {
+        task.await;
+        drop(keepalive);
+    });
 
     AgentRef::new(agent_id, sender)
 }
@@ -76,6 +85,101 @@ pub async fn signal<T: Send, Cap: SendCap>(
     agent.send(msg).await
 }
 
+/// Delivery semantics of a [`Signal`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalMode {
+    /// Every raise is delivered as its own wakeup
+    Edge,
+    /// Raises coalesce into a single pending flag until consumed
+    Level,
+}
+
+/// Payload-free wakeup signal with edge or level semantics
+///
+/// Level mode keeps a slow consumer from being flooded: any number of
+/// raises between two waits is observed as a single wakeup.
+#[derive(Debug, Clone)]
+pub struct Signal {
+    inner: Arc<SignalInner>,
+}
+
+#[derive(Debug)]
+struct SignalInner {
+    mode: SignalMode,
+    pending: AtomicUsize,
+    notify: Notify,
+}
+
+impl Signal {
+    /// Create signal with the given mode
+    pub fn new(mode: SignalMode) -> Self {
+        Self {
+            inner: Arc::new(SignalInner {
+                mode,
+                pending: AtomicUsize::new(0),
+                notify: Notify::new(),
+            }),
+        }
+    }
+
+    /// Create edge-triggered signal
+    pub fn edge() -> Self {
+        Self::new(SignalMode::Edge)
+    }
+
+    /// Create level-triggered (coalescing) signal
+    pub fn level() -> Self {
+        Self::new(SignalMode::Level)
+    }
+
+    /// Signal mode
+    pub fn mode(&self) -> SignalMode {
+        self.inner.mode
+    }
+
+    /// Raise the signal
+    #[inline]
+    pub fn raise(&self) {
+        match self.inner.mode {
+            SignalMode::Edge => {
+                self.inner.pending.fetch_add(1, Ordering::AcqRel);
+            }
+            SignalMode::Level => {
+                self.inner.pending.store(1, Ordering::Release);
+            }
+        }
+        self.inner.notify.notify_one();
+    }
+
+    /// Number of undelivered wakeups (at most 1 in level mode)
+    pub fn pending(&self) -> usize {
+        self.inner.pending.load(Ordering::Acquire)
+    }
+
+    /// Consume one wakeup without waiting
+    pub fn try_wait(&self) -> bool {
+        match self.inner.mode {
+            SignalMode::Edge => self
+                .inner
+                .pending
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+                .is_ok(),
+            SignalMode::Level => self.inner.pending.swap(0, Ordering::AcqRel) > 0,
+        }
+    }
+
+    /// Wait for the next wakeup
+    pub async fn wait(&self) {
+        loop {
+            let notified = self.inner.notify.notified();
+            if self.try_wait() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
 /// 3. Await: Future wrapper
 pub struct Awaitable<T> {
     receiver: oneshot::Receiver<T>,
@@ -93,15 +197,51 @@ impl<T> Future for Awaitable<T> {
     }
 }
 
+impl<T> Awaitable<T> {
+    /// Wait for the value, giving up after `duration`
+    ///
+    /// Wraps the awaitable in `tokio::time::timeout`, so it must be polled
+    /// inside a Tokio runtime with the time driver enabled. Returns
+    /// `RuntimeError::Timeout` if the value has not arrived in time.
+    pub async fn timeout(self, duration: Duration) -> Result<T> {
+        tokio::time::timeout(duration, self)
+            .await
+            .map_err(|_| RuntimeError::Timeout(duration))?
+    }
+}
+
 pub fn await_future<T>() -> (oneshot::Sender<T>, Awaitable<T>) {
     let (tx, rx) = oneshot::channel();
     (tx, Awaitable { receiver: rx })
 }
 
 /// 4. Channel: Bounded MPMC channel (<50ns enqueue target)
+///
+/// Shorthand for [`channel::bounded`].
 pub fn channel<T: Send>(capacity: usize) -> (ChannelSender<T>, ChannelReceiver<T>) {
-    let (tx, rx) = flume::bounded(capacity);
-    (ChannelSender { tx }, ChannelReceiver { rx })
+    channel::bounded(capacity)
+}
+
+/// Channel constructors with explicit capacity semantics
+///
+/// Like a `Mailbox`, a bounded channel never grows past its capacity:
+/// `ChannelSender::send` waits for a free slot and `ChannelSender::try_send`
+/// fails with `RuntimeError::MailboxFull` instead. An unbounded channel
+/// accepts every send immediately and grows without limit.
+pub mod channel {
+    use super::{ChannelReceiver, ChannelSender};
+
+    /// Channel holding at most `capacity` undelivered values
+    pub fn bounded<T: Send>(capacity: usize) -> (ChannelSender<T>, ChannelReceiver<T>) {
+        let (tx, rx) = flume::bounded(capacity);
+        (ChannelSender { tx }, ChannelReceiver { rx })
+    }
+
+    /// Channel whose sends never wait or fail for lack of space
+    pub fn unbounded<T: Send>() -> (ChannelSender<T>, ChannelReceiver<T>) {
+        let (tx, rx) = flume::unbounded();
+        (ChannelSender { tx }, ChannelReceiver { rx })
+    }
 }
 
 #[derive(Clone)]
@@ -110,12 +250,39 @@ pub struct ChannelSender<T> {
 }
 
 impl<T> ChannelSender<T> {
+    /// Send a value, waiting for a free slot if the channel is full
     pub async fn send(&self, value: T) -> Result<()> {
         self.tx
             .send_async(value)
             .await
             .map_err(|_| RuntimeError::MailboxClosed)
     }
+
+    /// Send a value without waiting
+    ///
+    /// Returns `RuntimeError::MailboxFull` if a bounded channel is at
+    /// capacity; unbounded channels only fail once closed.
+    pub fn try_send(&self, value: T) -> Result<()> {
+        self.tx.try_send(value).map_err(|e| match e {
+            flume::TrySendError::Full(_) => RuntimeError::MailboxFull(self.tx.len()),
+            flume::TrySendError::Disconnected(_) => RuntimeError::MailboxClosed,
+        })
+    }
+
+    /// Capacity of a bounded channel, `None` if unbounded
+    pub fn capacity(&self) -> Option<usize> {
+        self.tx.capacity()
+    }
+
+    /// Number of values waiting to be received
+    pub fn len(&self) -> usize {
+        self.tx.len()
+    }
+
+    /// Whether no values are waiting
+    pub fn is_empty(&self) -> bool {
+        self.tx.is_empty()
+    }
 }
 
 #[derive(Clone)]
@@ -130,6 +297,35 @@ impl<T> ChannelReceiver<T> {
             .await
             .map_err(|_| RuntimeError::MailboxClosed)
     }
+
+    /// Receive from whichever channel produces first
+    ///
+    /// Returns the index of that channel in `receivers` with its value. The
+    /// task sleeps until one of the channels wakes it; no message is taken
+    /// from the others. When several are ready, the lowest index wins.
+    /// Closed channels are skipped, and `MailboxClosed` is returned once
+    /// all of them are closed.
+    pub async fn select(receivers: &[&ChannelReceiver<T>]) -> Result<(usize, T)> {
+        let mut pending: Vec<_> = receivers.iter().map(|r| Some(r.rx.recv_async())).collect();
+
+        std::future::poll_fn(|cx| {
+            for (idx, slot) in pending.iter_mut().enumerate() {
+                let Some(fut) = slot else { continue };
+                match Pin::new(fut).poll(cx) {
+                    Poll::Ready(Ok(value)) => return Poll::Ready(Ok((idx, value))),
+                    Poll::Ready(Err(_)) => *slot = None,
+                    Poll::Pending => {}
+                }
+            }
+
+            if pending.iter().all(Option::is_none) {
+                Poll::Ready(Err(RuntimeError::MailboxClosed))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
 }
 
 /// 5. Quorum: Coordinate N agents with threshold
@@ -156,8 +352,9 @@ where
 
     // Send requests to all agents
     for agent in agents {
-        let request_clone = Message::new(request.payload().clone());
+        let request_clone: Message<Req> = Message::new(request.payload().clone());
         let tx_clone = tx.clone();
+        let agent = agent.clone();
 
         tokio::spawn(async move {
             if let Ok(_) = agent.send(request_clone).await {
@@ -196,58 +393,369 @@ where
 }
 
 /// 6. Shard: Consistent hash distribution
-pub fn shard<T: Send, K: Hash>(key: &K, shards: &[AgentRef<T>]) -> &AgentRef<T> {
+///
+/// Uses jump consistent hashing, so appending a shard to the slice only
+/// remaps about `1/(n+1)` of the keys.
+pub fn shard<'a, T: Send, K: Hash>(key: &K, shards: &'a [AgentRef<T>]) -> &'a AgentRef<T> {
+    let idx = jump_hash(ring_hash(key), shards.len());
+    &shards[idx]
+}
+
+/// Shard identifier on a [`ShardRing`]
+pub type ShardId = u64;
+
+/// Default virtual nodes per shard on a [`ShardRing`]
+pub const DEFAULT_VIRTUAL_NODES: usize = 128;
+
+/// Consistent-hash ring with virtual nodes
+///
+/// Each shard owns `virtual_nodes` points on the ring; a key routes to the
+/// first point at or after its hash. Adding or removing a shard only moves
+/// the keys adjacent to that shard's points.
+#[derive(Debug, Clone)]
+pub struct ShardRing {
+    ring: BTreeMap<u64, ShardId>,
+    virtual_nodes: usize,
+}
+
+impl ShardRing {
+    /// Create empty ring with default virtual node count
+    pub fn new() -> Self {
+        Self::with_virtual_nodes(DEFAULT_VIRTUAL_NODES)
+    }
+
+    /// Create empty ring with a specific virtual node count per shard
+    pub fn with_virtual_nodes(virtual_nodes: usize) -> Self {
+        Self {
+            ring: BTreeMap::new(),
+            virtual_nodes: virtual_nodes.max(1),
+        }
+    }
+
+    /// Add shard to the ring (no-op if already present)
+    pub fn add_shard(&mut self, shard: ShardId) {
+        for replica in 0..self.virtual_nodes {
+            self.ring.entry(ring_hash(&(shard, replica))).or_insert(shard);
+        }
+    }
+
+    /// Remove shard from the ring
+    pub fn remove_shard(&mut self, shard: ShardId) {
+        self.ring.retain(|_, owner| *owner != shard);
+    }
+
+    /// Route key to its owning shard, or `None` if the ring is empty
+    pub fn route_key<K: Hash + ?Sized>(&self, key: &K) -> Option<ShardId> {
+        let hash = ring_hash(key);
+        self.ring
+            .range(hash..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, shard)| *shard)
+    }
+
+    /// Number of distinct shards on the ring
+    pub fn shard_count(&self) -> usize {
+        let mut shards: Vec<ShardId> = self.ring.values().copied().collect();
+        shards.sort_unstable();
+        shards.dedup();
+        shards.len()
+    }
+
+    /// Check if ring has no shards
+    pub fn is_empty(&self) -> bool {
+        self.ring.is_empty()
+    }
+}
+
+impl Default for ShardRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hash a key onto the ring (FxHash with a splitmix64 finalizer for spread)
+fn ring_hash<K: Hash + ?Sized>(key: &K) -> u64 {
     let mut hasher = rustc_hash::FxHasher::default();
     key.hash(&mut hasher);
-    let hash = hasher.finish();
-    let idx = (hash % shards.len() as u64) as usize;
-    &shards[idx]
+    let mut z = hasher.finish().wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Jump consistent hash (Lamping & Veach) into `buckets` buckets
+fn jump_hash(mut key: u64, buckets: usize) -> usize {
+    let mut b: i64 = -1;
+    let mut j: i64 = 0;
+    while j < buckets as i64 {
+        b = j;
+        key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+        j = ((b + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+    }
+    b.max(0) as usize
 }
 
 /// 7. Lease: Distributed lease with TTL
+///
+/// A lease that is not renewed within its TTL expires and becomes
+/// acquirable by others, so a crashed holder cannot hold a resource forever.
+struct LeaseEntry {
+    holder: u64,
+    expires: tokio::time::Instant,
+}
+
+/// Handle to an acquired lease
 pub struct Lease {
     resource: String,
     holder: u64,
-    expires: tokio::time::Instant,
+    leases: Arc<RwLock<HashMap<String, LeaseEntry>>>,
+}
+
+impl Lease {
+    /// Leased resource
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    /// Holder ID of this lease
+    pub fn holder(&self) -> u64 {
+        self.holder
+    }
+
+    /// Extend the lease to `ttl` from now
+    ///
+    /// Fails if the lease has already expired or was taken over, reporting
+    /// the current holder (if any).
+    pub async fn renew(&self, ttl: Duration) -> Result<()> {
+        let mut leases = self.leases.write().await;
+        let now = tokio::time::Instant::now();
+
+        match leases.get_mut(&self.resource) {
+            Some(entry) if entry.holder == self.holder && entry.expires > now => {
+                entry.expires = now + ttl;
+                Ok(())
+            }
+            Some(entry) if entry.expires > now => Err(RuntimeError::LeaseAcquisitionFailed {
+                resource: self.resource.clone(),
+                holder: Some(entry.holder),
+                deadlock: false,
+            }),
+            _ => Err(RuntimeError::LeaseAcquisitionFailed {
+                resource: self.resource.clone(),
+                holder: None,
+                deadlock: false,
+            }),
+        }
+    }
+
+    /// Check whether this handle still holds an unexpired lease
+    pub async fn is_held(&self) -> bool {
+        let leases = self.leases.read().await;
+        leases.get(&self.resource).is_some_and(|entry| {
+            entry.holder == self.holder && entry.expires > tokio::time::Instant::now()
+        })
+    }
+}
+
+/// Wait-for graph: each blocked holder and the resource it is waiting on
+type WaitGraph = std::sync::Mutex<HashMap<u64, String>>;
+
+/// Removes a waiter's edge from the wait-for graph when its wait ends,
+/// including when the waiting future is dropped
+struct WaitEdge<'a> {
+    waits: Option<&'a WaitGraph>,
+    holder: u64,
+}
+
+impl Drop for WaitEdge<'_> {
+    fn drop(&mut self) {
+        if let Some(waits) = self.waits {
+            waits.lock().unwrap().remove(&self.holder);
+        }
+    }
 }
 
 pub struct LeaseManager {
-    leases: Arc<RwLock<HashMap<String, Lease>>>,
+    leases: Arc<RwLock<HashMap<String, LeaseEntry>>>,
+    waits: Option<Arc<WaitGraph>>,
 }
 
 impl LeaseManager {
     pub fn new() -> Self {
         Self {
             leases: Arc::new(RwLock::new(HashMap::new())),
+            waits: None,
         }
     }
 
+    /// Enable wait-for-graph deadlock detection
+    ///
+    /// Waiters in `acquire_timeout_as` record the resource they are blocked
+    /// on. A waiter whose wait would close a cycle of holders is picked as
+    /// the victim and fails at once with `deadlock: true` instead of
+    /// waiting out its timeout; releasing its leases unblocks the others.
+    pub fn with_deadlock_detection(mut self) -> Self {
+        self.waits = Some(Arc::new(WaitGraph::default()));
+        self
+    }
+
     /// Acquire lease with TTL
-    pub async fn acquire(&self, resource: String, ttl: Duration) -> Result<u64> {
+    ///
+    /// An expired lease is taken over; a live one fails with its holder.
+    pub async fn acquire(&self, resource: String, ttl: Duration) -> Result<Lease> {
+        self.acquire_as(resource, crate::allocate_agent_id(), ttl).await
+    }
+
+    /// Acquire lease with TTL on behalf of `holder`
+    ///
+    /// Taking every lease of an agent under one holder ID (e.g. its agent
+    /// ID) lets the deadlock detector relate what it holds to what it waits on.
+    pub async fn acquire_as(&self, resource: String, holder: u64, ttl: Duration) -> Result<Lease> {
         let mut leases = self.leases.write().await;
-        let holder_id = crate::allocate_agent_id();
-        let expires = tokio::time::Instant::now() + ttl;
+        let now = tokio::time::Instant::now();
 
         // Check if resource is already leased
         if let Some(existing) = leases.get(&resource) {
-            if existing.expires > tokio::time::Instant::now() {
-                return Err(RuntimeError::LeaseAcquisitionFailed(format!(
-                    "Resource {} already leased",
-                    resource
-                )));
+            if existing.expires > now {
+                return Err(RuntimeError::LeaseAcquisitionFailed {
+                    resource,
+                    holder: Some(existing.holder),
+                    deadlock: false,
+                });
             }
         }
 
         leases.insert(
             resource.clone(),
-            Lease {
-                resource,
-                holder: holder_id,
-                expires,
+            LeaseEntry {
+                holder,
+                expires: now + ttl,
             },
         );
 
-        Ok(holder_id)
+        Ok(Lease {
+            resource,
+            holder,
+            leases: self.leases.clone(),
+        })
+    }
+
+    /// Acquire lease, waiting up to `timeout` for the current holder to
+    /// release it or let it expire
+    pub async fn acquire_timeout(
+        &self,
+        resource: String,
+        ttl: Duration,
+        timeout: Duration,
+    ) -> Result<Lease> {
+        self.acquire_timeout_as(resource, crate::allocate_agent_id(), ttl, timeout)
+            .await
+    }
+
+    /// Acquire lease on behalf of `holder`, waiting up to `timeout`
+    ///
+    /// With deadlock detection enabled, fails early with `deadlock: true`
+    /// if `holder` is part of a cycle of lease waiters.
+    pub async fn acquire_timeout_as(
+        &self,
+        resource: String,
+        holder: u64,
+        ttl: Duration,
+        timeout: Duration,
+    ) -> Result<Lease> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let _edge = WaitEdge {
+            waits: self.waits.as_deref(),
+            holder,
+        };
+
+        loop {
+            match self.acquire_as(resource.clone(), holder, ttl).await {
+                Ok(lease) => return Ok(lease),
+                Err(err) => {
+                    if let RuntimeError::LeaseAcquisitionFailed {
+                        holder: Some(blocker),
+                        ..
+                    } = err
+                    {
+                        if self.closes_cycle(&resource, holder).await {
+                            return Err(RuntimeError::LeaseAcquisitionFailed {
+                                resource,
+                                holder: Some(blocker),
+                                deadlock: true,
+                            });
+                        }
+                    }
+
+                    let now = tokio::time::Instant::now();
+                    if now >= deadline {
+                        return Err(err);
+                    }
+
+                    // Wake at the current expiry, but re-check periodically
+                    // in case the holder releases early
+                    let expires = self
+                        .leases
+                        .read()
+                        .await
+                        .get(&resource)
+                        .map(|entry| entry.expires)
+                        .unwrap_or(now);
+                    let wake = expires
+                        .min(deadline)
+                        .min(now + Duration::from_millis(10));
+                    tokio::time::sleep_until(wake).await;
+                }
+            }
+        }
+    }
+
+    /// Record that `waiter` is blocked on `resource`, unless doing so would
+    /// close a cycle in the wait-for graph
+    ///
+    /// Returns `true` for a deadlock; the edge is then left out so the
+    /// remaining waiters see an acyclic graph.
+    async fn closes_cycle(&self, resource: &str, waiter: u64) -> bool {
+        let Some(waits) = &self.waits else {
+            return false;
+        };
+        let leases = self.leases.read().await;
+        let now = tokio::time::Instant::now();
+        let mut waits = waits.lock().unwrap();
+
+        // Follow resource -> holder -> resource it waits on; each holder has
+        // at most one outgoing edge, so a walk longer than the graph loops
+        // without involving the waiter
+        let mut next = resource;
+        for _ in 0..=waits.len() {
+            let Some(holder) = leases
+                .get(next)
+                .filter(|entry| entry.expires > now)
+                .map(|entry| entry.holder)
+            else {
+                break;
+            };
+            if holder == waiter {
+                return true;
+            }
+            match waits.get(&holder) {
+                Some(wanted) => next = wanted,
+                None => break,
+            }
+        }
+
+        waits.insert(waiter, resource.to_string());
+        false
+    }
+
+    /// Current holder of a resource, if its lease has not expired
+    pub async fn holder(&self, resource: &str) -> Option<u64> {
+        let leases = self.leases.read().await;
+        leases
+            .get(resource)
+            .filter(|entry| entry.expires > tokio::time::Instant::now())
+            .map(|entry| entry.holder)
     }
 
     /// Release lease
@@ -259,14 +767,25 @@ impl LeaseManager {
                 leases.remove(resource);
                 Ok(())
             } else {
-                Err(RuntimeError::LeaseAcquisitionFailed(
-                    "Not the lease holder".to_string(),
-                ))
+                Err(RuntimeError::LeaseAcquisitionFailed {
+                    resource: resource.to_string(),
+                    holder: Some(lease.holder),
+                    deadlock: false,
+                })
             }
         } else {
             Ok(()) // Already released
         }
     }
+
+    /// Drop expired leases, returning how many were removed
+    pub async fn purge_expired(&self) -> usize {
+        let mut leases = self.leases.write().await;
+        let now = tokio::time::Instant::now();
+        let before = leases.len();
+        leases.retain(|_, entry| entry.expires > now);
+        before - leases.len()
+    }
 }
 
 impl Default for LeaseManager {
@@ -299,13 +818,202 @@ pub async fn broadcast<T: Clone + Send>(
     // Send to selected agents
     for idx in selected {
         let agent = &agents[idx];
-        let msg_clone = Message::new(msg.payload().clone());
+        let msg_clone: Message<T> = Message::new(msg.payload().clone());
         agent.send(msg_clone).await?;
     }
 
     Ok(())
 }
 
+/// What a [`Broadcaster`] does with a subscriber whose buffer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// Drop the subscriber's oldest message and count it as lagged
+    SkipOldest,
+    /// Evict the subscriber; its next receive fails with
+    /// [`RuntimeError::SubscriberEvicted`]
+    Evict,
+}
+
+/// Broadcast channel with bounded per-subscriber buffers
+///
+/// A stalled subscriber never blocks the sender or other subscribers: once
+/// its buffer holds `capacity` messages the [`LagPolicy`] applies. Dropping
+/// the broadcaster closes the channel: subscribers drain what is buffered,
+/// then receive [`RuntimeError::MailboxClosed`].
+pub struct Broadcaster<T> {
+    state: Arc<parking_lot::Mutex<BroadcastState<T>>>,
+}
+
+struct BroadcastState<T> {
+    subscribers: HashMap<u64, SubscriberQueue<T>>,
+    capacity: usize,
+    policy: LagPolicy,
+    next_id: u64,
+    /// Set once the broadcaster is dropped
+    closed: bool,
+}
+
+struct SubscriberQueue<T> {
+    buffer: VecDeque<T>,
+    lagged: u64,
+    evicted: bool,
+    notify: Arc<Notify>,
+}
+
+impl<T: Clone + Send> Broadcaster<T> {
+    /// Create broadcaster with per-subscriber capacity and lag policy
+    pub fn new(capacity: usize, policy: LagPolicy) -> Self {
+        Self {
+            state: Arc::new(parking_lot::Mutex::new(BroadcastState {
+                subscribers: HashMap::new(),
+                capacity: capacity.max(1),
+                policy,
+                next_id: 0,
+                closed: false,
+            })),
+        }
+    }
+
+    /// Register a new subscriber; it sees messages sent from now on
+    pub fn subscribe(&self) -> Subscriber<T> {
+        let mut state = self.state.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let notify = Arc::new(Notify::new());
+        let buffer = VecDeque::with_capacity(state.capacity);
+        state.subscribers.insert(
+            id,
+            SubscriberQueue {
+                buffer,
+                lagged: 0,
+                evicted: false,
+                notify: notify.clone(),
+            },
+        );
+
+        Subscriber {
+            id,
+            state: self.state.clone(),
+            notify,
+        }
+    }
+
+    /// Send value to every live subscriber, returning how many received it
+    pub fn send(&self, value: T) -> usize {
+        let mut state = self.state.lock();
+        let capacity = state.capacity;
+        let policy = state.policy;
+        let mut delivered = 0;
+
+        for queue in state.subscribers.values_mut() {
+            if queue.evicted {
+                continue;
+            }
+
+            if queue.buffer.len() >= capacity {
+                queue.lagged += 1;
+                match policy {
+                    LagPolicy::SkipOldest => {
+                        queue.buffer.pop_front();
+                    }
+                    LagPolicy::Evict => {
+                        queue.evicted = true;
+                        queue.buffer.clear();
+                        queue.notify.notify_one();
+                        continue;
+                    }
+                }
+            }
+
+            queue.buffer.push_back(value.clone());
+            queue.notify.notify_one();
+            delivered += 1;
+        }
+
+        delivered
+    }
+
+    /// Number of subscribers that have not been evicted
+    pub fn subscriber_count(&self) -> usize {
+        self.state
+            .lock()
+            .subscribers
+            .values()
+            .filter(|queue| !queue.evicted)
+            .count()
+    }
+}
+
+impl<T> Drop for Broadcaster<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        state.closed = true;
+        for queue in state.subscribers.values() {
+            queue.notify.notify_waiters();
+        }
+    }
+}
+
+/// Receiving side of a [`Broadcaster`]
+pub struct Subscriber<T> {
+    id: u64,
+    state: Arc<parking_lot::Mutex<BroadcastState<T>>>,
+    notify: Arc<Notify>,
+}
+
+impl<T> Subscriber<T> {
+    /// Receive next message
+    pub async fn recv(&self) -> Result<T> {
+        loop {
+            let notified = self.notify.notified();
+            if let Some(result) = self.poll_queue() {
+                return result;
+            }
+            notified.await;
+        }
+    }
+
+    /// Receive next message without waiting
+    ///
+    /// Fails with [`RuntimeError::Empty`] if no message is buffered yet.
+    pub fn try_recv(&self) -> Result<T> {
+        self.poll_queue().unwrap_or(Err(RuntimeError::Empty))
+    }
+
+    /// Messages this subscriber missed because it fell behind
+    pub fn lagged(&self) -> u64 {
+        self.state
+            .lock()
+            .subscribers
+            .get(&self.id)
+            .map(|queue| queue.lagged)
+            .unwrap_or(0)
+    }
+
+    fn poll_queue(&self) -> Option<Result<T>> {
+        let mut state = self.state.lock();
+        let closed = state.closed;
+        let queue = state.subscribers.get_mut(&self.id)?;
+
+        if queue.evicted {
+            return Some(Err(RuntimeError::SubscriberEvicted(queue.lagged)));
+        }
+        match queue.buffer.pop_front() {
+            Some(value) => Some(Ok(value)),
+            None if closed => Some(Err(RuntimeError::MailboxClosed)),
+            None => None,
+        }
+    }
+}
+
+impl<T> Drop for Subscriber<T> {
+    fn drop(&mut self) {
+        self.state.lock().subscribers.remove(&self.id);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -327,10 +1035,157 @@ mod tests {
     async fn test_signal() {
         let agent = spawn(|_mailbox: Mailbox<String>| async move {}).await;
 
-        let result = signal(&agent, Message::new("test".to_string())).await;
+        let result = signal(&agent, Message::<String, Iso>::new("test".to_string())).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_signal_reaches_receiving_agent() {
+        let (tx, rx) = await_future::<String>();
+        let agent = spawn(|mailbox: Mailbox<String>| async move {
+            if let Ok(msg) = mailbox.recv().await {
+                let _ = tx.send(msg.into_payload());
+            }
+        })
+        .await;
+
+        signal(&agent, Message::<String, Iso>::new("test".to_string())).await.unwrap();
+        assert_eq!(rx.timeout(Duration::from_secs(1)).await.unwrap(), "test");
+    }
+
+    #[tokio::test]
+    async fn test_level_signal_coalesces() {
+        let signal = Signal::level();
+
+        for _ in 0..100 {
+            signal.raise();
+        }
+        assert_eq!(signal.pending(), 1);
+
+        let mut wakeups = 0;
+        while tokio::time::timeout(Duration::from_millis(10), signal.wait())
+            .await
+            .is_ok()
+        {
+            wakeups += 1;
+        }
+        assert_eq!(wakeups, 1);
+    }
+
+    #[tokio::test]
+    async fn test_edge_signal_delivers_all() {
+        let signal = Signal::edge();
+        let consumer = {
+            let signal = signal.clone();
+            tokio::spawn(async move {
+                let mut wakeups = 0;
+                while tokio::time::timeout(Duration::from_millis(50), signal.wait())
+                    .await
+                    .is_ok()
+                {
+                    wakeups += 1;
+                }
+                wakeups
+            })
+        };
+
+        for _ in 0..100 {
+            signal.raise();
+        }
+        assert_eq!(consumer.await.unwrap(), 100);
+        assert_eq!(signal.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_slow_subscriber_skips() {
+        let broadcaster = Broadcaster::new(4, LagPolicy::SkipOldest);
+        let fast = broadcaster.subscribe();
+        let stalled = broadcaster.subscribe();
+
+        for i in 0..20 {
+            assert_eq!(broadcaster.send(i), 2);
+            assert_eq!(fast.recv().await.unwrap(), i);
+        }
+
+        assert_eq!(fast.lagged(), 0);
+        assert_eq!(stalled.lagged(), 16);
+        assert_eq!(stalled.recv().await.unwrap(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_slow_subscriber_evicted() {
+        let broadcaster = Broadcaster::new(4, LagPolicy::Evict);
+        let fast = broadcaster.subscribe();
+        let stalled = broadcaster.subscribe();
+
+        for i in 0..10 {
+            broadcaster.send(i);
+            assert_eq!(fast.recv().await.unwrap(), i);
+        }
+
+        assert_eq!(broadcaster.subscriber_count(), 1);
+        assert!(matches!(
+            stalled.recv().await,
+            Err(RuntimeError::SubscriberEvicted(1))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_closes_when_dropped() {
+        let broadcaster = Broadcaster::new(4, LagPolicy::SkipOldest);
+        let waiting = broadcaster.subscribe();
+        let buffered = broadcaster.subscribe();
+        assert!(matches!(buffered.try_recv(), Err(RuntimeError::Empty)));
+
+        let receiver = tokio::spawn(async move { waiting.recv().await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        broadcaster.send(1);
+        assert_eq!(receiver.await.unwrap().unwrap(), 1);
+
+        broadcaster.send(2);
+        drop(broadcaster);
+
+        // Buffered messages are still delivered, then the channel is closed
+        assert_eq!(buffered.recv().await.unwrap(), 1);
+        assert_eq!(buffered.recv().await.unwrap(), 2);
+        assert!(matches!(buffered.try_recv(), Err(RuntimeError::MailboxClosed)));
+        assert!(matches!(
+            tokio::time::timeout(Duration::from_secs(1), buffered.recv()).await,
+            Ok(Err(RuntimeError::MailboxClosed))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_drop_wakes_waiting_subscriber() {
+        let broadcaster = Broadcaster::<i32>::new(4, LagPolicy::SkipOldest);
+        let subscriber = broadcaster.subscribe();
+
+        let receiver = tokio::spawn(async move { subscriber.recv().await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(broadcaster);
+
+        let result = tokio::time::timeout(Duration::from_secs(1), receiver).await;
+        assert!(matches!(result, Ok(Ok(Err(RuntimeError::MailboxClosed)))));
+    }
+
+    #[tokio::test]
+    async fn test_awaitable_timeout() {
+        let (fast_tx, fast) = await_future::<i32>();
+        let (slow_tx, slow) = await_future::<i32>();
+
+        fast_tx.send(7).unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            let _ = slow_tx.send(8);
+        });
+
+        assert_eq!(fast.timeout(Duration::from_millis(50)).await.unwrap(), 7);
+        assert!(matches!(
+            slow.timeout(Duration::from_millis(20)).await,
+            Err(RuntimeError::Timeout(d)) if d == Duration::from_millis(20)
+        ));
+    }
+
     #[tokio::test]
     async fn test_channel() {
         let (tx, rx) = channel::<i32>(10);
@@ -340,6 +1195,58 @@ mod tests {
         assert_eq!(value, 42);
     }
 
+    #[tokio::test]
+    async fn test_bounded_channel_full() {
+        let (tx, rx) = channel::bounded::<i32>(2);
+        assert_eq!(tx.capacity(), Some(2));
+
+        tx.try_send(1).unwrap();
+        tx.send(2).await.unwrap();
+        assert!(matches!(tx.try_send(3), Err(RuntimeError::MailboxFull(2))));
+
+        // An async send waits for a permit instead of failing
+        let blocked = tokio::spawn({
+            let tx = tx.clone();
+            async move { tx.send(3).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(rx.recv().await.unwrap(), 1);
+        blocked.await.unwrap().unwrap();
+        assert_eq!(tx.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unbounded_channel_never_blocks() {
+        let (tx, rx) = channel::unbounded::<usize>();
+        assert_eq!(tx.capacity(), None);
+
+        for i in 0..10_000 {
+            tx.try_send(i).unwrap();
+        }
+        assert_eq!(tx.len(), 10_000);
+        assert_eq!(rx.recv().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_channel_select() {
+        let (tx_a, rx_a) = channel::<&str>(4);
+        let (tx_b, rx_b) = channel::<&str>(4);
+
+        tx_b.send("from b").await.unwrap();
+        let (idx, value) = ChannelReceiver::select(&[&rx_a, &rx_b]).await.unwrap();
+        assert_eq!((idx, value), (1, "from b"));
+
+        // A waiting select wakes on the first send
+        let waiter = tokio::spawn(async move {
+            ChannelReceiver::select(&[&rx_a, &rx_b]).await.unwrap()
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        tx_a.send("from a").await.unwrap();
+        assert_eq!(waiter.await.unwrap(), (0, "from a"));
+    }
+
     #[tokio::test]
     async fn test_shard() {
         let agents = vec![
@@ -352,11 +1259,48 @@ mod tests {
         assert!(agents.iter().any(|a| a.id == agent.id));
     }
 
+    #[test]
+    fn test_shard_ring_minimal_remapping() {
+        let shards = 8u64;
+        let mut ring = ShardRing::new();
+        for id in 0..shards {
+            ring.add_shard(id);
+        }
+
+        let keys: Vec<String> = (0..20_000).map(|i| format!("key-{}", i)).collect();
+        let before: Vec<ShardId> = keys.iter().map(|k| ring.route_key(k).unwrap()).collect();
+
+        ring.add_shard(shards);
+        assert_eq!(ring.shard_count(), 9);
+
+        let ring_moved = keys
+            .iter()
+            .zip(&before)
+            .filter(|(k, old)| ring.route_key(*k).unwrap() != **old)
+            .count();
+        let modulo_moved = keys
+            .iter()
+            .filter(|k| ring_hash(*k) % shards != ring_hash(*k) % (shards + 1))
+            .count();
+
+        // Ideal is 1/(N+1) ≈ 11%; modulo moves ≈ N/(N+1) ≈ 89%
+        let fraction = ring_moved as f64 / keys.len() as f64;
+        assert!(fraction > 0.05 && fraction < 0.2, "moved {}", fraction);
+        assert!(ring_moved * 4 < modulo_moved);
+
+        // Removing the shard restores the original routing
+        ring.remove_shard(shards);
+        assert!(keys
+            .iter()
+            .zip(&before)
+            .all(|(k, old)| ring.route_key(k).unwrap() == *old));
+    }
+
     #[tokio::test]
     async fn test_lease_manager() {
         let manager = LeaseManager::new();
 
-        let holder = manager
+        let lease = manager
             .acquire("resource1".to_string(), Duration::from_secs(1))
             .await
             .unwrap();
@@ -368,11 +1312,91 @@ mod tests {
         assert!(result.is_err());
 
         // Release and re-acquire
-        manager.release("resource1", holder).await.unwrap();
+        manager.release("resource1", lease.holder()).await.unwrap();
 
         let result = manager
             .acquire("resource1".to_string(), Duration::from_secs(1))
             .await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_lease_expiry_and_renewal() {
+        let manager = LeaseManager::new();
+
+        let lease = manager
+            .acquire("resource1".to_string(), Duration::from_millis(30))
+            .await
+            .unwrap();
+
+        // Renewal keeps the lease alive past its original TTL
+        lease.renew(Duration::from_millis(60)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        match manager
+            .acquire("resource1".to_string(), Duration::from_secs(1))
+            .await
+        {
+            Err(RuntimeError::LeaseAcquisitionFailed { holder, .. }) => {
+                assert_eq!(holder, Some(lease.holder()));
+            }
+            _ => panic!("renewed lease should still be held"),
+        }
+
+        // Without further renewal, a waiter takes over after expiry
+        let waiter = manager
+            .acquire_timeout(
+                "resource1".to_string(),
+                Duration::from_secs(1),
+                Duration::from_millis(500),
+            )
+            .await
+            .unwrap();
+        assert_ne!(waiter.holder(), lease.holder());
+        assert!(waiter.is_held().await);
+        assert!(!lease.is_held().await);
+        assert!(lease.renew(Duration::from_secs(1)).await.is_err());
+        assert_eq!(manager.holder("resource1").await, Some(waiter.holder()));
+    }
+
+    #[tokio::test]
+    async fn test_lease_deadlock_detection() {
+        let manager = LeaseManager::new().with_deadlock_detection();
+        let ttl = Duration::from_secs(10);
+        let timeout = Duration::from_secs(5);
+        let (agent_a, agent_b) = (crate::allocate_agent_id(), crate::allocate_agent_id());
+
+        manager.acquire_as("a".to_string(), agent_a, ttl).await.unwrap();
+        manager.acquire_as("b".to_string(), agent_b, ttl).await.unwrap();
+
+        // Each agent wants the other's lease; a victim gives up its own
+        let wait = |agent: u64, held: &'static str, wanted: &'static str| {
+            let manager = &manager;
+            async move {
+                let result = manager
+                    .acquire_timeout_as(wanted.to_string(), agent, ttl, timeout)
+                    .await;
+                if result.is_err() {
+                    manager.release(held, agent).await.unwrap();
+                }
+                result
+            }
+        };
+
+        let start = tokio::time::Instant::now();
+        let (a, b) = tokio::join!(wait(agent_a, "a", "b"), wait(agent_b, "b", "a"));
+        assert!(start.elapsed() < timeout);
+
+        let (winner, victim) = match (a, b) {
+            (Ok(lease), Err(err)) | (Err(err), Ok(lease)) => (lease, err),
+            _ => panic!("exactly one agent should be chosen as the victim"),
+        };
+        match victim {
+            RuntimeError::LeaseAcquisitionFailed { deadlock, holder, .. } => {
+                assert!(deadlock);
+                assert_eq!(holder, Some(winner.holder()));
+            }
+            other => panic!("expected deadlock, got {:?}", other),
+        }
+        assert!(winner.is_held().await);
+    }
 }
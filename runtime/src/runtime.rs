@@ -1,14 +1,33 @@
 //! Main runtime coordinator
 
 use crate::mailbox::Mailbox;
-use crate::orchestration::{spawn as spawn_agent, AgentRef};
+use crate::orchestration::AgentRef;
 use crate::primitives::{Metrics, RuntimeConfig};
-use crate::scheduler::Scheduler;
+use crate::scheduler::{panic_message, Affinity, Priority, Scheduler};
+use crate::{Result, RuntimeError};
+use futures::future::AbortHandle;
+use futures::FutureExt;
+use hashbrown::HashMap;
+use parking_lot::Mutex;
 use std::future::Future;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
+/// Outcome of a graceful [`Runtime::shutdown`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// Agents whose mailboxes were drained
+    pub agents: usize,
+    /// Messages processed while draining
+    pub processed: usize,
+    /// Messages still queued when the drain timeout expired, discarded
+    /// after their agents were stopped
+    pub dropped: usize,
+}
+
 /// Main agent runtime
 pub struct Runtime {
     /// Runtime configuration
@@ -22,6 +41,36 @@ pub struct Runtime {
 
     /// Running state
     running: Arc<AtomicBool>,
+
+    /// Whether new spawns are accepted (cleared on shutdown)
+    accepting: AtomicBool,
+
+    /// Running agents, by agent id
+    agents: AgentRegistry,
+}
+
+type AgentRegistry = Arc<Mutex<HashMap<u64, Arc<AgentHandle>>>>;
+
+/// Shutdown controls of a running agent
+struct AgentHandle {
+    /// Queue length of the agent's mailbox
+    len: Arc<AtomicUsize>,
+    /// Stops the agent's behavior at its next poll
+    abort: AbortHandle,
+    /// Discards the agent's queued messages, returning how many there were
+    drain: Box<dyn Fn() -> usize + Send + Sync>,
+}
+
+/// Registration of a running agent, removed when the agent exits
+struct MailboxEntry {
+    agent_id: u64,
+    agents: AgentRegistry,
+}
+
+impl Drop for MailboxEntry {
+    fn drop(&mut self) {
+        self.agents.lock().remove(&self.agent_id);
+    }
 }
 
 impl Runtime {
@@ -39,6 +88,8 @@ impl Runtime {
             scheduler,
             metrics: Arc::new(RwLock::new(Metrics::new())),
             running: Arc::new(AtomicBool::new(false)),
+            accepting: AtomicBool::new(true),
+            agents: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -66,15 +117,82 @@ impl Runtime {
         }
     }
 
+    /// Gracefully shut down the runtime
+    ///
+    /// New spawns are refused immediately with [`RuntimeError::SchedulerError`].
+    /// Running agents get up to `timeout` to drain their mailboxes. Agents
+    /// still running at the deadline are then aborted, the messages left in
+    /// their mailboxes are discarded and reported as dropped, and the
+    /// workers are stopped.
+    pub async fn shutdown(&self, timeout: Duration) -> Result<ShutdownReport> {
+        if !self.accepting.swap(false, Ordering::SeqCst) {
+            return Err(RuntimeError::SchedulerError(
+                "Runtime already shut down".to_string(),
+            ));
+        }
+
+        let agents: Vec<_> = self.agents.lock().values().cloned().collect();
+        let pending = || -> usize {
+            agents
+                .iter()
+                .map(|agent| agent.len.load(Ordering::Relaxed))
+                .sum()
+        };
+
+        let initial = pending();
+        let deadline = tokio::time::Instant::now() + timeout;
+        while pending() > 0 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Stop the agents before counting, so nothing queued is consumed
+        // after it has been reported as dropped
+        let mut dropped = 0;
+        for agent in &agents {
+            agent.abort.abort();
+            dropped += (agent.drain)();
+        }
+
+        self.stop().await;
+
+        let report = ShutdownReport {
+            agents: agents.len(),
+            processed: initial.saturating_sub(dropped),
+            dropped,
+        };
+        tracing::info!(
+            "Runtime shut down: {} processed, {} dropped across {} agents",
+            report.processed,
+            report.dropped,
+            report.agents
+        );
+
+        Ok(report)
+    }
+
+    /// Check whether the runtime still accepts new spawns
+    pub fn is_accepting(&self) -> bool {
+        self.accepting.load(Ordering::SeqCst)
+    }
+
     /// Spawn a new agent
-    pub async fn spawn<T, F, Fut>(&self, behavior: F) -> AgentRef<T>
+    ///
+    /// Fails once [`Runtime::shutdown`] has been called.
+    pub async fn spawn<T, F, Fut>(&self, behavior: F) -> Result<AgentRef<T>>
     where
         T: Send + 'static,
         F: FnOnce(Mailbox<T>) -> Fut + Send + 'static,
         Fut: Future<Output = ()> + Send + 'static,
     {
+        self.check_accepting()?;
+
         let start = quanta::Instant::now();
-        let agent = spawn_agent(behavior).await;
+        let agent_id = crate::allocate_agent_id();
+        let mailbox = Mailbox::new();
+        let sender = mailbox.sender();
+        let receiver = mailbox.receiver();
+        tokio::spawn(self.tracked(agent_id, &sender, receiver, behavior(mailbox)));
+        let agent = AgentRef::new(agent_id, sender);
         let latency = start.elapsed().as_nanos() as u64;
 
         // Record metrics
@@ -87,7 +205,123 @@ impl Runtime {
             latency
         );
 
-        agent
+        Ok(agent)
+    }
+
+    /// Spawn a new agent on the scheduler with a placement hint
+    ///
+    /// The agent's behavior runs as a scheduler task on the worker chosen
+    /// from `affinity`; the choice is available through
+    /// [`Runtime::placement`]. Agents co-located with [`Affinity::With`]
+    /// share a worker, which keeps latency-sensitive pairs on one core.
+    /// Fails once [`Runtime::shutdown`] has been called.
+    pub async fn spawn_with_affinity<T, F, Fut>(
+        &self,
+        behavior: F,
+        affinity: Affinity,
+    ) -> Result<AgentRef<T>>
+    where
+        T: Send + 'static,
+        F: FnOnce(Mailbox<T>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.check_accepting()?;
+
+        let start = quanta::Instant::now();
+        let agent_id = crate::allocate_agent_id();
+        let mailbox = Mailbox::new();
+        let sender = mailbox.sender();
+        let receiver = mailbox.receiver();
+        let task = self.tracked(agent_id, &sender, receiver, behavior(mailbox));
+
+        let worker_id = self.scheduler.place(agent_id, affinity);
+        self.scheduler
+            .submit_agent(worker_id, agent_id, Box::pin(task), Priority::Normal);
+        let latency = start.elapsed().as_nanos() as u64;
+
+        let mut metrics = self.metrics.write().await;
+        metrics.record_spawn(latency);
+
+        tracing::debug!(
+            "Spawned agent {} on worker {} in {}ns",
+            agent_id,
+            worker_id,
+            latency
+        );
+
+        Ok(AgentRef::new(agent_id, sender))
+    }
+
+    fn check_accepting(&self) -> Result<()> {
+        if self.is_accepting() {
+            return Ok(());
+        }
+        tracing::warn!("Spawn refused: runtime is shutting down");
+        Err(RuntimeError::SchedulerError(
+            "Runtime is shutting down".to_string(),
+        ))
+    }
+
+    /// Register an agent for shutdown until `behavior` ends
+    ///
+    /// The registration holds `receiver`, which keeps the mailbox open for
+    /// as long, even if the behavior drops its `Mailbox` early. The returned
+    /// future ends early once [`Runtime::shutdown`] aborts the agent. A
+    /// panic in the behavior ends it too and is recorded as the agent's
+    /// [`Runtime::failure`].
+    fn tracked<T: Send + 'static>(
+        &self,
+        agent_id: u64,
+        sender: &crate::mailbox::MailboxSender<T>,
+        receiver: crate::mailbox::MailboxReceiver<T>,
+        behavior: impl Future<Output = ()> + Send + 'static,
+    ) -> impl Future<Output = ()> + Send + 'static {
+        let (behavior, abort) = futures::future::abortable(behavior);
+        let handle = AgentHandle {
+            len: sender.len_handle(),
+            abort,
+            drain: Box::new(move || receiver.drain()),
+        };
+        self.agents.lock().insert(agent_id, Arc::new(handle));
+        let entry = MailboxEntry {
+            agent_id,
+            agents: self.agents.clone(),
+        };
+        let scheduler = self.scheduler.clone();
+
+        async move {
+            let _entry = entry;
+            if let Err(payload) = AssertUnwindSafe(behavior).catch_unwind().await {
+                let error = RuntimeError::Internal(format!(
+                    "agent {} panicked: {}",
+                    agent_id,
+                    panic_message(payload.as_ref())
+                ));
+                tracing::error!("{}", error);
+                scheduler.record_failure(agent_id, error);
+            }
+        }
+    }
+
+    /// Number of agents whose behavior is still running
+    pub fn running_agents(&self) -> usize {
+        self.agents.lock().len()
+    }
+
+    /// Worker an agent spawned with [`Runtime::spawn_with_affinity`] was placed on
+    pub fn placement(&self, agent_id: u64) -> Option<usize> {
+        self.scheduler.placement(agent_id)
+    }
+
+    /// Termination reason of an agent that panicked
+    ///
+    /// A panicking agent is stopped and reported here as
+    /// `RuntimeError::Internal`, however it was spawned; agents sharing its
+    /// worker keep running.
+    /// Under `panic = "abort"` (the release profile) a panic aborts the
+    /// process instead and nothing is reported.
+    pub fn failure(&self, agent_id: u64) -> Option<RuntimeError> {
+        self.scheduler.failure(agent_id)
     }
 
     /// Get runtime metrics
@@ -143,7 +377,8 @@ mod tests {
                     // Process message
                 }
             })
-            .await;
+            .await
+            .unwrap();
 
         assert!(agent.id > 0);
 
@@ -152,4 +387,224 @@ mod tests {
 
         runtime.stop().await;
     }
+
+    #[tokio::test]
+    async fn test_spawn_with_affinity_colocates_agents() {
+        let runtime = Runtime::with_config(RuntimeConfig {
+            worker_threads: 4,
+            ..RuntimeConfig::default()
+        });
+        runtime.start();
+
+        let received = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let received_clone = received.clone();
+
+        let first = runtime
+            .spawn_with_affinity(
+                move |mailbox: Mailbox<i32>| async move {
+                    while let Ok(_msg) = mailbox.recv().await {
+                        received_clone.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+                Affinity::Core(2),
+            )
+            .await
+            .unwrap();
+        let second = runtime
+            .spawn_with_affinity(
+                |mailbox: Mailbox<i32>| async move {
+                    while let Ok(_msg) = mailbox.recv().await {}
+                },
+                Affinity::With(first.id),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(runtime.placement(first.id), Some(2));
+        assert_eq!(runtime.placement(second.id), runtime.placement(first.id));
+
+        first.send(crate::message::Message::iso(7)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+
+        runtime.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_mailboxes() {
+        let runtime = Runtime::new();
+        runtime.start();
+
+        let received = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let received_clone = received.clone();
+        let agent = runtime
+            .spawn(move |mailbox: Mailbox<i32>| async move {
+                while let Ok(_msg) = mailbox.recv().await {
+                    received_clone.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .await
+            .unwrap();
+
+        for i in 0..50 {
+            agent.send(crate::message::Message::iso(i)).await.unwrap();
+        }
+
+        let report = runtime.shutdown(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(report.processed, 50);
+        assert_eq!(report.dropped, 0);
+        assert_eq!(received.load(Ordering::SeqCst), 50);
+        assert!(!runtime.is_running());
+
+        // Spawns after shutdown are refused
+        assert!(runtime.spawn(|_: Mailbox<i32>| async {}).await.is_err());
+        assert!(runtime
+            .spawn_with_affinity(|_: Mailbox<i32>| async {}, Affinity::Spread)
+            .await
+            .is_err());
+        assert!(runtime.shutdown(Duration::from_secs(1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exited_agents_are_not_tracked() {
+        let runtime = Runtime::new();
+        runtime.start();
+
+        let agent = runtime
+            .spawn(|mailbox: Mailbox<i32>| async move {
+                let _ = mailbox.recv().await;
+            })
+            .await
+            .unwrap();
+        let _idle = runtime
+            .spawn_with_affinity(|_: Mailbox<i32>| async {}, Affinity::Spread)
+            .await
+            .unwrap();
+        let _parked = runtime
+            .spawn(|mailbox: Mailbox<i32>| async move {
+                while let Ok(_msg) = mailbox.recv().await {}
+            })
+            .await
+            .unwrap();
+        assert_eq!(runtime.running_agents(), 3);
+
+        agent.send(crate::message::Message::iso(1)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(runtime.running_agents(), 1);
+
+        // Only the agent that is still running is drained on shutdown
+        let report = runtime.shutdown(Duration::from_millis(10)).await.unwrap();
+        assert_eq!(report.agents, 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_reports_dropped_messages() {
+        let runtime = Runtime::new();
+        runtime.start();
+
+        let received = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let received_clone = received.clone();
+        let agent = runtime
+            .spawn(move |mailbox: Mailbox<i32>| async move {
+                while let Ok(_msg) = mailbox.recv().await {
+                    received_clone.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+            })
+            .await
+            .unwrap();
+
+        for i in 0..10 {
+            agent.send(crate::message::Message::iso(i)).await.unwrap();
+        }
+
+        let report = runtime.shutdown(Duration::from_millis(1)).await.unwrap();
+        assert!(report.dropped > 0);
+        assert_eq!(report.processed + report.dropped, 10);
+
+        // The agent was stopped: nothing reported as dropped is consumed later
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(received.load(Ordering::SeqCst), report.processed);
+        assert_eq!(runtime.running_agents(), 0);
+        assert!(agent.send(crate::message::Message::iso(99)).await.is_err());
+    }
+
+    #[cfg(panic = "unwind")]
+    #[tokio::test]
+    async fn test_panicking_agent_is_isolated() {
+        let runtime = Runtime::with_config(RuntimeConfig {
+            worker_threads: 1,
+            ..RuntimeConfig::default()
+        });
+        runtime.start();
+
+        let crashing = runtime
+            .spawn_with_affinity(
+                |mailbox: Mailbox<i32>| async move {
+                    if let Ok(msg) = mailbox.recv().await {
+                        panic!("bad message {}", msg.payload());
+                    }
+                },
+                Affinity::Core(0),
+            )
+            .await
+            .unwrap();
+
+        let received = Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let received_clone = received.clone();
+        let healthy = runtime
+            .spawn_with_affinity(
+                move |mailbox: Mailbox<i32>| async move {
+                    while let Ok(_msg) = mailbox.recv().await {
+                        received_clone.fetch_add(1, Ordering::SeqCst);
+                    }
+                },
+                Affinity::With(crashing.id),
+            )
+            .await
+            .unwrap();
+
+        crashing.send(crate::message::Message::iso(13)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The shared worker survives and keeps serving the other agent
+        for i in 0..3 {
+            healthy.send(crate::message::Message::iso(i)).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+
+        assert!(matches!(
+            runtime.failure(crashing.id),
+            Some(RuntimeError::Internal(reason)) if reason.contains("bad message 13")
+        ));
+        assert!(runtime.failure(healthy.id).is_none());
+
+        runtime.stop().await;
+    }
+
+    #[cfg(panic = "unwind")]
+    #[tokio::test]
+    async fn test_panicking_spawned_agent_is_reported() {
+        let runtime = Runtime::new();
+
+        let crashing = runtime
+            .spawn(|mailbox: Mailbox<i32>| async move {
+                if let Ok(msg) = mailbox.recv().await {
+                    panic!("bad message {}", msg.payload());
+                }
+            })
+            .await
+            .unwrap();
+        assert!(runtime.failure(crashing.id).is_none());
+
+        crashing.send(crate::message::Message::iso(7)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert!(matches!(
+            runtime.failure(crashing.id),
+            Some(RuntimeError::Internal(reason)) if reason.contains("bad message 7")
+        ));
+        assert_eq!(runtime.running_agents(), 0);
+    }
 }
@@ -5,12 +5,19 @@
 //! - Global MPMC queue with epoch reclamation
 //! - Predictive victim selection
 //! - Throttled stealing
+//!
+//! A task that panics is dropped and its failure recorded; the panic never
+//! unwinds into the worker loop, so other tasks keep running. This relies on
+//! unwinding: under `panic = "abort"`, which the workspace release profile
+//! sets, a panicking task aborts the whole process instead.
 
 use crate::profile::AgentProfile;
-use crossbeam::deque::{Injector, Steal, Stealer, Worker};
+use crate::RuntimeError;
+use crossbeam::deque::{Injector, Steal};
 use parking_lot::Mutex;
 use rand::Rng;
 use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -23,56 +30,103 @@ use tokio::task::JoinHandle;
 /// Task future type
 pub type TaskFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
 
+/// Per-worker task queues, shared with wakers so woken tasks can be re-enqueued
+type WorkerQueues = Arc<Vec<Injector<Arc<Task>>>>;
+
+/// Termination reasons of agents whose task panicked
+type Failures = Arc<Mutex<hashbrown::HashMap<u64, RuntimeError>>>;
+
+/// Placements of running agents
+type Placements = Arc<Mutex<hashbrown::HashMap<u64, Placement>>>;
+
+/// Worker an agent was placed on
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    worker: usize,
+    /// Whether the agent must stay on `worker` (never stolen)
+    pinned: bool,
+}
+
 /// Runnable task
 pub struct Task {
     pub(crate) id: u64,
     pub(crate) future: Mutex<Option<TaskFuture>>,
     pub(crate) priority: Priority,
     pub(crate) agent_id: Option<u64>,
+    /// Worker whose queue the task is re-enqueued on when woken
+    pub(crate) home: AtomicUsize,
+    /// Whether the task only runs on its home worker
+    pub(crate) pinned: bool,
 }
 
 impl Task {
     /// Create new task
     pub fn new(id: u64, future: TaskFuture) -> Arc<Self> {
-        Arc::new(Self {
-            id,
-            future: Mutex::new(Some(future)),
-            priority: Priority::Normal,
-            agent_id: None,
-        })
+        Self::with_priority(id, future, Priority::Normal)
     }
 
     /// Create task with priority
     pub fn with_priority(id: u64, future: TaskFuture, priority: Priority) -> Arc<Self> {
+        Self::build(id, future, priority, None, false)
+    }
+
+    fn build(
+        id: u64,
+        future: TaskFuture,
+        priority: Priority,
+        agent_id: Option<u64>,
+        pinned: bool,
+    ) -> Arc<Self> {
         Arc::new(Self {
             id,
             future: Mutex::new(Some(future)),
             priority,
-            agent_id: None,
+            agent_id,
+            home: AtomicUsize::new(0),
+            pinned,
         })
     }
 
     /// Run the task
-    pub(crate) fn run(self: Arc<Self>) -> bool {
-        let waker = TaskWaker::new(self.clone());
+    ///
+    /// Returns whether the task completed. A panic while polling is caught
+    /// and returned as `RuntimeError::Internal`; the future is dropped and
+    /// the task counts as completed. Panics can only be caught when built
+    /// with `panic = "unwind"`.
+    pub(crate) fn run(self: Arc<Self>, queues: &WorkerQueues) -> Result<bool, RuntimeError> {
+        let waker = TaskWaker::new(self.clone(), queues.clone());
         let mut context = Context::from_waker(&waker);
 
         let mut future_guard = self.future.lock();
         if let Some(mut future) = future_guard.take() {
-            match future.as_mut().poll(&mut context) {
-                Poll::Ready(_) => true, // Task completed
-                Poll::Pending => {
+            match catch_unwind(AssertUnwindSafe(|| future.as_mut().poll(&mut context))) {
+                Ok(Poll::Ready(_)) => Ok(true), // Task completed
+                Ok(Poll::Pending) => {
                     // Re-insert future
                     *future_guard = Some(future);
-                    false
+                    Ok(false)
                 }
+                Err(payload) => Err(RuntimeError::Internal(format!(
+                    "task {} panicked: {}",
+                    self.id,
+                    panic_message(payload.as_ref())
+                ))),
             }
         } else {
-            true // Already completed
+            Ok(true) // Already completed
         }
     }
 }
 
+/// Text of a panic payload, when it carries one
+pub(crate) fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
 /// Task priority levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Priority {
@@ -85,31 +139,44 @@ pub enum Priority {
 /// Task waker
 struct TaskWaker {
     task: Arc<Task>,
+    queues: WorkerQueues,
 }
 
 impl TaskWaker {
-    fn new(task: Arc<Task>) -> Waker {
-        Arc::new(Self { task }).into()
+    fn new(task: Arc<Task>, queues: WorkerQueues) -> Waker {
+        Arc::new(Self { task, queues }).into()
     }
 }
 
 impl Wake for TaskWaker {
     fn wake(self: Arc<Self>) {
-        // Re-schedule task (implementation depends on scheduler integration)
+        // Re-schedule task on its home worker
         tracing::trace!("Task {} woken", self.task.id);
+        let home = self.task.home.load(Ordering::Relaxed) % self.queues.len();
+        self.queues[home].push(self.task.clone());
     }
 }
 
+/// Placement hint for a newly spawned agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Affinity {
+    /// Let the scheduler pick a worker
+    Any,
+    /// Pin to a worker (core index, wrapped to the worker count)
+    Core(usize),
+    /// Co-locate with an already placed agent, pinned to its worker
+    With(u64),
+    /// Place on the worker with the fewest placed agents
+    Spread,
+}
+
 /// Work-stealing scheduler
 pub struct Scheduler {
     /// Global task injector (overflow queue)
     global_queue: Arc<Injector<Arc<Task>>>,
 
-    /// Per-worker local queues
-    workers: Vec<Worker<Arc<Task>>>,
-
-    /// Stealers for each worker
-    stealers: Vec<Stealer<Arc<Task>>>,
+    /// Per-worker local queues (also the steal targets of other workers)
+    workers: WorkerQueues,
 
     /// Number of worker threads
     worker_count: usize,
@@ -123,6 +190,12 @@ pub struct Scheduler {
     /// Agent profiles for predictive scheduling
     profiles: Arc<Mutex<hashbrown::HashMap<u64, AgentProfile>>>,
 
+    /// Worker each running placed agent was assigned to
+    placements: Placements,
+
+    /// Agents whose task panicked, with the resulting error
+    failures: Failures,
+
     /// Worker thread handles
     handles: Mutex<Vec<JoinHandle<()>>>,
 }
@@ -138,24 +211,19 @@ impl Scheduler {
 
     /// Create scheduler with specific worker count
     pub fn with_workers(worker_count: usize) -> Self {
+        let worker_count = worker_count.max(1);
         let global_queue = Arc::new(Injector::new());
-        let mut workers = Vec::with_capacity(worker_count);
-        let mut stealers = Vec::with_capacity(worker_count);
-
-        for _ in 0..worker_count {
-            let worker = Worker::new_fifo();
-            stealers.push(worker.stealer());
-            workers.push(worker);
-        }
+        let workers = Arc::new((0..worker_count).map(|_| Injector::new()).collect());
 
         Self {
             global_queue,
             workers,
-            stealers,
             worker_count,
             running: Arc::new(AtomicBool::new(false)),
             task_counter: AtomicU64::new(1),
             profiles: Arc::new(Mutex::new(hashbrown::HashMap::new())),
+            placements: Arc::new(Mutex::new(hashbrown::HashMap::new())),
+            failures: Arc::new(Mutex::new(hashbrown::HashMap::new())),
             handles: Mutex::new(Vec::new()),
         }
     }
@@ -174,13 +242,24 @@ impl Scheduler {
 
         for worker_id in 0..self.worker_count {
             let global_queue = self.global_queue.clone();
-            let stealers = self.stealers.clone();
+            let queues = self.workers.clone();
             let running = self.running.clone();
             let profiles = self.profiles.clone();
+            let failures = self.failures.clone();
+            let placements = self.placements.clone();
 
             // Spawn worker thread
             let handle = tokio::spawn(async move {
-                Self::worker_loop(worker_id, global_queue, stealers, running, profiles).await;
+                Self::worker_loop(
+                    worker_id,
+                    global_queue,
+                    queues,
+                    running,
+                    profiles,
+                    failures,
+                    placements,
+                )
+                .await;
             });
 
             handles.push(handle);
@@ -204,19 +283,115 @@ impl Scheduler {
 
     /// Submit task with priority
     pub fn submit_with_priority(&self, future: TaskFuture, priority: Priority) -> u64 {
+        let worker_id = fastrand::usize(..self.worker_count);
+        self.submit_to(worker_id, future, priority)
+    }
+
+    /// Submit task to a specific worker's local queue
+    ///
+    /// The worker index wraps to the worker count. The task is re-enqueued on
+    /// the same worker whenever it is woken, even if another worker stole it.
+    pub fn submit_to(&self, worker_id: usize, future: TaskFuture, priority: Priority) -> u64 {
+        self.submit_task(worker_id, future, priority, None, false)
+    }
+
+    /// Submit an agent's behavior to a specific worker's local queue
+    ///
+    /// If the behavior panics, the panic is recorded as the agent's
+    /// termination reason, available from [`Scheduler::failure`] (only when
+    /// panics unwind; see the module docs). An agent pinned by
+    /// [`Scheduler::place`] is never stolen by another worker, and its
+    /// placement is forgotten once the behavior ends.
+    pub fn submit_agent(
+        &self,
+        worker_id: usize,
+        agent_id: u64,
+        future: TaskFuture,
+        priority: Priority,
+    ) -> u64 {
+        let pinned = self
+            .placements
+            .lock()
+            .get(&agent_id)
+            .is_some_and(|placement| placement.pinned);
+        self.submit_task(worker_id, future, priority, Some(agent_id), pinned)
+    }
+
+    fn submit_task(
+        &self,
+        worker_id: usize,
+        future: TaskFuture,
+        priority: Priority,
+        agent_id: Option<u64>,
+        pinned: bool,
+    ) -> u64 {
         let task_id = self.task_counter.fetch_add(1, Ordering::Relaxed);
-        let task = Task::with_priority(task_id, future, priority);
+        self.enqueue(worker_id, Task::build(task_id, future, priority, agent_id, pinned))
+    }
 
-        // Try to push to local worker queue first
-        let worker_id = fastrand::usize(..self.worker_count);
-        self.workers[worker_id].push(task.clone());
+    fn enqueue(&self, worker_id: usize, task: Arc<Task>) -> u64 {
+        let task_id = task.id;
+        let worker_id = worker_id % self.worker_count;
 
-        // Fallback to global queue (though local push should always succeed)
-        // self.global_queue.push(task);
+        task.home.store(worker_id, Ordering::Relaxed);
+        self.workers[worker_id].push(task);
 
         task_id
     }
 
+    /// Resolve an affinity hint to a worker and record the agent's placement
+    ///
+    /// [`Affinity::Core`] and [`Affinity::With`] pin the agent to the chosen
+    /// worker.
+    pub fn place(&self, agent_id: u64, affinity: Affinity) -> usize {
+        let mut placements = self.placements.lock();
+
+        let worker_id = match affinity {
+            Affinity::Core(core) => core % self.worker_count,
+            Affinity::With(peer) => placements
+                .get(&peer)
+                .map(|placement| placement.worker)
+                .unwrap_or_else(|| fastrand::usize(..self.worker_count)),
+            Affinity::Spread => {
+                let mut load = vec![0usize; self.worker_count];
+                for placement in placements.values() {
+                    load[placement.worker] += 1;
+                }
+                (0..self.worker_count)
+                    .min_by_key(|worker| load[*worker])
+                    .unwrap_or(0)
+            }
+            Affinity::Any => fastrand::usize(..self.worker_count),
+        };
+
+        let pinned = matches!(affinity, Affinity::Core(_) | Affinity::With(_));
+        placements.insert(agent_id, Placement { worker: worker_id, pinned });
+        worker_id
+    }
+
+    /// Worker a running agent was placed on, if it was spawned with an affinity hint
+    pub fn placement(&self, agent_id: u64) -> Option<usize> {
+        self.placements
+            .lock()
+            .get(&agent_id)
+            .map(|placement| placement.worker)
+    }
+
+    /// Why an agent's task terminated abnormally, if it panicked
+    pub fn failure(&self, agent_id: u64) -> Option<RuntimeError> {
+        self.failures.lock().get(&agent_id).cloned()
+    }
+
+    /// Record that an agent running outside the scheduler panicked
+    pub(crate) fn record_failure(&self, agent_id: u64, error: RuntimeError) {
+        self.failures.lock().insert(agent_id, error);
+    }
+
+    /// Number of worker threads
+    pub fn worker_count(&self) -> usize {
+        self.worker_count
+    }
+
     /// Update agent profile for predictive scheduling
     pub fn update_profile(&self, agent_id: u64, profile: AgentProfile) {
         self.profiles.lock().insert(agent_id, profile);
@@ -226,19 +401,41 @@ impl Scheduler {
     async fn worker_loop(
         worker_id: usize,
         global_queue: Arc<Injector<Arc<Task>>>,
-        stealers: Vec<Stealer<Arc<Task>>>,
+        queues: WorkerQueues,
         running: Arc<AtomicBool>,
         _profiles: Arc<Mutex<hashbrown::HashMap<u64, AgentProfile>>>,
+        failures: Failures,
+        placements: Placements,
     ) {
-        let local_worker = Worker::new_fifo();
+        let run = |task: Arc<Task>| {
+            let (task_id, agent_id) = (task.id, task.agent_id);
+            let finished = match task.run(&queues) {
+                Ok(completed) => completed,
+                Err(error) => {
+                    tracing::error!("Worker {} dropped task {}: {}", worker_id, task_id, error);
+                    if let Some(agent_id) = agent_id {
+                        failures.lock().insert(agent_id, error);
+                    }
+                    true
+                }
+            };
+            if let (true, Some(agent_id)) = (finished, agent_id) {
+                placements.lock().remove(&agent_id);
+            }
+        };
+
         let mut check_global_counter = 0u32;
         let check_global_interval = 61; // Prime number for better distribution
 
         while running.load(Ordering::Relaxed) {
             // Try local queue first
-            if let Some(task) = local_worker.pop() {
-                task.run();
-                continue;
+            match queues[worker_id].steal() {
+                Steal::Success(task) => {
+                    run(task);
+                    continue;
+                }
+                Steal::Retry => continue,
+                Steal::Empty => {}
             }
 
             // Check global queue periodically
@@ -246,7 +443,7 @@ impl Scheduler {
             if check_global_counter % check_global_interval == 0 {
                 match global_queue.steal() {
                     Steal::Success(task) => {
-                        task.run();
+                        run(task);
                         continue;
                     }
                     Steal::Empty => {}
@@ -255,8 +452,8 @@ impl Scheduler {
             }
 
             // Work stealing
-            if let Some(task) = Self::steal_work(worker_id, &stealers) {
-                task.run();
+            if let Some(task) = Self::steal_work(worker_id, &queues) {
+                run(task);
                 continue;
             }
 
@@ -267,7 +464,10 @@ impl Scheduler {
     }
 
     /// Steal work from random victim
-    fn steal_work(worker_id: usize, stealers: &[Stealer<Arc<Task>>]) -> Option<Arc<Task>> {
+    ///
+    /// A pinned task taken from another worker's queue is handed back to
+    /// its home worker instead of being run here.
+    fn steal_work(worker_id: usize, stealers: &[Injector<Arc<Task>>]) -> Option<Arc<Task>> {
         let num_stealers = stealers.len();
         if num_stealers <= 1 {
             return None;
@@ -282,6 +482,13 @@ impl Scheduler {
             }
 
             match stealers[victim_id].steal() {
+                Steal::Success(task) if task.pinned => {
+                    let home = task.home.load(Ordering::Relaxed);
+                    if home == worker_id {
+                        return Some(task);
+                    }
+                    stealers[home].push(task);
+                }
                 Steal::Success(task) => return Some(task),
                 Steal::Empty => continue,
                 Steal::Retry => continue,
@@ -360,4 +567,43 @@ mod tests {
 
         scheduler.stop().await;
     }
+
+    #[test]
+    fn test_steal_skips_pinned_tasks() {
+        let scheduler = Scheduler::with_workers(2);
+        scheduler.place(1, Affinity::Core(1));
+        scheduler.submit_agent(1, 1, Box::pin(async {}), Priority::Normal);
+
+        // Worker 0 may not take the pinned agent from worker 1
+        for _ in 0..10 {
+            assert!(Scheduler::steal_work(0, &scheduler.workers).is_none());
+        }
+        assert_eq!(scheduler.queue_depths(), vec![0, 1]);
+        assert!(matches!(scheduler.workers[1].steal(), Steal::Success(_)));
+
+        // Unpinned work is still stolen
+        scheduler.place(2, Affinity::Spread);
+        scheduler.submit_agent(1, 2, Box::pin(async {}), Priority::Normal);
+        assert!((0..10).any(|_| Scheduler::steal_work(0, &scheduler.workers).is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_exited_agents_leave_placements() {
+        let scheduler = Scheduler::with_workers(2);
+        scheduler.start();
+
+        scheduler.place(1, Affinity::Core(0));
+        scheduler.submit_agent(0, 1, Box::pin(std::future::pending()), Priority::Normal);
+        scheduler.place(2, Affinity::Core(1));
+        scheduler.submit_agent(1, 2, Box::pin(async {}), Priority::Normal);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(scheduler.placement(1), Some(0));
+        assert_eq!(scheduler.placement(2), None);
+
+        // Only the running agent counts towards worker load
+        assert_eq!(scheduler.place(3, Affinity::Spread), 1);
+
+        scheduler.stop().await;
+    }
 }
@@ -1,26 +1,46 @@
 //! Causal graph for episode relationships
+//!
+//! All internal maps are ordered so inferred links and traced paths do not
+//! depend on insertion order.
 
-use crate::agentdb::{Episode, EpisodeId};
-use std::collections::{HashMap, HashSet};
+use crate::agentdb::{EntityId, Episode, EpisodeId};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::sync::RwLock;
 
 pub struct CausalGraph {
-    edges: RwLock<HashMap<EpisodeId, HashSet<EpisodeId>>>,
+    edges: RwLock<BTreeMap<EpisodeId, BTreeSet<EpisodeId>>>,
+    /// Timestamp and entities of every known episode, used for inference
+    known: RwLock<BTreeMap<EpisodeId, (u64, Vec<EntityId>)>>,
 }
 
 impl CausalGraph {
     pub fn new() -> Self {
         Self {
-            edges: RwLock::new(HashMap::new()),
+            edges: RwLock::new(BTreeMap::new()),
+            known: RwLock::new(BTreeMap::new()),
         }
     }
 
+    /// Infer causal antecedents of an episode
+    ///
+    /// An earlier episode is an antecedent when it shares at least one entity.
+    /// Episodes with equal timestamps are ordered by id, which keeps the graph
+    /// acyclic. Links are returned in (timestamp, id) order.
     pub async fn infer_causal_links(&self, episode: &Episode) -> Result<Vec<EpisodeId>, Box<dyn std::error::Error>> {
-        // TODO: Implement causal inference based on:
-        // - Temporal ordering
-        // - Shared entities
-        // - Similar contexts
-        Ok(Vec::new())
+        let known = self.known.read().unwrap();
+        let entities: HashSet<&EntityId> = episode.entities.iter().collect();
+
+        let mut antecedents: Vec<(u64, &EpisodeId)> = known
+            .iter()
+            .filter(|(id, (timestamp, _))| {
+                (*timestamp, *id) < (episode.timestamp, &episode.id)
+            })
+            .filter(|(_, (_, other))| other.iter().any(|e| entities.contains(e)))
+            .map(|(id, (timestamp, _))| (*timestamp, id))
+            .collect();
+        antecedents.sort();
+
+        Ok(antecedents.into_iter().map(|(_, id)| id.clone()).collect())
     }
 
     pub async fn add_episode(&self, episode: &Episode) -> Result<(), Box<dyn std::error::Error>> {
@@ -28,10 +48,15 @@ impl CausalGraph {
 
         for causal_link in &episode.causal_links {
             edges.entry(episode.id.clone())
-                .or_insert_with(HashSet::new)
+                .or_default()
                 .insert(causal_link.clone());
         }
 
+        self.known.write().unwrap().insert(
+            episode.id.clone(),
+            (episode.timestamp, episode.entities.clone()),
+        );
+
         Ok(())
     }
 
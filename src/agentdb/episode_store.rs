@@ -29,7 +29,7 @@ impl EpisodeStore {
     pub async fn get_recent(&self, limit: usize) -> Result<Vec<Episode>, Box<dyn std::error::Error>> {
         let episodes = self.episodes.read().unwrap();
         let mut sorted: Vec<_> = episodes.values().cloned().collect();
-        sorted.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        sorted.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| a.id.cmp(&b.id)));
         Ok(sorted.into_iter().take(limit).collect())
     }
 }
@@ -1,23 +1,28 @@
 //! Explainable recall with reasoning traces
 
-use crate::agentdb::{Episode, RecallExplanation};
+use crate::agentdb::{EntityId, Episode, EpisodeId, RecallExplanation};
+use std::collections::HashSet;
 
 pub fn explain_episode_recall(
     query: &str,
     episode: &Episode,
     similarity: f32,
-    causal_chain: &[String],
+    causal_chain: &[EpisodeId],
 ) -> RecallExplanation {
+    let matching_entities = extract_matching_entities(query, &episode.entities);
+
     let reasoning = format!(
         "Retrieved '{}' because:\n\
          - High similarity score ({:.3}) to query '{}'\n\
          - {} causal antecedents in history\n\
+         - {} entities shared with query\n\
          - Last accessed {} days ago\n\
          - Access count: {}",
         episode.context,
         similarity,
         query,
         causal_chain.len(),
+        matching_entities.len(),
         episode.age_days(),
         episode.access_count
     );
@@ -25,8 +30,41 @@ pub fn explain_episode_recall(
     RecallExplanation {
         episode_id: episode.id.clone(),
         similarity_score: similarity,
-        matching_entities: Vec::new(), // TODO: Extract from query
+        matching_entities,
         causal_chain: causal_chain.to_vec(),
         reasoning,
     }
 }
+
+/// Extract the episode entities mentioned by the query
+///
+/// Matching is case-insensitive on token boundaries, so `"acct-42"` matches
+/// a query containing `"ACCT-42"` but not `"acct-421"`. Multi-token entities
+/// match when their tokens appear contiguously in the query. Results keep the
+/// episode's entity order and contain no duplicates.
+pub fn extract_matching_entities(query: &str, entities: &[EntityId]) -> Vec<EntityId> {
+    let query_tokens = tokenize(query);
+    let mut seen = HashSet::new();
+
+    entities
+        .iter()
+        .filter(|entity| {
+            let entity_tokens = tokenize(entity);
+            !entity_tokens.is_empty()
+                && query_tokens
+                    .windows(entity_tokens.len())
+                    .any(|window| window == entity_tokens.as_slice())
+        })
+        .filter(|entity| seen.insert(entity.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Split text into lowercase tokens, keeping `-` and `_` inside identifiers
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .map(|token| token.trim_matches(|c| c == '-' || c == '_'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
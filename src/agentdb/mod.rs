@@ -11,7 +11,10 @@ pub mod reasoning_bank;
 pub mod explainable_recall;
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Borrow;
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
@@ -55,7 +58,54 @@ impl Default for AgentDbConfig {
 }
 
 /// Episode ID type
-pub type EpisodeId = String;
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EpisodeId(String);
+
+impl EpisodeId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// Generate a stable, content-addressed id
+    ///
+    /// The id is the hex SHA-256 of the content and timestamp, so the same
+    /// episode recorded twice always maps to the same id.
+    pub fn generate(content: &str, timestamp: u64) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(content.as_bytes());
+        hasher.update(timestamp.to_le_bytes());
+        Self(hex::encode(hasher.finalize()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for EpisodeId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for EpisodeId {
+    fn from(id: &str) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl Borrow<str> for EpisodeId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for EpisodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
 
 /// Entity ID type
 pub type EntityId = String;
@@ -103,6 +153,29 @@ impl Episode {
         }
     }
 
+    /// Create an episode with a content-addressed id and explicit timestamp
+    pub fn with_generated_id(
+        timestamp: u64,
+        context: String,
+        action: String,
+        outcome: String,
+        embedding: Vec<f32>,
+        entities: Vec<EntityId>,
+    ) -> Self {
+        let content = format!("{}\n{}\n{}", context, action, outcome);
+        let mut episode = Self::new(
+            EpisodeId::generate(&content, timestamp),
+            context,
+            action,
+            outcome,
+            embedding,
+            entities,
+        );
+        episode.timestamp = timestamp;
+        episode.last_accessed = timestamp;
+        episode
+    }
+
     pub fn age_days(&self) -> u64 {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -210,16 +283,20 @@ impl AgentDb {
                     .await
                     .unwrap_or_default();
 
+                let matching_entities =
+                    explainable_recall::extract_matching_entities(query, &episode.entities);
+
                 let explanation = RecallExplanation {
                     episode_id: episode.id.clone(),
                     similarity_score: result.score,
-                    matching_entities: Vec::new(), // TODO: extract from query
-                    causal_chain,
                     reasoning: format!(
-                        "Retrieved because: high similarity ({:.3}) to query and {} causal antecedents",
+                        "Retrieved because: high similarity ({:.3}) to query, {} shared entities and {} causal antecedents",
                         result.score,
+                        matching_entities.len(),
                         episode.causal_links.len()
                     ),
+                    matching_entities,
+                    causal_chain,
                 };
 
                 episodes.push(episode);
@@ -273,4 +350,71 @@ mod tests {
         let config = AgentDbConfig::default();
         // Test with mock or local Qdrant instance
     }
+
+    #[test]
+    fn test_recall_explanation_matching_entities() {
+        let episode = Episode::new(
+            "ep-1".into(),
+            "Refund requested".to_string(),
+            "issue_refund".to_string(),
+            "success".to_string(),
+            vec![0.1, 0.2, 0.3],
+            vec!["customer-42".to_string(), "order-7".to_string(), "stripe".to_string()],
+        );
+
+        let explanation = explainable_recall::explain_episode_recall(
+            "What happened with the refund for Customer-42?",
+            &episode,
+            0.9,
+            &[],
+        );
+
+        assert_eq!(explanation.matching_entities, vec!["customer-42".to_string()]);
+    }
+
+    #[test]
+    fn test_generated_episode_id_is_stable() {
+        let a = EpisodeId::generate("deploy v2", 1_700_000_000);
+        let b = EpisodeId::generate("deploy v2", 1_700_000_000);
+        let c = EpisodeId::generate("deploy v2", 1_700_000_001);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a.as_str().len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_causal_inference_is_order_independent() {
+        let episode = |ts: u64, context: &str, entities: &[&str]| {
+            Episode::with_generated_id(
+                ts,
+                context.to_string(),
+                "act".to_string(),
+                "ok".to_string(),
+                vec![],
+                entities.iter().map(|e| e.to_string()).collect(),
+            )
+        };
+
+        let history = vec![
+            episode(10, "disk alert", &["db-1"]),
+            episode(20, "failover", &["db-1", "db-2"]),
+            episode(20, "cache flush", &["cache"]),
+            episode(30, "replica lag", &["db-2"]),
+        ];
+        let probe = episode(40, "outage", &["db-1", "db-2"]);
+
+        let forward = causal_graph::CausalGraph::new();
+        for e in &history {
+            forward.add_episode(e).await.unwrap();
+        }
+        let reverse = causal_graph::CausalGraph::new();
+        for e in history.iter().rev() {
+            reverse.add_episode(e).await.unwrap();
+        }
+
+        let links = forward.infer_causal_links(&probe).await.unwrap();
+        assert_eq!(links, reverse.infer_causal_links(&probe).await.unwrap());
+        assert_eq!(links, vec![history[0].id.clone(), history[1].id.clone(), history[3].id.clone()]);
+    }
 }
@@ -9,6 +9,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default half-life of a judged outcome's influence on `suggest`
+const DEFAULT_HALF_LIFE: Duration = Duration::from_secs(30 * 86400);
 
 /// Trajectory represents a sequence of optimization attempts
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,10 +86,22 @@ pub struct ReasoningStats {
     pub avg_latency_improvement_pct: f32,
 }
 
+/// Strategy ranked by `ReasoningBank::suggest`
+#[derive(Debug, Clone)]
+pub struct StrategySuggestion {
+    /// Action sequence shared by the strategy's trajectories
+    pub actions: Vec<String>,
+    /// Time-decayed success rate, scaled by how much recent evidence backs it, in [0, 1]
+    pub weight: f32,
+    /// Number of judged trajectories using the strategy
+    pub sample_count: u32,
+}
+
 /// ReasoningBank implementation
 pub struct ReasoningBank {
     trajectories: Arc<RwLock<HashMap<String, Trajectory>>>,
     patterns: Arc<RwLock<HashMap<String, DistilledPattern>>>,
+    half_life: Duration,
 }
 
 impl ReasoningBank {
@@ -93,9 +109,18 @@ impl ReasoningBank {
         Self {
             trajectories: Arc::new(RwLock::new(HashMap::new())),
             patterns: Arc::new(RwLock::new(HashMap::new())),
+            half_life: DEFAULT_HALF_LIFE,
         }
     }
 
+    /// Set how quickly old outcomes lose influence on `suggest`
+    ///
+    /// An outcome one half-life old counts half as much as one from now.
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
     /// Track a new trajectory
     pub async fn track(&self, trajectory: Trajectory) -> Result<(), Box<dyn std::error::Error>> {
         let mut trajectories = self.trajectories.write().unwrap();
@@ -187,6 +212,66 @@ impl ReasoningBank {
             .collect()
     }
 
+    /// Rank strategies for a task by time-decayed success rate
+    pub async fn suggest(&self, task_type: &str) -> Vec<StrategySuggestion> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.suggest_at(task_type, now).await
+    }
+
+    /// Rank strategies for a task as of `now` (seconds since the epoch)
+    ///
+    /// A strategy is the action sequence of a trajectory. Each judged
+    /// trajectory counts with weight `0.5^(age / half_life)`, so a strategy
+    /// that used to succeed but has started failing drops below one that
+    /// keeps succeeding. The success rate alone would not fall with age, so
+    /// it is scaled by `1 - 0.5^total`, where `total` is the decayed number of
+    /// samples: a strategy only tried long ago ranks below a recent one.
+    pub async fn suggest_at(&self, task_type: &str, now: u64) -> Vec<StrategySuggestion> {
+        let trajectories = self.trajectories.read().unwrap();
+        let half_life = self.half_life.as_secs_f64().max(1.0);
+
+        // actions -> (decayed successes, decayed total, samples)
+        let mut tallies: HashMap<Vec<String>, (f64, f64, u32)> = HashMap::new();
+        for trajectory in trajectories.values().filter(|t| t.task_type == task_type) {
+            let Some(verdict) = &trajectory.verdict else {
+                continue;
+            };
+            let actions = trajectory.steps.iter().map(|s| s.action.clone()).collect();
+            let age = now.saturating_sub(trajectory.created_at) as f64;
+            let weight = 0.5f64.powf(age / half_life);
+
+            let tally = tallies.entry(actions).or_default();
+            if verdict.success {
+                tally.0 += weight;
+            }
+            tally.1 += weight;
+            tally.2 += 1;
+        }
+
+        let mut suggestions: Vec<_> = tallies
+            .into_iter()
+            .map(|(actions, (successes, total, sample_count))| StrategySuggestion {
+                actions,
+                weight: if total > 0.0 {
+                    (successes / total * (1.0 - 0.5f64.powf(total))) as f32
+                } else {
+                    0.0
+                },
+                sample_count,
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then(b.sample_count.cmp(&a.sample_count))
+                .then_with(|| a.actions.cmp(&b.actions))
+        });
+        suggestions
+    }
+
     /// Get statistics
     pub async fn stats(&self) -> ReasoningStats {
         let trajectories = self.trajectories.read().unwrap();
@@ -243,4 +328,90 @@ mod tests {
     async fn test_pattern_distillation() {
         // Test successful trajectory -> pattern distillation
     }
+
+    fn judged(id: &str, action: &str, success: bool, created_at: u64) -> Trajectory {
+        Trajectory {
+            id: id.to_string(),
+            task_type: "lane_selection".to_string(),
+            steps: vec![TrajectoryStep {
+                step_id: format!("{}_step", id),
+                step_type: StepType::LaneSelection,
+                input_state: "request".to_string(),
+                action: action.to_string(),
+                output_state: "routed".to_string(),
+                cost: 0.0,
+                latency_ms: 0,
+                timestamp: created_at,
+            }],
+            verdict: Some(Verdict {
+                success,
+                score: if success { 0.5 } else { 0.0 },
+                metrics: VerdictMetrics {
+                    cost_savings_pct: 0.0,
+                    latency_improvement_pct: 0.0,
+                    correctness_preserved: success,
+                    test_coverage_pct: 0.0,
+                },
+                reasoning: String::new(),
+            }),
+            metadata: HashMap::new(),
+            created_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_suggest_decays_stale_successes() {
+        const DAY: u64 = 86400;
+        let now = 200 * DAY;
+        let rb = ReasoningBank::new().with_half_life(Duration::from_secs(7 * DAY));
+
+        // "anthropic" won often long ago but has failed lately; "openrouter"
+        // has fewer, all recent, successes
+        for i in 0..6 {
+            rb.track(judged(&format!("old_{}", i), "anthropic", true, 10 * DAY + i))
+                .await
+                .unwrap();
+        }
+        for i in 0..2 {
+            rb.track(judged(&format!("fail_{}", i), "anthropic", false, now - DAY))
+                .await
+                .unwrap();
+        }
+        for i in 0..2 {
+            rb.track(judged(&format!("new_{}", i), "openrouter", true, now - 2 * DAY))
+                .await
+                .unwrap();
+        }
+
+        let suggestions = rb.suggest_at("lane_selection", now).await;
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].actions, vec!["openrouter".to_string()]);
+        assert_eq!(suggestions[1].actions, vec!["anthropic".to_string()]);
+        assert_eq!(suggestions[1].sample_count, 8);
+
+        // Undecayed, anthropic would sit at 6/8 successes
+        let stale = &suggestions[1];
+        assert!(stale.weight < 0.01, "weight {}", stale.weight);
+        assert!(stale.weight < suggestions[0].weight);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_prefers_recent_evidence() {
+        const DAY: u64 = 86400;
+        let now = 200 * DAY;
+        let rb = ReasoningBank::new().with_half_life(Duration::from_secs(7 * DAY));
+
+        // Both strategies never failed, but "anthropic" was last tried two months ago
+        for i in 0..5 {
+            rb.track(judged(&format!("old_{}", i), "anthropic", true, now - 60 * DAY))
+                .await
+                .unwrap();
+        }
+        rb.track(judged("new", "openrouter", true, now - DAY)).await.unwrap();
+
+        let suggestions = rb.suggest_at("lane_selection", now).await;
+        assert_eq!(suggestions[0].actions, vec!["openrouter".to_string()]);
+        assert_eq!(suggestions[1].actions, vec!["anthropic".to_string()]);
+        assert!(suggestions[1].weight < 0.05, "weight {}", suggestions[1].weight);
+    }
 }
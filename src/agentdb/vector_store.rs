@@ -8,6 +8,10 @@
 use crate::agentdb::{AgentDbConfig, Episode, SemanticFact};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Vector search result
 #[derive(Debug, Clone)]
@@ -17,12 +21,94 @@ pub struct SearchResult {
     pub payload: HashMap<String, serde_json::Value>,
 }
 
+/// A vector with its payload, as upserted into the backend
+#[derive(Debug, Clone)]
+pub struct VectorPoint {
+    pub id: String,
+    pub vector: Vec<f32>,
+    pub payload: HashMap<String, serde_json::Value>,
+}
+
+/// Boxed future returned by backend and clock methods
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Storage backend behind a `VectorStore` (in-memory HNSW or remote Qdrant)
+pub trait VectorBackend: Send + Sync {
+    /// Insert or replace a batch of points
+    fn upsert<'a>(
+        &'a self,
+        points: &'a [VectorPoint],
+    ) -> BoxFuture<'a, Result<(), Box<dyn std::error::Error>>>;
+
+    /// Search for the `limit` nearest points to `query`
+    fn search<'a>(
+        &'a self,
+        query: &'a [f32],
+        limit: usize,
+        ef_search: u32,
+    ) -> BoxFuture<'a, Result<Vec<SearchResult>, Box<dyn std::error::Error>>>;
+}
+
+/// Time source for retry backoff
+pub trait Clock: Send + Sync {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()>;
+}
+
+/// Tokio timer
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Exponential backoff for upserts
+///
+/// Attempt `n` (0-based) that fails waits `initial_backoff * 2^n`, capped
+/// at `max_backoff`, before the next attempt.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first (at least 1)
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Try once, never retry
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
 /// Vector store implementation
 pub struct VectorStore {
     config: AgentDbConfig,
     // TODO: Integrate actual Qdrant client
-    // For now, use in-memory HNSW implementation
-    hnsw_index: HnswIndex,
+    // For now, default to the in-memory HNSW implementation
+    backend: Box<dyn VectorBackend>,
+    clock: Arc<dyn Clock>,
+    retry: RetryPolicy,
 }
 
 impl VectorStore {
@@ -35,27 +121,39 @@ impl VectorStore {
 
         Ok(Self {
             config: config.clone(),
-            hnsw_index,
+            backend: Box::new(hnsw_index),
+            clock: Arc::new(TokioClock),
+            retry: RetryPolicy::default(),
         })
     }
 
+    /// Use a different storage backend
+    pub fn with_backend(mut self, backend: Box<dyn VectorBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Use a different clock for retry backoff
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the retry policy for upserts
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Store episode in vector database
     pub async fn store_episode(&self, episode: &Episode) -> Result<(), Box<dyn std::error::Error>> {
-        let mut payload = HashMap::new();
-        payload.insert("timestamp".to_string(), serde_json::json!(episode.timestamp));
-        payload.insert("context".to_string(), serde_json::json!(episode.context));
-        payload.insert("action".to_string(), serde_json::json!(episode.action));
-        payload.insert("outcome".to_string(), serde_json::json!(episode.outcome));
-        payload.insert("entities".to_string(), serde_json::json!(episode.entities));
-        payload.insert("causal_links".to_string(), serde_json::json!(episode.causal_links));
-
-        self.hnsw_index.insert(
-            &episode.id,
-            &episode.embedding,
-            payload,
-        ).await?;
+        self.upsert(&[Self::episode_point(episode)]).await
+    }
 
-        Ok(())
+    /// Store a batch of episodes in a single upsert
+    pub async fn store_episodes(&self, episodes: &[Episode]) -> Result<(), Box<dyn std::error::Error>> {
+        let points: Vec<_> = episodes.iter().map(Self::episode_point).collect();
+        self.upsert(&points).await
     }
 
     /// Store semantic fact
@@ -66,13 +164,47 @@ impl VectorStore {
         payload.insert("source_episodes".to_string(), serde_json::json!(fact.source_episodes));
         payload.insert("created_at".to_string(), serde_json::json!(fact.created_at));
 
-        self.hnsw_index.insert(
-            &fact.id,
-            &fact.embedding,
+        self.upsert(&[VectorPoint {
+            id: fact.id.clone(),
+            vector: fact.embedding.clone(),
             payload,
-        ).await?;
+        }])
+        .await
+    }
+
+    fn episode_point(episode: &Episode) -> VectorPoint {
+        let mut payload = HashMap::new();
+        payload.insert("timestamp".to_string(), serde_json::json!(episode.timestamp));
+        payload.insert("context".to_string(), serde_json::json!(episode.context));
+        payload.insert("action".to_string(), serde_json::json!(episode.action));
+        payload.insert("outcome".to_string(), serde_json::json!(episode.outcome));
+        payload.insert("entities".to_string(), serde_json::json!(episode.entities));
+        payload.insert("causal_links".to_string(), serde_json::json!(episode.causal_links));
 
-        Ok(())
+        VectorPoint {
+            id: episode.id.to_string(),
+            vector: episode.embedding.clone(),
+            payload,
+        }
+    }
+
+    /// Upsert with exponential backoff, returning the last error once
+    /// `max_attempts` is exhausted
+    async fn upsert(&self, points: &[VectorPoint]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut attempt = 0;
+        loop {
+            let err = match self.backend.upsert(points).await {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+
+            attempt += 1;
+            if attempt >= self.retry.max_attempts {
+                return Err(err);
+            }
+            tracing::warn!("vector upsert failed (attempt {}): {}", attempt, err);
+            self.clock.sleep(self.retry.backoff(attempt - 1)).await;
+        }
     }
 
     /// Search for similar vectors
@@ -84,7 +216,7 @@ impl VectorStore {
         limit: usize,
         ef_search: u32,
     ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
-        self.hnsw_index.search(query, limit, ef_search).await
+        self.backend.search(query, limit, ef_search).await
     }
 
     /// Batch search for multiple queries
@@ -125,28 +257,6 @@ impl HnswIndex {
         }
     }
 
-    async fn insert(
-        &self,
-        id: &str,
-        vector: &[f32],
-        payload: HashMap<String, serde_json::Value>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Implement actual HNSW insertion
-        // For now, just store in hashmap
-        Ok(())
-    }
-
-    async fn search(
-        &self,
-        query: &[f32],
-        limit: usize,
-        ef_search: u32,
-    ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error>> {
-        // TODO: Implement actual HNSW search with beam search
-        // For now, return mock results
-        Ok(Vec::new())
-    }
-
     fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
         let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
         let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -160,6 +270,28 @@ impl HnswIndex {
     }
 }
 
+impl VectorBackend for HnswIndex {
+    fn upsert<'a>(
+        &'a self,
+        points: &'a [VectorPoint],
+    ) -> BoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        // TODO: Implement actual HNSW insertion
+        // For now, just store in hashmap
+        Box::pin(async { Ok(()) })
+    }
+
+    fn search<'a>(
+        &'a self,
+        query: &'a [f32],
+        limit: usize,
+        ef_search: u32,
+    ) -> BoxFuture<'a, Result<Vec<SearchResult>, Box<dyn std::error::Error>>> {
+        // TODO: Implement actual HNSW search with beam search
+        // For now, return mock results
+        Box::pin(async { Ok(Vec::new()) })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +305,114 @@ mod tests {
     async fn test_batch_search() {
         // Test batch search throughput
     }
+
+    /// Backend that fails the first `failures` upserts
+    struct FlakyBackend {
+        failures: std::sync::atomic::AtomicU32,
+        stored: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl FlakyBackend {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures: failures.into(),
+                stored: Default::default(),
+            }
+        }
+    }
+
+    impl VectorBackend for Arc<FlakyBackend> {
+        fn upsert<'a>(
+            &'a self,
+            points: &'a [VectorPoint],
+        ) -> BoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+            use std::sync::atomic::Ordering;
+            Box::pin(async move {
+                let failed = self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                if failed {
+                    return Err("connection reset".into());
+                }
+                let mut stored = self.stored.lock().unwrap();
+                stored.extend(points.iter().map(|p| p.id.clone()));
+                Ok(())
+            })
+        }
+
+        fn search<'a>(
+            &'a self,
+            _query: &'a [f32],
+            _limit: usize,
+            _ef_search: u32,
+        ) -> BoxFuture<'a, Result<Vec<SearchResult>, Box<dyn std::error::Error>>> {
+            Box::pin(async { Ok(Vec::new()) })
+        }
+    }
+
+    /// Clock that records requested sleeps and returns immediately
+    #[derive(Default)]
+    struct RecordingClock(std::sync::Mutex<Vec<Duration>>);
+
+    impl Clock for RecordingClock {
+        fn sleep(&self, duration: Duration) -> BoxFuture<'_, ()> {
+            self.0.lock().unwrap().push(duration);
+            Box::pin(async {})
+        }
+    }
+
+    fn episode(id: &str) -> Episode {
+        Episode::new(
+            id.into(),
+            "ctx".to_string(),
+            "act".to_string(),
+            "ok".to_string(),
+            vec![0.1, 0.2],
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_store_retries_transient_failures() {
+        let backend = Arc::new(FlakyBackend::new(2));
+        let clock = Arc::new(RecordingClock::default());
+        let store = VectorStore::new(&AgentDbConfig::default())
+            .await
+            .unwrap()
+            .with_backend(Box::new(backend.clone()))
+            .with_clock(clock.clone());
+
+        store.store_episode(&episode("ep-1")).await.unwrap();
+
+        assert_eq!(*backend.stored.lock().unwrap(), vec!["ep-1".to_string()]);
+        assert_eq!(
+            *clock.0.lock().unwrap(),
+            vec![Duration::from_millis(50), Duration::from_millis(100)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_store_fails_after_retries_exhausted() {
+        let backend = Arc::new(FlakyBackend::new(u32::MAX));
+        let clock = Arc::new(RecordingClock::default());
+        let store = VectorStore::new(&AgentDbConfig::default())
+            .await
+            .unwrap()
+            .with_backend(Box::new(backend.clone()))
+            .with_clock(clock.clone())
+            .with_retry(RetryPolicy {
+                max_attempts: 3,
+                ..RetryPolicy::default()
+            });
+
+        let err = store
+            .store_episodes(&[episode("ep-1"), episode("ep-2")])
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "connection reset");
+        assert!(backend.stored.lock().unwrap().is_empty());
+        assert_eq!(clock.0.lock().unwrap().len(), 2);
+    }
 }
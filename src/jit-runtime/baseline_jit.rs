@@ -1,9 +1,22 @@
 //! Tier 1: Baseline JIT (1-5ms compile, 5-15x speed)
 
-pub fn compile(_function_id: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+use super::bytecode;
+use super::interpreter::Expr;
+
+/// Size of the placeholder code emitted by this tier
+pub const CODE_SIZE_HINT: usize = 64;
+
+pub fn compile(
+    _function_id: &str,
+    body: Option<&Expr>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     // TODO: Implement fast baseline compilation
     // - Cranelift backend
     // - Streaming compilation
     // - Minimal optimizations
-    Ok(Vec::new())
+    Ok(match body {
+        Some(body) => bytecode::lower(body),
+        // Placeholder of typical size for this tier until codegen lands
+        None => vec![0; CODE_SIZE_HINT],
+    })
 }
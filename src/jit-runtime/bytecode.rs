@@ -0,0 +1,81 @@
+//! Stack bytecode shared by the compiled tiers
+//!
+//! Stands in for native code: the compiled tiers lower an `Expr` to this
+//! encoding, which must evaluate exactly as the interpreter does.
+
+use super::interpreter::{self, Expr, JitValue};
+
+const OP_CONST: u8 = 0x01;
+const OP_ARG: u8 = 0x02;
+const OP_ADD: u8 = 0x03;
+const OP_SUB: u8 = 0x04;
+const OP_MUL: u8 = 0x05;
+const OP_DIV: u8 = 0x06;
+
+/// Lower an expression to postfix bytecode
+pub fn lower(body: &Expr) -> Vec<u8> {
+    let mut code = Vec::new();
+    emit(body, &mut code);
+    code
+}
+
+fn emit(expr: &Expr, code: &mut Vec<u8>) {
+    let (op, l, r) = match expr {
+        Expr::Const(n) => {
+            code.push(OP_CONST);
+            code.extend_from_slice(&n.to_le_bytes());
+            return;
+        }
+        Expr::Arg(i) => {
+            code.push(OP_ARG);
+            code.extend_from_slice(&(*i as u32).to_le_bytes());
+            return;
+        }
+        Expr::Add(l, r) => (OP_ADD, l, r),
+        Expr::Sub(l, r) => (OP_SUB, l, r),
+        Expr::Mul(l, r) => (OP_MUL, l, r),
+        Expr::Div(l, r) => (OP_DIV, l, r),
+    };
+    emit(l, code);
+    emit(r, code);
+    code.push(op);
+}
+
+pub fn run(code: &[u8], args: &[JitValue]) -> Result<JitValue, Box<dyn std::error::Error>> {
+    let mut stack: Vec<JitValue> = Vec::new();
+    let mut pc = 0;
+
+    while pc < code.len() {
+        let op = code[pc];
+        pc += 1;
+        match op {
+            OP_CONST => {
+                let bytes = code.get(pc..pc + 8).ok_or("Truncated constant")?;
+                stack.push(JitValue::Float(f64::from_le_bytes(bytes.try_into()?)));
+                pc += 8;
+            }
+            OP_ARG => {
+                let bytes = code.get(pc..pc + 4).ok_or("Truncated argument index")?;
+                let index = u32::from_le_bytes(bytes.try_into()?) as usize;
+                stack.push(interpreter::arg(args, index)?);
+                pc += 4;
+            }
+            OP_ADD | OP_SUB | OP_MUL | OP_DIV => {
+                let rhs = stack.pop().ok_or("Stack underflow")?;
+                let lhs = stack.pop().ok_or("Stack underflow")?;
+                stack.push(match op {
+                    OP_ADD => interpreter::add(lhs, rhs),
+                    OP_SUB => interpreter::sub(lhs, rhs),
+                    OP_MUL => interpreter::mul(lhs, rhs),
+                    _ => interpreter::divide(lhs, rhs)?,
+                });
+            }
+            other => return Err(format!("Invalid opcode 0x{:02x}", other).into()),
+        }
+    }
+
+    match stack.as_slice() {
+        [result] => Ok(*result),
+        _ => Err("Malformed bytecode".into()),
+    }
+}
@@ -1,9 +1,169 @@
 //! Tier 0: Interpreter (0ms startup, 1x speed)
+//!
+//! Evaluates a minimal arithmetic expression language over the numeric
+//! arguments of a call. Execution works on `JitValue`; JSON is only seen
+//! at the `JitRuntime::execute` boundary.
+
+use serde_json::Value;
+use std::fmt;
+
+/// Value passed through execution
+///
+/// `Copy` and heap-free, so evaluating an operation never allocates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JitValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null,
+}
+
+impl JitValue {
+    /// Convert a JSON argument; strings, arrays and objects have no equivalent
+    pub fn from_json(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(JitValue::Null),
+            Value::Bool(b) => Some(JitValue::Bool(*b)),
+            Value::Number(n) => Some(match n.as_i64() {
+                Some(i) => JitValue::Int(i),
+                None => JitValue::Float(n.as_f64()?),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Integral results are returned as JSON integers
+    pub fn to_json(self) -> Value {
+        match self {
+            JitValue::Int(i) => Value::from(i),
+            JitValue::Float(n) if n.fract() == 0.0 && n.abs() < (1u64 << 53) as f64 => {
+                Value::from(n as i64)
+            }
+            JitValue::Float(n) => Value::from(n),
+            JitValue::Bool(b) => Value::Bool(b),
+            JitValue::Null => Value::Null,
+        }
+    }
+
+    pub fn is_number(self) -> bool {
+        matches!(self, JitValue::Int(_) | JitValue::Float(_))
+    }
+
+    /// Numeric value; only called on values `arg` has checked are numbers
+    fn number(self) -> f64 {
+        match self {
+            JitValue::Int(i) => i as f64,
+            JitValue::Float(n) => n,
+            JitValue::Bool(_) | JitValue::Null => f64::NAN,
+        }
+    }
+}
+
+impl fmt::Display for JitValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+/// Function body
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(f64),
+    /// Positional argument
+    Arg(usize),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+}
+
+impl std::ops::Add for Expr {
+    type Output = Expr;
+
+    fn add(self, rhs: Expr) -> Expr {
+        Expr::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::Sub for Expr {
+    type Output = Expr;
+
+    fn sub(self, rhs: Expr) -> Expr {
+        Expr::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::Mul for Expr {
+    type Output = Expr;
+
+    fn mul(self, rhs: Expr) -> Expr {
+        Expr::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl std::ops::Div for Expr {
+    type Output = Expr;
+
+    fn div(self, rhs: Expr) -> Expr {
+        Expr::Div(Box::new(self), Box::new(rhs))
+    }
+}
 
 pub fn execute(
-    _function_id: &str,
-    _args: &[serde_json::Value],
-) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
-    // TODO: Implement bytecode interpreter
-    Ok(serde_json::Value::Null)
+    body: &Expr,
+    args: &[JitValue],
+) -> Result<JitValue, Box<dyn std::error::Error>> {
+    Ok(match body {
+        Expr::Const(n) => JitValue::Float(*n),
+        Expr::Arg(i) => arg(args, *i)?,
+        Expr::Add(l, r) => add(execute(l, args)?, execute(r, args)?),
+        Expr::Sub(l, r) => sub(execute(l, args)?, execute(r, args)?),
+        Expr::Mul(l, r) => mul(execute(l, args)?, execute(r, args)?),
+        Expr::Div(l, r) => divide(execute(l, args)?, execute(r, args)?)?,
+    })
+}
+
+/// Numeric argument `index`
+pub fn arg(args: &[JitValue], index: usize) -> Result<JitValue, Box<dyn std::error::Error>> {
+    let value = *args.get(index)
+        .ok_or_else(|| format!("Missing argument {}", index))?;
+    if !value.is_number() {
+        return Err(format!("Argument {} is not a number: {}", index, value).into());
+    }
+    Ok(value)
+}
+
+/// Integer arithmetic stays exact; mixed operands or overflow fall back to floats
+fn arith(
+    lhs: JitValue,
+    rhs: JitValue,
+    int_op: fn(i64, i64) -> Option<i64>,
+    float_op: fn(f64, f64) -> f64,
+) -> JitValue {
+    if let (JitValue::Int(a), JitValue::Int(b)) = (lhs, rhs) {
+        if let Some(n) = int_op(a, b) {
+            return JitValue::Int(n);
+        }
+    }
+    JitValue::Float(float_op(lhs.number(), rhs.number()))
+}
+
+pub fn add(lhs: JitValue, rhs: JitValue) -> JitValue {
+    arith(lhs, rhs, i64::checked_add, |a, b| a + b)
+}
+
+pub fn sub(lhs: JitValue, rhs: JitValue) -> JitValue {
+    arith(lhs, rhs, i64::checked_sub, |a, b| a - b)
+}
+
+pub fn mul(lhs: JitValue, rhs: JitValue) -> JitValue {
+    arith(lhs, rhs, i64::checked_mul, |a, b| a * b)
+}
+
+/// Division is always floating point, as in the JSON results it feeds
+pub fn divide(lhs: JitValue, rhs: JitValue) -> Result<JitValue, Box<dyn std::error::Error>> {
+    if rhs.number() == 0.0 {
+        return Err("Division by zero".into());
+    }
+    Ok(JitValue::Float(lhs.number() / rhs.number()))
 }
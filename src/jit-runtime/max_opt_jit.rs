@@ -1,10 +1,23 @@
 //! Tier 3: Max-Opt JIT (100-500ms compile, 50-200x speed)
 
-pub fn compile(_function_id: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+use super::bytecode;
+use super::interpreter::Expr;
+
+/// Size of the placeholder code emitted by this tier
+pub const CODE_SIZE_HINT: usize = 1024;
+
+pub fn compile(
+    _function_id: &str,
+    body: Option<&Expr>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     // TODO: Implement maximum optimization
     // - Aggressive inlining
     // - Full escape analysis
     // - Loop optimizations
     // - LLVM backend
-    Ok(Vec::new())
+    Ok(match body {
+        Some(body) => bytecode::lower(body),
+        // Placeholder of typical size for this tier until codegen lands
+        None => vec![0; CODE_SIZE_HINT],
+    })
 }
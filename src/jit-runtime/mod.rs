@@ -7,18 +7,22 @@
 //! - Tier 3: Max-Opt JIT (100-500ms compile, 50-200x speed)
 
 pub mod interpreter;
+pub mod bytecode;
 pub mod baseline_jit;
 pub mod optimizing_jit;
 pub mod max_opt_jit;
 pub mod osr;
 pub mod profiling;
 
+pub use interpreter::JitValue;
+
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 /// JIT tier level
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum JitTier {
     Tier0Interpreter,
     Tier1Baseline,
@@ -35,10 +39,14 @@ pub struct FunctionState {
     pub deopt_count: u32,
     pub type_feedback: HashMap<usize, TypeProfile>,
     pub compiled_code: Option<Vec<u8>>,
+    /// Type speculation baked into `compiled_code`, if any
+    pub guard_plan: Option<optimizing_jit::GuardPlan>,
+    /// Runtime tick of the most recent call, for eviction
+    pub last_executed: u64,
 }
 
 /// Type profile for speculative optimization
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypeProfile {
     pub types_seen: Vec<String>,
     pub dominant_type: Option<String>,
@@ -46,9 +54,16 @@ pub struct TypeProfile {
 }
 
 /// JIT runtime state
+///
+/// Locks are taken `definitions` before `functions`, and compilation holds
+/// only one of them at a time, so writers queued on either lock cannot
+/// deadlock readers.
 pub struct JitRuntime {
     functions: Arc<RwLock<HashMap<String, FunctionState>>>,
+    /// Function bodies; undefined functions are opaque and evaluate to null
+    definitions: RwLock<HashMap<String, interpreter::Expr>>,
     profiler: Arc<profiling::Profiler>,
+    tick: AtomicU64,
     config: JitRuntimeConfig,
 }
 
@@ -59,6 +74,8 @@ pub struct JitRuntimeConfig {
     pub tier3_threshold: u32,
     pub deopt_threshold: u32,
     pub enable_osr: bool,
+    /// Ceiling on the total size of compiled code across all functions
+    pub code_budget_bytes: usize,
 }
 
 impl Default for JitRuntimeConfig {
@@ -69,25 +86,80 @@ impl Default for JitRuntimeConfig {
             tier3_threshold: 1000,
             deopt_threshold: 3,
             enable_osr: true,
+            code_budget_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl JitRuntimeConfig {
+    /// Check that tier thresholds strictly increase and deopts are tolerated
+    pub fn validate(&self) -> Result<(), String> {
+        if self.tier1_threshold >= self.tier2_threshold || self.tier2_threshold >= self.tier3_threshold {
+            return Err(format!(
+                "Tier thresholds must be strictly increasing (tier1 < tier2 < tier3), got {} / {} / {}",
+                self.tier1_threshold, self.tier2_threshold, self.tier3_threshold
+            ));
+        }
+
+        if self.deopt_threshold == 0 {
+            return Err("deopt_threshold must be non-zero".to_string());
         }
+
+        Ok(())
     }
 }
 
 impl JitRuntime {
-    pub fn new(config: JitRuntimeConfig) -> Self {
-        Self {
+    pub fn new(config: JitRuntimeConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        config.validate()?;
+
+        Ok(Self {
             functions: Arc::new(RwLock::new(HashMap::new())),
+            definitions: RwLock::new(HashMap::new()),
             profiler: Arc::new(profiling::Profiler::new()),
+            tick: AtomicU64::new(0),
             config,
+        })
+    }
+
+    /// Register the body of a function, discarding code compiled for any previous body
+    pub fn define(&self, function_id: &str, body: interpreter::Expr) {
+        self.definitions.write().unwrap().insert(function_id.to_string(), body);
+
+        let mut functions = self.functions.write().unwrap();
+        if let Some(state) = functions.get_mut(function_id) {
+            state.current_tier = JitTier::Tier0Interpreter;
+            state.compiled_code = None;
+            state.guard_plan = None;
         }
     }
 
     /// Execute function with adaptive tiering
+    ///
+    /// Arguments and the result are converted between JSON and `JitValue`
+    /// here only; use `execute_values` to skip the conversion.
     pub fn execute(
         &self,
         function_id: &str,
         args: &[serde_json::Value],
     ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let args = args.iter()
+            .enumerate()
+            .map(|(i, value)| {
+                JitValue::from_json(value)
+                    .ok_or_else(|| format!("Argument {} is not a number: {}", i, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.execute_values(function_id, &args)?.to_json())
+    }
+
+    /// Execute function with adaptive tiering, without JSON conversion
+    pub fn execute_values(
+        &self,
+        function_id: &str,
+        args: &[JitValue],
+    ) -> Result<JitValue, Box<dyn std::error::Error>> {
         // Get or create function state
         let mut functions = self.functions.write().unwrap();
         let state = functions.entry(function_id.to_string())
@@ -98,14 +170,19 @@ impl JitRuntime {
                 deopt_count: 0,
                 type_feedback: HashMap::new(),
                 compiled_code: None,
+                guard_plan: None,
+                last_executed: 0,
             });
 
         // Increment call count
         state.call_count += 1;
+        state.last_executed = self.tick.fetch_add(1, Ordering::Relaxed) + 1;
 
         // Check if we should optimize
-        if let Some(target_tier) = self.should_optimize(state) {
-            drop(functions); // Release lock
+        let target_tier = self.should_optimize(state);
+        drop(functions); // Release lock before compiling or executing
+
+        if let Some(target_tier) = target_tier {
             self.optimize_function(function_id, target_tier)?;
         }
 
@@ -125,10 +202,8 @@ impl JitRuntime {
             if state.current_tier < JitTier::Tier2Optimizing {
                 return Some(JitTier::Tier2Optimizing);
             }
-        } else if calls > self.config.tier1_threshold {
-            if state.current_tier < JitTier::Tier1Baseline {
-                return Some(JitTier::Tier1Baseline);
-            }
+        } else if calls > self.config.tier1_threshold && state.current_tier < JitTier::Tier1Baseline {
+            return Some(JitTier::Tier1Baseline);
         }
 
         None
@@ -151,73 +226,161 @@ impl JitRuntime {
         function_id: &str,
         target_tier: JitTier,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        match target_tier {
-            JitTier::Tier1Baseline => {
-                self.compile_baseline(function_id)?;
-            }
+        let (code, guard_plan) = match target_tier {
+            JitTier::Tier1Baseline => (self.compile_baseline(function_id)?, None),
             JitTier::Tier2Optimizing => {
-                self.compile_optimizing(function_id)?;
+                let (code, plan) = self.compile_optimizing(function_id)?;
+                (code, Some(plan))
             }
             JitTier::Tier3MaxOpt => {
-                self.compile_max_opt(function_id)?;
+                // Max-opt code speculates on the same feedback as tier 2
+                let plan = self.with_type_feedback(function_id, optimizing_jit::GuardPlan::from_feedback);
+                (self.compile_max_opt(function_id)?, Some(plan))
             }
-            _ => {}
-        }
+            JitTier::Tier0Interpreter => return Ok(()),
+        };
 
         // Update tier
         let mut functions = self.functions.write().unwrap();
         if let Some(state) = functions.get_mut(function_id) {
             state.current_tier = target_tier;
+            state.compiled_code = Some(code);
+            state.guard_plan = guard_plan;
         }
 
+        self.enforce_code_budget(&mut functions, function_id);
+
         Ok(())
     }
 
+    /// Deoptimize least-recently-executed functions until compiled code fits the budget
+    ///
+    /// The function that was just compiled is never evicted. Evicted functions
+    /// restart their call count, so they must prove hot again before being
+    /// recompiled rather than evicting another function on their next call.
+    fn enforce_code_budget(&self, functions: &mut HashMap<String, FunctionState>, keep: &str) {
+        let mut total: usize = functions.values()
+            .filter_map(|state| state.compiled_code.as_ref())
+            .map(|code| code.len())
+            .sum();
+
+        while total > self.config.code_budget_bytes {
+            let victim = functions.values_mut()
+                .filter(|state| state.compiled_code.is_some() && state.function_id != keep)
+                .min_by_key(|state| state.last_executed);
+
+            let Some(victim) = victim else { break };
+            total -= victim.compiled_code.take().map_or(0, |code| code.len());
+            victim.guard_plan = None;
+            victim.current_tier = JitTier::Tier0Interpreter;
+            victim.call_count = 0;
+        }
+    }
+
+    /// Total size of compiled code currently held
+    pub fn compiled_code_size(&self) -> usize {
+        self.functions.read().unwrap().values()
+            .filter_map(|state| state.compiled_code.as_ref())
+            .map(|code| code.len())
+            .sum()
+    }
+
     fn compile_baseline(
         &self,
         function_id: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Compile with baseline JIT (1-5ms)
-        Ok(())
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        // Compile with baseline JIT (1-5ms)
+        baseline_jit::compile(function_id, self.definition(function_id).as_ref())
     }
 
     fn compile_optimizing(
         &self,
         function_id: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Compile with optimizing JIT (10-50ms)
+    ) -> Result<(Vec<u8>, optimizing_jit::GuardPlan), Box<dyn std::error::Error>> {
         // Use type feedback for speculative optimization
-        Ok(())
+        let body = self.definition(function_id);
+        self.with_type_feedback(function_id, |feedback| {
+            optimizing_jit::compile(function_id, body.as_ref(), feedback)
+        })
+    }
+
+    /// Copy of a function's body, taken without holding any other lock
+    fn definition(&self, function_id: &str) -> Option<interpreter::Expr> {
+        self.definitions.read().unwrap().get(function_id).cloned()
+    }
+
+    fn with_type_feedback<T>(
+        &self,
+        function_id: &str,
+        f: impl FnOnce(&HashMap<usize, TypeProfile>) -> T,
+    ) -> T {
+        let functions = self.functions.read().unwrap();
+        match functions.get(function_id) {
+            Some(state) => f(&state.type_feedback),
+            None => f(&HashMap::new()),
+        }
+    }
+
+    /// Check observed argument types against the function's speculative guards
+    ///
+    /// On a violation the function is deoptimized to the interpreter, its
+    /// compiled code dropped, and the offending type recorded so the site is
+    /// no longer speculated on. Returns whether all guards held.
+    pub fn check_guards(&self, function_id: &str, observed_types: &[(usize, &str)]) -> bool {
+        let violated = {
+            let mut functions = self.functions.write().unwrap();
+            let Some(state) = functions.get_mut(function_id) else { return true };
+            let Some(plan) = &state.guard_plan else { return true };
+            let Some(guard) = plan.violation(observed_types) else { return true };
+
+            let call_site = guard.call_site;
+            state.current_tier = JitTier::Tier0Interpreter;
+            state.compiled_code = None;
+            state.guard_plan = None;
+            state.deopt_count += 1;
+            call_site
+        };
+
+        for (site, ty) in observed_types.iter().filter(|(site, _)| *site == violated) {
+            self.record_type_feedback(function_id, *site, ty);
+        }
+
+        false
     }
 
     fn compile_max_opt(
         &self,
         function_id: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // TODO: Compile with max optimization (100-500ms)
-        // Apply all optimizations: inlining, escape analysis, etc.
-        Ok(())
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        // TODO: Apply all optimizations: inlining, escape analysis, etc.
+        max_opt_jit::compile(function_id, self.definition(function_id).as_ref())
     }
 
     fn execute_at_tier(
         &self,
         function_id: &str,
-        args: &[serde_json::Value],
-    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        args: &[JitValue],
+    ) -> Result<JitValue, Box<dyn std::error::Error>> {
+        // Same order as everywhere else: definitions, then functions
+        let definitions = self.definitions.read().unwrap();
         let functions = self.functions.read().unwrap();
         let state = functions.get(function_id)
             .ok_or("Function not found")?;
 
+        let Some(body) = definitions.get(function_id) else {
+            return Ok(JitValue::Null);
+        };
+
         match state.current_tier {
             JitTier::Tier0Interpreter => {
-                interpreter::execute(function_id, args)
+                interpreter::execute(body, args)
             }
             _ => {
                 // Execute compiled code
                 if let Some(code) = &state.compiled_code {
                     self.execute_compiled(code, args)
                 } else {
-                    interpreter::execute(function_id, args)
+                    interpreter::execute(body, args)
                 }
             }
         }
@@ -226,10 +389,10 @@ impl JitRuntime {
     fn execute_compiled(
         &self,
         code: &[u8],
-        args: &[serde_json::Value],
-    ) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        args: &[JitValue],
+    ) -> Result<JitValue, Box<dyn std::error::Error>> {
         // TODO: Execute compiled native code
-        Ok(serde_json::Value::Null)
+        bytecode::run(code, args)
     }
 
     /// Record type feedback for speculative optimization
@@ -264,6 +427,53 @@ impl JitRuntime {
         }
     }
 
+    /// Snapshot call counts and type feedback for reuse by a later run
+    pub fn export_profile(&self) -> profiling::ProfileSnapshot {
+        let functions = self.functions.read().unwrap();
+        let mut profiles: Vec<_> = functions.values()
+            .map(|state| profiling::FunctionProfile {
+                function_id: state.function_id.clone(),
+                call_count: state.call_count,
+                deopt_count: state.deopt_count,
+                type_feedback: state.type_feedback.clone(),
+            })
+            .collect();
+        profiles.sort_by(|a, b| a.function_id.cmp(&b.function_id));
+
+        profiling::ProfileSnapshot {
+            functions: profiles,
+            loop_iterations: self.profiler.loop_iterations(),
+        }
+    }
+
+    /// Pre-seed function state from an exported profile
+    ///
+    /// Functions start in the interpreter but are promoted on their next call
+    /// to whatever tier the imported counts and feedback justify. Imported
+    /// counts replace those of functions already known to this runtime.
+    pub fn import_profile(&self, snapshot: &profiling::ProfileSnapshot) {
+        let mut functions = self.functions.write().unwrap();
+        for profile in &snapshot.functions {
+            let state = functions.entry(profile.function_id.clone())
+                .or_insert_with(|| FunctionState {
+                    function_id: profile.function_id.clone(),
+                    current_tier: JitTier::Tier0Interpreter,
+                    call_count: 0,
+                    deopt_count: 0,
+                    type_feedback: HashMap::new(),
+                    compiled_code: None,
+                    guard_plan: None,
+                    last_executed: 0,
+                });
+
+            state.call_count = profile.call_count;
+            state.deopt_count = profile.deopt_count;
+            state.type_feedback = profile.type_feedback.clone();
+        }
+
+        self.profiler.seed_loop_iterations(&snapshot.loop_iterations);
+    }
+
     /// On-stack replacement for hot loops
     pub fn osr_compile_loop(
         &self,
@@ -284,7 +494,7 @@ mod tests {
 
     #[test]
     fn test_tiered_compilation() {
-        let runtime = JitRuntime::new(JitRuntimeConfig::default());
+        let runtime = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
 
         // Execute function multiple times
         for i in 0..1100 {
@@ -296,4 +506,267 @@ mod tests {
         let state = functions.get("test_fn").unwrap();
         assert_eq!(state.current_tier, JitTier::Tier3MaxOpt);
     }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(JitRuntimeConfig::default().validate().is_ok());
+
+        let inverted = JitRuntimeConfig {
+            tier1_threshold: 100,
+            tier2_threshold: 50,
+            ..JitRuntimeConfig::default()
+        };
+        let err = JitRuntime::new(inverted).err().unwrap();
+        assert!(err.to_string().contains("strictly increasing"));
+
+        let equal = JitRuntimeConfig {
+            tier2_threshold: 1000,
+            tier3_threshold: 1000,
+            ..JitRuntimeConfig::default()
+        };
+        assert!(equal.validate().is_err());
+
+        let no_deopts = JitRuntimeConfig {
+            deopt_threshold: 0,
+            ..JitRuntimeConfig::default()
+        };
+        assert!(JitRuntime::new(no_deopts).is_err());
+
+        let custom = JitRuntimeConfig {
+            tier1_threshold: 1,
+            tier2_threshold: 2,
+            tier3_threshold: 3,
+            deopt_threshold: 1,
+            ..JitRuntimeConfig::default()
+        };
+        assert!(JitRuntime::new(custom).is_ok());
+    }
+
+    #[test]
+    fn test_code_budget_evicts_least_recent() {
+        // Room for three baseline-compiled functions
+        let config = JitRuntimeConfig {
+            code_budget_bytes: 3 * baseline_jit::CODE_SIZE_HINT,
+            ..JitRuntimeConfig::default()
+        };
+        let runtime = JitRuntime::new(config).unwrap();
+
+        for _ in 0..20 {
+            runtime.execute("hot", &[]).unwrap();
+        }
+        for i in 0..4 {
+            for _ in 0..20 {
+                runtime.execute(&format!("cold_{}", i), &[]).unwrap();
+            }
+            // Keep the hot function recently used
+            runtime.execute("hot", &[]).unwrap();
+        }
+
+        assert!(runtime.compiled_code_size() <= 3 * baseline_jit::CODE_SIZE_HINT);
+
+        let functions = runtime.functions.read().unwrap();
+        assert!(functions["hot"].compiled_code.is_some());
+        assert_eq!(functions["hot"].current_tier, JitTier::Tier1Baseline);
+
+        for evicted in ["cold_0", "cold_1"] {
+            assert!(functions[evicted].compiled_code.is_none());
+            assert_eq!(functions[evicted].current_tier, JitTier::Tier0Interpreter);
+        }
+        for kept in ["cold_2", "cold_3"] {
+            assert!(functions[kept].compiled_code.is_some());
+        }
+    }
+
+    #[test]
+    fn test_evicted_function_is_not_recompiled_immediately() {
+        let config = JitRuntimeConfig {
+            code_budget_bytes: 3 * baseline_jit::CODE_SIZE_HINT,
+            ..JitRuntimeConfig::default()
+        };
+        let runtime = JitRuntime::new(config).unwrap();
+
+        for i in 0..4 {
+            for _ in 0..20 {
+                runtime.execute(&format!("cold_{}", i), &[]).unwrap();
+            }
+        }
+
+        // A single call to the evicted function stays in the interpreter and
+        // leaves the other compiled functions alone
+        runtime.execute("cold_0", &[]).unwrap();
+
+        let functions = runtime.functions.read().unwrap();
+        assert_eq!(functions["cold_0"].current_tier, JitTier::Tier0Interpreter);
+        assert!(functions["cold_0"].compiled_code.is_none());
+        for kept in ["cold_1", "cold_2", "cold_3"] {
+            assert!(functions[kept].compiled_code.is_some(), "{} was evicted", kept);
+        }
+    }
+
+    #[test]
+    fn test_guard_violation_deoptimizes() {
+        let runtime = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
+
+        runtime.execute("add", &[]).unwrap();
+        runtime.record_type_feedback("add", 0, "int");
+        for _ in 0..150 {
+            runtime.execute("add", &[]).unwrap();
+        }
+
+        {
+            let functions = runtime.functions.read().unwrap();
+            let state = &functions["add"];
+            assert_eq!(state.current_tier, JitTier::Tier2Optimizing);
+            assert_eq!(state.guard_plan.as_ref().unwrap().guards, vec![optimizing_jit::TypeGuard {
+                call_site: 0,
+                expected_type: "int".to_string(),
+            }]);
+        }
+
+        // The speculated type keeps the optimized code
+        assert!(runtime.check_guards("add", &[(0, "int")]));
+
+        assert!(!runtime.check_guards("add", &[(0, "string")]));
+        let functions = runtime.functions.read().unwrap();
+        let state = &functions["add"];
+        assert_eq!(state.current_tier, JitTier::Tier0Interpreter);
+        assert_eq!(state.deopt_count, 1);
+        assert!(state.compiled_code.is_none());
+        assert!(state.guard_plan.is_none());
+        assert_eq!(state.type_feedback[&0].types_seen.len(), 2);
+    }
+
+    #[test]
+    fn test_profile_round_trip_skips_rewarming() {
+        let runtime = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
+        runtime.execute("add", &[]).unwrap();
+        runtime.record_type_feedback("add", 0, "int");
+        for _ in 0..150 {
+            runtime.execute("add", &[]).unwrap();
+        }
+        runtime.profiler.record_loop_iteration("add:loop0");
+        assert_eq!(runtime.functions.read().unwrap()["add"].current_tier, JitTier::Tier2Optimizing);
+
+        let json = serde_json::to_string(&runtime.export_profile()).unwrap();
+        let snapshot: profiling::ProfileSnapshot = serde_json::from_str(&json).unwrap();
+
+        let fresh = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
+        fresh.import_profile(&snapshot);
+        {
+            let functions = fresh.functions.read().unwrap();
+            let state = &functions["add"];
+            assert_eq!(state.current_tier, JitTier::Tier0Interpreter);
+            assert_eq!(fresh.should_optimize(state), Some(JitTier::Tier2Optimizing));
+        }
+        assert_eq!(fresh.profiler.get_iteration_count("add:loop0"), 1);
+
+        // A single call reaches the optimizing tier
+        fresh.execute("add", &[]).unwrap();
+        assert_eq!(fresh.functions.read().unwrap()["add"].current_tier, JitTier::Tier2Optimizing);
+    }
+
+    #[test]
+    fn test_tiers_agree_on_results() {
+        use interpreter::Expr;
+
+        let runtime = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
+        let body = Expr::Arg(0) + Expr::Arg(1);
+        runtime.define("add", body.clone());
+
+        let args = [serde_json::json!(2), serde_json::json!(3)];
+        assert_eq!(runtime.execute("add", &args).unwrap(), serde_json::json!(5));
+        assert_eq!(runtime.functions.read().unwrap()["add"].current_tier, JitTier::Tier0Interpreter);
+
+        for _ in 0..1100 {
+            runtime.execute("add", &[serde_json::json!(1.5), serde_json::json!(-4)]).unwrap();
+        }
+        {
+            let functions = runtime.functions.read().unwrap();
+            let state = &functions["add"];
+            assert_eq!(state.current_tier, JitTier::Tier3MaxOpt);
+            assert_eq!(state.compiled_code.as_deref(), Some(bytecode::lower(&body).as_slice()));
+        }
+
+        assert_eq!(runtime.execute("add", &args).unwrap(), serde_json::json!(5));
+        assert_eq!(
+            runtime.execute("add", &[serde_json::json!(1.5), serde_json::json!(-4)]).unwrap(),
+            serde_json::json!(-2.5)
+        );
+
+        // Errors surface identically from the compiled path
+        let err = runtime.execute("add", &[serde_json::json!("x"), serde_json::json!(1)]);
+        assert!(err.unwrap_err().to_string().contains("not a number"));
+        let err = runtime.execute_values("add", &[JitValue::Bool(true), JitValue::Int(1)]);
+        assert!(err.unwrap_err().to_string().contains("not a number"));
+        let division = Expr::Arg(0) / (Expr::Arg(1) - Expr::Const(1.0));
+        let values = [JitValue::Int(2), JitValue::Int(3)];
+        assert!(bytecode::run(&bytecode::lower(&division), &values).is_ok());
+        let ones = [JitValue::Int(1), JitValue::Int(1)];
+        assert_eq!(
+            bytecode::run(&bytecode::lower(&division), &ones).unwrap_err().to_string(),
+            interpreter::execute(&division, &ones).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn test_concurrent_define_and_execute() {
+        use interpreter::Expr;
+
+        let runtime = Arc::new(JitRuntime::new(JitRuntimeConfig::default()).unwrap());
+        let workers: Vec<_> = (0..4)
+            .map(|worker| {
+                let runtime = runtime.clone();
+                std::thread::spawn(move || {
+                    for i in 0..300 {
+                        if (i + worker) % 50 == 0 {
+                            runtime.define("f", Expr::Arg(0) + Expr::Const(i as f64));
+                        }
+                        runtime.execute_values("f", &[JitValue::Int(1)]).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_value_path_matches_json_path() {
+        use interpreter::Expr;
+
+        // Operations pass JitValue by copy, so evaluating one never touches the heap
+        fn assert_copy<T: Copy>() {}
+        assert_copy::<JitValue>();
+
+        // (a * b - a) / 2 + 0.25
+        let body = (Expr::Arg(0) * Expr::Arg(1) - Expr::Arg(0)) / Expr::Const(2.0) + Expr::Const(0.25);
+        let json_runtime = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
+        let value_runtime = JitRuntime::new(JitRuntimeConfig::default()).unwrap();
+        json_runtime.define("f", body.clone());
+        value_runtime.define("f", body.clone());
+
+        // Enough calls to cross every tier on both runtimes
+        for i in -600i64..600 {
+            let json_args = [serde_json::json!(i), serde_json::json!(i as f64 / 4.0)];
+            let value_args = [JitValue::Int(i), JitValue::Float(i as f64 / 4.0)];
+            let expected = json_runtime.execute("f", &json_args).unwrap();
+            let actual = value_runtime.execute_values("f", &value_args).unwrap();
+            assert_eq!(actual.to_json(), expected);
+        }
+        assert_eq!(value_runtime.functions.read().unwrap()["f"].current_tier, JitTier::Tier3MaxOpt);
+
+        // Integer arithmetic stays exact past f64's 53-bit mantissa
+        let big = Expr::Arg(0) + Expr::Arg(1);
+        let max = JitValue::Int(i64::MAX - 1);
+        assert_eq!(
+            interpreter::execute(&big, &[max, JitValue::Int(1)]).unwrap(),
+            JitValue::Int(i64::MAX)
+        );
+        assert_eq!(
+            bytecode::run(&bytecode::lower(&big), &[max, JitValue::Int(1)]).unwrap(),
+            JitValue::Int(i64::MAX)
+        );
+    }
 }
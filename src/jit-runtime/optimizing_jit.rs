@@ -1,9 +1,68 @@
 //! Tier 2: Optimizing JIT (10-50ms compile, 20-50x speed)
 
-pub fn compile(_function_id: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+use super::bytecode;
+use super::interpreter::Expr;
+use super::TypeProfile;
+use std::collections::HashMap;
+
+/// Size of the placeholder code emitted by this tier
+pub const CODE_SIZE_HINT: usize = 256;
+
+/// Minimum type stability for a call site to be speculated on
+pub const GUARD_STABILITY_THRESHOLD: f32 = 0.85;
+
+/// Speculation that a call site only sees one type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeGuard {
+    pub call_site: usize,
+    pub expected_type: String,
+}
+
+/// Guards the compiled code relies on; a violation requires deoptimization
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GuardPlan {
+    pub guards: Vec<TypeGuard>,
+}
+
+impl GuardPlan {
+    /// Guard every call site whose feedback is stable enough to speculate on
+    pub fn from_feedback(type_feedback: &HashMap<usize, TypeProfile>) -> Self {
+        let mut guards: Vec<TypeGuard> = type_feedback.iter()
+            .filter(|(_, profile)| profile.type_stability >= GUARD_STABILITY_THRESHOLD)
+            .filter_map(|(&call_site, profile)| {
+                profile.dominant_type.clone().map(|expected_type| TypeGuard {
+                    call_site,
+                    expected_type,
+                })
+            })
+            .collect();
+        guards.sort_by_key(|guard| guard.call_site);
+
+        Self { guards }
+    }
+
+    /// First guard contradicted by the observed (call site, type) pairs
+    pub fn violation<'a>(&'a self, observed_types: &[(usize, &str)]) -> Option<&'a TypeGuard> {
+        self.guards.iter().find(|guard| {
+            observed_types.iter()
+                .any(|(site, ty)| *site == guard.call_site && *ty != guard.expected_type)
+        })
+    }
+}
+
+pub fn compile(
+    _function_id: &str,
+    body: Option<&Expr>,
+    type_feedback: &HashMap<usize, TypeProfile>,
+) -> Result<(Vec<u8>, GuardPlan), Box<dyn std::error::Error>> {
     // TODO: Implement optimizing compilation
     // - Inline caching
     // - Type specialization
     // - Basic escape analysis
-    Ok(Vec::new())
+    let code = match body {
+        Some(body) => bytecode::lower(body),
+        // Placeholder of typical size for this tier until codegen lands
+        None => vec![0; CODE_SIZE_HINT],
+    };
+    Ok((code, GuardPlan::from_feedback(type_feedback)))
 }
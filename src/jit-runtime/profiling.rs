@@ -1,8 +1,26 @@
 //! Runtime profiling for adaptive optimization
 
+use super::TypeProfile;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::RwLock;
 
+/// Persisted profile of one function
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionProfile {
+    pub function_id: String,
+    pub call_count: u32,
+    pub deopt_count: u32,
+    pub type_feedback: HashMap<usize, TypeProfile>,
+}
+
+/// Profile data carried across runs to skip re-warming
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileSnapshot {
+    pub functions: Vec<FunctionProfile>,
+    pub loop_iterations: HashMap<String, u32>,
+}
+
 pub struct Profiler {
     loop_iterations: RwLock<HashMap<String, u32>>,
 }
@@ -23,4 +41,22 @@ impl Profiler {
         let iterations = self.loop_iterations.read().unwrap();
         *iterations.get(loop_id).unwrap_or(&0)
     }
+
+    pub fn loop_iterations(&self) -> HashMap<String, u32> {
+        self.loop_iterations.read().unwrap().clone()
+    }
+
+    /// Add previously recorded iteration counts
+    pub fn seed_loop_iterations(&self, counts: &HashMap<String, u32>) {
+        let mut iterations = self.loop_iterations.write().unwrap();
+        for (loop_id, count) in counts {
+            *iterations.entry(loop_id.clone()).or_insert(0) += count;
+        }
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
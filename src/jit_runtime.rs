@@ -1,24 +0,0 @@
-//! JIT Runtime Module
-//!
-//! Stub module for JIT runtime features.
-//! TODO: Implement JIT runtime functionality
-
-#![allow(dead_code)]
-
-/// JIT Runtime stub
-pub struct JitRuntime {
-    // TODO: Add fields
-}
-
-impl JitRuntime {
-    /// Create new JIT runtime
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
-impl Default for JitRuntime {
-    fn default() -> Self {
-        Self::new()
-    }
-}
@@ -6,11 +6,17 @@
 
 pub mod agentdb;
 pub mod llm_compiler;
+#[path = "jit-runtime/mod.rs"]
 pub mod jit_runtime;
+#[path = "multi-lane/mod.rs"]
 pub mod multi_lane;
 
 // Re-exports for convenience
 pub use agentdb::{AgentDb, AgentDbConfig, Episode, SemanticFact};
 pub use llm_compiler::LlmCompiler;
 pub use jit_runtime::JitRuntime;
-pub use multi_lane::MultiLane;
+pub use multi_lane::LaneRouter;
+
+/// Former placeholder for the lane router
+#[deprecated(since = "0.3.0", note = "use `LaneRouter`, which replaced the placeholder")]
+pub type MultiLane = LaneRouter;
@@ -12,12 +12,19 @@ use std::collections::HashMap;
 use std::sync::RwLock;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Default alerting threshold for the RMS prediction error (5%)
+pub const DEFAULT_PREDICTION_ERROR_THRESHOLD: f32 = 0.05;
+
 /// Cost statistics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostStats {
     pub total_cost: f32,
     pub cost_by_provider: HashMap<Provider, f32>,
     pub cost_variance: f32,
+    /// Root-mean-square relative error of realized vs predicted cost
+    pub prediction_rms_error: f32,
+    /// Set when `prediction_rms_error` exceeds the tracker's threshold
+    pub prediction_error_alert: bool,
     pub predicted_monthly_cost: f32,
     pub savings_vs_anthropic_only: f32,
 }
@@ -25,19 +32,56 @@ pub struct CostStats {
 /// Individual cost record
 #[derive(Debug, Clone)]
 struct CostRecord {
+    #[allow(dead_code)]
     provider: Provider,
     tokens: u32,
     cost: f32,
+    #[allow(dead_code)]
     latency: Duration,
     timestamp: u64,
 }
 
+/// Running error between predicted and realized cost
+///
+/// Squared relative errors are accumulated rather than a variance around
+/// their mean, so a prediction that is consistently off still alerts.
+#[derive(Debug, Clone, Default)]
+struct PredictionError {
+    samples: u32,
+    sum_squared: f32,
+}
+
+impl PredictionError {
+    fn record(&mut self, predicted: f32, realized: f32) {
+        let relative = if predicted > 0.0 {
+            (realized - predicted) / predicted
+        } else if realized > 0.0 {
+            1.0 // Predicted free, charged anyway
+        } else {
+            0.0
+        };
+
+        self.samples += 1;
+        self.sum_squared += relative * relative;
+    }
+
+    fn rms(&self) -> f32 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            (self.sum_squared / self.samples as f32).sqrt()
+        }
+    }
+}
+
 /// Cost tracker
 pub struct CostTracker {
     records: RwLock<Vec<CostRecord>>,
     budget: RwLock<CostBudget>,
     total_cost: RwLock<f32>,
     cost_by_provider: RwLock<HashMap<Provider, f32>>,
+    prediction_error: RwLock<PredictionError>,
+    prediction_error_threshold: f32,
 }
 
 impl CostTracker {
@@ -54,11 +98,35 @@ impl CostTracker {
             budget: RwLock::new(budget),
             total_cost: RwLock::new(0.0),
             cost_by_provider: RwLock::new(HashMap::new()),
+            prediction_error: RwLock::new(PredictionError::default()),
+            prediction_error_threshold: DEFAULT_PREDICTION_ERROR_THRESHOLD,
         }
     }
 
-    /// Record inference cost
+    /// Set the RMS prediction error above which `CostStats::prediction_error_alert` is raised
+    pub fn with_prediction_error_threshold(mut self, threshold: f32) -> Self {
+        self.prediction_error_threshold = threshold;
+        self
+    }
+
+    /// Record inference cost against the cost predicted before routing
     pub async fn record_inference(
+        &self,
+        provider: Provider,
+        tokens: u32,
+        predicted_cost: f32,
+        cost: f32,
+        latency: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.prediction_error.write().unwrap().record(predicted_cost, cost);
+        self.charge(provider, tokens, cost, latency).await
+    }
+
+    /// Record a cost that was estimated rather than observed
+    ///
+    /// Counts toward the totals and the budget, but not toward the
+    /// prediction error, since there is no realized cost to compare.
+    pub async fn charge(
         &self,
         provider: Provider,
         tokens: u32,
@@ -109,6 +177,7 @@ impl CostTracker {
 
         // Calculate variance
         let variance = self.calculate_variance();
+        let prediction_rms_error = self.prediction_error.read().unwrap().rms();
 
         // Predict monthly cost
         let records = self.records.read().unwrap();
@@ -137,6 +206,8 @@ impl CostTracker {
             total_cost,
             cost_by_provider,
             cost_variance: variance,
+            prediction_rms_error,
+            prediction_error_alert: prediction_rms_error > self.prediction_error_threshold,
             predicted_monthly_cost,
             savings_vs_anthropic_only,
         }
@@ -180,6 +251,12 @@ impl CostTracker {
     }
 }
 
+impl Default for CostTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,6 +270,7 @@ mod tests {
             Provider::OnnxLocal,
             1000,
             0.0,
+            0.0,
             Duration::from_millis(50),
         ).await.unwrap();
 
@@ -200,11 +278,45 @@ mod tests {
             Provider::Anthropic,
             1000,
             0.10,
+            0.10,
             Duration::from_millis(100),
         ).await.unwrap();
 
         let stats = tracker.stats().await;
         assert_eq!(stats.total_cost, 0.10);
+        assert_eq!(stats.prediction_rms_error, 0.0);
+        assert!(!stats.prediction_error_alert);
+    }
+
+    #[tokio::test]
+    async fn test_prediction_error_alert() {
+        let tracker = CostTracker::new().with_prediction_error_threshold(0.10);
+
+        // Realized costs 20% above and below the prediction
+        for realized in [0.12, 0.08, 0.12, 0.08] {
+            tracker.record_inference(
+                Provider::Anthropic,
+                1000,
+                0.10,
+                realized,
+                Duration::from_millis(100),
+            ).await.unwrap();
+        }
+
+        let stats = tracker.stats().await;
+        assert!((stats.prediction_rms_error - 0.2).abs() < 1e-3);
+        assert!(stats.prediction_error_alert);
+
+        // The same divergence stays quiet under a looser threshold
+        let tolerant = CostTracker::new().with_prediction_error_threshold(0.25);
+        tolerant.record_inference(
+            Provider::Anthropic,
+            1000,
+            0.10,
+            0.12,
+            Duration::from_millis(100),
+        ).await.unwrap();
+        assert!(!tolerant.stats().await.prediction_error_alert);
     }
 
     #[tokio::test]
@@ -21,3 +21,9 @@ impl LaneSelector {
         Ok(Provider::OnnxLocal)
     }
 }
+
+impl Default for LaneSelector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
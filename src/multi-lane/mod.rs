@@ -13,10 +13,14 @@
 pub mod lane_selector;
 pub mod cost_tracker;
 pub mod performance_predictor;
+pub mod rate_limiter;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::{Arc, RwLock};
+use std::task::Poll;
 use std::time::Duration;
 
 /// Provider lane
@@ -48,12 +52,78 @@ pub struct InferenceLane {
     pub rate_limit: RateLimit,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RateLimit {
     pub requests_per_minute: u32,
     pub tokens_per_minute: u32,
 }
 
+/// Deployment-supplied description of a lane
+///
+/// Latencies are in milliseconds so specs can be written directly in JSON
+/// or TOML config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LaneSpec {
+    pub provider: Provider,
+    pub latency_p50_ms: u64,
+    pub latency_p99_ms: u64,
+    pub cost_per_token: f32,
+    pub availability: f32,
+    pub rate_limit: RateLimit,
+}
+
+impl From<LaneSpec> for InferenceLane {
+    fn from(spec: LaneSpec) -> Self {
+        InferenceLane {
+            provider: spec.provider,
+            latency_p50: Duration::from_millis(spec.latency_p50_ms),
+            latency_p99: Duration::from_millis(spec.latency_p99_ms),
+            cost_per_token: spec.cost_per_token,
+            availability: spec.availability,
+            rate_limit: spec.rate_limit,
+        }
+    }
+}
+
+/// The built-in lanes used by `LaneRouter::new`
+pub fn default_specs() -> Vec<LaneSpec> {
+    vec![
+        LaneSpec {
+            provider: Provider::OnnxLocal,
+            latency_p50_ms: 50,
+            latency_p99_ms: 200,
+            cost_per_token: 0.0, // Free local inference
+            availability: 0.99,
+            rate_limit: RateLimit {
+                requests_per_minute: 1000,
+                tokens_per_minute: 100000,
+            },
+        },
+        LaneSpec {
+            provider: Provider::Anthropic,
+            latency_p50_ms: 100,
+            latency_p99_ms: 300,
+            cost_per_token: 0.0001, // $0.10 per 1K tokens
+            availability: 0.999,
+            rate_limit: RateLimit {
+                requests_per_minute: 60,
+                tokens_per_minute: 100000,
+            },
+        },
+        LaneSpec {
+            provider: Provider::OpenRouter,
+            latency_p50_ms: 150,
+            latency_p99_ms: 500,
+            cost_per_token: 0.00005, // $0.05 per 1K tokens
+            availability: 0.95,
+            rate_limit: RateLimit {
+                requests_per_minute: 100,
+                tokens_per_minute: 200000,
+            },
+        },
+    ]
+}
+
 /// Cost budget for quota enforcement
 #[derive(Debug, Clone)]
 pub struct CostBudget {
@@ -81,11 +151,32 @@ pub enum RequestPriority {
     Critical,
 }
 
+/// Pending result of a call to a provider
+pub type LaneFuture<'a> = Pin<Box<
+    dyn Future<Output = Result<InferenceResponse, Box<dyn std::error::Error + Send + Sync>>> + Send + 'a,
+>>;
+
+/// Transport used to call providers, replacing the built-in stubs
+pub trait LaneBackend: Send + Sync {
+    fn execute<'a>(&'a self, provider: Provider, request: &'a InferenceRequest) -> LaneFuture<'a>;
+}
+
+/// Which lanes of a race are charged to the cost tracker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaceCostPolicy {
+    /// Only the winning lane's realized cost
+    WinnerOnly,
+    /// The winner's realized cost plus the predicted cost of every other lane
+    AllDispatched,
+}
+
 /// Multi-lane router
 pub struct LaneRouter {
     lanes: Arc<RwLock<HashMap<Provider, InferenceLane>>>,
     cost_tracker: Arc<cost_tracker::CostTracker>,
     predictor: Arc<performance_predictor::PerformancePredictor>,
+    rate_limiter: rate_limiter::RateLimiter,
+    backend: Option<Arc<dyn LaneBackend>>,
     config: LaneRouterConfig,
 }
 
@@ -96,6 +187,7 @@ pub struct LaneRouterConfig {
     pub availability_weight: f32,
     pub enable_adaptive_routing: bool,
     pub enable_cost_prediction: bool,
+    pub race_cost: RaceCostPolicy,
 }
 
 impl Default for LaneRouterConfig {
@@ -106,59 +198,49 @@ impl Default for LaneRouterConfig {
             availability_weight: 0.2,
             enable_adaptive_routing: true,
             enable_cost_prediction: true,
+            race_cost: RaceCostPolicy::WinnerOnly,
         }
     }
 }
 
 impl LaneRouter {
+    /// Create a router with the built-in lanes from `default_specs`
     pub fn new(config: LaneRouterConfig) -> Self {
-        let mut lanes = HashMap::new();
-
-        // Initialize default lanes
-        lanes.insert(Provider::OnnxLocal, InferenceLane {
-            provider: Provider::OnnxLocal,
-            latency_p50: Duration::from_millis(50),
-            latency_p99: Duration::from_millis(200),
-            cost_per_token: 0.0, // Free local inference
-            availability: 0.99,
-            rate_limit: RateLimit {
-                requests_per_minute: 1000,
-                tokens_per_minute: 100000,
-            },
-        });
-
-        lanes.insert(Provider::Anthropic, InferenceLane {
-            provider: Provider::Anthropic,
-            latency_p50: Duration::from_millis(100),
-            latency_p99: Duration::from_millis(300),
-            cost_per_token: 0.0001, // $0.10 per 1K tokens
-            availability: 0.999,
-            rate_limit: RateLimit {
-                requests_per_minute: 60,
-                tokens_per_minute: 100000,
-            },
-        });
+        Self::from_specs(config, default_specs())
+    }
 
-        lanes.insert(Provider::OpenRouter, InferenceLane {
-            provider: Provider::OpenRouter,
-            latency_p50: Duration::from_millis(150),
-            latency_p99: Duration::from_millis(500),
-            cost_per_token: 0.00005, // $0.05 per 1K tokens
-            availability: 0.95,
-            rate_limit: RateLimit {
-                requests_per_minute: 100,
-                tokens_per_minute: 200000,
-            },
-        });
+    /// Create a router with deployment-specific lanes
+    ///
+    /// A later spec for the same provider replaces an earlier one.
+    /// Providers without a spec are never routed to.
+    pub fn from_specs(config: LaneRouterConfig, specs: Vec<LaneSpec>) -> Self {
+        let lanes: HashMap<_, _> = specs
+            .into_iter()
+            .map(|spec| (spec.provider, InferenceLane::from(spec)))
+            .collect();
 
         Self {
             lanes: Arc::new(RwLock::new(lanes)),
             cost_tracker: Arc::new(cost_tracker::CostTracker::new()),
             predictor: Arc::new(performance_predictor::PerformancePredictor::new()),
+            rate_limiter: rate_limiter::RateLimiter::default(),
+            backend: None,
             config,
         }
     }
 
+    /// Use a custom time source for rate-limit refills
+    pub fn with_clock(mut self, clock: Arc<dyn rate_limiter::Clock>) -> Self {
+        self.rate_limiter = rate_limiter::RateLimiter::new(clock);
+        self
+    }
+
+    /// Call providers through a custom backend
+    pub fn with_backend(mut self, backend: Arc<dyn LaneBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
     /// Route request to optimal lane
     pub async fn route(
         &self,
@@ -167,9 +249,9 @@ impl LaneRouter {
         // Get current budget
         let budget = self.cost_tracker.current_budget().await;
 
-        // Filter candidates by budget and latency
-        let lanes = self.lanes.read().unwrap();
-        let candidates: Vec<_> = lanes.values()
+        // Filter candidates by budget, latency and rate limit; the lane
+        // table lock is released before scoring awaits
+        let candidates: Vec<InferenceLane> = self.lanes.read().unwrap().values()
             .filter(|lane| {
                 let estimated_cost = lane.cost_per_token * request.estimated_tokens as f32 / 1000.0;
                 let within_budget = estimated_cost <= budget.remaining;
@@ -177,9 +259,15 @@ impl LaneRouter {
                     .map(|req| lane.latency_p99 <= req)
                     .unwrap_or(true);
                 let available = lane.availability > 0.95;
+                let admitted = self.rate_limiter.can_admit(
+                    lane.provider,
+                    &lane.rate_limit,
+                    request.estimated_tokens,
+                );
 
-                within_budget && meets_latency && available
+                within_budget && meets_latency && available && admitted
             })
+            .cloned()
             .collect();
 
         if candidates.is_empty() {
@@ -187,16 +275,24 @@ impl LaneRouter {
         }
 
         // Score candidates
-        let best_lane = self.select_best_lane(&candidates, request, &budget).await?;
+        let best_lane = self.select_best_lane(&candidates, request).await?;
+
+        // Reserve the request slot and tokens on the chosen lane
+        if !self.rate_limiter.try_acquire(
+            best_lane.provider,
+            &best_lane.rate_limit,
+            request.estimated_tokens,
+        ) {
+            return Err("Rate limit exceeded for selected lane".into());
+        }
 
         Ok(best_lane.provider)
     }
 
     async fn select_best_lane(
         &self,
-        candidates: &[&InferenceLane],
+        candidates: &[InferenceLane],
         request: &InferenceRequest,
-        budget: &CostBudget,
     ) -> Result<InferenceLane, Box<dyn std::error::Error>> {
         let mut best_score = f32::MIN;
         let mut best_lane = None;
@@ -209,7 +305,7 @@ impl LaneRouter {
 
             if score > best_score {
                 best_score = score;
-                best_lane = Some((*lane).clone());
+                best_lane = Some(lane.clone());
             }
         }
 
@@ -248,21 +344,145 @@ impl LaneRouter {
         provider: Provider,
         request: &InferenceRequest,
     ) -> Result<InferenceResponse, Box<dyn std::error::Error>> {
+        // Prediction made before the call, compared against the realized cost
+        let predicted_cost = self.predicted_cost(provider, request);
+
         let start = std::time::Instant::now();
+        let response = self.dispatch(provider, request).await?;
+        let latency = start.elapsed();
 
-        // Execute based on provider
-        let response = match provider {
-            Provider::OnnxLocal => self.execute_onnx_local(request).await?,
-            Provider::Anthropic => self.execute_anthropic(request).await?,
-            Provider::OpenRouter => self.execute_openrouter(request).await?,
+        self.record(provider, predicted_cost, &response, latency).await?;
+
+        Ok(response)
+    }
+
+    /// Send a request to several lanes at once and keep the first success
+    ///
+    /// Only lanes whose estimated cost fits the remaining budget and whose
+    /// rate limit admits the request are raced; each reserves its slot and
+    /// tokens as `route` would. Lanes still in flight when a winner arrives
+    /// are dropped, which cancels them. Costs are charged according to
+    /// `LaneRouterConfig::race_cost`.
+    pub async fn race(
+        &self,
+        request: &InferenceRequest,
+        lanes: &[Provider],
+    ) -> Result<InferenceResponse, Box<dyn std::error::Error>> {
+        if lanes.is_empty() {
+            return Err("No lanes to race".into());
+        }
+
+        let budget = self.cost_tracker.current_budget().await;
+        let lanes: Vec<Provider> = {
+            let table = self.lanes.read().unwrap();
+            lanes.iter()
+                .copied()
+                .filter(|provider| {
+                    table.get(provider).is_some_and(|lane| {
+                        let estimated_cost = lane.cost_per_token * request.estimated_tokens as f32 / 1000.0;
+                        estimated_cost <= budget.remaining
+                            && self.rate_limiter.try_acquire(
+                                lane.provider,
+                                &lane.rate_limit,
+                                request.estimated_tokens,
+                            )
+                    })
+                })
+                .collect()
         };
+        if lanes.is_empty() {
+            return Err("No raced lane is within budget and rate limits".into());
+        }
+
+        let start = std::time::Instant::now();
+        let mut pending: Vec<_> = lanes.iter()
+            .map(|&provider| Box::pin(async move {
+                (provider, self.dispatch(provider, request).await)
+            }))
+            .collect();
+
+        let mut last_error = None;
+        let winner = std::future::poll_fn(|cx| {
+            let mut i = 0;
+            while i < pending.len() {
+                match pending[i].as_mut().poll(cx) {
+                    Poll::Ready((provider, Ok(response))) => {
+                        return Poll::Ready(Some((provider, response)));
+                    }
+                    Poll::Ready((_, Err(e))) => {
+                        last_error = Some(e);
+                        drop(pending.swap_remove(i));
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+
+            if pending.is_empty() { Poll::Ready(None) } else { Poll::Pending }
+        }).await;
 
+        // Cancel the lanes that lost
+        drop(pending);
+
+        let (winner, response) = match winner {
+            Some(won) => won,
+            None => return Err(last_error.unwrap_or_else(|| "All lanes failed".into())),
+        };
         let latency = start.elapsed();
 
+        self.record(winner, self.predicted_cost(winner, request), &response, latency).await?;
+
+        if self.config.race_cost == RaceCostPolicy::AllDispatched {
+            for &provider in lanes.iter().filter(|&&p| p != winner) {
+                // Losers never report a cost, so the estimate is charged
+                // without counting as a prediction sample
+                self.cost_tracker.charge(
+                    provider,
+                    request.estimated_tokens,
+                    self.predicted_cost(provider, request),
+                    latency,
+                ).await?;
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn predicted_cost(&self, provider: Provider, request: &InferenceRequest) -> f32 {
+        self.lanes.read().unwrap()
+            .get(&provider)
+            .map(|lane| lane.cost_per_token * request.estimated_tokens as f32 / 1000.0)
+            .unwrap_or(0.0)
+    }
+
+    async fn dispatch(
+        &self,
+        provider: Provider,
+        request: &InferenceRequest,
+    ) -> Result<InferenceResponse, Box<dyn std::error::Error>> {
+        if let Some(backend) = &self.backend {
+            return backend.execute(provider, request).await.map_err(|e| e as Box<dyn std::error::Error>);
+        }
+
+        // Execute based on provider
+        match provider {
+            Provider::OnnxLocal => self.execute_onnx_local(request).await,
+            Provider::Anthropic => self.execute_anthropic(request).await,
+            Provider::OpenRouter => self.execute_openrouter(request).await,
+        }
+    }
+
+    async fn record(
+        &self,
+        provider: Provider,
+        predicted_cost: f32,
+        response: &InferenceResponse,
+        latency: Duration,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Track cost
         self.cost_tracker.record_inference(
             provider,
             response.tokens_used,
+            predicted_cost,
             response.cost,
             latency,
         ).await?;
@@ -272,7 +492,7 @@ impl LaneRouter {
             self.predictor.update(provider, latency, response.cost).await?;
         }
 
-        Ok(response)
+        Ok(())
     }
 
     async fn execute_onnx_local(
@@ -293,7 +513,7 @@ impl LaneRouter {
     ) -> Result<InferenceResponse, Box<dyn std::error::Error>> {
         // TODO: Call Anthropic API
         let lanes = self.lanes.read().unwrap();
-        let lane = lanes.get(&Provider::Anthropic).unwrap();
+        let lane = lanes.get(&Provider::Anthropic).ok_or("Anthropic lane not configured")?;
         let cost = lane.cost_per_token * request.estimated_tokens as f32 / 1000.0;
 
         Ok(InferenceResponse {
@@ -309,7 +529,7 @@ impl LaneRouter {
     ) -> Result<InferenceResponse, Box<dyn std::error::Error>> {
         // TODO: Call OpenRouter API
         let lanes = self.lanes.read().unwrap();
-        let lane = lanes.get(&Provider::OpenRouter).unwrap();
+        let lane = lanes.get(&Provider::OpenRouter).ok_or("OpenRouter lane not configured")?;
         let cost = lane.cost_per_token * request.estimated_tokens as f32 / 1000.0;
 
         Ok(InferenceResponse {
@@ -353,6 +573,229 @@ mod tests {
         println!("Selected provider: {:?}", provider);
     }
 
+    #[tokio::test]
+    async fn test_router_from_custom_specs() {
+        // A deployment where the hosted lane is free and local inference is not
+        let specs: Vec<LaneSpec> = serde_json::from_str(r#"[
+            {"provider": "OnnxLocal", "latency_p50_ms": 80, "latency_p99_ms": 200,
+             "cost_per_token": 0.002, "availability": 0.99,
+             "rate_limit": {"requests_per_minute": 1000, "tokens_per_minute": 100000}},
+            {"provider": "Anthropic", "latency_p50_ms": 80, "latency_p99_ms": 200,
+             "cost_per_token": 0.0, "availability": 0.99,
+             "rate_limit": {"requests_per_minute": 60, "tokens_per_minute": 100000}}
+        ]"#).unwrap();
+        let router = LaneRouter::from_specs(LaneRouterConfig::default(), specs);
+
+        let request = InferenceRequest {
+            prompt: "test".to_string(),
+            estimated_tokens: 1000,
+            max_tokens: 2000,
+            latency_requirement: None,
+            priority: RequestPriority::Low,
+        };
+        assert_eq!(router.route(&request).await.unwrap(), Provider::Anthropic);
+
+        // The unconfigured lane is never available
+        assert!(router.execute_inference(Provider::OpenRouter, &request).await.is_err());
+
+        // The built-in lanes pick local inference for the same request
+        let default_router = LaneRouter::new(LaneRouterConfig::default());
+        assert_eq!(default_router.route(&request).await.unwrap(), Provider::OnnxLocal);
+    }
+
+    struct ManualClock(std::sync::Mutex<std::time::Instant>);
+
+    impl rate_limiter::Clock for ManualClock {
+        fn now(&self) -> std::time::Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn lane_spec(provider: Provider, cost_per_token: f32, rate_limit: RateLimit) -> LaneSpec {
+        LaneSpec {
+            provider,
+            latency_p50_ms: 100,
+            latency_p99_ms: 300,
+            cost_per_token,
+            availability: 0.99,
+            rate_limit,
+        }
+    }
+
+    fn small_request(estimated_tokens: u32) -> InferenceRequest {
+        InferenceRequest {
+            prompt: "test".to_string(),
+            estimated_tokens,
+            max_tokens: 2000,
+            latency_requirement: None,
+            priority: RequestPriority::Low,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drained_lane_skipped_until_refill() {
+        let clock = Arc::new(ManualClock(std::sync::Mutex::new(std::time::Instant::now())));
+        let specs = vec![
+            lane_spec(Provider::OnnxLocal, 0.0, RateLimit {
+                requests_per_minute: 2,
+                tokens_per_minute: 100000,
+            }),
+            lane_spec(Provider::Anthropic, 0.0001, RateLimit {
+                requests_per_minute: 60,
+                tokens_per_minute: 100000,
+            }),
+        ];
+        let router = LaneRouter::from_specs(LaneRouterConfig::default(), specs)
+            .with_clock(clock.clone());
+        let request = small_request(100);
+
+        assert_eq!(router.route(&request).await.unwrap(), Provider::OnnxLocal);
+        assert_eq!(router.route(&request).await.unwrap(), Provider::OnnxLocal);
+
+        // Local request slots are exhausted, so the hosted lane takes over
+        assert_eq!(router.route(&request).await.unwrap(), Provider::Anthropic);
+
+        // Two requests per minute refill one slot every 30 seconds
+        *clock.0.lock().unwrap() += Duration::from_secs(30);
+        assert_eq!(router.route(&request).await.unwrap(), Provider::OnnxLocal);
+        assert_eq!(router.route(&request).await.unwrap(), Provider::Anthropic);
+    }
+
+    #[tokio::test]
+    async fn test_large_request_exceeds_token_bucket() {
+        let specs = vec![
+            lane_spec(Provider::OnnxLocal, 0.0, RateLimit {
+                requests_per_minute: 1000,
+                tokens_per_minute: 5000,
+            }),
+        ];
+        let router = LaneRouter::from_specs(LaneRouterConfig::default(), specs);
+
+        assert!(router.route(&small_request(8000)).await.is_err());
+
+        // Token reservations accumulate until the bucket runs dry
+        assert!(router.route(&small_request(3000)).await.is_ok());
+        assert!(router.route(&small_request(3000)).await.is_err());
+        assert!(router.route(&small_request(2000)).await.is_ok());
+    }
+
+    /// Answers from Anthropic after 10ms and from OpenRouter after a second
+    struct FastSlowBackend {
+        slow_cancelled: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    /// Flags the slow lane as cancelled if it is dropped before finishing
+    struct CancelGuard(Arc<std::sync::atomic::AtomicBool>, bool);
+
+    impl Drop for CancelGuard {
+        fn drop(&mut self) {
+            if !self.1 {
+                self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+    }
+
+    impl LaneBackend for FastSlowBackend {
+        fn execute<'a>(&'a self, provider: Provider, request: &'a InferenceRequest) -> LaneFuture<'a> {
+            Box::pin(async move {
+                let delay = match provider {
+                    Provider::OpenRouter => Duration::from_secs(1),
+                    _ => Duration::from_millis(10),
+                };
+                let mut guard = CancelGuard(self.slow_cancelled.clone(), provider != Provider::OpenRouter);
+                tokio::time::sleep(delay).await;
+                guard.1 = true;
+
+                Ok(InferenceResponse {
+                    content: format!("{} response", provider.as_str()),
+                    tokens_used: request.estimated_tokens,
+                    cost: 0.1,
+                })
+            })
+        }
+    }
+
+    fn racing_router(race_cost: RaceCostPolicy) -> (LaneRouter, Arc<std::sync::atomic::AtomicBool>) {
+        let slow_cancelled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let config = LaneRouterConfig { race_cost, ..LaneRouterConfig::default() };
+        let router = LaneRouter::new(config)
+            .with_backend(Arc::new(FastSlowBackend { slow_cancelled: slow_cancelled.clone() }));
+        (router, slow_cancelled)
+    }
+
+    #[tokio::test]
+    async fn test_race_fast_lane_wins() {
+        tokio::time::pause();
+        let (router, slow_cancelled) = racing_router(RaceCostPolicy::WinnerOnly);
+        let mut request = small_request(1000);
+        request.priority = RequestPriority::Critical;
+
+        let start = tokio::time::Instant::now();
+        let response = router.race(&request, &[Provider::OpenRouter, Provider::Anthropic]).await.unwrap();
+
+        // The paused clock stops short of the slow lane's reply
+        assert_eq!(response.content, format!("{} response", Provider::Anthropic.as_str()));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(slow_cancelled.load(std::sync::atomic::Ordering::SeqCst));
+
+        let stats = router.cost_stats().await;
+        assert!(stats.cost_by_provider.contains_key(&Provider::Anthropic));
+        assert!(!stats.cost_by_provider.contains_key(&Provider::OpenRouter));
+    }
+
+    #[tokio::test]
+    async fn test_race_can_charge_every_lane() {
+        let (router, _) = racing_router(RaceCostPolicy::AllDispatched);
+        let request = small_request(1000);
+
+        router.race(&request, &[Provider::OpenRouter, Provider::Anthropic]).await.unwrap();
+
+        let stats = router.cost_stats().await;
+        assert!(stats.cost_by_provider.contains_key(&Provider::Anthropic));
+        assert!(stats.cost_by_provider.contains_key(&Provider::OpenRouter));
+        assert!(router.race(&request, &[]).await.is_err());
+
+        // The loser's estimate is not a prediction sample: the winner's
+        // realized cost of 0.1 against its prediction is the only one
+        let predicted = router.predicted_cost(Provider::Anthropic, &request);
+        let expected = ((0.1 - predicted) / predicted).abs();
+        assert!((stats.prediction_rms_error - expected).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_race_skips_lanes_over_rate_limit_or_budget() {
+        tokio::time::pause();
+        let slow_cancelled = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let specs = vec![
+            lane_spec(Provider::Anthropic, 0.0001, RateLimit {
+                requests_per_minute: 1,
+                tokens_per_minute: 100000,
+            }),
+            lane_spec(Provider::OpenRouter, 0.0001, RateLimit {
+                requests_per_minute: 60,
+                tokens_per_minute: 100000,
+            }),
+            // Estimated at $200 for 1000 tokens, over the $100 budget
+            lane_spec(Provider::OnnxLocal, 200.0, RateLimit {
+                requests_per_minute: 60,
+                tokens_per_minute: 100000,
+            }),
+        ];
+        let router = LaneRouter::from_specs(LaneRouterConfig::default(), specs)
+            .with_backend(Arc::new(FastSlowBackend { slow_cancelled }));
+        let request = small_request(1000);
+        let all = [Provider::OpenRouter, Provider::Anthropic, Provider::OnnxLocal];
+
+        // The fast lane spends its only request slot on the first race
+        let first = router.race(&request, &all).await.unwrap();
+        assert_eq!(first.content, format!("{} response", Provider::Anthropic.as_str()));
+        let second = router.race(&request, &all).await.unwrap();
+        assert_eq!(second.content, format!("{} response", Provider::OpenRouter.as_str()));
+
+        assert!(router.race(&request, &[Provider::OnnxLocal]).await.is_err());
+        assert!(!router.cost_stats().await.cost_by_provider.contains_key(&Provider::OnnxLocal));
+    }
+
     #[tokio::test]
     async fn test_cost_savings() {
         // Test 30%+ cost savings vs single provider
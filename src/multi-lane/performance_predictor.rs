@@ -27,13 +27,13 @@ impl PerformancePredictor {
         // Record latency
         let mut latency_hist = self.latency_history.write().unwrap();
         latency_hist.entry(provider)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(latency);
 
         // Record cost
         let mut cost_hist = self.cost_history.write().unwrap();
         cost_hist.entry(provider)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(cost);
 
         // Keep only recent history (last 100 samples)
@@ -84,3 +84,9 @@ impl PerformancePredictor {
         })
     }
 }
+
+impl Default for PerformancePredictor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,154 @@
+//! Per-lane token-bucket rate limiting
+//!
+//! Each lane gets two buckets sized from its `RateLimit`:
+//! - Request slots, refilled at `requests_per_minute`
+//! - Tokens, refilled at `tokens_per_minute`
+//!
+//! Buckets start full and are created lazily the first time a lane is seen.
+
+use super::{Provider, RateLimit};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Time source for bucket refills
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A single bucket refilled continuously up to its capacity
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: f64,
+    available: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn per_minute(capacity: u32, now: Instant) -> Self {
+        Self {
+            capacity: capacity as f64,
+            available: capacity as f64,
+            refill_per_sec: capacity as f64 / 60.0,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.available = (self.available + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn covers(&self, amount: f64) -> bool {
+        self.available >= amount
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LaneBuckets {
+    requests: TokenBucket,
+    tokens: TokenBucket,
+}
+
+/// Rate limiter over all lanes
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<Provider, LaneBuckets>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RateLimiter {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            clock,
+        }
+    }
+
+    /// Whether the lane has a request slot and `tokens` tokens available
+    pub fn can_admit(&self, provider: Provider, limit: &RateLimit, tokens: u32) -> bool {
+        let now = self.clock.now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let lane = Self::refilled(&mut buckets, provider, limit, now);
+
+        lane.requests.covers(1.0) && lane.tokens.covers(tokens as f64)
+    }
+
+    /// Take a request slot and `tokens` tokens, or nothing if either is short
+    pub fn try_acquire(&self, provider: Provider, limit: &RateLimit, tokens: u32) -> bool {
+        let now = self.clock.now();
+        let mut buckets = self.buckets.lock().unwrap();
+        let lane = Self::refilled(&mut buckets, provider, limit, now);
+
+        if !(lane.requests.covers(1.0) && lane.tokens.covers(tokens as f64)) {
+            return false;
+        }
+
+        lane.requests.available -= 1.0;
+        lane.tokens.available -= tokens as f64;
+        true
+    }
+
+    fn refilled<'a>(
+        buckets: &'a mut HashMap<Provider, LaneBuckets>,
+        provider: Provider,
+        limit: &RateLimit,
+        now: Instant,
+    ) -> &'a mut LaneBuckets {
+        let lane = buckets.entry(provider).or_insert_with(|| LaneBuckets {
+            requests: TokenBucket::per_minute(limit.requests_per_minute, now),
+            tokens: TokenBucket::per_minute(limit.tokens_per_minute, now),
+        });
+        lane.requests.refill(now);
+        lane.tokens.refill(now);
+        lane
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemClock))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    struct ManualClock(Mutex<Instant>);
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let clock = Arc::new(ManualClock(Mutex::new(Instant::now())));
+        let limiter = RateLimiter::new(clock.clone());
+        let limit = RateLimit {
+            requests_per_minute: 60,
+            tokens_per_minute: 600,
+        };
+
+        assert!(limiter.try_acquire(Provider::Anthropic, &limit, 600));
+        assert!(!limiter.can_admit(Provider::Anthropic, &limit, 10));
+
+        // One second refills one request and ten tokens
+        *clock.0.lock().unwrap() += Duration::from_secs(1);
+        assert!(limiter.can_admit(Provider::Anthropic, &limit, 10));
+        assert!(!limiter.can_admit(Provider::Anthropic, &limit, 11));
+    }
+}
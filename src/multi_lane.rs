@@ -1,24 +0,0 @@
-//! Multi-Lane Module
-//!
-//! Stub module for multi-lane routing features.
-//! TODO: Implement multi-lane functionality
-
-#![allow(dead_code)]
-
-/// Multi-lane router stub
-pub struct MultiLane {
-    // TODO: Add fields
-}
-
-impl MultiLane {
-    /// Create new multi-lane router
-    pub fn new() -> Self {
-        Self {}
-    }
-}
-
-impl Default for MultiLane {
-    fn default() -> Self {
-        Self::new()
-    }
-}
@@ -5,14 +5,14 @@ mod tests {
     use leanr_syntax::{Lexer, Parser};
     use leanr_syntax::span::SourceFile;
     use leanr_elab::elaborate_decl;
-    use lean_agentic::{Arena, Environment};
+    use lean_agentic::{level::LevelArena, Arena, Environment, SymbolTable};
 
-    fn setup() -> (Arena, Environment) {
-        (Arena::new(), Environment::new())
+    fn setup() -> (Arena, SymbolTable, LevelArena, Environment) {
+        (Arena::new(), SymbolTable::new(), LevelArena::new(), Environment::new())
     }
 
     fn parse_and_elaborate(source: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let (mut arena, mut env) = setup();
+        let (mut arena, symbols, mut levels, mut env) = setup();
 
         let source_file = SourceFile::new(0, "test.lean".to_string(), source.to_string());
         let tokens = Lexer::new(source_file).tokenize();
@@ -20,7 +20,7 @@ mod tests {
         let decls = parser.parse_decls()?;
 
         for decl in &decls {
-            elaborate_decl(decl, &mut arena, &mut env)?;
+            elaborate_decl(decl, &mut arena, &symbols, &mut levels, &mut env)?;
         }
 
         Ok(())
@@ -262,7 +262,7 @@ mod rag_gateway_tests {
 
         let response = gateway.process(query).unwrap();
 
-        assert!(response.proof.verify(), "Proof should be valid");
+        assert!(response.proof.verify().is_valid(), "Proof should be valid");
         assert!(!response.proof.claims.is_empty(), "Proof should have claims");
         assert!(!response.proof.proof_hash.is_empty(), "Proof should have hash");
     }